@@ -0,0 +1,125 @@
+//! Benchmarks for the engine's hot paths: `cargo bench`
+//!
+//! Registry indexing and lookups (`core::query`), manifest parsing, and the checksum and
+//! extraction steps of an install (`core::install`).
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use rush::core::{extract_binary, verify_checksum};
+use rush::models::PackageManifest;
+use rush::test_utils::MockEnvironment;
+use sha2::{Digest, Sha256};
+use std::hint::black_box;
+
+const MANIFEST: &str = r#"
+version = "0.56.3"
+description = "A command-line fuzzy finder"
+source = "junegunn/fzf"
+license = "MIT"
+homepage = "https://github.com/junegunn/fzf"
+
+[targets.x86_64-linux]
+url = "https://github.com/junegunn/fzf/releases/download/v0.56.3/fzf-0.56.3-linux_amd64.tar.gz"
+bin = "fzf"
+sha256 = "a3fd8f1ce7e3e8fb0fa3e4d9d2c5d9c27d5e2ab0f0f8c0f4ae2dbd1b8b4b6e70"
+
+[targets.aarch64-linux]
+url = "https://github.com/junegunn/fzf/releases/download/v0.56.3/fzf-0.56.3-linux_arm64.tar.gz"
+bin = "fzf"
+sha256 = "6c9a5e4d3e1a8b0c2f9d7e6b5a4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f8a7b6c"
+
+[targets.aarch64-macos]
+url = "https://github.com/junegunn/fzf/releases/download/v0.56.3/fzf-0.56.3-darwin_arm64.tar.gz"
+bin = "fzf"
+sha256 = "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0"
+"#;
+
+/// A .tar.gz holding a few decoys and then `bin` with `size` bytes of content
+fn tarball(bin: &str, size: usize) -> Vec<u8> {
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let mut append = |path: &str, content: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        tar.append_data(&mut header, path, content).unwrap();
+    };
+
+    append("LICENSE", b"MIT");
+    append("README.md", &[b'#'; 4096]);
+    let content: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+    append(&format!("dist/{}", bin), &content);
+
+    tar.into_inner().unwrap().finish().unwrap()
+}
+
+fn registry_indexing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("registry");
+
+    for count in [100, 1000] {
+        let mock = MockEnvironment::new();
+        for i in 0..count {
+            mock.add_package(&format!("pkg{:04}", i), "1.0.0", "tool");
+        }
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("list", count), &engine, |b, engine| {
+            b.iter(|| black_box(engine.list_available_packages()))
+        });
+        group.bench_with_input(BenchmarkId::new("find", count), &engine, |b, engine| {
+            b.iter(|| black_box(engine.find_package("pkg0042")))
+        });
+    }
+
+    group.finish();
+}
+
+fn manifest_parsing(c: &mut Criterion) {
+    c.bench_function("manifest/parse", |b| {
+        b.iter(|| toml::from_str::<PackageManifest>(black_box(MANIFEST)).unwrap())
+    });
+}
+
+fn checksum_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checksum");
+
+    for size in [64 << 10, 16 << 20] {
+        let content = vec![0x5a; size];
+        let expected = hex::encode(Sha256::digest(&content));
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &content, |b, content| {
+            b.iter(|| verify_checksum(black_box(content), &expected).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn tar_extraction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extract");
+    let bin_dir = tempfile::tempdir().unwrap();
+
+    for size in [64 << 10, 16 << 20] {
+        let archive = tarball("tool", size);
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &archive, |b, archive| {
+            b.iter(|| extract_binary(black_box(archive), "tool", bin_dir.path()).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    registry_indexing,
+    manifest_parsing,
+    checksum_verification,
+    tar_extraction
+);
+criterion_main!(benches);
@@ -0,0 +1,299 @@
+# English messages of the rush CLI, looked up by `t!` in src/ui.rs.
+#
+# A translation goes in locales/<lang>/rush.ftl, with the same ids, and is listed in
+# CATALOGS in src/i18n.rs. Commands and flags in { $command } and the like are passed in
+# as they are, and stay untranslated. https://projectfluent.org/fluent/guide/
+
+## Labels and statuses
+
+label-error = Error:
+label-warning = Warning:
+label-warning-loud = WARNING:
+label-success = Success:
+label-failed = Failed:
+label-interrupted = Interrupted:
+interrupted-left-as-is = the package in progress was left as it was.
+progress-interrupted = interrupted
+status-ok = ok
+status-failed = failed
+status-skipped = skipped
+status-imported = imported
+status-bumped = bumped
+status-added = added
+status-updated = updated
+status-removed = removed
+exited-with-status = exited with status { $code }
+
+## list, info, search, find
+
+list-title = Installed Packages:
+list-empty = (No packages installed)
+list-dependency = dependency
+list-pinned = pinned
+list-constrained = pinned to { $constraint }
+info-registry = registry
+info-license = license
+info-homepage = homepage
+info-not-in-registry = (not in the registry)
+info-installed = installed
+info-not-installed = no
+info-files = files
+info-targets = targets
+info-url = url
+info-size = size
+info-no-target = { $package } has no build for { $target }, so it can't be installed here.
+target-current = current
+info-files-count = { $count } in the data dir
+info-provenance = Provenance:
+info-provenance-unrecorded = (not recorded; installed by an older rush)
+info-installed-at = installed at
+info-artifact = artifact
+info-sha256 = sha256
+info-revision = revision
+info-manifest = manifest
+info-signature = signature
+info-registry-unlisted = (none: installed from a manifest the registry doesn't list)
+search-title = Available Packages
+registry-empty = (Registry empty or not found. Run 'rush update')
+find-needs-terminal = rush find needs a terminal; use 'rush search' in scripts
+package-not-found = Package '{ $package }' not found.
+version-not-found = The registry has no { $package } { $version } (it has { $versions })
+
+## install, uninstall, upgrade
+
+install-start = Installing
+install-success = Installed to { $path }
+install-already-installed = { $package } is already installed
+install-up-to-date = { $package } is up to date (v{ $version })
+install-skipped = Skipped { $package }: { $reason }
+skip-already-installed = v{ $version } is already installed
+skip-up-to-date = already at v{ $version }
+skip-not-in-registry = no longer in the registry
+skip-no-compatible-target = the new version has no binary for { $target }
+skip-held = held at { $constraint } (v{ $latest } is out)
+skip-bad-constraint = its constraint { $constraint } can't be read: { $error }
+skip-binary-clash = the new version would overwrite { $bin }, which belongs to { $owner }
+skip-data-file-clash = the new version would overwrite { $file }, which belongs to { $owner }
+skip-pinned = pinned (v{ $latest } is out)
+skip-unmet-dependency = the new version needs { $dependency }, which can't be installed
+skip-dependency-cycle = the new version's dependencies go round in a cycle ({ $cycle })
+install-from-it = Install from it with:
+install-staged = { $path } is running, so the new version of { $package } can't replace it yet. It waits beside it and takes its place the next time rush runs.
+path-hint = { $path } is not on your PATH. Add { $line } to { $file } to run what rush installs, or run { $command } to add it.
+path-hint-profile = { $path } is not on your PATH. Add { $line } to your PowerShell profile ($PROFILE) to run what rush installs.
+prompt-init = Add it to { $file } now?
+init-added = Added { $line } to { $file }.
+init-new-shell = Open a new shell, or source the file, for it to take effect.
+init-already = { $file } already puts rush's binaries on PATH.
+init-manual = Add { $line } to your PowerShell profile ($PROFILE) to put rush's binaries on PATH.
+checksum-verifying = Verifying checksum...
+checksum-verified = Checksum verified.
+download-complete = Download complete
+summary = { $fetched }, verified in { $verify }, installed in { $install }
+summary-local = Used the local copy
+summary-downloaded = Downloaded { $size } in { $time }
+uninstall-start = Uninstalling
+uninstall-deleted = Deleted { $path }
+uninstall-success = Uninstalled
+uninstall-not-installed = Package '{ $package }' is not installed
+uninstall-needed-by = { $package } is needed by { $dependents }, which may stop working
+uninstall-needed-by-refused = { $package } is needed by { $dependents } (use --force to uninstall it anyway)
+man-none = { $package } has no manual pages installed
+man-not-found = { $package } has no manual page called { $page }; it has:
+man-several = { $package } has several manual pages; pick one with `rush man { $package } <page>`:
+pin-success = { $package } will only be upgraded to versions matching { $constraint }
+pin-held = { $package } will stay at its installed version until `rush unpin { $package }`
+unpin-success = { $package } will be upgraded to the registry's version again
+upgrade-checking = Checking for upgrades...
+upgrade-start = Upgrading
+upgrade-success = { $count } packages upgraded.
+
+## Hooks, checks and requirements
+
+hook-label = { $point } hook:
+hook-unfinished = did not finish
+hook-failed = { $point } hook { $status }: { $command }
+check-label = check:
+check-timed-out = timed out
+check-killed = was killed
+check-failed = check { $status }: { $command }
+check-not-run = { $package }: check not run: { $error }
+requirement-unmet = { $package } requires { $requirement }: { $problem }
+
+## Dry runs
+
+dry-run = Dry run: nothing was changed.
+dry-run-nothing = (Nothing to do)
+dry-run-install = Would install { $package } (v{ $version })
+dry-run-upgrade = Would upgrade { $package } (v{ $from } -> v{ $to })
+dry-run-remove = Would remove { $package } (v{ $version })
+dry-run-download = Download
+dry-run-write = Write
+dry-run-delete = Delete
+dry-run-run = Run
+dry-run-hook = { $point } hook for '{ $package }'
+
+## update, outdated, schedule, self-update
+
+registry-fetching = Fetching registry
+registry-fetching-from = from { $source }
+registry-updated = Registry updated from { $source }.
+hash-changes = { $count } artifact(s) changed since rush first saw them. Installs of these are refused:
+hash-changes-accept = A release should never change once published. If you trust the new artifacts, run { $command }.
+hash-change-accepted = Accepted the new artifact of { $package } v{ $version } ({ $target }).
+outdated-none = All installed packages are up to date.
+outdated-run-upgrade = Run { $command } to upgrade them.
+notify-title = rush: { $count } update(s) available
+notify-failed = Couldn't show a desktop notification.
+schedule-installed = rush will check for package updates daily.
+schedule-none = No scheduled update check was installed.
+schedule-removed = Removed the scheduled update check.
+self-update-updated = Updated rush { $current } -> { $latest } ({ $path }).
+self-update-up-to-date = rush { $current } is up to date (latest release: { $latest }).
+self-update-available = The latest release of rush is { $latest }; this is { $current }. Run { $command } to update.
+
+## clean, bundles, export, mirror
+
+clean-nothing = No trash found. System is clean.
+clean-deleted = Deleted trash:
+clean-success = Cleaned { $count } temporary files.
+clean-all-would = Would remove:
+clean-all-removed = Removed:
+leftover-temp-file = temp file
+leftover-cache = cache
+leftover-old-version = old version
+leftover-staged = staged copy
+leftover-receipt = record
+clean-all-total = { $count ->
+    [one] 1 item
+   *[other] { $count } items
+}, { $size }
+bundle-left-out = Left out (installed before rush recorded artifacts, and no longer in the registry): { $packages }
+bundle-success = Bundled { $count } packages into { $path }.
+export-left-out = Left out { $package }: { $reason }
+export-success = Exported { $count } packages to { $path }.
+mirror-unsupported = { $count } packages have no build for some of the selected targets.
+mirror-summary = Mirrored { $count } packages to { $path } ({ $downloaded } artifacts downloaded, { $reused } already present).
+mirror-failures = { $count } artifacts could not be fetched.
+
+## Projects
+
+shell-entering = Entering
+shell-entering-project = { $root } ({ $tools }). Type { $command } to leave.
+shell-no-tools = no tools
+sync-success = { $count ->
+    [one] 1 tool in { $path }
+   *[other] { $count } tools in { $path }
+}
+sync-check-missing = not fetched
+sync-check-not-linked = not in .rush/bin
+sync-check-unavailable = not in the registry for this machine
+sync-check-success = The project's tools are all in place
+sync-check-failed = { $failed } of { $count } tool(s) not in place; run '{ $command }' in { $root }
+
+## audit, upstream
+
+audit-known = { $count ->
+    [one] 1 known vulnerability
+   *[other] { $count } known vulnerabilities
+}
+audit-unrated = unrated
+audit-no-fix = no fix yet
+audit-fixed-in = fixed in { $versions }
+audit-aka = aka { $aliases }
+audit-unchecked = Not checked (no [osv] entry in their manifest): { $packages }
+audit-clean = No known vulnerabilities in { $count } checked packages.
+audit-vulnerable = { $vulnerable } of { $count } checked packages have known vulnerabilities.
+upstream-match = matches upstream
+upstream-mismatch = registry has { $registry }, upstream published { $upstream }
+upstream-no-checksum-file = no upstream checksum file recorded
+upstream-failed = { $failed } of { $count } targets don't match their upstream checksums.
+upstream-nothing-checked = No target records an upstream checksum file; nothing was checked.
+upstream-success = { $count } targets match their upstream checksums.
+
+## registry stats
+
+stats-packages = Packages:
+stats-targets = Targets:
+stats-newest = Newest:
+stats-oldest = Oldest:
+stats-missing-description = Missing a description
+stats-missing-size = Missing a size
+stats-unreadable = Unreadable manifests
+
+## dev: importing and editing packages
+
+dev-fetching = Fetching and hashing:
+dev-upstream-checksum = Using upstream checksum for
+dev-upstream-checksum-verified = Upstream checksum for { $asset } verified.
+dev-using-binary = Using binary
+dev-using-binary-override = (pass { $flag } to override)
+dev-added = Added { $package } to local registry.
+dev-fetching-metadata = Fetching
+dev-fetching-metadata-for = metadata for { $repo }
+dev-rate-limited = GitHub API rate limit exceeded, waiting { $minutes }m { $seconds }s for it to reset...
+dev-found-release = Found Release:
+dev-no-releases = No releases found for { $repo }
+dev-wizard-complete = Import wizard complete.
+dev-skipping-target = Skipping { $target }
+new-package-success = Created { $package } at { $path }.
+import-nothing = No installable targets found for { $package }
+import-success = Imported { $count } targets of { $package } { $version }
+score-minimum = auto-import needs a score of at least { $score }
+bump-hashing = Hashing
+bump-up-to-date = { $package } is up to date ({ $version }).
+bump-success = Bumped { $package } from { $from } to { $to } ({ $count } targets).
+bump-failed = Could not bump { $package } to { $version }; the manifest was left unchanged.
+refresh-checking = { $count } packages for new releases...
+refresh-summary = { $bumped } bumped, { $current } up to date, { $failed } failed.
+publish-success = Pushed '{ $branch }' and opened { $url }
+keygen-success = Wrote signing key to { $path }.
+keygen-public-key = public key:
+keygen-keep-private = Keep the secret key private; publish the public key.
+sign-success = Signed { $count } manifests into { $path }.
+sign-signature = signature
+
+## Prompts
+
+prompt-archive-empty = The archive contains no files.
+prompt-executable = [executable]
+prompt-recommended = (Recommended)
+prompt-skip-target = Skip this target
+prompt-which-binary = Which file is the binary?
+prompt-package-name = Package name
+prompt-version = Version
+prompt-description = Description
+prompt-license = License (SPDX, e.g. MIT)
+prompt-homepage = Homepage
+prompt-targets = Targets (space to toggle, enter to confirm)
+prompt-pick-a-target = Pick at least one target.
+prompt-target-url = { $target } download URL or local path
+prompt-target-bin = { $target } binary name (empty to detect)
+prompt-unpublished = unpublished
+prompt-prerelease = [prerelease]
+prompt-select-release = Select release to import
+prompt-select-asset = Select asset for { $target }
+
+## dev verify, test, lint, check-urls, serve
+
+verify-start = Verifying registry integrity...
+verify-checking = Checking
+verify-success = Verified { $count } packages ({ $targets } targets). All clean.
+verify-failed = Verification failed for { $count } targets!
+verify-failures = Failures:
+lint-error = error
+lint-warning = warning
+lint-success = Linted { $count } manifests ({ $warnings } warnings).
+lint-failed = Verification failed: { $errors } errors, { $warnings } warnings in { $count } manifests.
+links-checking = { $count } links...
+links-success = All { $count } links are reachable.
+links-failed = { $failed } of { $count } links need attention!
+links-moved = moved to { $location }
+trial-installed = Trial install of
+trial-no-check = no check defined; only the install was tried
+trial-success = { $package } installs cleanly.
+trial-failed = { $package } installed, but its check failed.
+serve-serving = Serving
+serve-artifacts = artifacts:
+serve-try = try:
+serve-stop = Press Ctrl+C to stop.
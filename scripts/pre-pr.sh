@@ -67,7 +67,7 @@ fi
 if [ "$CHANGES_DETECTED" = true ]; then
     echo "📦 Detected changes in packages/. Verifying registry..."
     export RUSH_REGISTRY_URL="."
-    cargo run -- dev verify
+    cargo run -- dev verify --deep
 else
     echo "⏭️  No changes in packages/. Skipping registry verification."
 fi
@@ -1,5 +1,7 @@
-use clap::{Parser, Subcommand};
+use crate::models::PackageOrder;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
+use std::path::PathBuf;
 
 // --- CLI ---
 #[derive(Parser, Debug)] // Added Debug
@@ -9,24 +11,195 @@ use clap_complete::Shell;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Log what the engine is doing to stderr (-v: operations, -vv: each step, with timings)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Also append a detailed log (every step, with timings) to this file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// How to show download progress: bars, or plain percentage lines for CI logs
+    #[arg(long, value_enum, global = true, default_value_t = ProgressMode::Auto)]
+    pub progress: ProgressMode,
+}
+
+/// `--progress`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ProgressMode {
+    /// Bars on a terminal; plain lines when stderr isn't one, or CI is set
+    Auto,
+    /// Progress bars, redrawn in place
+    Bar,
+    /// A line every 10%, with no control characters
+    Plain,
 }
 
 #[derive(Subcommand, Debug, PartialEq)]
 pub enum Commands {
-    /// Install a package
-    Install { name: String },
+    /// Install a package by name (`name@version` for an older release the registry still
+    /// lists), or from a local manifest file (e.g. ./fzf.toml)
+    Install {
+        name: String,
+        /// Show what would be downloaded, written, and recorded, without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run a package's binary without installing it (fetched into the cache if needed),
+    /// e.g. `rush run ripgrep@14.1.0 -- --version`
+    Run {
+        /// Package name, optionally with a version (`name@version`)
+        package: String,
+        /// Arguments for the binary
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Open the manual page an installed package put in place
+    Man {
+        name: String,
+        /// Which page, when the package has several (e.g. "tool.conf")
+        page: Option<String>,
+    },
+    /// Start a shell with the tools in the project's rush.toml first on PATH, fetching
+    /// any that are missing; with a command, run that instead
+    Shell {
+        /// Command to run in the project's environment (default: your $SHELL)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// List installed packages the registry has another version of
+    Outdated {
+        /// Also raise a desktop notification when there are any (for scheduled checks)
+        #[arg(long)]
+        notify: bool,
+        /// Print them as JSON
+        #[arg(long)]
+        json: bool,
+        /// Tab-separated `name, installed, available` lines, for scripts
+        #[arg(long, conflicts_with = "json")]
+        porcelain: bool,
+        /// Exit with status 10 when there are any, so CI jobs can detect drift
+        #[arg(long)]
+        exit_code: bool,
+    },
     /// Uninstall a package
-    Uninstall { name: String },
+    Uninstall {
+        name: String,
+        /// Show what would be deleted, without doing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Uninstall it even if installed packages depend on it
+        #[arg(long)]
+        force: bool,
+    },
+    /// Hold a package at its installed version, or only upgrade it to versions matching a
+    /// constraint (e.g. "~20", ">=1.2, <1.5")
+    Pin {
+        name: String,
+        constraint: Option<String>,
+    },
+    /// Let upgrades move a pinned package to the registry's version again
+    Unpin { name: String },
     /// List installed packages
-    List,
+    List {
+        /// Tab-separated `name, version, binaries` lines, for scripts
+        #[arg(long)]
+        porcelain: bool,
+    },
     /// Search for available packages
-    Search,
+    Search {
+        /// Tab-separated `name, version, description` lines, for scripts
+        #[arg(long)]
+        porcelain: bool,
+        /// List every package with the targets it has builds for, not only the ones that
+        /// can be installed here
+        #[arg(long)]
+        targets: bool,
+        /// Order by name, most recently updated, or smallest download for this machine
+        #[arg(long, value_enum, default_value_t = PackageOrder::Name)]
+        sort: PackageOrder,
+    },
+    /// Pick a package to install with an interactive fuzzy finder over names and descriptions
+    Find {
+        /// What to start with in the search box
+        query: Option<String>,
+    },
+    /// Show what is installed of a package and what the registry has
+    Info {
+        name: String,
+        /// Also show where the installed binary came from (registry, manifest, artifact,
+        /// signature, time)
+        #[arg(long)]
+        provenance: bool,
+        /// Print everything as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Update the registry (for now, just re-reads the local file)
-    Update,
+    Update {
+        /// Show what would be fetched and replaced, without doing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Trust the new artifact hashes of these packages, after a re-published release
+        /// was reported
+        #[arg(long, value_name = "PACKAGE", value_delimiter = ',')]
+        accept_changed: Vec<String>,
+    },
     /// Upgrade all installed packages
-    Upgrade,
+    Upgrade {
+        /// Show which packages would be upgraded, without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Remove temporary files from failed installs
-    Clean,
+    Clean {
+        /// Also remove orphaned cache entries, replaced AppImage versions, and the records
+        /// of packages whose files are gone
+        #[arg(long)]
+        all: bool,
+        /// Show which files would be deleted, without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check installed packages for known vulnerabilities (via osv.dev)
+    Audit,
+    /// Check a package's registry hashes against the checksum files its release published
+    Verify {
+        #[arg(long, value_name = "PACKAGE")]
+        upstream: String,
+    },
+    /// Pack installed packages' artifacts and a lockfile into a bundle that reinstalls them
+    /// offline
+    ExportBundle {
+        /// The bundle to write (e.g. toolchain.tar.gz)
+        out: PathBuf,
+    },
+    /// Describe the installed packages for another package manager
+    Export {
+        /// What to write
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Install everything in a bundle from export-bundle, without the network
+    ImportBundle {
+        /// The bundle to install (e.g. toolchain.tar.gz)
+        bundle: PathBuf,
+    },
+    /// Download the registry and its artifacts into a directory that works as an offline
+    /// registry (RUSH_REGISTRY_URL=<dir>)
+    Mirror {
+        dir: PathBuf,
+        /// Targets to fetch artifacts for, comma-separated (default: this machine's)
+        #[arg(long = "target", alias = "targets", value_delimiter = ',')]
+        targets: Vec<String>,
+        /// Fetch artifacts for every supported target
+        #[arg(long, conflicts_with = "targets")]
+        all_targets: bool,
+    },
 
     #[command(hide = true)]
     /// Developer commands (hidden from help)
@@ -39,6 +212,80 @@ pub enum Commands {
         #[arg(value_enum)]
         shell: Shell,
     },
+    /// Print the shell code that puts rush's binaries on PATH
+    /// (e.g. `eval "$(rush env)"` in ~/.bashrc)
+    Env {
+        /// Shell to print it for (default: POSIX sh, which bash and zsh understand)
+        #[arg(long, value_enum)]
+        shell: Option<Shell>,
+        /// Put the project's tools (from its rush.toml) on PATH instead, fetching any
+        /// that are missing
+        #[arg(long)]
+        project: bool,
+    },
+    /// Add the `rush env` line to your shell's startup file (e.g. ~/.bashrc), so rush's
+    /// binaries are on PATH in every new shell
+    Init {
+        /// Shell to set up (default: the one in $SHELL)
+        #[arg(long, value_enum)]
+        shell: Option<Shell>,
+    },
+    /// Replace this rush with its latest release, if that is newer (checked against the
+    /// release's published checksums)
+    SelfUpdate {
+        /// Only say whether there is a newer release
+        #[arg(long)]
+        check: bool,
+    },
+    /// Check for package updates daily in the background (systemd on Linux, launchd on macOS)
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommands,
+    },
+    /// Fetch the tools in the project's rush.toml into its .rush/, as `rush shell` does
+    Sync {
+        /// Only check that every tool is there at its version; exit with status 1 if not
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print a `use_rush` function for direnv, so `use rush` in an .envrc puts the
+    /// project's tools on PATH (e.g. `rush direnv > ~/.config/direnv/lib/rush.sh`)
+    Direnv,
+    /// Look at the registry as a whole
+    Registry {
+        #[command(subcommand)]
+        command: RegistryCommands,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+/// `rush schedule` commands
+pub enum ScheduleCommands {
+    /// Run `rush update && rush outdated --notify` once a day
+    Install,
+    /// Stop the daily check and delete its files
+    Remove,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+/// `rush registry` commands
+pub enum RegistryCommands {
+    /// Count packages and per-target coverage, and list manifests missing descriptions or
+    /// sizes
+    Stats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Formats `rush export` can write
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    /// A Nix expression with one derivation per package, fetching the installed artifacts
+    Nix,
+    /// A Dockerfile that adds the installed artifacts, pinned by sha256, to an image
+    Dockerfile,
 }
 
 #[derive(Subcommand, Debug, PartialEq)]
@@ -52,19 +299,150 @@ pub enum DevCommands {
         version: String,
         /// System target (e.g. "x86_64-linux")
         target: String,
-        /// Download URL
+        /// Download URL, or path to a local artifact
         url: String,
-        /// Binary name inside the archive (defaults to package name)
+        /// Binary name inside the archive (detected from the archive if omitted)
         #[arg(long)]
         bin: Option<String>,
+        /// Copy a local artifact into the registry's artifacts/ folder
+        #[arg(long)]
+        copy: bool,
     },
-    /// Interactive wizard to import a package from GitHub
+    /// Interactive wizard to import a package from GitHub or GitLab
     Import {
-        /// Repository (e.g. "sharkdp/bat")
+        /// Repository (e.g. "sharkdp/bat", or "gitlab:group/project" for GitLab)
+        repo: String,
+        /// Release tag to import (e.g. "v0.24.0"); otherwise pick from recent releases
+        #[arg(long)]
+        tag: Option<String>,
+        /// How many recent releases to offer when no tag is given
+        #[arg(long, default_value_t = 10, conflicts_with_all = ["tag", "auto"])]
+        recent: usize,
+        /// Don't prompt: take the best-scoring asset for each target
+        #[arg(long)]
+        auto: bool,
+        /// In --auto mode, skip targets whose best asset scores below this
+        #[arg(
+            long,
+            default_value_t = 25,
+            requires = "auto",
+            allow_negative_numbers = true
+        )]
+        min_score: i32,
+        /// Targets to import, comma-separated (default: all Linux and macOS targets)
+        #[arg(long, value_delimiter = ',')]
+        targets: Vec<String>,
+        /// In --auto mode, download assets to confirm upstream checksums instead of trusting them
+        #[arg(long, requires = "auto")]
+        verify: bool,
+        /// Wait for the GitHub API rate limit to reset instead of failing
+        #[arg(long)]
+        wait: bool,
+        /// Offer (or, with --auto, take) prereleases and nightly builds too
+        #[arg(long, conflicts_with = "tag")]
+        include_prereleases: bool,
+    },
+    /// Show how each asset of a release scores for each target, and why
+    Score {
+        /// GitHub repository (e.g. "sharkdp/bat") or "gitlab:group/project"
         repo: String,
+        /// Release tag (default: latest)
+        #[arg(long)]
+        tag: Option<String>,
+        /// Targets to score, comma-separated (default: all Linux and macOS targets)
+        #[arg(long = "target", alias = "targets", value_delimiter = ',')]
+        targets: Vec<String>,
+        /// Wait for the GitHub API rate limit to reset instead of failing
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Import a crate's cargo-dist release, taking targets and hashes from its dist-manifest.json
+    ImportCrate {
+        /// Crate name on crates.io (e.g. "cargo-dist")
+        name: String,
+        /// Version to import (default: latest stable)
+        #[arg(long)]
+        version: Option<String>,
+        /// Targets to import, comma-separated (default: all Linux and macOS targets)
+        #[arg(long, value_delimiter = ',')]
+        targets: Vec<String>,
+        /// Wait for the GitHub API rate limit to reset instead of failing
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Interactively write a manifest for a new package
+    New,
+    /// Update a package in the local registry to its newest upstream release
+    Bump {
+        /// Package name (e.g. "bat")
+        name: String,
+        /// Bump even if the newest release is older than the manifest's version
+        #[arg(long)]
+        force: bool,
+    },
+    /// Bump every package that records its upstream source
+    RefreshAll {
+        /// How many packages to check at once
+        #[arg(long, default_value_t = 4)]
+        jobs: usize,
+        /// Seconds to spend on one package before reporting it as failed and moving on
+        #[arg(long, value_name = "SECS", default_value_t = 120)]
+        timeout: u64,
+    },
+    /// Commit the changed manifests to a branch and open a pull request
+    Publish {
+        /// Git remote to push the branch to
+        #[arg(long, default_value = "origin")]
+        remote: String,
+        /// Branch the pull request targets
+        #[arg(long, default_value = "main")]
+        base: String,
+        /// Branch name (default: generated from the changed packages)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+    /// Serve the local registry over HTTP on localhost, for end-to-end testing
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Directory served under /artifacts/ (default: the registry's artifacts/)
+        #[arg(long)]
+        artifacts: Option<PathBuf>,
+    },
+    /// Create an ed25519 key for signing the registry
+    Keygen {
+        /// Replace an existing key
+        #[arg(long)]
+        force: bool,
+    },
+    /// Write a signed index of every manifest in the local registry
+    Sign {
+        /// Secret key to sign with (default: the key from `dev keygen`)
+        #[arg(long)]
+        key: Option<PathBuf>,
+    },
+    /// Install a package into a throwaway root and run its check, to try a manifest
+    /// before publishing it
+    Test {
+        /// A package in the local registry, or a path to a manifest (e.g. ./fzf.toml)
+        package: String,
     },
     /// Verify the integrity of the local registry
-    Verify,
+    Verify {
+        /// Also download every artifact and re-check its sha256 and binary
+        #[arg(long)]
+        deep: bool,
+        /// Only download this many randomly chosen targets
+        #[arg(long, requires = "deep")]
+        sample: Option<usize>,
+    },
+    /// Check every artifact URL in the local registry for dead or moved links
+    CheckUrls {
+        /// Number of concurrent requests
+        #[arg(long, default_value_t = 8)]
+        jobs: usize,
+    },
 }
 
 // --- TESTS ---
@@ -87,18 +465,421 @@ mod tests {
         let cli = Cli::parse_from(args);
 
         match cli.command {
-            Commands::Install { name } => assert_eq!(name, "ripgrep"),
+            Commands::Install { name, dry_run } => {
+                assert_eq!(name, "ripgrep");
+                assert!(!dry_run);
+            }
             _ => panic!("Parsed incorrect subcommand"),
         }
     }
 
+    #[test]
+    fn test_dry_run_parsing() {
+        let cli = Cli::parse_from(["rush", "upgrade", "--dry-run"]);
+        assert_eq!(cli.command, Commands::Upgrade { dry_run: true });
+
+        let cli = Cli::parse_from(["rush", "uninstall", "fzf", "--dry-run"]);
+        assert_eq!(
+            cli.command,
+            Commands::Uninstall {
+                name: "fzf".to_string(),
+                dry_run: true,
+                force: false,
+            }
+        );
+
+        // Read-only commands have nothing to dry-run
+        assert!(Cli::try_parse_from(["rush", "list", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn test_clean_all_parsing() {
+        let cli = Cli::parse_from(["rush", "clean"]);
+        assert_eq!(
+            cli.command,
+            Commands::Clean {
+                all: false,
+                dry_run: false
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "clean", "--all", "--dry-run"]);
+        assert_eq!(
+            cli.command,
+            Commands::Clean {
+                all: true,
+                dry_run: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_info_command_parsing() {
+        let cli = Cli::parse_from(["rush", "info", "fzf", "--provenance"]);
+        assert_eq!(
+            cli.command,
+            Commands::Info {
+                name: "fzf".to_string(),
+                provenance: true,
+                json: false
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "info", "fzf", "--json"]);
+        assert!(matches!(cli.command, Commands::Info { json: true, .. }));
+    }
+
+    #[test]
+    fn test_audit_command_parsing() {
+        let cli = Cli::parse_from(["rush", "audit"]);
+        assert_eq!(cli.command, Commands::Audit);
+    }
+
+    #[test]
+    fn test_verify_upstream_parsing() {
+        let cli = Cli::parse_from(["rush", "verify", "--upstream", "fzf"]);
+        assert_eq!(
+            cli.command,
+            Commands::Verify {
+                upstream: "fzf".to_string()
+            }
+        );
+        assert!(Cli::try_parse_from(["rush", "verify"]).is_err());
+    }
+
+    #[test]
+    fn test_export_bundle_command_parsing() {
+        let cli = Cli::parse_from(["rush", "export-bundle", "out.tar.gz"]);
+        assert_eq!(
+            cli.command,
+            Commands::ExportBundle {
+                out: PathBuf::from("out.tar.gz")
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_command_parsing() {
+        let cli = Cli::parse_from(["rush", "run", "rg@14.1.0", "--", "-i", "todo"]);
+        assert_eq!(
+            cli.command,
+            Commands::Run {
+                package: "rg@14.1.0".to_string(),
+                args: vec!["-i".to_string(), "todo".to_string()]
+            }
+        );
+        let cli = Cli::parse_from(["rush", "run", "fd", "--hidden"]);
+        assert_eq!(
+            cli.command,
+            Commands::Run {
+                package: "fd".to_string(),
+                args: vec!["--hidden".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn test_porcelain_parsing() {
+        let cli = Cli::parse_from(["rush", "list", "--porcelain"]);
+        assert_eq!(cli.command, Commands::List { porcelain: true });
+        let cli = Cli::parse_from(["rush", "search"]);
+        assert_eq!(
+            cli.command,
+            Commands::Search {
+                porcelain: false,
+                targets: false,
+                sort: PackageOrder::Name
+            }
+        );
+        let cli = Cli::parse_from(["rush", "outdated", "--porcelain"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Outdated {
+                porcelain: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_find_command_parsing() {
+        let cli = Cli::parse_from(["rush", "find"]);
+        assert_eq!(cli.command, Commands::Find { query: None });
+        let cli = Cli::parse_from(["rush", "find", "grep"]);
+        assert_eq!(
+            cli.command,
+            Commands::Find {
+                query: Some("grep".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_man_command_parsing() {
+        let cli = Cli::parse_from(["rush", "man", "tool", "tool.conf"]);
+        assert_eq!(
+            cli.command,
+            Commands::Man {
+                name: "tool".to_string(),
+                page: Some("tool.conf".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_shell_command_parsing() {
+        let cli = Cli::parse_from(["rush", "shell"]);
+        assert_eq!(cli.command, Commands::Shell { command: vec![] });
+        let cli = Cli::parse_from(["rush", "shell", "--", "make", "-j4"]);
+        assert_eq!(
+            cli.command,
+            Commands::Shell {
+                command: vec!["make".to_string(), "-j4".to_string()]
+            }
+        );
+    }
+
+    #[test]
+    fn test_sync_command_parsing() {
+        let cli = Cli::parse_from(["rush", "sync"]);
+        assert_eq!(cli.command, Commands::Sync { check: false });
+        let cli = Cli::parse_from(["rush", "sync", "--check"]);
+        assert_eq!(cli.command, Commands::Sync { check: true });
+    }
+
+    #[test]
+    fn test_env_command_parsing() {
+        let cli = Cli::parse_from(["rush", "env"]);
+        assert_eq!(
+            cli.command,
+            Commands::Env {
+                shell: None,
+                project: false
+            }
+        );
+        let cli = Cli::parse_from(["rush", "env", "--shell", "fish", "--project"]);
+        assert_eq!(
+            cli.command,
+            Commands::Env {
+                shell: Some(Shell::Fish),
+                project: true
+            }
+        );
+        let cli = Cli::parse_from(["rush", "direnv"]);
+        assert_eq!(cli.command, Commands::Direnv);
+    }
+
+    #[test]
+    fn test_init_command_parsing() {
+        let cli = Cli::parse_from(["rush", "init"]);
+        assert_eq!(cli.command, Commands::Init { shell: None });
+        let cli = Cli::parse_from(["rush", "init", "--shell", "zsh"]);
+        assert_eq!(
+            cli.command,
+            Commands::Init {
+                shell: Some(Shell::Zsh)
+            }
+        );
+    }
+
+    #[test]
+    fn test_self_update_command_parsing() {
+        let cli = Cli::parse_from(["rush", "self-update"]);
+        assert_eq!(cli.command, Commands::SelfUpdate { check: false });
+        let cli = Cli::parse_from(["rush", "self-update", "--check"]);
+        assert_eq!(cli.command, Commands::SelfUpdate { check: true });
+    }
+
+    #[test]
+    fn test_outdated_and_schedule_parsing() {
+        let cli = Cli::parse_from(["rush", "outdated", "--notify"]);
+        assert_eq!(
+            cli.command,
+            Commands::Outdated {
+                notify: true,
+                json: false,
+                porcelain: false,
+                exit_code: false
+            }
+        );
+        let cli = Cli::parse_from(["rush", "outdated", "--json", "--exit-code"]);
+        assert_eq!(
+            cli.command,
+            Commands::Outdated {
+                notify: false,
+                json: true,
+                porcelain: false,
+                exit_code: true
+            }
+        );
+        assert!(Cli::try_parse_from(["rush", "outdated", "--json", "--porcelain"]).is_err());
+        let cli = Cli::parse_from(["rush", "schedule", "install"]);
+        assert_eq!(
+            cli.command,
+            Commands::Schedule {
+                command: ScheduleCommands::Install
+            }
+        );
+        let cli = Cli::parse_from(["rush", "schedule", "remove"]);
+        assert_eq!(
+            cli.command,
+            Commands::Schedule {
+                command: ScheduleCommands::Remove
+            }
+        );
+    }
+
+    #[test]
+    fn test_registry_stats_parsing() {
+        let cli = Cli::parse_from(["rush", "registry", "stats", "--json"]);
+        assert_eq!(
+            cli.command,
+            Commands::Registry {
+                command: RegistryCommands::Stats { json: true }
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_command_parsing() {
+        let cli = Cli::parse_from(["rush", "export", "--format", "nix", "-o", "rush.nix"]);
+        assert_eq!(
+            cli.command,
+            Commands::Export {
+                format: ExportFormat::Nix,
+                out: Some(PathBuf::from("rush.nix"))
+            }
+        );
+        let cli = Cli::parse_from(["rush", "export", "--format", "dockerfile"]);
+        assert_eq!(
+            cli.command,
+            Commands::Export {
+                format: ExportFormat::Dockerfile,
+                out: None
+            }
+        );
+        assert!(Cli::try_parse_from(["rush", "export"]).is_err());
+        assert!(Cli::try_parse_from(["rush", "export", "--format", "rpm"]).is_err());
+    }
+
+    #[test]
+    fn test_import_bundle_command_parsing() {
+        let cli = Cli::parse_from(["rush", "import-bundle", "toolchain.tar.gz"]);
+        assert_eq!(
+            cli.command,
+            Commands::ImportBundle {
+                bundle: PathBuf::from("toolchain.tar.gz")
+            }
+        );
+    }
+
+    #[test]
+    fn test_mirror_command_parsing() {
+        let cli = Cli::parse_from([
+            "rush",
+            "mirror",
+            "/srv/rush",
+            "--target",
+            "x86_64-linux,aarch64-linux",
+        ]);
+        assert_eq!(
+            cli.command,
+            Commands::Mirror {
+                dir: PathBuf::from("/srv/rush"),
+                targets: vec!["x86_64-linux".to_string(), "aarch64-linux".to_string()],
+                all_targets: false,
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "mirror", "/srv/rush", "--all-targets"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Mirror {
+                all_targets: true,
+                ..
+            }
+        ));
+        assert!(
+            Cli::try_parse_from(["rush", "mirror", "m", "--all-targets", "--target", "x"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_logging_flags_parsing() {
+        let cli = Cli::parse_from(["rush", "install", "fzf", "-vv", "--log-file", "rush.log"]);
+        assert_eq!(cli.verbose, 2);
+        assert_eq!(cli.log_file, Some(PathBuf::from("rush.log")));
+
+        let cli = Cli::parse_from(["rush", "list"]);
+        assert_eq!(cli.verbose, 0);
+        assert_eq!(cli.log_file, None);
+    }
+
+    #[test]
+    fn test_progress_flag_parsing() {
+        let cli = Cli::parse_from(["rush", "install", "fzf", "--progress", "plain"]);
+        assert_eq!(cli.progress, ProgressMode::Plain);
+
+        let cli = Cli::parse_from(["rush", "--progress", "bar", "upgrade"]);
+        assert_eq!(cli.progress, ProgressMode::Bar);
+
+        let cli = Cli::parse_from(["rush", "list"]);
+        assert_eq!(cli.progress, ProgressMode::Auto);
+        assert!(Cli::try_parse_from(["rush", "list", "--progress", "fancy"]).is_err());
+    }
+
+    #[test]
+    fn test_update_accept_changed_parsing() {
+        let cli = Cli::parse_from(["rush", "update", "--accept-changed", "fzf,bat"]);
+        assert_eq!(
+            cli.command,
+            Commands::Update {
+                dry_run: false,
+                accept_changed: vec!["fzf".to_string(), "bat".to_string()],
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "update"]);
+        assert!(
+            matches!(cli.command, Commands::Update { accept_changed, .. } if accept_changed.is_empty())
+        );
+    }
+
     #[test]
     fn test_upgrade_command_parsing() {
         let args = vec!["rush", "upgrade"];
         let cli = Cli::parse_from(args);
 
         // We implemented PartialEq on the Enum so we can compare directly
-        assert_eq!(cli.command, Commands::Upgrade);
+        assert_eq!(cli.command, Commands::Upgrade { dry_run: false });
+    }
+
+    #[test]
+    fn test_pin_command_parsing() {
+        let cli = Cli::parse_from(["rush", "pin", "node", ">=20, <22"]);
+        assert_eq!(
+            cli.command,
+            Commands::Pin {
+                name: "node".to_string(),
+                constraint: Some(">=20, <22".to_string())
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "pin", "node"]);
+        assert_eq!(
+            cli.command,
+            Commands::Pin {
+                name: "node".to_string(),
+                constraint: None
+            }
+        );
+        let cli = Cli::parse_from(["rush", "unpin", "node"]);
+        assert_eq!(
+            cli.command,
+            Commands::Unpin {
+                name: "node".to_string()
+            }
+        );
     }
 
     #[test]
@@ -124,12 +905,14 @@ mod tests {
                     target,
                     url,
                     bin,
+                    copy,
                 } => {
                     assert_eq!(name, "my-tool");
                     assert_eq!(version, "1.2.3");
                     assert_eq!(target, "x86_64-linux");
                     assert_eq!(url, "http://example.com/tool.tar.gz");
                     assert_eq!(bin, Some("tool-bin".to_string()));
+                    assert!(!copy);
                 }
                 _ => panic!("Parsed incorrect dev subcommand"),
             },
@@ -144,10 +927,277 @@ mod tests {
 
         match cli.command {
             Commands::Dev { command } => match command {
-                DevCommands::Verify => {} // Success
+                DevCommands::Verify { deep, sample } => {
+                    assert!(!deep);
+                    assert_eq!(sample, None);
+                }
                 _ => panic!("Parsed incorrect dev subcommand"),
             },
             _ => panic!("Parsed incorrect top-level command"),
         }
     }
+
+    #[test]
+    fn test_dev_import_tag_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "import", "sharkdp/bat", "--tag", "v0.24.0"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Import {
+                    repo: "sharkdp/bat".to_string(),
+                    tag: Some("v0.24.0".to_string()),
+                    recent: 10,
+                    auto: false,
+                    min_score: 25,
+                    targets: vec![],
+                    verify: false,
+                    wait: false,
+                    include_prereleases: false,
+                }
+            }
+        );
+
+        // Picking from recent releases only makes sense without a tag
+        let args = [
+            "rush", "dev", "import", "a/b", "--tag", "v1", "--recent", "3",
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_dev_import_auto_parsing() {
+        let cli = Cli::parse_from([
+            "rush",
+            "dev",
+            "import",
+            "a/b",
+            "--auto",
+            "--min-score",
+            "40",
+        ]);
+        match cli.command {
+            Commands::Dev {
+                command:
+                    DevCommands::Import {
+                        auto, min_score, ..
+                    },
+            } => {
+                assert!(auto);
+                assert_eq!(min_score, 40);
+            }
+            _ => panic!("Parsed incorrect command"),
+        }
+
+        // A threshold without --auto would be silently ignored
+        assert!(Cli::try_parse_from(["rush", "dev", "import", "a/b", "--min-score", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_dev_import_targets_parsing() {
+        let args = [
+            "rush",
+            "dev",
+            "import",
+            "a/b",
+            "--targets",
+            "x86_64-linux,x86_64-windows",
+        ];
+        match Cli::parse_from(args).command {
+            Commands::Dev {
+                command: DevCommands::Import { targets, .. },
+            } => assert_eq!(targets, vec!["x86_64-linux", "x86_64-windows"]),
+            _ => panic!("Parsed incorrect command"),
+        }
+    }
+
+    #[test]
+    fn test_dev_import_crate_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "import-crate", "tool", "--version", "1.2.0"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::ImportCrate {
+                    name: "tool".to_string(),
+                    version: Some("1.2.0".to_string()),
+                    targets: vec![],
+                    wait: false,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_bump_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "bump", "bat"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Bump {
+                    name: "bat".to_string(),
+                    force: false
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_new_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "new"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::New
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_score_parsing() {
+        let cli = Cli::parse_from([
+            "rush",
+            "dev",
+            "score",
+            "sharkdp/bat",
+            "--target",
+            "x86_64-linux,aarch64-macos",
+        ]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Score {
+                    repo: "sharkdp/bat".to_string(),
+                    tag: None,
+                    targets: vec!["x86_64-linux".to_string(), "aarch64-macos".to_string()],
+                    wait: false,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_refresh_all_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "refresh-all", "--jobs", "2"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::RefreshAll {
+                    jobs: 2,
+                    timeout: 120
+                }
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "dev", "refresh-all", "--timeout", "30"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::RefreshAll {
+                    jobs: 4,
+                    timeout: 30
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_publish_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "publish", "--branch", "add-bat"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Publish {
+                    remote: "origin".to_string(),
+                    base: "main".to_string(),
+                    branch: Some("add-bat".to_string()),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_serve_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "serve", "--artifacts", "dist"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Serve {
+                    port: 8080,
+                    artifacts: Some(PathBuf::from("dist")),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_keygen_and_sign_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "keygen", "--force"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Keygen { force: true }
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "dev", "sign", "--key", "maintainer.key"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Sign {
+                    key: Some(PathBuf::from("maintainer.key"))
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_test_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "test", "./fzf.toml"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::Test {
+                    package: "./fzf.toml".to_string()
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_check_urls_parsing() {
+        let cli = Cli::parse_from(["rush", "dev", "check-urls"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::CheckUrls { jobs: 8 }
+            }
+        );
+
+        let cli = Cli::parse_from(["rush", "dev", "check-urls", "--jobs", "2"]);
+        assert_eq!(
+            cli.command,
+            Commands::Dev {
+                command: DevCommands::CheckUrls { jobs: 2 }
+            }
+        );
+    }
+
+    #[test]
+    fn test_dev_verify_deep_sample_parsing() {
+        let args = vec!["rush", "dev", "verify", "--deep", "--sample", "5"];
+        let cli = Cli::parse_from(args);
+
+        match cli.command {
+            Commands::Dev {
+                command: DevCommands::Verify { deep, sample },
+            } => {
+                assert!(deep);
+                assert_eq!(sample, Some(5));
+            }
+            _ => panic!("Parsed incorrect command"),
+        }
+
+        // --sample without --deep makes no sense
+        let args = vec!["rush", "dev", "verify", "--sample", "5"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
 }
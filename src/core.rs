@@ -1,116 +1,612 @@
+mod appimage;
+mod archive;
+mod audit;
+mod builder;
+mod bundle;
+mod cancel;
+mod check;
 mod clean;
+mod config;
+mod constraint;
+mod datafiles;
+mod dependencies;
 mod dev;
+mod dockerfile;
+mod find;
+mod hooks;
+mod init;
 mod install;
+mod lock;
+mod mirror;
+mod nix;
+#[cfg(feature = "async")]
+mod nonblocking;
+mod pending;
+mod pins;
+mod plan;
+mod preview;
+mod project;
+mod provenance;
 mod query;
+mod registry;
+mod requires;
+mod run;
+mod sandbox;
+mod schedule;
+mod self_update;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+mod stats;
+mod store;
+pub(crate) mod target;
+mod transport;
 mod uninstall;
 mod update;
+mod upstream;
 mod util;
+mod watch;
+
+pub use builder::RushEngineBuilder;
+pub use bundle::LOCKFILE;
+pub use cancel::CancellationToken;
+pub use dev::{RateLimitError, TARGETS};
+pub use find::{fuzzy_filter, fuzzy_score};
+pub use install::extract_binary;
+#[cfg(feature = "async")]
+pub use nonblocking::AsyncRushEngine;
+pub use query::{Packages, load_manifest, sort_packages};
+pub use registry::{
+    GitBackend, LocalDirBackend, RegistryBackend, SparseHttpBackend, TarballBackend,
+    backend_for_source,
+};
+#[cfg(feature = "sqlite")]
+pub use sqlite_store::SqliteStore;
+pub use store::{JsonFileStore, MemoryStore, StateStore};
+pub use target::{Arch, Libc, Os, Target};
+pub use transport::{HttpTransport, Transport, TransportResponse, http_client};
+pub use util::verify_checksum;
 
 use crate::models::{
-    CleanResult, ImportCandidate, InstallEvent, InstallResult, PackageManifest, State,
-    TargetDefinition, UninstallResult, UpdateEvent, UpdateResult, VerifyEvent, VerifyResult,
+    AssetPreview, AuditResult, BumpResult, BundleResult, ChangeSet, CheckResult, ChecksumSource,
+    CleanAllResult, CleanResult, Config, DistImportResult, ExportResult, FinderEntry,
+    GitHubRelease, HashChange, HookPoint, ImportCandidate, InitResult, InstallOutcome,
+    InstallResult, KeygenResult, LinkCheckResult, LintResult, MirrorResult, NewPackage,
+    OutdatedPackage, PackageInfo, PackageManifest, Plan, ProjectCheck, ProjectEnv, PublishResult,
+    RefreshResult, RegistryStats, Reporter, RequirementWarning, ResolveRequest, ScheduleResult,
+    ScoreReport, ScoredAsset, SelfUpdateResult, SignResult, State, StateChange, SubscriptionId,
+    TargetDefinition, TrialResult, UninstallResult, UpdateResult, UpstreamResult, VerifyResult,
 };
 use anyhow::{Context, Result};
-use std::fs::{self};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Default URL to fetch the registry from, overridable by env variable
-const DEFAULT_REGISTRY_URL: &str =
+pub(crate) const DEFAULT_REGISTRY_URL: &str =
     "https://github.com/ekourtakis/rush/archive/refs/heads/main.tar.gz";
 
 /// The core engine that handles state and I/O
 pub struct RushEngine {
     pub state: State,
-    pub(crate) state_path: PathBuf, // ~/.local/share/rush/installed.json
+    pub(crate) store: Box<dyn StateStore>, // ~/.local/share/rush/installed.json
+    pub(crate) saved_state: State,         // What the store last saw, for change events
+    pub(crate) subscribers: watch::Subscribers,
     pub(crate) registry_dir: PathBuf, // ~/.local/share/rush/registry/
-    pub(crate) bin_path: PathBuf,   // ~/.local/bin
-    pub(crate) client: reqwest::blocking::Client, // HTTP Client
+    pub(crate) known_hashes_path: PathBuf, // ~/.local/share/rush/known-hashes.json
+    pub(crate) apps_dir: PathBuf,     // ~/.local/share/rush/apps/
+    pub(crate) bin_path: PathBuf,     // ~/.local/share/rush/shims
+    pub(crate) data_dir: PathBuf,     // ~/.local/share
+    pub(crate) config_dir: PathBuf,   // ~/.config/rush
+    pub(crate) cache_dir: PathBuf,    // ~/.cache/rush
+    pub(crate) client: reqwest::blocking::Client, // HTTP Client (APIs)
+    pub(crate) transport: Arc<dyn Transport>, // Downloads (shared with download threads)
     pub(crate) registry_source: String,
+    pub(crate) registry: Box<dyn RegistryBackend>, // Where update/search read from
+    pub(crate) config: Config,
+    pub(crate) target: String, // What installs are for, e.g. "x86_64-linux"
+    pub(crate) offline: bool,
+    pub(crate) cancel: CancellationToken,
 }
 
 impl RushEngine {
     /// Standard constructor
     /// Reads HOME and Env Vars automatically.
     pub fn new() -> Result<Self> {
-        let home = dirs::home_dir().context("No home dir")?;
-        let source =
-            std::env::var("RUSH_REGISTRY_URL").unwrap_or_else(|_| DEFAULT_REGISTRY_URL.to_string());
-        Self::init(home, source)
+        Self::builder().build()
     }
 
-    /// Test constructor: Isolated Root + Default Registry
-    pub fn with_root(root: PathBuf) -> Result<Self> {
-        Self::init(root, DEFAULT_REGISTRY_URL.to_string())
+    /// Configure a custom engine (isolated root, registry, offline, ...)
+    pub fn builder() -> RushEngineBuilder {
+        RushEngineBuilder::default()
     }
 
-    /// Test constructor: Isolated Root + Custom Registry Source
-    pub fn with_root_and_registry(root: PathBuf, registry_source: String) -> Result<Self> {
-        Self::init(root, registry_source)
+    /// Where binaries are installed; the one directory rush needs on `PATH`
+    pub fn bin_dir(&self) -> &Path {
+        &self.bin_path
     }
 
-    /// Shared initialization logic
-    fn init(root: PathBuf, registry_source: String) -> Result<Self> {
-        let state_dir = root.join(".local/share/rush");
-        let bin_path = root.join(".local/bin");
-        let state_path = state_dir.join("installed.json");
-        let registry_dir = state_dir.join("registry");
+    /// Whether `bin_dir()` is on `PATH`, so what rush installs can be run by name
+    pub fn bin_dir_on_path(&self) -> bool {
+        std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir == self.bin_path))
+    }
 
-        fs::create_dir_all(&state_dir)?;
-        fs::create_dir_all(&bin_path)?;
+    /// The target installs are for: the host, unless `RUSH_TARGET` or config.toml's
+    /// `target` says otherwise
+    pub fn target(&self) -> &str {
+        &self.target
+    }
 
-        let state = if state_path.exists() {
-            let content = fs::read_to_string(&state_path)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            State::default()
-        };
+    /// Whether installs are for this machine, so installed binaries can be run here
+    pub fn targets_host(&self) -> bool {
+        self.target == Target::host().to_string()
+    }
 
-        let client = reqwest::blocking::Client::builder()
-            .user_agent(concat!("rush/", env!("CARGO_PKG_VERSION")))
-            .build()?;
-
-        Ok(Self {
-            state,
-            state_path,
-            registry_dir,
-            bin_path,
-            client,
-            registry_source,
-        })
+    /// A handle that cancels this engine's in-flight install or registry update
+    /// (and every later one, until `reset()`), e.g. from a Ctrl-C handler
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
     }
 
-    /// Save state to disk
-    pub(crate) fn save(&self) -> Result<()> {
-        let content = serde_json::to_string_pretty(&self.state)?;
-        fs::write(&self.state_path, content)?;
+    /// Fails if offline mode forbids downloading `url`
+    pub(crate) fn ensure_online(&self, url: &str) -> Result<()> {
+        if self.offline && !url.starts_with("file://") {
+            return Err(crate::Error::Offline {
+                url: url.to_string(),
+            }
+            .into());
+        }
         Ok(())
     }
 
-    /// Download and Install a package.
-    pub fn install_package<F>(
+    /// Save state through the state store, then tell subscribers what changed
+    pub(crate) fn save(&mut self) -> Result<()> {
+        self.store.save(&self.state)?;
+        let changes = watch::diff(&self.saved_state, &self.state);
+        self.saved_state = self.state.clone();
+        self.subscribers.notify(&changes);
+        Ok(())
+    }
+
+    /// Call `callback` for every package installed, removed, or upgraded from now on,
+    /// right after the change is saved
+    pub fn subscribe<F>(&mut self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&StateChange) + Send + Sync + 'static,
+    {
+        self.subscribers.add(Box::new(callback))
+    }
+
+    /// Stop a `subscribe()` callback; false if it wasn't subscribed
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscribers.remove(id)
+    }
+
+    /// Download and Install a package, running the configured install hooks around it.
+    pub fn install_package<R>(
         &mut self,
         name: &str,
         version: &str,
         target: &TargetDefinition,
-        on_event: F,
+        reporter: &mut R,
     ) -> Result<InstallResult>
     where
-        F: FnMut(InstallEvent),
+        R: Reporter,
+    {
+        self.with_install_hooks(name, version, reporter, |engine, reporter| {
+            install::install_package(engine, name, version, target, |e| reporter.progress(e))
+        })
+    }
+
+    /// Helper: Run `install` between the install hooks of `name`, as `install_package` does
+    pub(crate) fn with_install_hooks<R, T, I>(
+        &mut self,
+        name: &str,
+        version: &str,
+        reporter: &mut R,
+        install: I,
+    ) -> Result<T>
+    where
+        R: Reporter,
+        I: FnOnce(&mut Self, &mut R) -> Result<T>,
+    {
+        self.cancel.check()?;
+        hooks::run_hook(self, HookPoint::PreInstall, name, version, |e| {
+            reporter.hook(e)
+        })?;
+        let result = install(self, reporter);
+        let result = self.cancel.settle(result)?;
+        hooks::run_hook(self, HookPoint::PostInstall, name, version, |e| {
+            reporter.hook(e)
+        })?;
+        Ok(result)
+    }
+
+    /// Install the `target_key` target (e.g. "x86_64-linux") of an already-parsed manifest,
+    /// as `install_package` does. The registry is only consulted for its `dependencies`,
+    /// which are installed first when missing and recorded as dependencies. If `name` is
+    /// already installed, nothing is done (beyond recording it as asked for by name) and
+    /// the outcome says so. If it can't be planned (no binary for the target, a clash, a
+    /// dependency that can't be installed) it fails with `rush::Error::PlanConflicts`, or
+    /// `rush::Error::NoCompatibleTarget` when that is the only problem.
+    pub fn install_from_manifest<R>(
+        &mut self,
+        name: &str,
+        manifest: &PackageManifest,
+        target_key: &str,
+        reporter: &mut R,
+    ) -> Result<InstallOutcome>
+    where
+        R: Reporter,
+    {
+        if let Some(installed) = self.state.packages.get_mut(name) {
+            let version = installed.version.clone();
+            // Asked for by name, it no longer only serves as a dependency
+            if std::mem::take(&mut installed.dependency) {
+                self.save()?;
+            }
+            return Ok(InstallOutcome::AlreadyInstalled {
+                package: name.to_string(),
+                version,
+            });
+        }
+        self.replace_from_manifest(name, manifest, target_key, reporter)
+    }
+
+    /// Install `manifest`'s version of `name` as `install_from_manifest` does, replacing
+    /// the installed version if it is another one (`rush install tool@1.0.0` with 2.0.0
+    /// installed). The package keeps its constraint or pin, and is recorded as asked for
+    /// by name.
+    pub fn replace_from_manifest<R>(
+        &mut self,
+        name: &str,
+        manifest: &PackageManifest,
+        target_key: &str,
+        reporter: &mut R,
+    ) -> Result<InstallOutcome>
+    where
+        R: Reporter,
+    {
+        let plan = plan::resolve_manifest(self, name, manifest, target_key);
+        let outcomes =
+            plan::apply(self, &plan, reporter).map_err(|e| plan::conflicts_error(name, e))?;
+        if let Some(installed) = self.state.packages.get_mut(name)
+            && std::mem::take(&mut installed.dependency)
+        {
+            self.save()?;
+        }
+        let outcome = outcomes.into_iter().find(|o| o.package() == name);
+        Ok(outcome.expect("the plan installs the package it was made for"))
+    }
+
+    /// The path of `name`'s binary, to run it without installing it: the installed one if
+    /// it is at `version` (default: the registry's), or else one fetched into the run
+    /// cache. Nothing is recorded as installed, and only Reporter progress is reported.
+    pub fn prepare_run<R>(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        reporter: &mut R,
+    ) -> Result<PathBuf>
+    where
+        R: Reporter,
+    {
+        let result = run::prepare_run(self, name, version, |e| reporter.progress(e));
+        self.cancel.settle(result)
+    }
+
+    /// Fetch the tools listed in the `rush.toml` of the project `start` is in (searching
+    /// upwards) into the project's `.rush/`, and return its bin dir to put on PATH.
+    /// Nothing is recorded as installed.
+    pub fn sync_project<R>(&self, start: &Path, reporter: &mut R) -> Result<ProjectEnv>
+    where
+        R: Reporter,
+    {
+        let result = project::sync_project(self, start, |e| reporter.progress(e));
+        self.cancel.settle(result)
+    }
+
+    /// Whether the tools in the `rush.toml` of the project `start` is in are all fetched at
+    /// their versions and on the project's PATH, without fetching or changing anything
+    pub fn check_project(&self, start: &Path) -> Result<ProjectCheck> {
+        project::check_project(self, start)
+    }
+
+    /// What is known about `name`: its installed version, binaries, and provenance, and
+    /// the registry's manifest. `None` if it is neither installed nor in the registry.
+    pub fn package_info(&self, name: &str) -> Option<PackageInfo> {
+        let installed = self.state.packages.get(name).cloned();
+        let available = self.find_package(name);
+        if installed.is_none() && available.is_none() {
+            return None;
+        }
+        Some(PackageInfo {
+            name: name.to_string(),
+            installed,
+            available,
+        })
+    }
+
+    /// Look up every installed package in the OSV vulnerability database (osv.dev).
+    /// Packages whose manifest has no `[osv]` table can't be looked up and are listed
+    /// as unchecked.
+    pub fn audit(&self) -> Result<AuditResult> {
+        audit::audit(self)
+    }
+
+    /// Check the registry's hashes for `name` against the checksum files its releases
+    /// published (each target's `checksum_url`). A target without one is reported as such.
+    pub fn verify_upstream(&self, name: &str) -> Result<UpstreamResult> {
+        upstream::verify_upstream(self, name)
+    }
+
+    /// Have the system's scheduler (a systemd user timer, or a launchd agent on macOS) run
+    /// `rush update && rush outdated --notify` daily, with this rush executable
+    pub fn install_schedule(&self) -> Result<ScheduleResult> {
+        schedule::install_schedule(self)
+    }
+
+    /// Undo `install_schedule()`
+    pub fn remove_schedule(&self) -> Result<ScheduleResult> {
+        schedule::remove_schedule(self)
+    }
+
+    /// Add `line` (the shell code that puts `bin_dir()` on `PATH`) to `rc_file`, a shell
+    /// startup file relative to the home directory, unless it is already there
+    pub fn init_shell(&self, rc_file: &Path, line: &str) -> Result<InitResult> {
+        init::init_shell(self, rc_file, line)
+    }
+
+    /// Look up the latest release of rush itself and, unless `check_only`, replace the
+    /// running executable with it when it is newer. The download must match a checksum
+    /// the release publishes.
+    pub fn self_update<R>(&self, check_only: bool, reporter: &mut R) -> Result<SelfUpdateResult>
+    where
+        R: Reporter,
+    {
+        let result = self_update::self_update(self, check_only, |e| reporter.progress(e));
+        self.cancel.settle(result)
+    }
+
+    /// Sync the registry, then copy it into `dir` with every artifact for `targets`, so that
+    /// `dir` can serve as a local registry installs read artifacts from instead of the
+    /// network. Re-running only downloads what changed; artifacts that can't be fetched
+    /// are listed in the result rather than failing the mirror.
+    pub fn mirror<R>(
+        &self,
+        dir: &Path,
+        targets: &[String],
+        reporter: &mut R,
+    ) -> Result<MirrorResult>
+    where
+        R: Reporter,
+    {
+        mirror::mirror(self, dir, targets, |e| reporter.progress(e))
+    }
+
+    /// Pack what is installed into the bundle `out` (a `.tar.gz`): each package's exact
+    /// artifact for `target`, a manifest pinned to it, and a `rush.lock` listing them.
+    /// Extracted, a bundle works as a registry (`RUSH_REGISTRY_URL`) that reinstalls the
+    /// same binaries with no network. Packages whose artifact isn't known are skipped.
+    pub fn export_bundle<R>(
+        &self,
+        out: &Path,
+        target: &str,
+        reporter: &mut R,
+    ) -> Result<BundleResult>
+    where
+        R: Reporter,
+    {
+        bundle::export_bundle(self, out, target, |e| reporter.progress(e))
+    }
+
+    /// The installed packages as a Nix expression for `target`: one derivation each,
+    /// fetching the artifact rush installed by its recorded hash. Packages whose artifact
+    /// isn't known, or that Nix can't unpack, are left out.
+    pub fn export_nix(&self, target: &str) -> ExportResult {
+        nix::export_nix(self, target)
+    }
+
+    /// The installed packages as a Dockerfile for `target`, which must be Linux: each
+    /// artifact is fetched with `ADD --checksum` and unpacked in a busybox stage, and the
+    /// binaries and data files are copied into `/usr/local` of the final image.
+    pub fn export_dockerfile(&self, target: &str) -> Result<ExportResult> {
+        dockerfile::export_dockerfile(self, target)
+    }
+
+    /// Install a bundle from `export_bundle` without the network: its artifacts are checked
+    /// against its `rush.lock` and moved into the cache, then each locked package is
+    /// installed at its locked version. Like `apply`, refuses the whole bundle if any of it
+    /// was made for a target other than `target`.
+    pub fn import_bundle<R>(
+        &mut self,
+        path: &Path,
+        target: &str,
+        reporter: &mut R,
+    ) -> Result<Vec<InstallOutcome>>
+    where
+        R: Reporter,
+    {
+        bundle::import_bundle(self, path, target, reporter)
+    }
+
+    /// Smoke-test an installed package by running its manifest's `check` in a sandbox with
+    /// no network and a scratch working dir. `None` if the manifest has no check; an error
+    /// (`rush::Error::SandboxUnavailable`) rather than an unconfined run where there is no
+    /// sandbox.
+    pub fn check_package(
+        &self,
+        name: &str,
+        manifest: &PackageManifest,
+    ) -> Result<Option<CheckResult>> {
+        check::check_package(self, name, manifest)
+    }
+
+    /// The manifest's `requires` this system doesn't seem to meet (Linux only), to warn
+    /// about before installing `name`
+    pub fn unmet_requirements(
+        &self,
+        name: &str,
+        manifest: &PackageManifest,
+    ) -> Vec<RequirementWarning> {
+        requires::unmet_requirements(name, manifest)
+    }
+
+    /// Libraries the installed binaries of `name` need that `ldd` can't resolve (Linux
+    /// only, and empty where `ldd` can't be run in the sandbox)
+    pub fn missing_libraries(&self, name: &str) -> Vec<RequirementWarning> {
+        requires::missing_libraries(self, name)
+    }
+
+    /// What `install_package` would download, write, and record, without doing it
+    pub fn preview_install(
+        &self,
+        name: &str,
+        version: &str,
+        target: &TargetDefinition,
+    ) -> ChangeSet {
+        preview::preview_install(self, name, version, target)
+    }
+
+    /// What `install_from_manifest` would do, without doing it
+    pub fn preview_install_from_manifest(
+        &self,
+        name: &str,
+        manifest: &PackageManifest,
+        target_key: &str,
+    ) -> Result<ChangeSet> {
+        if self.state.packages.contains_key(name) {
+            return Ok(ChangeSet::default());
+        }
+        self.preview_replace_from_manifest(name, manifest, target_key)
+    }
+
+    /// What `replace_from_manifest` would do, without doing it
+    pub fn preview_replace_from_manifest(
+        &self,
+        name: &str,
+        manifest: &PackageManifest,
+        target_key: &str,
+    ) -> Result<ChangeSet> {
+        let plan = plan::resolve_manifest(self, name, manifest, target_key);
+        preview::preview_apply(self, &plan).map_err(|e| plan::conflicts_error(name, e))
+    }
+
+    /// Work out what `request` would install or upgrade (versions, artifacts, download
+    /// sizes, conflicts) without touching the bin dir
+    pub fn resolve(&self, request: &ResolveRequest) -> Plan {
+        plan::resolve(self, request)
+    }
+
+    /// Carry out a plan from `resolve()`, one `install_package` per step, returning an
+    /// outcome for every step and skipped package.
+    /// Plans with conflicts fail with `rush::Error::PlanConflicts` before anything is done.
+    pub fn apply<R>(&mut self, plan: &Plan, reporter: &mut R) -> Result<Vec<InstallOutcome>>
+    where
+        R: Reporter,
+    {
+        plan::apply(self, plan, reporter)
+    }
+
+    /// What `apply` (or `apply_upgrade`) would do with `plan`, without doing it
+    pub fn preview_apply(&self, plan: &Plan) -> Result<ChangeSet> {
+        preview::preview_apply(self, plan)
+    }
+
+    /// Plan upgrading every installed package to the registry's version for `target`.
+    /// Packages that can't be upgraded there are skipped, with the reason.
+    pub fn plan_upgrade(&self, target: &str) -> Plan {
+        plan::plan_upgrade(self, target)
+    }
+
+    /// Carry out a plan from `plan_upgrade()`; the reporter's `step` sees each upgrade start
+    pub fn apply_upgrade<R>(&mut self, plan: &Plan, reporter: &mut R) -> Result<Vec<InstallOutcome>>
+    where
+        R: Reporter,
+    {
+        plan::apply(self, plan, reporter)
+    }
+
+    /// The installed packages that list `name` among their dependencies (per the
+    /// registry), which uninstalling it would leave without it
+    pub fn dependents(&self, name: &str) -> Vec<String> {
+        dependencies::dependents(self, name)
+    }
+
+    /// Uninstall a package, running the configured uninstall hooks around it.
+    pub fn uninstall_package<R>(
+        &mut self,
+        name: &str,
+        reporter: &mut R,
+    ) -> Result<Option<UninstallResult>>
+    where
+        R: Reporter,
     {
-        install::install_package(self, name, version, target, on_event)
+        let Some(version) = self.state.packages.get(name).map(|p| p.version.clone()) else {
+            return Ok(None);
+        };
+        hooks::run_hook(self, HookPoint::PreUninstall, name, &version, |e| {
+            reporter.hook(e)
+        })?;
+        let result = uninstall::uninstall_package(self, name)?;
+        hooks::run_hook(self, HookPoint::PostUninstall, name, &version, |e| {
+            reporter.hook(e)
+        })?;
+        Ok(result)
+    }
+
+    /// Only let upgrades move installed `name` to versions `constraint` allows (e.g. "~20"
+    /// or ">=1.2, <1.5"), picking the newest such release in the registry; this replaces a
+    /// `hold_package` pin. Returns false if `name` isn't installed; an invalid constraint
+    /// is an error.
+    pub fn pin_package(&mut self, name: &str, constraint: &str) -> Result<bool> {
+        constraint::pin(self, name, constraint)
+    }
+
+    /// Hold installed `name` at its installed version: upgrades skip it until
+    /// `unpin_package`. Returns false if `name` isn't installed.
+    pub fn hold_package(&mut self, name: &str) -> Result<bool> {
+        constraint::hold(self, name)
     }
 
-    /// Uninstall a package.
-    pub fn uninstall_package(&mut self, name: &str) -> Result<Option<UninstallResult>> {
-        uninstall::uninstall_package(self, name)
+    /// Drop installed `name`'s pin or version constraint, so upgrades move it to the
+    /// registry's version again. Returns false if `name` isn't installed.
+    pub fn unpin_package(&mut self, name: &str) -> Result<bool> {
+        constraint::unpin(self, name)
+    }
+
+    /// The manual pages installed `name` put in the data dir (from its `files`); None if
+    /// it isn't installed
+    pub fn man_pages(&self, name: &str) -> Option<Vec<PathBuf>> {
+        datafiles::man_pages(self, name)
+    }
+
+    /// What `uninstall_package` would delete and record; None if `name` isn't installed
+    pub fn preview_uninstall(&self, name: &str) -> Option<ChangeSet> {
+        preview::preview_uninstall(self, name)
     }
 
     /// Download the registry from the internet OR copy it from a local directory
-    pub fn update_registry<F>(&self, on_event: F) -> Result<UpdateResult>
+    pub fn update_registry<R>(&self, reporter: &mut R) -> Result<UpdateResult>
     where
-        F: FnMut(UpdateEvent),
+        R: Reporter,
     {
-        update::update_registry(self, on_event)
+        self.cancel.check()?;
+        let result = update::update_registry(self, |e| reporter.progress(e));
+        self.cancel.settle(result)
+    }
+
+    /// Trust the hashes the registry now lists for `packages`, after `update_registry`
+    /// reported them changed. Returns the pins that were replaced.
+    pub fn accept_hash_changes(&self, packages: &[String]) -> Result<Vec<HashChange>> {
+        let mut accepted = Vec::new();
+        for name in packages {
+            let manifest = self
+                .find_package(name)
+                .with_context(|| format!("Package '{}' not found", name))?;
+            accepted.extend(pins::accept(self, name, &manifest)?);
+        }
+        Ok(accepted)
     }
 
     /// Look up a specific package file (e.g. .../registry/packages/f/fzf.toml)
@@ -118,38 +614,293 @@ impl RushEngine {
         query::find_package(self, name)
     }
 
+    /// Look up a package as of `version`, from its current release or its `history`;
+    /// the latest when `version` is None
+    pub fn find_package_version(
+        &self,
+        name: &str,
+        version: Option<&str>,
+    ) -> Option<PackageManifest> {
+        query::find_package_version(self, name, version)
+    }
+
+    /// Installed packages whose registry version differs from the installed one
+    pub fn outdated(&self) -> Vec<OutdatedPackage> {
+        query::outdated(self)
+    }
+
     /// Scan the folder structure to list all available packages
     pub fn list_available_packages(&self) -> Vec<(String, PackageManifest)> {
         query::list_available_packages(self)
     }
 
+    /// The registry's packages with a build for the target, for `rush find` to filter
+    /// with `fuzzy_filter`
+    pub fn finder_entries(&self) -> Vec<FinderEntry> {
+        find::finder_entries(self)
+    }
+
+    /// Every package name in the registry, sorted, without parsing any manifests
+    pub fn package_names(&self) -> Vec<String> {
+        query::package_names(self)
+    }
+
+    /// Lazily iterate the registry's packages in name order; each manifest is parsed only
+    /// when the iterator reaches it, so `take(n)` or `find(..)` stops early
+    pub fn packages(&self) -> Packages<'_> {
+        query::packages(self)
+    }
+
+    /// Package and target counts for the synced registry, and what its manifests lack
+    pub fn registry_stats(&self) -> RegistryStats {
+        stats::registry_stats(self)
+    }
+
+    /// What `update_registry` would fetch and replace, without doing it
+    pub fn preview_update_registry(&self) -> ChangeSet {
+        preview::preview_update_registry(self)
+    }
+
     /// Clean up old temorary files from atomic installs
     pub fn clean_trash(&self) -> Result<CleanResult> {
         clean::clean_trash(self)
     }
 
-    /// Developer Tool: Create/Update a local package manifest
-    pub fn add_package_manual<F>(
+    /// The temporary files `clean_trash` would delete
+    pub fn preview_clean(&self) -> Result<ChangeSet> {
+        preview::preview_clean(self)
+    }
+
+    /// Remove everything rush left behind that nothing uses: temp files, orphaned cache
+    /// entries, replaced AppImage versions, and the records of packages whose files are gone
+    pub fn clean_all(&mut self) -> Result<CleanAllResult> {
+        clean::clean_all(self)
+    }
+
+    /// What `clean_all` would remove, with sizes
+    pub fn find_leftovers(&self) -> Result<CleanAllResult> {
+        clean::find_leftovers(self)
+    }
+
+    /// Clean up temporary files left by this process only (e.g. after an interrupt)
+    pub fn clean_own_trash(&self) -> Result<CleanResult> {
+        clean::clean_own_trash(self)
+    }
+
+    /// Developer Tool: Turn a URL or local artifact path into a manifest URL
+    pub fn resolve_artifact_location(
+        &self,
+        name: &str,
+        version: &str,
+        location: &str,
+        copy: bool,
+    ) -> Result<String> {
+        dev::resolve_artifact_location(self, name, version, location, copy)
+    }
+
+    /// Developer Tool: Create/Update a local package manifest, returning the binary name used
+    pub fn add_package_manual<R>(
         &self,
         name: String,
         version: String,
         target_arch: String,
         url: String,
         bin_name: Option<String>,
-        on_event: F,
-    ) -> Result<()>
+        reporter: &mut R,
+    ) -> Result<String>
     where
-        F: FnMut(InstallEvent),
+        R: Reporter,
     {
-        dev::add_package_manual(self, name, version, target_arch, url, bin_name, on_event)
+        dev::add_package_manual(self, name, version, target_arch, url, bin_name, |e| {
+            reporter.progress(e)
+        })
+    }
+
+    /// Developer Tool: Add an asset chosen in the import wizard, preferring upstream checksums
+    pub fn add_import_asset<R>(
+        &self,
+        name: String,
+        version: String,
+        target_arch: String,
+        scored: &ScoredAsset,
+        verify: bool,
+        reporter: &mut R,
+    ) -> Result<ChecksumSource>
+    where
+        R: Reporter,
+    {
+        dev::add_import_asset(self, name, version, target_arch, scored, verify, |e| {
+            reporter.progress(e)
+        })
+    }
+
+    /// Developer Tool: Download an import asset and list its contents, suggesting the binary
+    pub fn preview_import_asset<R>(
+        &self,
+        package_name: &str,
+        scored: &ScoredAsset,
+        reporter: &mut R,
+    ) -> Result<AssetPreview>
+    where
+        R: Reporter,
+    {
+        dev::preview_import_asset(self, package_name, scored, |e| reporter.progress(e))
+    }
+
+    /// Developer Tool: Write a manifest target whose hash is already known
+    pub fn add_package_hashed(
+        &self,
+        name: &str,
+        version: &str,
+        target_arch: &str,
+        url: &str,
+        bin_name: String,
+        sha256: &str,
+    ) -> Result<()> {
+        dev::write_package_manifest(
+            &self.registry_source,
+            name,
+            version,
+            target_arch,
+            url,
+            Some(bin_name),
+            sha256,
+            None,
+        )
     }
 
-    /// Developer Tool: Interactive Import wizard from GitHub
+    /// Developer Tool: Interactive Import wizard from GitHub (latest release unless `tag` is given)
     pub fn fetch_github_import_candidates(
         &self,
         repo: &str,
+        tag: Option<&str>,
+        targets: &[String],
     ) -> Result<(String, String, Vec<ImportCandidate>)> {
-        dev::fetch_github_import_candidates(self, repo)
+        dev::fetch_github_import_candidates(self, repo, tag, targets)
+    }
+
+    /// Developer Tool: Import a crate's cargo-dist release using its `dist-manifest.json`
+    pub fn import_cargo_dist(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+        targets: &[String],
+    ) -> Result<DistImportResult> {
+        dev::import_cargo_dist(self, crate_name, version, targets)
+    }
+
+    /// Developer Tool: Record a package's upstream (`dev import` syntax) in its manifest
+    pub fn set_package_source(&self, name: &str, source: &str) -> Result<()> {
+        dev::set_package_source(self, name, source)
+    }
+
+    /// Developer Tool: Record the upstream checksum file a target's hash was checked against
+    pub fn set_checksum_url(
+        &self,
+        name: &str,
+        target_arch: &str,
+        checksum_url: &str,
+    ) -> Result<()> {
+        dev::set_checksum_url(self, name, target_arch, checksum_url)
+    }
+
+    /// Developer Tool: Move a package to its newest upstream release, re-hashing every target.
+    /// An older release than the manifest's is refused unless `force` is set.
+    pub fn bump_package<R>(&self, name: &str, force: bool, reporter: &mut R) -> Result<BumpResult>
+    where
+        R: Reporter,
+    {
+        dev::bump_package(self, name, force, |e| reporter.bump(e))
+    }
+
+    /// Developer Tool: Write a complete manifest for a new package, hashing every target
+    pub fn create_package<R>(&self, spec: &NewPackage, reporter: &mut R) -> Result<PathBuf>
+    where
+        R: Reporter,
+    {
+        dev::create_package(self, spec, |e| reporter.bump(e))
+    }
+
+    /// Developer Tool: Bump every package that records its upstream, `jobs` at a time.
+    /// A package that takes longer than `timeout` is given up on and reported as failed.
+    pub fn refresh_all<R>(
+        &self,
+        jobs: usize,
+        timeout: Duration,
+        reporter: &mut R,
+    ) -> Result<RefreshResult>
+    where
+        R: Reporter,
+    {
+        dev::refresh_all(self, jobs, timeout, |e| reporter.refresh(e))
+    }
+
+    /// Developer Tool: Push the changed manifests to a branch and open a registry pull request
+    pub fn publish(&self, remote: &str, base: &str, branch: Option<&str>) -> Result<PublishResult> {
+        dev::publish(self, remote, base, branch)
+    }
+
+    /// Developer Tool: Serve the local registry (and artifacts) over HTTP on localhost.
+    /// Blocks until the process is interrupted.
+    pub fn serve_registry<R>(
+        &self,
+        port: u16,
+        artifacts_dir: Option<&Path>,
+        reporter: &mut R,
+    ) -> Result<()>
+    where
+        R: Reporter,
+    {
+        dev::serve_registry(self, port, artifacts_dir, |e| reporter.serve(e))
+    }
+
+    /// Developer Tool: Install a local registry package (or a `.toml` manifest path) into
+    /// a throwaway root and run its `check`, to try a manifest before publishing it
+    pub fn trial_install<R>(&self, package: &str, reporter: &mut R) -> Result<TrialResult>
+    where
+        R: Reporter,
+    {
+        dev::trial_install(self, package, reporter)
+    }
+
+    /// Developer Tool: Create a maintainer signing key (`force` replaces an existing one)
+    pub fn generate_signing_key(&self, force: bool) -> Result<KeygenResult> {
+        dev::generate_signing_key(self, force)
+    }
+
+    /// Developer Tool: Write a signed index of every manifest in the local registry
+    pub fn sign_registry(&self, key_path: Option<&Path>) -> Result<SignResult> {
+        dev::sign_registry(self, key_path)
+    }
+
+    /// Developer Tool: The `limit` most recent GitHub releases of `repo`, newest first
+    pub fn list_github_releases(
+        &self,
+        repo: &str,
+        limit: usize,
+        include_prereleases: bool,
+    ) -> Result<Vec<GitHubRelease>> {
+        dev::list_github_releases(self, repo, limit, include_prereleases)
+    }
+
+    /// Developer Tool: Build import candidates from a release returned by `list_github_releases`
+    pub fn import_candidates_from_release(
+        &self,
+        repo: &str,
+        release: &GitHubRelease,
+        targets: &[String],
+    ) -> Result<(String, String, Vec<ImportCandidate>)> {
+        dev::import_candidates_from_release(self, repo, release, targets)
+    }
+
+    /// Developer Tool: Every asset of a release scored per target, with the reasons
+    pub fn explain_scores(
+        &self,
+        repo: &str,
+        tag: Option<&str>,
+        targets: &[String],
+    ) -> Result<ScoreReport> {
+        dev::explain_scores(self, repo, tag, targets)
     }
 
     /// Helper: verify RUSH_REGISTRY_URL is a valid local path for writing.
@@ -157,12 +908,29 @@ impl RushEngine {
         dev::ensure_local_registry(&self.registry_source)
     }
 
-    /// Developer Tool: Verify integrity of all packages in the registry
-    pub fn verify_registry<F>(&self, on_event: F) -> Result<VerifyResult>
+    /// Developer Tool: Check every manifest in the local registry for mistakes
+    pub fn lint_registry(&self) -> Result<LintResult> {
+        dev::lint_registry(self)
+    }
+
+    /// Developer Tool: HEAD every artifact URL in the registry, `jobs` requests at a time
+    pub fn check_urls<R>(&self, jobs: usize, reporter: &mut R) -> Result<LinkCheckResult>
+    where
+        R: Reporter,
+    {
+        dev::check_urls(self, jobs, |e| reporter.link_check(e))
+    }
+
+    /// Developer Tool: Verify integrity of all (or `sample` random) targets in the registry
+    pub fn verify_registry<R>(
+        &self,
+        sample: Option<usize>,
+        reporter: &mut R,
+    ) -> Result<VerifyResult>
     where
-        F: FnMut(VerifyEvent),
+        R: Reporter,
     {
-        dev::verify_registry(self, on_event)
+        dev::verify_registry(self, sample, |e| reporter.verify(e))
     }
 }
 
@@ -170,6 +938,7 @@ impl RushEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Error;
     use crate::models::InstalledPackage;
     use tempfile::tempdir;
 
@@ -177,7 +946,7 @@ mod tests {
     fn test_engine_initialization() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let _engine = RushEngine::with_root(root.clone()).unwrap();
+        let _engine = RushEngine::builder().root(root.clone()).build().unwrap();
         assert!(root.join(".local/share/rush").exists());
     }
 
@@ -187,18 +956,63 @@ mod tests {
         let root = temp_dir.path().to_path_buf();
 
         {
-            let mut engine = RushEngine::with_root(root.clone()).unwrap();
+            let mut engine = RushEngine::builder().root(root.clone()).build().unwrap();
             engine.state.packages.insert(
                 "fake-pkg".to_string(),
                 InstalledPackage {
                     version: "1.0.0".to_string(),
                     binaries: vec!["fake-bin".to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
                 },
             );
             engine.save().unwrap();
         }
 
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
         assert!(engine.state.packages.contains_key("fake-pkg"));
     }
+
+    #[test]
+    fn test_install_from_manifest_skips_registry() {
+        use crate::test_utils::{MockEnvironment, current_target};
+
+        let mock = MockEnvironment::new();
+        mock.add_package("local", "1.0.0", "local-bin");
+        let manifest = load_manifest(&mock.registry_source.join("packages/l/local.toml")).unwrap();
+
+        // Never synced: the registry copy is empty
+        let mut engine = mock.engine().unwrap();
+        assert!(engine.find_package("local").is_none());
+
+        let err = engine
+            .install_from_manifest("local", &manifest, "sparc-solaris", &mut ())
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::NoCompatibleTarget {
+                package: "local".to_string(),
+                target: "sparc-solaris".to_string(),
+            })
+        );
+
+        let outcome = engine
+            .install_from_manifest("local", &manifest, &current_target(), &mut ())
+            .unwrap();
+        assert!(
+            matches!(outcome, InstallOutcome::Installed(result) if result.path == engine.bin_path.join("local-bin"))
+        );
+        assert_eq!(engine.state.packages["local"].version, "1.0.0");
+
+        let again = engine
+            .install_from_manifest("local", &manifest, &current_target(), &mut ())
+            .unwrap();
+        assert!(matches!(
+            again,
+            InstallOutcome::AlreadyInstalled { package, version } if package == "local" && version == "1.0.0"
+        ));
+    }
 }
@@ -0,0 +1,232 @@
+//! AppImage artifacts: one self-contained executable rather than an archive to unpack.
+//!
+//! The image is kept under `~/.local/share/rush/apps/<package>/<version>/`, and the bin
+//! dir gets a shim named after the target's `bin` that runs it. With `extract = true` the
+//! image is unpacked there once (in the sandbox, since that means running it) and the shim
+//! runs its `AppRun` directly, which skips mounting the image on every start.
+
+use crate::core::{CancellationToken, RushEngine, clean, hooks, sandbox};
+use crate::models::TargetDefinition;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where installed images live, under the state dir
+pub(crate) const APPS_DIR: &str = "apps";
+
+/// How long unpacking an image may take
+const EXTRACT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Store the image in `content` as `version` of `name`, point the `bin` shim at it, and
+/// drop any other version. Returns the shim's path.
+pub(crate) fn install(
+    engine: &RushEngine,
+    name: &str,
+    version: &str,
+    target: &TargetDefinition,
+    content: &[u8],
+) -> Result<PathBuf> {
+    let app_dir = engine.apps_dir.join(name);
+    let version_dir = app_dir.join(version);
+    if version_dir.exists() {
+        fs::remove_dir_all(&version_dir)?;
+    }
+    fs::create_dir_all(&version_dir)?;
+
+    let image = version_dir.join(format!("{}.AppImage", target.main_bin()));
+    fs::write(&image, content)?;
+    make_executable(&image)?;
+
+    let entry = if target.extract {
+        // A faster start is a nicety; the image itself still works if this fails
+        match extract(&image, &version_dir) {
+            Ok(app_run) => {
+                fs::remove_file(&image)?;
+                app_run
+            }
+            Err(e) => {
+                tracing::warn!(package = name, error = %format!("{:#}", e), "running the AppImage unextracted");
+                image
+            }
+        }
+    } else {
+        image
+    };
+
+    let shim = write_shim(&engine.bin_path, target.main_bin(), &entry, &engine.cancel)?;
+
+    // Only the version the shim points at is kept
+    for old in fs::read_dir(&app_dir)?.flatten() {
+        if old.file_name() != version {
+            fs::remove_dir_all(old.path())?;
+        }
+    }
+    Ok(shim)
+}
+
+/// Delete every stored version of `name`, if it was installed from an AppImage
+pub(crate) fn remove(engine: &RushEngine, name: &str) -> Result<()> {
+    let app_dir = engine.apps_dir.join(name);
+    if app_dir.exists() {
+        fs::remove_dir_all(app_dir)?;
+    }
+    Ok(())
+}
+
+/// Helper: Unpack `image` into `dir` with its own `--appimage-extract`, returning the
+/// `AppRun` to start
+fn extract(image: &Path, dir: &Path) -> Result<PathBuf> {
+    let output = sandbox::run_in(image, &["--appimage-extract"], dir, EXTRACT_TIMEOUT)?;
+    let app_run = dir.join("squashfs-root").join("AppRun");
+
+    if output.exit_code != Some(0) || !app_run.exists() {
+        anyhow::bail!(
+            "--appimage-extract failed ({}): {}",
+            output
+                .exit_code
+                .map_or("killed".to_string(), |c| format!("exit code {}", c)),
+            output.stderr
+        );
+    }
+    Ok(app_run)
+}
+
+/// Helper: Atomically write a script named `bin` into `bin_dir` that runs `entry`.
+/// Like an extracted binary, it is only moved into place if `cancel` hasn't fired.
+fn write_shim(
+    bin_dir: &Path,
+    bin: &str,
+    entry: &Path,
+    cancel: &CancellationToken,
+) -> Result<PathBuf> {
+    let entry = entry.to_str().context("AppImage path is not valid UTF-8")?;
+    let dest = bin_dir.join(bin);
+
+    let mut temp_file = tempfile::Builder::new()
+        .prefix(&clean::own_trash_prefix())
+        .tempfile_in(bin_dir)?;
+    write!(
+        temp_file,
+        "#!/bin/sh\n# Installed by rush\nexec {} \"$@\"\n",
+        hooks::shell_quote(entry)
+    )?;
+    make_executable(temp_file.path())?;
+
+    cancel.check()?;
+    temp_file.persist(&dest)?;
+    Ok(dest)
+}
+
+/// Helper: chmod 755
+pub(crate) fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArtifactFormat;
+    use crate::test_utils::MockEnvironment;
+    use sha2::{Digest, Sha256};
+    use std::process::Command;
+
+    /// Stands in for an AppImage: runs as itself, and `--appimage-extract` unpacks an
+    /// `AppRun` the way the real runtime does
+    const FAKE_IMAGE: &str = r#"#!/bin/sh
+if [ "$1" = "--appimage-extract" ]; then
+    mkdir -p squashfs-root
+    printf '#!/bin/sh\necho "extracted $*"\n' > squashfs-root/AppRun
+    chmod +x squashfs-root/AppRun
+    exit 0
+fi
+echo "image $*"
+"#;
+
+    fn image_target(extract: bool) -> TargetDefinition {
+        TargetDefinition {
+            url: "https://example.com/tool.AppImage".to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: hex::encode(Sha256::digest(FAKE_IMAGE)),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::AppImage,
+            extract,
+            files: Vec::new(),
+        }
+    }
+
+    fn run(shim: &Path) -> String {
+        let output = Command::new(shim).arg("hi").output().unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn test_appimage_installs_behind_shim() {
+        let mock = MockEnvironment::new();
+        let mut engine = mock.engine().unwrap();
+        let target = image_target(false);
+
+        let result = crate::core::install::install_from_bytes(
+            &mut engine,
+            "tool",
+            "1.0.0",
+            &target,
+            FAKE_IMAGE.as_bytes(),
+            None,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(result.path, engine.bin_path.join("tool"));
+        assert!(engine.apps_dir.join("tool/1.0.0/tool.AppImage").is_file());
+        assert_eq!(run(&result.path), "image hi");
+
+        // A new version replaces the old one
+        crate::core::install::install_from_bytes(
+            &mut engine,
+            "tool",
+            "2.0.0",
+            &target,
+            FAKE_IMAGE.as_bytes(),
+            None,
+            |_| {},
+        )
+        .unwrap();
+        assert!(!engine.apps_dir.join("tool/1.0.0").exists());
+        assert_eq!(run(&result.path), "image hi");
+
+        engine.uninstall_package("tool", &mut ()).unwrap();
+        assert!(!engine.bin_path.join("tool").exists());
+        assert!(!engine.apps_dir.join("tool").exists());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_appimage_extracts_for_faster_start() {
+        let mock = MockEnvironment::new();
+        let mut engine = mock.engine().unwrap();
+
+        let result = crate::core::install::install_from_bytes(
+            &mut engine,
+            "tool",
+            "1.0.0",
+            &image_target(true),
+            FAKE_IMAGE.as_bytes(),
+            None,
+            |_| {},
+        )
+        .unwrap();
+        let version_dir = engine.apps_dir.join("tool/1.0.0");
+        assert!(version_dir.join("squashfs-root/AppRun").is_file());
+        assert!(!version_dir.join("tool.AppImage").exists());
+        assert_eq!(run(&result.path), "extracted hi");
+    }
+}
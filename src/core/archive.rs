@@ -0,0 +1,333 @@
+use crate::models::ArchiveEntry;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::Path;
+use tar::Archive;
+
+#[cfg(feature = "distro-packages")]
+mod distro;
+mod zip;
+
+/// Directories whose contents are never the main binary
+const NON_BINARY_DIRS: &[&str] = &[
+    "doc",
+    "docs",
+    "man",
+    "completions",
+    "complete",
+    "autocomplete",
+];
+
+/// How a .deb (an `ar` archive) starts
+const AR_MAGIC: &[u8] = b"!<arch>\n";
+/// How an .rpm (its "lead") starts
+const RPM_MAGIC: &[u8] = &[0xed, 0xab, 0xee, 0xdb];
+
+/// How the compressed streams a tarball (or a distro package's payload) may come in start
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+/// Where a POSIX tar header says "ustar", for uncompressed tarballs
+const USTAR_OFFSET: usize = 257;
+
+/// A regular file's path and permission bits, as `visit_files` hands it over
+pub(crate) type Visitor<'a, T> = dyn FnMut(&Path, u32, &mut dyn Read) -> Result<Option<T>> + 'a;
+
+/// Lists the regular files in an artifact
+pub fn list_entries(content: &[u8]) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    visit_files::<()>(content, &mut |path, mode, reader| {
+        entries.push(ArchiveEntry {
+            path: path.to_string_lossy().into_owned(),
+            size: std::io::copy(reader, &mut std::io::sink())?,
+            executable: mode & 0o111 != 0,
+        });
+        Ok(None)
+    })?;
+    Ok(entries)
+}
+
+/// Calls `visit` with each regular file in an artifact, in order, until it returns Some.
+/// Artifacts are tarballs (plain, or gzip, xz, zstd or bzip2 compressed, told apart by
+/// their first bytes) or .zip archives; with the `distro-packages` feature, also .deb and
+/// .rpm packages, whose payload is read the same way.
+/// An archive inside the artifact (a tarball wrapped in a zip, or the other way round) is
+/// unwrapped one level: its files are visited in its place, as `<inner archive>/<path>`.
+pub(crate) fn visit_files<T>(content: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    visit_container(content, &mut |path, mode, reader| {
+        if !is_archive_name(path) {
+            return visit(path, mode, reader);
+        }
+        let mut nested = Vec::new();
+        reader.read_to_end(&mut nested)?;
+        tracing::debug!(path = %path.display(), "unwrapping nested archive");
+        visit_container(&nested, &mut |inner, mode, reader| {
+            visit(&path.join(inner), mode, reader)
+        })
+        .with_context(|| format!("Failed to read nested archive {}", path.display()))
+    })
+}
+
+/// Helper: `visit_files` for one archive, without looking inside the files in it
+fn visit_container<T>(content: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    let package = if content.starts_with(AR_MAGIC) {
+        Some("deb")
+    } else if content.starts_with(RPM_MAGIC) {
+        Some("rpm")
+    } else {
+        None
+    };
+
+    match package {
+        None if content.starts_with(zip::ZIP_MAGIC) => zip::visit_zip(content, visit),
+        None => visit_tar(tar_stream(content)?, visit),
+        #[cfg(feature = "distro-packages")]
+        Some("deb") => distro::visit_deb(content, visit),
+        #[cfg(feature = "distro-packages")]
+        Some(_) => distro::visit_rpm(content, visit),
+        #[cfg(not(feature = "distro-packages"))]
+        Some(kind) => anyhow::bail!(
+            "This is a .{} package, and rush was built without .deb/.rpm support \
+             (the `distro-packages` feature)",
+            kind
+        ),
+    }
+}
+
+/// Helper: Whether a file in an artifact is itself an archive to unwrap
+pub(crate) fn is_archive_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+    [
+        ".tar", ".tar.gz", ".tgz", ".tar.xz", ".txz", ".tar.zst", ".tzst", ".tar.bz2", ".tbz2",
+        ".zip",
+    ]
+    .iter()
+    .any(|ext| name.ends_with(ext))
+}
+
+/// Helper: The tar stream in a tarball, decompressed if it is
+fn tar_stream(content: &[u8]) -> Result<Box<dyn Read + '_>> {
+    let compressed = [GZIP_MAGIC, XZ_MAGIC, ZSTD_MAGIC, BZIP2_MAGIC]
+        .iter()
+        .any(|magic| content.starts_with(magic));
+    let is_tar = content.get(USTAR_OFFSET..USTAR_OFFSET + 5) == Some(b"ustar");
+    if !compressed && !is_tar {
+        anyhow::bail!(
+            "Not a recognized archive (expected a .tar.gz, .tar.xz, .tar.zst, .tar.bz2, \
+             .tar or .zip)"
+        );
+    }
+    decompress(content)
+}
+
+/// Helper: `data` decompressed by whichever of gzip, xz, zstd or bzip2 it starts like; as
+/// it is if none
+pub(crate) fn decompress(data: &[u8]) -> Result<Box<dyn Read + '_>> {
+    if data.starts_with(GZIP_MAGIC) {
+        Ok(Box::new(GzDecoder::new(data)))
+    } else if data.starts_with(XZ_MAGIC) {
+        // lzma-rs only decompresses whole streams
+        let mut out = Vec::new();
+        lzma_rs::xz_decompress(&mut &data[..], &mut out).context("Corrupt xz data")?;
+        Ok(Box::new(std::io::Cursor::new(out)))
+    } else if data.starts_with(ZSTD_MAGIC) {
+        let decoder = ruzstd::decoding::StreamingDecoder::new(data).context("Corrupt zstd data")?;
+        Ok(Box::new(decoder))
+    } else if data.starts_with(BZIP2_MAGIC) {
+        Ok(Box::new(bzip2::read::BzDecoder::new(data)))
+    } else {
+        Ok(Box::new(data))
+    }
+}
+
+/// Helper: `visit_files` over an uncompressed tar stream
+fn visit_tar<T, R: Read>(tar: R, visit: &mut Visitor<T>) -> Result<Option<T>> {
+    let mut archive = Archive::new(tar);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?.into_owned();
+        let mode = entry.header().mode().unwrap_or(0);
+        if let Some(found) = visit(&path, mode, &mut entry)? {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+/// Picks the entry most likely to be the package's binary, if any looks like one
+pub fn detect_binary(entries: &[ArchiveEntry], package_name: &str) -> Option<usize> {
+    entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| binary_score(e, package_name).map(|score| (score, i)))
+        // Ties go to the first entry
+        .max_by_key(|&(score, i)| (score, std::cmp::Reverse(i)))
+        .map(|(_, i)| i)
+}
+
+/// Helper: How binary-like an entry is; None if it can't be one
+fn binary_score(entry: &ArchiveEntry, package_name: &str) -> Option<i32> {
+    let path = Path::new(&entry.path);
+    let file_name = path.file_name()?.to_str()?;
+
+    let in_doc_dir = path.components().any(|c| {
+        c.as_os_str()
+            .to_str()
+            .is_some_and(|c| NON_BINARY_DIRS.contains(&c.to_lowercase().as_str()))
+    });
+    if in_doc_dir || file_name.starts_with('.') {
+        return None;
+    }
+
+    let mut score = 0;
+    if entry.executable {
+        score += 20;
+    }
+    // Binaries rarely have extensions; READMEs, licenses, and scripts do
+    match path.extension().and_then(|e| e.to_str()) {
+        None => score += 10,
+        Some("exe") => score += 5,
+        Some(_) => score -= 30,
+    }
+    if file_name == package_name {
+        score += 30;
+    } else if file_name.starts_with(package_name) || package_name.starts_with(file_name) {
+        score += 10;
+    }
+    if file_name.to_uppercase().starts_with("LICENSE") || file_name.to_uppercase() == "README" {
+        score -= 50;
+    }
+    // Prefer shallow entries ("tool-1.0/tool" over "tool-1.0/contrib/x/tool")
+    score -= path.components().count() as i32;
+
+    (score > 0).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::tar_gz;
+
+    fn entry(path: &str, executable: bool) -> ArchiveEntry {
+        ArchiveEntry {
+            path: path.to_string(),
+            size: 1,
+            executable,
+        }
+    }
+
+    #[test]
+    fn test_list_entries() {
+        let tarball = tar_gz(&[
+            ("tool-1.0/tool", 0o755, b"abc"),
+            ("tool-1.0/README.md", 0o644, b"abc"),
+        ]);
+
+        let entries = list_entries(&tarball).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "tool-1.0/tool");
+        assert!(entries[0].executable);
+        assert!(!entries[1].executable);
+
+        assert!(list_entries(b"not an archive").is_err());
+    }
+
+    #[test]
+    fn test_nested_archives_are_unwrapped() {
+        // A tarball in a zip
+        let inner = tar_gz(&[("tool-1.0/tool", 0o755, b"bin")]);
+        let zip = zip::build(&[("README.txt", 0o644, b"hi"), ("tool.tar.gz", 0o644, &inner)]);
+        let entries = list_entries(&zip).unwrap();
+        let paths: Vec<_> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["README.txt", "tool.tar.gz/tool-1.0/tool"]);
+        assert!(entries[1].executable);
+
+        // A zip in a tarball
+        let inner = zip::build(&[("tool", 0o755, b"bin")]);
+        let outer = tar_gz(&[("dist/tool.zip", 0o644, &inner)]);
+        let entries = list_entries(&outer).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "dist/tool.zip/tool");
+
+        // Only one level is unwrapped
+        let outer = zip::build(&[("wrapped.zip", 0o644, &zip)]);
+        let paths: Vec<_> = list_entries(&outer)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.path)
+            .collect();
+        assert_eq!(paths, ["wrapped.zip/README.txt", "wrapped.zip/tool.tar.gz"]);
+    }
+
+    #[test]
+    fn test_tarball_compressions() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(3);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "tool-1.0/tool", "bin".as_bytes())
+            .unwrap();
+        let tar = builder.into_inner().unwrap();
+
+        let mut xz = Vec::new();
+        lzma_rs::xz_compress(&mut &tar[..], &mut xz).unwrap();
+        let zst = ruzstd::encoding::compress_to_vec(
+            &tar[..],
+            ruzstd::encoding::CompressionLevel::Fastest,
+        );
+        let mut bz2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::fast());
+        std::io::Write::write_all(&mut bz2, &tar).unwrap();
+        let bz2 = bz2.finish().unwrap();
+
+        for (kind, content) in [("tar", &tar), ("xz", &xz), ("zstd", &zst), ("bzip2", &bz2)] {
+            let entries = list_entries(content).unwrap_or_else(|e| panic!("{}: {}", kind, e));
+            assert_eq!(entries.len(), 1, "{}", kind);
+            assert_eq!(entries[0].path, "tool-1.0/tool");
+            assert!(entries[0].executable);
+        }
+
+        // Recognized by name when nested, and cut short, an error
+        assert!(is_archive_name(Path::new("dist/tool.tar.zst")));
+        assert!(list_entries(&xz[..xz.len() / 2]).is_err());
+        let err = list_entries(b"#!/bin/sh").unwrap_err();
+        assert!(err.to_string().contains("Not a recognized archive"));
+    }
+
+    #[cfg(not(feature = "distro-packages"))]
+    #[test]
+    fn test_distro_packages_need_feature() {
+        let err = list_entries(b"!<arch>\ndebian-binary   ").unwrap_err();
+        assert!(err.to_string().contains("distro-packages"));
+    }
+
+    #[test]
+    fn test_detect_binary_prefers_executable_matching_name() {
+        let entries = vec![
+            entry("rg-14.0/README.md", false),
+            entry("rg-14.0/doc/rg.1", false),
+            entry("rg-14.0/complete/rg.bash", false),
+            entry("rg-14.0/rg", true),
+        ];
+        assert_eq!(detect_binary(&entries, "ripgrep"), Some(3));
+
+        // Package name matches the binary even without an exec bit
+        let entries = vec![entry("LICENSE", false), entry("dist/tool", false)];
+        assert_eq!(detect_binary(&entries, "tool"), Some(1));
+
+        // Nothing binary-like
+        let entries = vec![entry("README.md", false), entry("docs/guide.txt", false)];
+        assert_eq!(detect_binary(&entries, "tool"), None);
+    }
+}
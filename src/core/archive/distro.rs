@@ -0,0 +1,257 @@
+//! The payload of distro packages, for upstreams that ship nothing else.
+//!
+//! A .deb is an `ar` archive whose `data.tar.*` member holds the files. An .rpm is a lead,
+//! a signature header and a header, then a compressed `cpio` archive of the files. Only
+//! the files matter to rush; scripts, dependencies and package signatures are ignored.
+
+use super::{AR_MAGIC, Visitor, decompress, visit_tar};
+use anyhow::{Context, Result, bail};
+use std::io::Read;
+use std::path::Path;
+
+/// Size of an .rpm's lead, which comes before its signature header
+const RPM_LEAD_SIZE: usize = 96;
+/// How each .rpm header starts: magic, then version 1
+const RPM_HEADER_MAGIC: &[u8] = &[0x8e, 0xad, 0xe8, 0x01];
+/// Size of an `ar` member header
+const AR_HEADER_SIZE: usize = 60;
+/// Size of a `cpio` "newc" entry header
+const CPIO_HEADER_SIZE: usize = 110;
+/// The `cpio` entry that ends the archive
+const CPIO_TRAILER: &[u8] = b"TRAILER!!!";
+
+/// `visit_files` for a .deb: the files in its `data.tar`
+pub(super) fn visit_deb<T>(content: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    let mut rest = &content[AR_MAGIC.len()..];
+
+    while !rest.is_empty() {
+        // name(16) mtime(12) uid(6) gid(6) mode(8) size(10) "`\n"
+        let header = rest
+            .get(..AR_HEADER_SIZE)
+            .context("Truncated .deb member header")?;
+        let name = std::str::from_utf8(&header[..16])?
+            .trim_end()
+            .trim_end_matches('/');
+        let size: usize = std::str::from_utf8(&header[48..58])?
+            .trim()
+            .parse()
+            .context("Bad .deb member size")?;
+        let data = rest
+            .get(AR_HEADER_SIZE..AR_HEADER_SIZE + size)
+            .context("Truncated .deb member")?;
+
+        if name.starts_with("data.tar") {
+            return visit_tar(decompress(data)?, visit);
+        }
+        // Members are padded to an even size
+        rest = rest
+            .get(AR_HEADER_SIZE + size + size % 2..)
+            .unwrap_or_default();
+    }
+
+    bail!("No data.tar in .deb package")
+}
+
+/// `visit_files` for an .rpm: the files in its `cpio` payload
+pub(super) fn visit_rpm<T>(content: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    // The signature header is padded to a multiple of 8 bytes
+    let header_start = header_end(content, RPM_LEAD_SIZE)?.next_multiple_of(8);
+    let payload_start = header_end(content, header_start)?;
+    let payload = content
+        .get(payload_start..)
+        .context("Truncated .rpm package")?;
+
+    let mut cpio = Vec::new();
+    decompress(payload)?.read_to_end(&mut cpio)?;
+    visit_cpio(&cpio, visit)
+}
+
+/// Helper: Where the .rpm header starting at `start` ends
+fn header_end(content: &[u8], start: usize) -> Result<usize> {
+    // magic(4) reserved(4) index entries(4) store size(4), then 16 bytes per index entry
+    let header = content
+        .get(start..start + 16)
+        .context("Truncated .rpm header")?;
+    if !header.starts_with(RPM_HEADER_MAGIC) {
+        bail!("Not an .rpm package");
+    }
+    let entries = u32::from_be_bytes(header[8..12].try_into()?) as usize;
+    let store = u32::from_be_bytes(header[12..16].try_into()?) as usize;
+
+    Ok(start + 16 + entries * 16 + store)
+}
+
+/// Helper: `visit_files` over a `cpio` archive in the "newc" format rpm uses
+fn visit_cpio<T>(cpio: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    let mut offset = 0;
+
+    loop {
+        let header = cpio
+            .get(offset..offset + CPIO_HEADER_SIZE)
+            .context("Truncated cpio entry header")?;
+        if !matches!(&header[..6], b"070701" | b"070702") {
+            bail!("Unsupported cpio format in .rpm payload");
+        }
+        // Thirteen 8-digit hex fields follow the magic
+        let field = |i: usize| -> Result<usize> {
+            let hex = std::str::from_utf8(&header[6 + i * 8..14 + i * 8])?;
+            Ok(usize::from_str_radix(hex, 16)?)
+        };
+        let (mode, size, name_size) = (field(1)?, field(6)?, field(11)?);
+
+        // The name is NUL-terminated; it and the data are each padded to 4 bytes
+        let name_start = offset + CPIO_HEADER_SIZE;
+        let name = name_size
+            .checked_sub(1)
+            .and_then(|len| cpio.get(name_start..name_start + len))
+            .context("Truncated cpio entry name")?;
+        let data_start = (name_start + name_size).next_multiple_of(4);
+        let data = cpio
+            .get(data_start..data_start + size)
+            .context("Truncated cpio entry")?;
+
+        if name == CPIO_TRAILER {
+            return Ok(None);
+        }
+        if mode & 0o170000 == 0o100000 {
+            let path = Path::new(std::str::from_utf8(name)?);
+            if let Some(found) = visit(path, (mode & 0o7777) as u32, &mut &data[..])? {
+                return Ok(Some(found));
+            }
+        }
+        offset = (data_start + size).next_multiple_of(4);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::archive::{RPM_MAGIC, list_entries, visit_files};
+
+    const BINARY: &[u8] = b"\x7fELF pretend binary";
+
+    /// A tar with the binary and its docs, laid out like a distro package's files
+    fn payload_tar() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, mode, data) in [
+            ("./usr/share/doc/tool/copyright", 0o644, &b"MIT"[..]),
+            ("./usr/bin/tool", 0o755, BINARY),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn deb() -> Vec<u8> {
+        let mut data_tar = Vec::new();
+        lzma_rs::xz_compress(&mut &payload_tar()[..], &mut data_tar).unwrap();
+
+        let mut deb = AR_MAGIC.to_vec();
+        for (name, data) in [
+            ("debian-binary", &b"2.0\n"[..]),
+            ("control.tar", &b"odd"[..]),
+            ("data.tar.xz", &data_tar[..]),
+        ] {
+            let header = format!(
+                "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                format!("{}/", name),
+                0,
+                0,
+                0,
+                100644,
+                data.len()
+            );
+            deb.extend_from_slice(header.as_bytes());
+            deb.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                deb.push(b'\n');
+            }
+        }
+        deb
+    }
+
+    fn rpm() -> Vec<u8> {
+        let mut cpio = Vec::new();
+        let mut entry = |name: &str, mode: u32, data: &[u8]| {
+            let header = format!(
+                "070701{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}{:08x}",
+                0,
+                mode,
+                0,
+                0,
+                1,
+                0,
+                data.len(),
+                0,
+                0,
+                0,
+                0,
+                name.len() + 1,
+                0
+            );
+            cpio.extend_from_slice(header.as_bytes());
+            cpio.extend_from_slice(name.as_bytes());
+            cpio.push(0);
+            cpio.resize(cpio.len().next_multiple_of(4), 0);
+            cpio.extend_from_slice(data);
+            cpio.resize(cpio.len().next_multiple_of(4), 0);
+        };
+        entry("./usr/share/doc/tool", 0o040755, b"");
+        entry("./usr/share/doc/tool/copyright", 0o100644, b"MIT");
+        entry("./usr/bin/tool", 0o100755, BINARY);
+        entry("TRAILER!!!", 0, b"");
+
+        let header = |rpm: &mut Vec<u8>, entries: u32, store: u32| {
+            rpm.extend_from_slice(RPM_HEADER_MAGIC);
+            rpm.extend_from_slice(&[0; 4]);
+            rpm.extend_from_slice(&entries.to_be_bytes());
+            rpm.extend_from_slice(&store.to_be_bytes());
+            rpm.resize(rpm.len() + 16 * entries as usize + store as usize, 0);
+        };
+        let mut rpm = RPM_MAGIC.to_vec();
+        rpm.resize(RPM_LEAD_SIZE, 0);
+        // A signature header (padded to 8 bytes), then an empty main header
+        header(&mut rpm, 1, 5);
+        rpm.resize(rpm.len().next_multiple_of(8), 0);
+        header(&mut rpm, 0, 0);
+
+        rpm.extend(ruzstd::encoding::compress_to_vec(
+            &cpio[..],
+            ruzstd::encoding::CompressionLevel::Fastest,
+        ));
+        rpm
+    }
+
+    #[test]
+    fn test_distro_package_payloads() {
+        for package in [deb(), rpm()] {
+            let entries = list_entries(&package).unwrap();
+            assert_eq!(entries.len(), 2, "{:?}", entries);
+            let binary = entries
+                .iter()
+                .find(|e| e.path.ends_with("usr/bin/tool"))
+                .unwrap();
+            assert!(binary.executable);
+            assert_eq!(binary.size, BINARY.len() as u64);
+
+            let content = visit_files(&package, &mut |path, _, reader| {
+                if path.file_name() != Some("tool".as_ref()) {
+                    return Ok(None);
+                }
+                let mut content = Vec::new();
+                reader.read_to_end(&mut content)?;
+                Ok(Some(content))
+            })
+            .unwrap();
+            assert_eq!(content.as_deref(), Some(BINARY));
+        }
+
+        // Cut short, either is an error rather than an empty package
+        assert!(list_entries(&deb()[..200]).is_err());
+        assert!(list_entries(&rpm()[..120]).is_err());
+    }
+}
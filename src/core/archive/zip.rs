@@ -0,0 +1,199 @@
+//! .zip archives, read through their central directory.
+//!
+//! Only what release zips use is supported: stored and deflated entries, no encryption,
+//! no zip64. Permission bits come from the external attributes when the archive was
+//! made on Unix; otherwise files are taken to be plain (not executable).
+
+use super::Visitor;
+use anyhow::{Context, Result, bail};
+use flate2::read::DeflateDecoder;
+use std::path::Path;
+
+/// How each local file header starts, and so a .zip
+pub(super) const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+/// How the end of central directory record starts
+const EOCD_MAGIC: &[u8] = b"PK\x05\x06";
+/// How each central directory entry starts
+const CENTRAL_MAGIC: &[u8] = b"PK\x01\x02";
+
+const EOCD_SIZE: usize = 22;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const LOCAL_HEADER_SIZE: usize = 30;
+
+/// "Made by" host for Unix, whose mode sits in the top half of the external attributes
+const HOST_UNIX: u16 = 3;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+/// `visit_files` for a .zip
+pub(super) fn visit_zip<T>(content: &[u8], visit: &mut Visitor<T>) -> Result<Option<T>> {
+    // The record is last, followed only by a comment of up to 64 KiB
+    let search_from = content.len().saturating_sub(EOCD_SIZE + u16::MAX as usize);
+    let eocd = content[search_from..]
+        .windows(EOCD_MAGIC.len())
+        .rposition(|w| w == EOCD_MAGIC)
+        .map(|i| search_from + i)
+        .context("Not a .zip archive (no end of central directory)")?;
+    let eocd = content
+        .get(eocd..eocd + EOCD_SIZE)
+        .context("Truncated .zip end of central directory")?;
+
+    let entries = u16_at(eocd, 10);
+    let mut offset = u32_at(eocd, 16) as usize;
+    if entries == u16::MAX || offset == u32::MAX as usize {
+        bail!("zip64 archives are not supported");
+    }
+
+    for _ in 0..entries {
+        let header = content
+            .get(offset..offset + CENTRAL_HEADER_SIZE)
+            .filter(|h| h.starts_with(CENTRAL_MAGIC))
+            .context("Bad .zip central directory entry")?;
+        let host = u16_at(header, 4) >> 8;
+        let flags = u16_at(header, 8);
+        let method = u16_at(header, 10);
+        let compressed_size = u32_at(header, 20) as usize;
+        let name_len = u16_at(header, 28) as usize;
+        let extra_len = u16_at(header, 30) as usize;
+        let comment_len = u16_at(header, 32) as usize;
+        let external = u32_at(header, 38);
+        let local_offset = u32_at(header, 42) as usize;
+
+        let name_start = offset + CENTRAL_HEADER_SIZE;
+        let name = content
+            .get(name_start..name_start + name_len)
+            .context("Truncated .zip entry name")?;
+        let name = std::str::from_utf8(name).context("Non-UTF-8 .zip entry name")?;
+        offset = name_start + name_len + extra_len + comment_len;
+
+        let mode = if host == HOST_UNIX { external >> 16 } else { 0 };
+        // Directories, and on Unix anything but a regular file (symlinks)
+        let file_type = mode & 0o170000;
+        if name.ends_with('/') || (file_type != 0 && file_type != 0o100000) {
+            continue;
+        }
+        if flags & 1 != 0 {
+            bail!("{} is encrypted", name);
+        }
+
+        let data = entry_data(content, local_offset, compressed_size)
+            .with_context(|| format!("Bad .zip entry {}", name))?;
+        let found = match method {
+            METHOD_STORED => visit(Path::new(name), mode & 0o7777, &mut &*data)?,
+            METHOD_DEFLATED => visit(
+                Path::new(name),
+                mode & 0o7777,
+                &mut DeflateDecoder::new(data),
+            )?,
+            other => bail!("{} uses unsupported compression method {}", name, other),
+        };
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
+/// Helper: An entry's compressed bytes, which follow its local header
+fn entry_data(content: &[u8], offset: usize, size: usize) -> Result<&[u8]> {
+    let header = content
+        .get(offset..offset + LOCAL_HEADER_SIZE)
+        .filter(|h| h.starts_with(ZIP_MAGIC))
+        .context("Bad local header")?;
+    // The local name and extra field can differ in length from the central directory's
+    let start =
+        offset + LOCAL_HEADER_SIZE + u16_at(header, 26) as usize + u16_at(header, 28) as usize;
+    content.get(start..start + size).context("Truncated data")
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// A .zip of `(path, mode, content)` entries, deflated and made on Unix. CRCs are left
+/// zero, as nothing here checks them.
+#[cfg(test)]
+pub(super) fn build(files: &[(&str, u32, &[u8])]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for &(name, mode, data) in files {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let offset = zip.len() as u32;
+
+        // version(2) flags(2) method(2) time(2) date(2) crc(4) sizes(4+4) name len(2)
+        let common = [
+            &[20, 0, 0, 0][..],
+            &METHOD_DEFLATED.to_le_bytes(),
+            &[0; 8],
+            &(compressed.len() as u32).to_le_bytes(),
+            &(data.len() as u32).to_le_bytes(),
+            &(name.len() as u16).to_le_bytes(),
+        ]
+        .concat();
+
+        zip.extend_from_slice(ZIP_MAGIC);
+        zip.extend_from_slice(&common);
+        zip.extend_from_slice(&[0, 0]); // extra len
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(&compressed);
+
+        central.extend_from_slice(CENTRAL_MAGIC);
+        central.extend_from_slice(&((HOST_UNIX << 8) | 20).to_le_bytes());
+        central.extend_from_slice(&common);
+        central.extend_from_slice(&[0; 8]); // extra, comment, disk, internal attributes
+        central.extend_from_slice(&((0o100000 | mode) << 16).to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(EOCD_MAGIC);
+    zip.extend_from_slice(&[0; 4]); // disk numbers
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    zip.extend_from_slice(&central_offset.to_le_bytes());
+    zip.extend_from_slice(&[0, 0]); // comment len
+    zip
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visit_zip() {
+        let zip = build(&[
+            ("tool-1.0/", 0o755, b""),
+            ("tool-1.0/tool", 0o755, b"#!/bin/sh\necho hi\n"),
+            ("tool-1.0/README.md", 0o644, b"# tool"),
+        ]);
+
+        let mut seen = Vec::new();
+        let found = visit_zip(&zip, &mut |path, mode, reader| {
+            let mut content = String::new();
+            reader.read_to_string(&mut content)?;
+            seen.push((path.to_path_buf(), mode, content));
+            Ok(None::<()>)
+        })
+        .unwrap();
+
+        assert!(found.is_none());
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].0, Path::new("tool-1.0/tool"));
+        assert_eq!(seen[0].1, 0o755);
+        assert_eq!(seen[0].2, "#!/bin/sh\necho hi\n");
+        assert_eq!(seen[1].1, 0o644);
+
+        assert!(visit_zip(&zip[..zip.len() - 4], &mut |_, _, _| Ok(None::<()>)).is_err());
+    }
+}
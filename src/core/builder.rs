@@ -0,0 +1,477 @@
+use crate::Error;
+use crate::core::cancel::CancellableTransport;
+use crate::core::{
+    CancellationToken, DEFAULT_REGISTRY_URL, HttpTransport, JsonFileStore, RegistryBackend,
+    RushEngine, StateStore, Target, Transport, appimage, backend_for_source, config, http_client,
+    pending, pins,
+};
+use crate::models::{Config, State, StateBackend};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where binaries go by default, inside rush's data dir
+const SHIMS_DIR: &str = "shims";
+
+/// Configures and creates a `RushEngine`.
+///
+/// Everything is optional; unset values fall back to the standard layout under `root`:
+///
+/// ```no_run
+/// # use rush::core::RushEngine;
+/// let engine = RushEngine::builder()
+///     .root("/tmp/rush-test")
+///     .registry_source("/path/to/local/registry")
+///     .offline(true)
+///     .build()?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Default)]
+pub struct RushEngineBuilder {
+    root: Option<PathBuf>,
+    bin_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+    cache_dir: Option<PathBuf>,
+    registry_source: Option<String>,
+    registry: Option<Box<dyn RegistryBackend>>,
+    store: Option<Box<dyn StateStore>>,
+    client: Option<reqwest::blocking::Client>,
+    transport: Option<Box<dyn Transport>>,
+    target: Option<String>,
+    offline: bool,
+    cancel: Option<CancellationToken>,
+}
+
+impl RushEngineBuilder {
+    /// Home directory everything else lives under (default: `$HOME`)
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Where binaries are installed (default: `<root>/.local/share/rush/shims`, a directory
+    /// only rush writes to)
+    pub fn bin_dir(mut self, bin_dir: impl Into<PathBuf>) -> Self {
+        self.bin_dir = Some(bin_dir.into());
+        self
+    }
+
+    /// Where data packages install their `files` (default: `<root>/.local/share`)
+    pub fn data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
+
+    /// Where HTTP responses are cached (default: `<root>/.cache/rush`)
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Registry URL or local directory (default: `$RUSH_REGISTRY_URL`, then the main registry)
+    pub fn registry_source(mut self, source: impl Into<String>) -> Self {
+        self.registry_source = Some(source.into());
+        self
+    }
+
+    /// Read the registry through a custom backend instead of one picked from the source
+    /// (the backend's `source()` becomes the registry source)
+    pub fn registry_backend(mut self, backend: impl RegistryBackend + 'static) -> Self {
+        self.registry = Some(Box::new(backend));
+        self
+    }
+
+    /// Where installed-package state is kept
+    /// (default: `JsonFileStore` at `<root>/.local/share/rush/installed.json`)
+    pub fn state_store(mut self, store: impl StateStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// HTTP client for all downloads and API calls (default: `http_client`, tuned by
+    /// config.toml's `[http]`)
+    pub fn http_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// How artifacts, registries, and checksum files are downloaded
+    /// (default: `HttpTransport` over the HTTP client)
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Install for this target instead of the host (default: `$RUSH_TARGET`, then config.toml's
+    /// `target`, then the machine rush runs on)
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Refuse anything that needs the network; local registries and `file://` URLs still work
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Share a cancellation token with the engine (default: a fresh one, see
+    /// `RushEngine::cancellation_token()`)
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Create the directories, load state and config, and return the engine
+    pub fn build(self) -> Result<RushEngine> {
+        let root = match self.root {
+            Some(root) => root,
+            None => dirs::home_dir().context("No home dir")?,
+        };
+        let (registry_source, registry) = match self.registry {
+            Some(backend) => (backend.source().to_string(), backend),
+            None => {
+                let source = self.registry_source.unwrap_or_else(|| {
+                    std::env::var("RUSH_REGISTRY_URL")
+                        .unwrap_or_else(|_| DEFAULT_REGISTRY_URL.to_string())
+                });
+                let backend = backend_for_source(&source);
+                (source, backend)
+            }
+        };
+
+        let state_dir = root.join(".local/share/rush");
+        // Binaries used to go into ~/.local/bin; those are moved over once state is loaded
+        let legacy_bin_dir = self.bin_dir.is_none().then(|| root.join(".local/bin"));
+        let bin_path = self.bin_dir.unwrap_or_else(|| state_dir.join(SHIMS_DIR));
+        let data_dir = self.data_dir.unwrap_or_else(|| root.join(".local/share"));
+        let registry_dir = state_dir.join("registry");
+        let config_dir = root.join(".config/rush");
+        let cache_dir = self.cache_dir.unwrap_or_else(|| root.join(".cache/rush"));
+
+        fs::create_dir_all(&state_dir)?;
+        fs::create_dir_all(&bin_path)?;
+
+        let config = config::load_config(&config_dir)?;
+        let target = resolve_target(self.target, &config)?;
+
+        let store = match self.store {
+            Some(store) => store,
+            None => default_store(&state_dir, config.state.backend)?,
+        };
+        let state = store.load()?;
+        if let Some(legacy_bin_dir) = legacy_bin_dir {
+            move_legacy_binaries(&state, &legacy_bin_dir, &bin_path);
+        }
+        // Upgrades that had to wait for a running binary to exit
+        pending::finish_pending(&bin_path, state.packages.values().flat_map(|p| &p.binaries));
+
+        let client = match self.client {
+            Some(client) => client,
+            None => http_client(&config.http)?,
+        };
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Box::new(HttpTransport::new(client.clone())));
+        let cancel = self.cancel.unwrap_or_default();
+        let transport = Arc::new(CancellableTransport::new(transport, cancel.clone()));
+
+        Ok(RushEngine {
+            saved_state: state.clone(),
+            state,
+            store,
+            subscribers: Default::default(),
+            registry_dir,
+            known_hashes_path: state_dir.join(pins::KNOWN_HASHES_FILE),
+            apps_dir: state_dir.join(appimage::APPS_DIR),
+            bin_path,
+            data_dir,
+            config_dir,
+            cache_dir,
+            client,
+            transport,
+            registry_source,
+            registry,
+            config,
+            target,
+            offline: self.offline,
+            cancel,
+        })
+    }
+}
+
+/// Binaries installed before rush had its own directory sit in `~/.local/bin`, next to the
+/// user's own. Move the ones state says are rush's into the shims dir; anything that
+/// can't be moved (another filesystem, a name already taken) stays where it is.
+fn move_legacy_binaries(state: &State, legacy_bin_dir: &Path, bin_path: &Path) {
+    for bin in state.packages.values().flat_map(|p| &p.binaries) {
+        let (from, to) = (legacy_bin_dir.join(bin), bin_path.join(bin));
+        if !from.is_file() || to.exists() {
+            continue;
+        }
+        match fs::rename(&from, &to) {
+            Ok(()) => tracing::info!(bin = %bin, "moved from ~/.local/bin to the shims dir"),
+            Err(e) => tracing::warn!(bin = %bin, error = %e, "left in ~/.local/bin"),
+        }
+    }
+}
+
+/// The target to install for, in its canonical spelling; an override rush doesn't
+/// recognize is an error rather than a silent fallback to the host
+fn resolve_target(explicit: Option<String>, config: &Config) -> Result<String> {
+    let wanted = explicit
+        .or_else(|| std::env::var("RUSH_TARGET").ok().filter(|t| !t.is_empty()))
+        .or_else(|| config.target.clone());
+    match wanted {
+        Some(wanted) => match Target::parse(&wanted) {
+            Some(target) => Ok(target.to_string()),
+            None => Err(Error::UnknownTarget { target: wanted }.into()),
+        },
+        None => Ok(Target::host().to_string()),
+    }
+}
+
+/// The store `[state] backend` in config.toml asks for
+fn default_store(state_dir: &Path, backend: StateBackend) -> Result<Box<dyn StateStore>> {
+    let json = JsonFileStore::new(state_dir.join("installed.json"));
+    match backend {
+        StateBackend::Json => Ok(Box::new(json)),
+        #[cfg(feature = "sqlite")]
+        StateBackend::Sqlite => crate::core::sqlite_store::open_store(state_dir, json),
+        #[cfg(not(feature = "sqlite"))]
+        StateBackend::Sqlite => anyhow::bail!(
+            "config.toml sets `[state] backend = \"sqlite\"`, but this rush was built without the `sqlite` feature"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builder_overrides() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("home");
+        let bin = temp_dir.path().join("bin");
+        let data = temp_dir.path().join("data");
+        let cache = temp_dir.path().join("cache");
+
+        let mut engine = RushEngine::builder()
+            .root(&root)
+            .bin_dir(&bin)
+            .data_dir(&data)
+            .cache_dir(&cache)
+            .registry_source("/some/registry")
+            .offline(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(engine.bin_path, bin);
+        assert!(bin.exists());
+        assert_eq!(engine.data_dir, data);
+        assert_eq!(engine.cache_dir, cache);
+        assert_eq!(engine.registry_source, "/some/registry");
+        engine.save().unwrap();
+        assert!(root.join(".local/share/rush/installed.json").exists());
+        assert!(engine.offline);
+
+        // Defaults follow the root
+        let engine = RushEngine::builder().root(&root).build().unwrap();
+        assert_eq!(engine.bin_path, root.join(".local/share/rush/shims"));
+        assert_eq!(engine.data_dir, root.join(".local/share"));
+        assert_eq!(engine.cache_dir, root.join(".cache/rush"));
+        assert!(!engine.offline);
+    }
+
+    #[test]
+    fn test_legacy_binaries_move_to_shims() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let legacy = root.join(".local/bin");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join("tool"), "rush's").unwrap();
+        fs::write(legacy.join("mine"), "the user's").unwrap();
+
+        let mut engine = RushEngine::builder().root(root).build().unwrap();
+        engine.state.packages.insert(
+            "tool".to_string(),
+            crate::models::InstalledPackage {
+                version: "1.0.0".to_string(),
+                binaries: vec!["tool".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+        engine.save().unwrap();
+
+        let engine = RushEngine::builder().root(root).build().unwrap();
+        assert_eq!(
+            fs::read_to_string(engine.bin_path.join("tool")).unwrap(),
+            "rush's"
+        );
+        assert!(!legacy.join("tool").exists());
+        // Not rush's to move
+        assert!(legacy.join("mine").exists());
+        assert!(!engine.bin_path.join("mine").exists());
+    }
+
+    #[test]
+    fn test_pending_binaries_are_swapped_in_on_build() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+
+        let mut engine = RushEngine::builder().root(root).build().unwrap();
+        engine.state.packages.insert(
+            "tool".to_string(),
+            crate::models::InstalledPackage {
+                version: "2.0.0".to_string(),
+                binaries: vec!["tool".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+        engine.save().unwrap();
+        // Left by an upgrade while v1 was running
+        fs::write(engine.bin_path.join("tool"), "v1").unwrap();
+        fs::write(engine.bin_path.join("tool.new"), "v2").unwrap();
+
+        let engine = RushEngine::builder().root(root).build().unwrap();
+        assert_eq!(
+            fs::read_to_string(engine.bin_path.join("tool")).unwrap(),
+            "v2"
+        );
+        assert!(!engine.bin_path.join("tool.new").exists());
+    }
+
+    #[test]
+    fn test_target_override() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+
+        let engine = RushEngine::builder()
+            .root(root)
+            .target("arm64-linux")
+            .build()
+            .unwrap();
+        assert_eq!(engine.target(), "aarch64-linux");
+
+        let config_dir = root.join(".config/rush");
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(
+            config_dir.join(config::CONFIG_FILE),
+            "target = \"x86_64-apple-darwin\"\n",
+        )
+        .unwrap();
+        let config = config::load_config(&config_dir).unwrap();
+        assert_eq!(resolve_target(None, &config).unwrap(), "x86_64-macos");
+        assert_eq!(
+            resolve_target(Some("aarch64-linux".to_string()), &config).unwrap(),
+            "aarch64-linux"
+        );
+
+        let Err(err) = RushEngine::builder()
+            .root(root)
+            .target("sparc-solaris")
+            .build()
+        else {
+            panic!("an unknown target was accepted");
+        };
+        assert!(err.to_string().contains("Unknown target 'sparc-solaris'"));
+    }
+
+    #[test]
+    fn test_offline_refuses_network() {
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .registry_source("https://example.com/registry.tar.gz")
+            .offline(true)
+            .build()
+            .unwrap();
+
+        let err = engine.update_registry(&mut ()).unwrap_err();
+        assert!(err.to_string().contains("Offline mode"));
+        assert!(engine.ensure_online("file:///tmp/tool.tar.gz").is_ok());
+    }
+
+    #[test]
+    fn test_custom_registry_backend() {
+        use crate::models::{PackageManifest, ProgressKind};
+        use std::path::Path;
+
+        // Answers lookups itself; nothing is ever synced to disk
+        struct Fixed;
+        impl RegistryBackend for Fixed {
+            fn source(&self) -> &str {
+                "fixed://"
+            }
+            fn is_remote(&self) -> bool {
+                false
+            }
+            fn sync(
+                &self,
+                _: &Path,
+                _: &dyn Transport,
+                _: &mut dyn FnMut(ProgressKind),
+            ) -> Result<()> {
+                Ok(())
+            }
+            fn find_package(
+                &self,
+                _: &Path,
+                _: &dyn Transport,
+                name: &str,
+            ) -> Option<PackageManifest> {
+                (name == "fixed")
+                    .then(|| toml::from_str("version = \"9.9.9\"\n[targets]\n").unwrap())
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .registry_backend(Fixed)
+            .build()
+            .unwrap();
+
+        assert_eq!(engine.registry_source, "fixed://");
+        assert_eq!(engine.update_registry(&mut ()).unwrap().source, "fixed://");
+        assert_eq!(engine.find_package("fixed").unwrap().version, "9.9.9");
+        assert!(engine.list_available_packages().is_empty());
+    }
+
+    #[test]
+    fn test_state_backend_from_config() {
+        let temp_dir = tempdir().unwrap();
+        let config_dir = temp_dir.path().join(".config/rush");
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(
+            config_dir.join("config.toml"),
+            "[state]\nbackend = \"sqlite\"\n",
+        )
+        .unwrap();
+
+        let result = RushEngine::builder().root(temp_dir.path()).build();
+        if cfg!(feature = "sqlite") {
+            result.unwrap().save().unwrap();
+            assert!(temp_dir.path().join(".local/share/rush/state.db").exists());
+        } else {
+            assert!(
+                result
+                    .err()
+                    .unwrap()
+                    .to_string()
+                    .contains("`sqlite` feature")
+            );
+        }
+    }
+}
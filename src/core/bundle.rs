@@ -0,0 +1,490 @@
+//! Packing the installed environment up for a machine without internet access.
+//!
+//! A bundle is a `.tar.gz` laid out like a `rush mirror` directory: a manifest per
+//! installed package, pinned to the installed version and artifact, and each artifact in
+//! `artifacts/<sha256>`. Its `rush.lock` records the same pins. Extracted and used as
+//! `RUSH_REGISTRY_URL`, it installs exactly what was exported, offline; or
+//! `import_bundle` moves its artifacts into the cache and installs the lockfile directly.
+
+use crate::core::registry::{self, ARTIFACTS_DIR};
+use crate::core::{RushEngine, install, plan, util};
+use crate::models::{
+    ArtifactFormat, BundleResult, Conflict, InstallOutcome, InstalledPackage, LockedPackage,
+    Lockfile, PackageManifest, Plan, PlannedInstall, ProgressEvent, ProgressKind, Reporter,
+    SkipReason, SkippedPackage, TargetDefinition,
+};
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Name of the lockfile at the root of a bundle
+pub const LOCKFILE: &str = "rush.lock";
+
+/// The cache directory keeping the lockfile of every imported bundle, so `clean --all`
+/// leaves the artifacts it brought alone
+pub(crate) const IMPORTED_DIR: &str = "bundles";
+
+/// Write every installed package's artifact (for `target`, the machine's own) and a
+/// lockfile to the bundle `out`. Artifacts already on this machine (a mirror, the cache)
+/// are used as they are and others downloaded; all are checked against their recorded
+/// sha256.
+/// Nothing is left at `out` if anything fails.
+#[tracing::instrument(skip(engine, on_event), fields(out = %out.display()))]
+pub fn export_bundle<F>(
+    engine: &RushEngine,
+    out: &Path,
+    target: &str,
+    mut on_event: F,
+) -> Result<BundleResult>
+where
+    F: FnMut(ProgressEvent),
+{
+    let parent = match out.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    // Dropped (and deleted) on any error; only a complete bundle is moved to `out`
+    let file = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("Cannot write to {}", parent.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut names: Vec<&String> = engine.state.packages.keys().collect();
+    names.sort();
+
+    let mut lockfile = Lockfile::default();
+    let mut skipped = Vec::new();
+    for name in names {
+        let Some(prefix) = name.chars().next() else {
+            continue;
+        };
+        let installed = &engine.state.packages[name];
+        let manifest = engine.find_package(name);
+        let Some(artifact) = locked_artifact(installed, manifest.as_ref(), target) else {
+            skipped.push(name.clone());
+            continue;
+        };
+        engine.cancel.check()?;
+
+        let mut on_event = util::tag_progress(Some(name), &mut on_event);
+        let content = match install::local_artifact(engine, &artifact) {
+            Some(content) => content,
+            None => {
+                engine.ensure_online(&artifact.url)?;
+                util::download_url(engine.transport.as_ref(), &artifact.url, &mut on_event)?
+            }
+        };
+        on_event(ProgressKind::VerifyingChecksum);
+        util::verify_checksum(&content, &artifact.sha256)
+            .with_context(|| format!("The artifact of {} changed since it was installed", name))?;
+        append(
+            &mut tar,
+            &format!("{}/{}", ARTIFACTS_DIR, artifact.sha256),
+            &content,
+        )?;
+
+        let pinned = pin(manifest, installed, target, &artifact);
+        append(
+            &mut tar,
+            &format!("packages/{}/{}.toml", prefix, name),
+            toml::to_string(&pinned)?.as_bytes(),
+        )?;
+
+        lockfile.packages.push(LockedPackage {
+            name: name.clone(),
+            version: installed.version.clone(),
+            target: target.to_string(),
+            url: artifact.url,
+            bin: artifact.bin,
+            sha256: artifact.sha256,
+            format: artifact.format,
+            extract: artifact.extract,
+            files: artifact.files,
+        });
+    }
+
+    append(&mut tar, LOCKFILE, toml::to_string(&lockfile)?.as_bytes())?;
+    let file = tar.into_inner()?.finish()?;
+    file.persist(out)
+        .with_context(|| format!("Failed to write {}", out.display()))?;
+
+    Ok(BundleResult {
+        path: out.to_path_buf(),
+        lockfile,
+        skipped,
+    })
+}
+
+/// Install what the lockfile in the bundle at `path` lists, at its versions, with no
+/// network: its artifacts are checked against the lockfile and moved into the cache, then
+/// installed from there. Packages already at the locked version are skipped; a bundle made
+/// for another target is refused.
+#[tracing::instrument(skip(engine, reporter), fields(path = %path.display()))]
+pub fn import_bundle<R>(
+    engine: &mut RushEngine,
+    path: &Path,
+    target: &str,
+    reporter: &mut R,
+) -> Result<Vec<InstallOutcome>>
+where
+    R: Reporter,
+{
+    let lockfile = unpack_bundle(engine, path)?;
+    let plan = plan_import(engine, &lockfile, target);
+
+    // Everything is in the cache now; nothing should be fetched, so nothing can be
+    let offline = std::mem::replace(&mut engine.offline, true);
+    let outcomes = plan::apply(engine, &plan, reporter);
+    engine.offline = offline;
+    outcomes
+}
+
+/// Helper: Read the bundle's lockfile, and move each artifact it lists into the cache
+/// once its content matches the locked sha256
+fn unpack_bundle(engine: &RushEngine, path: &Path) -> Result<Lockfile> {
+    let file =
+        fs::File::open(path).with_context(|| format!("Cannot open bundle {}", path.display()))?;
+    fs::create_dir_all(&engine.cache_dir)?;
+    let staging = tempfile::tempdir_in(&engine.cache_dir)?;
+
+    let mut lockfile = None;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        if entry_path == Path::new(LOCKFILE) {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            lockfile = Some(
+                toml::from_str::<Lockfile>(&content)
+                    .with_context(|| format!("Invalid {} in {}", LOCKFILE, path.display()))?,
+            );
+        } else if let Some(dest) = entry_path
+            .strip_prefix(ARTIFACTS_DIR)
+            .ok()
+            .and_then(|name| name.to_str())
+            .and_then(|sha256| registry::artifact_file(staging.path(), sha256))
+        {
+            fs::create_dir_all(staging.path().join(ARTIFACTS_DIR))?;
+            entry.unpack(dest)?;
+        }
+    }
+    let lockfile = lockfile.with_context(|| format!("{} has no {}", path.display(), LOCKFILE))?;
+
+    fs::create_dir_all(engine.cache_dir.join(ARTIFACTS_DIR))?;
+    for locked in &lockfile.packages {
+        let (Some(staged), Some(cached)) = (
+            registry::artifact_file(staging.path(), &locked.sha256),
+            registry::artifact_file(&engine.cache_dir, &locked.sha256),
+        ) else {
+            anyhow::bail!("{} has an invalid sha256 in {}", locked.name, LOCKFILE);
+        };
+        // Packages can share an artifact, which is then already in the cache
+        let source = if staged.is_file() { &staged } else { &cached };
+        let content = fs::read(source)
+            .with_context(|| format!("The bundle has no artifact for {}", locked.name))?;
+        util::verify_checksum(&content, &locked.sha256)
+            .with_context(|| format!("The bundled artifact of {} is corrupt", locked.name))?;
+        if source == &staged {
+            fs::rename(&staged, &cached)?;
+        }
+    }
+
+    let record = toml::to_string(&lockfile)?;
+    let imported = engine.cache_dir.join(IMPORTED_DIR);
+    fs::create_dir_all(&imported)?;
+    let name = format!("{}.lock", hex::encode(Sha256::digest(&record)));
+    fs::write(imported.join(name), record)?;
+    Ok(lockfile)
+}
+
+/// The sha256 of every artifact an imported bundle brought into the cache. Unreadable
+/// records are passed over.
+pub(crate) fn imported_artifacts(cache_dir: &Path) -> HashSet<String> {
+    fs::read_dir(cache_dir.join(IMPORTED_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| toml::from_str::<Lockfile>(&content).ok())
+        .flat_map(|lockfile| lockfile.packages)
+        .map(|locked| locked.sha256)
+        .collect()
+}
+
+/// Helper: The plan that brings installed packages to the lockfile's versions
+fn plan_import(engine: &RushEngine, lockfile: &Lockfile, target: &str) -> Plan {
+    let mut plan = Plan {
+        target: target.to_string(),
+        steps: Vec::new(),
+        skipped: Vec::new(),
+        conflicts: Vec::new(),
+    };
+
+    for locked in &lockfile.packages {
+        if locked.target != target {
+            plan.conflicts.push(Conflict::NoCompatibleTarget {
+                package: locked.name.clone(),
+                target: target.to_string(),
+            });
+            continue;
+        }
+        let installed = engine
+            .state
+            .packages
+            .get(&locked.name)
+            .map(|p| p.version.clone());
+        if installed.as_deref() == Some(locked.version.as_str()) {
+            plan.skipped.push(SkippedPackage {
+                package: locked.name.clone(),
+                reason: SkipReason::AlreadyInstalled {
+                    version: locked.version.clone(),
+                },
+            });
+            continue;
+        }
+
+        plan.steps.push(PlannedInstall {
+            package: locked.name.clone(),
+            from: installed,
+            to: locked.version.clone(),
+            artifact: TargetDefinition {
+                url: locked.url.clone(),
+                bin: locked.bin.clone(),
+                sha256: locked.sha256.clone(),
+                checksum_url: None,
+                size: None,
+                format: locked.format,
+                extract: locked.extract,
+                files: locked.files.clone(),
+            },
+            download_size: None,
+            dependency: false,
+        });
+    }
+    plan
+}
+
+/// Helper: The artifact `installed` came from: as recorded at install time, or else the
+/// registry's, if the registry still has the installed version
+pub(crate) fn locked_artifact(
+    installed: &InstalledPackage,
+    manifest: Option<&PackageManifest>,
+    target: &str,
+) -> Option<TargetDefinition> {
+    if let Some(provenance) = &installed.provenance {
+        // Provenance doesn't say how the artifact was installed; a registry target with
+        // the same hash does
+        let listed =
+            manifest.and_then(|m| m.targets.values().find(|t| t.sha256 == provenance.sha256));
+        return Some(TargetDefinition {
+            url: provenance.url.clone(),
+            bin: installed.binaries.clone(),
+            sha256: provenance.sha256.clone(),
+            checksum_url: None,
+            size: None,
+            format: listed.map_or(ArtifactFormat::Archive, |t| t.format),
+            extract: listed.is_some_and(|t| t.extract),
+            files: listed.map(|t| t.files.clone()).unwrap_or_default(),
+        });
+    }
+    manifest
+        .filter(|m| m.version == installed.version)
+        .and_then(|m| m.target(target))
+        .map(|(_, artifact)| artifact.clone())
+}
+
+/// Helper: The registry's manifest (or a bare one), narrowed to the installed version
+/// and artifact
+fn pin(
+    manifest: Option<PackageManifest>,
+    installed: &InstalledPackage,
+    target: &str,
+    artifact: &TargetDefinition,
+) -> PackageManifest {
+    let mut pinned = manifest.unwrap_or_else(|| PackageManifest {
+        version: String::new(),
+        description: None,
+        source: None,
+        license: None,
+        homepage: None,
+        updated: None,
+        check: None,
+        osv: None,
+        requires: Vec::new(),
+        dependencies: Vec::new(),
+        targets: BTreeMap::new(),
+        history: Vec::new(),
+    });
+    pinned.version = installed.version.clone();
+    pinned.targets = BTreeMap::from([(target.to_string(), artifact.clone())]);
+    pinned.history.clear();
+    pinned
+}
+
+/// Helper: Add a regular file at `path` in the bundle
+fn append<W: std::io::Write>(tar: &mut tar::Builder<W>, path: &str, content: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn test_bundle_reinstalls_offline() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        mock.add_package("old", "2.0.0", "old");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let manifest = engine.find_package("tool").unwrap();
+        let target = manifest.targets[&current_target()].clone();
+        engine
+            .install_package("tool", "1.0.0", &target, &mut ())
+            .unwrap();
+        // Installed before provenance was recorded, at a version the registry moved past
+        engine.state.packages.insert(
+            "old".to_string(),
+            InstalledPackage {
+                version: "1.0.0".to_string(),
+                binaries: vec!["old".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+
+        let out = mock.home.join("team.tar.gz");
+        let result = export_bundle(&engine, &out, &current_target(), |_| {}).unwrap();
+        assert_eq!(result.skipped, ["old"]);
+        assert_eq!(
+            result.lockfile.packages,
+            [LockedPackage {
+                name: "tool".to_string(),
+                version: "1.0.0".to_string(),
+                target: current_target(),
+                url: target.url.clone(),
+                bin: target.bin.clone(),
+                sha256: target.sha256.clone(),
+                format: ArtifactFormat::Archive,
+                extract: false,
+                files: Vec::new(),
+            }]
+        );
+
+        // Extracted, the bundle is a registry that installs without the original source
+        let extracted = mock.home.join("bundle");
+        tar::Archive::new(GzDecoder::new(std::fs::File::open(&out).unwrap()))
+            .unpack(&extracted)
+            .unwrap();
+        let lockfile: Lockfile =
+            toml::from_str(&std::fs::read_to_string(extracted.join(LOCKFILE)).unwrap()).unwrap();
+        assert_eq!(lockfile, result.lockfile);
+
+        std::fs::remove_dir_all(&mock.registry_source).unwrap();
+        let mut teammate = RushEngine::builder()
+            .root(mock.home.join("teammate"))
+            .registry_source(extracted.to_str().unwrap())
+            .offline(true)
+            .build()
+            .unwrap();
+        teammate.update_registry(&mut ()).unwrap();
+        let pinned = teammate.find_package("tool").unwrap();
+        teammate
+            .install_package(
+                "tool",
+                &pinned.version,
+                &pinned.targets[&current_target()],
+                &mut (),
+            )
+            .unwrap();
+        assert!(teammate.bin_path.join("tool").exists());
+    }
+
+    #[test]
+    fn test_import_bundle_installs_from_cache() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let manifest = engine.find_package("tool").unwrap();
+        let target = manifest.targets[&current_target()].clone();
+        engine
+            .install_package("tool", "1.0.0", &target, &mut ())
+            .unwrap();
+        let out = mock.home.join("team.tar.gz");
+        let exported = export_bundle(&engine, &out, &current_target(), |_| {}).unwrap();
+
+        // A teammate with no registry and no network
+        std::fs::remove_dir_all(&mock.registry_source).unwrap();
+        let mut teammate = RushEngine::builder()
+            .root(mock.home.join("teammate"))
+            .registry_source("/nonexistent")
+            .build()
+            .unwrap();
+
+        let err = teammate
+            .import_bundle(&out, "sparc-solaris", &mut ())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::Error>(),
+            Some(crate::Error::PlanConflicts(_))
+        ));
+        assert!(teammate.state.packages.is_empty());
+
+        let outcomes = teammate
+            .import_bundle(&out, &current_target(), &mut ())
+            .unwrap();
+        assert!(matches!(&outcomes[..], [InstallOutcome::Installed(r)] if r.version == "1.0.0"));
+        assert!(teammate.bin_path.join("tool").exists());
+        assert!(!teammate.offline);
+        assert!(
+            registry::artifact_file(&teammate.cache_dir, &target.sha256)
+                .unwrap()
+                .is_file()
+        );
+        // Recorded, so `clean --all` keeps it
+        assert!(imported_artifacts(&teammate.cache_dir).contains(&target.sha256));
+
+        let outcomes = teammate
+            .import_bundle(&out, &current_target(), &mut ())
+            .unwrap();
+        assert!(matches!(
+            &outcomes[..],
+            [InstallOutcome::AlreadyInstalled { version, .. }] if version == "1.0.0"
+        ));
+
+        // An artifact that doesn't match the lockfile is refused before anything installs
+        let tampered = mock.home.join("tampered.tar.gz");
+        let mut tar = tar::Builder::new(GzEncoder::new(
+            std::fs::File::create(&tampered).unwrap(),
+            Compression::default(),
+        ));
+        append(&mut tar, &format!("artifacts/{}", target.sha256), b"evil").unwrap();
+        let lockfile = toml::to_string(&exported.lockfile).unwrap();
+        append(&mut tar, LOCKFILE, lockfile.as_bytes()).unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let err = teammate
+            .import_bundle(&tampered, &current_target(), &mut ())
+            .unwrap_err();
+        assert!(err.to_string().contains("corrupt"));
+    }
+}
@@ -0,0 +1,276 @@
+//! Aborting long operations from another thread (e.g. a Ctrl-C handler).
+//!
+//! Every download goes through a `CancellableTransport`, so cancelling stops it at the
+//! next chunk; installs also check between extraction steps. An install that is cancelled
+//! never moves a binary into place or saves state, and its temp files are dropped.
+//!
+//! A `Deadline` stops one operation the same way once it has run too long, without
+//! touching the others sharing the engine.
+
+use crate::Error;
+use crate::core::{Transport, TransportResponse};
+use anyhow::Result;
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Shared flag that cancels the engine's in-flight and future operations.
+/// Clones share the flag. Once cancelled, operations fail with `rush::Error::Cancelled`
+/// until `reset()`.
+#[derive(Clone, Default, Debug)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Let operations run again
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    /// Fails with `Error::Cancelled` if cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Whatever went wrong after a cancel (an aborted read, a half-written file) is
+    /// reported as the cancel itself
+    pub(crate) fn settle<T>(&self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(_) if self.is_cancelled() => Err(Error::Cancelled.into()),
+            other => other,
+        }
+    }
+}
+
+/// Wraps the engine's transport so downloads stop once the token is cancelled
+pub(crate) struct CancellableTransport {
+    inner: Box<dyn Transport>,
+    token: CancellationToken,
+}
+
+impl CancellableTransport {
+    pub(crate) fn new(inner: Box<dyn Transport>, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+}
+
+impl Transport for CancellableTransport {
+    fn get(&self, url: &str) -> Result<TransportResponse> {
+        self.token.check()?;
+        let response = self.inner.get(url)?;
+        Ok(TransportResponse {
+            content_length: response.content_length,
+            body: Box::new(CancellableBody {
+                inner: response.body,
+                token: self.token.clone(),
+            }),
+        })
+    }
+
+    fn head(&self, url: &str) -> Result<Option<u64>> {
+        self.token.check()?;
+        self.inner.head(url)
+    }
+}
+
+struct CancellableBody {
+    inner: Box<dyn Read + Send>,
+    token: CancellationToken,
+}
+
+impl Read for CancellableBody {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.token.is_cancelled() {
+            return Err(std::io::Error::other(Error::Cancelled));
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// A time limit on one operation, started when it was made.
+/// Past it, `check` fails with `rush::Error::TimedOut`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Deadline {
+    at: Instant,
+    limit: Duration,
+}
+
+impl Deadline {
+    pub(crate) fn after(limit: Duration) -> Self {
+        Self {
+            at: Instant::now() + limit,
+            limit,
+        }
+    }
+
+    /// How long is left, for a request's own timeout
+    pub(crate) fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    pub(crate) fn check(&self) -> Result<()> {
+        if Instant::now() >= self.at {
+            return Err(self.error().into());
+        }
+        Ok(())
+    }
+
+    fn error(&self) -> Error {
+        Error::TimedOut { after: self.limit }
+    }
+}
+
+/// Wraps a transport so downloads through it stop at `deadline`
+pub(crate) struct DeadlineTransport<'a> {
+    pub(crate) inner: &'a dyn Transport,
+    pub(crate) deadline: Deadline,
+}
+
+impl Transport for DeadlineTransport<'_> {
+    fn get(&self, url: &str) -> Result<TransportResponse> {
+        self.deadline.check()?;
+        let response = self.inner.get(url)?;
+        Ok(TransportResponse {
+            content_length: response.content_length,
+            body: Box::new(DeadlineBody {
+                inner: response.body,
+                deadline: self.deadline,
+            }),
+        })
+    }
+
+    fn head(&self, url: &str) -> Result<Option<u64>> {
+        self.deadline.check()?;
+        self.inner.head(url)
+    }
+}
+
+struct DeadlineBody {
+    inner: Box<dyn Read + Send>,
+    deadline: Deadline,
+}
+
+impl Read for DeadlineBody {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.deadline.remaining().is_zero() {
+            return Err(std::io::Error::other(self.deadline.error()));
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RushEngine;
+    use crate::core::transport::mock::{MockResponse, MockTransport};
+    use crate::models::{ArtifactFormat, ProgressEvent, ProgressKind, Reporter, TargetDefinition};
+    use tempfile::tempdir;
+
+    /// Cancels as soon as the first chunk of the download arrives
+    struct CancelMidDownload(CancellationToken);
+
+    impl Reporter for CancelMidDownload {
+        fn progress(&mut self, event: ProgressEvent) {
+            if let ProgressKind::Progress { bytes, .. } = event.kind
+                && bytes > 0
+            {
+                self.0.cancel();
+            }
+        }
+    }
+
+    #[test]
+    fn test_cancel_mid_download_leaves_no_trace() {
+        let temp_dir = tempdir().unwrap();
+        let transport = MockTransport::default().with(
+            "https://example.com/tool.tar.gz",
+            MockResponse::Slow(vec![0; 64]),
+        );
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .transport(transport)
+            .build()
+            .unwrap();
+        let token = engine.cancellation_token();
+
+        let target = TargetDefinition {
+            url: "https://example.com/tool.tar.gz".to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: "0".repeat(64),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::Archive,
+            extract: false,
+            files: Vec::new(),
+        };
+        let err = engine
+            .install_package(
+                "tool",
+                "1.0.0",
+                &target,
+                &mut CancelMidDownload(token.clone()),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::Cancelled),
+            "{:#}",
+            err
+        );
+        assert!(engine.state.packages.is_empty());
+        let leftovers: Vec<_> = std::fs::read_dir(&engine.bin_path).unwrap().collect();
+        assert!(leftovers.is_empty());
+
+        // Still cancelled until reset
+        assert!(engine.update_registry(&mut ()).is_err());
+        token.reset();
+        assert!(token.check().is_ok());
+    }
+
+    #[test]
+    fn test_deadline_stops_a_download() {
+        let transport = MockTransport::default().with(
+            "https://example.com/tool.tar.gz",
+            MockResponse::Slow(vec![0; 64]),
+        );
+        let limit = Duration::from_millis(50);
+        let limited = DeadlineTransport {
+            inner: &transport,
+            deadline: Deadline::after(limit),
+        };
+
+        // Started in time, but still streaming at the deadline
+        let mut body = limited.get("https://example.com/tool.tar.gz").unwrap().body;
+        std::thread::sleep(limit);
+        let err = body.read(&mut [0; 8]).unwrap_err();
+        assert_eq!(err.to_string(), "Gave up after 0s");
+
+        // Past it, nothing starts
+        let Err(err) = limited.get("https://example.com/tool.tar.gz") else {
+            panic!("the download started past its deadline");
+        };
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::TimedOut { after: limit })
+        );
+    }
+}
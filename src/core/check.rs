@@ -0,0 +1,85 @@
+use crate::core::{RushEngine, sandbox};
+use crate::models::{CheckResult, PackageManifest};
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// How long a `check` may run before it is killed and counted as failed
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run the manifest's `check` against the installed binary of `name`, in the sandbox.
+/// `None` if the manifest has no check.
+#[tracing::instrument(level = "debug", skip(engine, manifest))]
+pub fn check_package(
+    engine: &RushEngine,
+    name: &str,
+    manifest: &PackageManifest,
+) -> Result<Option<CheckResult>> {
+    let Some(check) = &manifest.check else {
+        return Ok(None);
+    };
+    let bin = engine
+        .state
+        .packages
+        .get(name)
+        .and_then(|pkg| pkg.binaries.first())
+        .with_context(|| format!("'{}' is not installed", name))?;
+
+    let program = engine.bin_path.join(bin);
+    let args: Vec<&str> = check.split_whitespace().collect();
+    let output = sandbox::run(&program, &args, CHECK_TIMEOUT)?;
+
+    Ok(Some(CheckResult {
+        package: name.to_string(),
+        command: format!("{} {}", bin, check).trim_end().to_string(),
+        exit_code: output.exit_code,
+        timed_out: output.timed_out,
+        stdout: output.stdout,
+        stderr: output.stderr,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InstalledPackage;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn test_check_runs_installed_binary() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+        let mut manifest: PackageManifest =
+            toml::from_str("version = \"1.0.0\"\ncheck = \"--version\"\n[targets]\n").unwrap();
+
+        let err = check_package(&engine, "tool", &manifest).unwrap_err();
+        assert_eq!(err.to_string(), "'tool' is not installed");
+
+        std::fs::create_dir_all(&engine.bin_path).unwrap();
+        let bin = engine.bin_path.join("tool");
+        std::fs::write(&bin, "#!/bin/sh\necho \"tool $1\"\n").unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        engine.state.packages.insert(
+            "tool".to_string(),
+            InstalledPackage {
+                version: "1.0.0".to_string(),
+                binaries: vec!["tool".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+
+        let result = check_package(&engine, "tool", &manifest).unwrap().unwrap();
+        assert!(result.succeeded());
+        assert_eq!(result.command, "tool --version");
+        assert_eq!(result.stdout, "tool --version");
+
+        manifest.check = None;
+        assert!(check_package(&engine, "tool", &manifest).unwrap().is_none());
+    }
+}
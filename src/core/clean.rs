@@ -1,22 +1,42 @@
-use super::RushEngine;
-use crate::models::CleanResult;
+use super::{RushEngine, bundle, lock, pending, query, registry, run};
+use crate::models::{CleanAllResult, CleanResult, Leftover, LeftoverKind};
 use anyhow::Result;
+use std::collections::HashSet;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Prefix of the temp files atomic installs write next to the final binary
+pub(crate) const TRASH_PREFIX: &str = ".rush-tmp-";
+
+/// Prefix of the temp files written by this process (`.rush-tmp-<pid>-...`)
+pub(crate) fn own_trash_prefix() -> String {
+    format!("{}{}-", TRASH_PREFIX, std::process::id())
+}
+
+/// Prefix of the staging directories `tempfile` makes in the cache (e.g. for bundles)
+const STAGING_PREFIX: &str = ".tmp";
+
+/// How old a staging directory must be to count as abandoned rather than in use
+const STAGING_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
 pub fn clean_trash(engine: &RushEngine) -> Result<CleanResult> {
-    let bin_dir = fs::read_dir(&engine.bin_path)?;
-    let mut deleted_files = Vec::new();
+    remove_trash(engine, TRASH_PREFIX)
+}
 
-    for entry in bin_dir {
-        let entry = entry?;
-        let path = entry.path();
+/// Only this process's leftovers, so concurrent installs keep their temp files
+pub fn clean_own_trash(engine: &RushEngine) -> Result<CleanResult> {
+    remove_trash(engine, &own_trash_prefix())
+}
 
-        if let Some(name) = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .filter(|n| n.starts_with(".rush-tmp-"))
-        {
-            fs::remove_file(&path)?;
+fn remove_trash(engine: &RushEngine, prefix: &str) -> Result<CleanResult> {
+    // Don't sweep up a temp file an install is about to move into place
+    let _lock = lock::lock_dir(&engine.bin_path);
+    let mut deleted_files = Vec::new();
+
+    for path in find_trash(engine, prefix)? {
+        fs::remove_file(&path)?;
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
             deleted_files.push(name.to_string());
         }
     }
@@ -26,19 +46,229 @@ pub fn clean_trash(engine: &RushEngine) -> Result<CleanResult> {
     })
 }
 
+/// Remove everything `find_leftovers` turns up, and the records of packages that are gone
+pub fn clean_all(engine: &mut RushEngine) -> Result<CleanAllResult> {
+    let _lock = lock::lock_dir(&engine.bin_path);
+    let result = find_leftovers(engine)?;
+
+    for leftover in &result.leftovers {
+        if leftover.path.is_dir() {
+            fs::remove_dir_all(&leftover.path)?;
+        } else {
+            fs::remove_file(&leftover.path)?;
+        }
+    }
+    if !result.receipts.is_empty() {
+        for name in &result.receipts {
+            engine.state.packages.remove(name);
+        }
+        engine.save()?;
+    }
+    Ok(result)
+}
+
+/// Everything rush put on disk that nothing uses anymore: temp files, orphaned cache
+/// entries, AppImage versions that were replaced, `.new` and `.old` copies of binaries
+/// that are no longer installed, and installed-state records of packages
+/// whose files are all gone
+pub fn find_leftovers(engine: &RushEngine) -> Result<CleanAllResult> {
+    let mut leftovers = Vec::new();
+    let mut add = |kind, path: PathBuf| {
+        let bytes = disk_size(&path);
+        leftovers.push(Leftover { kind, path, bytes });
+    };
+
+    for path in find_trash(engine, TRASH_PREFIX)? {
+        add(LeftoverKind::TempFile, path);
+    }
+    for path in stale_staging(&engine.cache_dir) {
+        add(LeftoverKind::TempFile, path);
+    }
+    for path in orphaned_cache(engine) {
+        add(LeftoverKind::Cache, path);
+    }
+    for path in old_versions(engine) {
+        add(LeftoverKind::OldVersion, path);
+    }
+    let binaries: HashSet<&str> = engine
+        .state
+        .packages
+        .values()
+        .flat_map(|pkg| pkg.binaries.iter().map(String::as_str))
+        .collect();
+    for path in pending::orphaned(&engine.bin_path, &binaries) {
+        add(LeftoverKind::Staged, path);
+    }
+
+    let mut receipts: Vec<String> = engine
+        .state
+        .packages
+        .iter()
+        .filter(|(_, pkg)| {
+            let binaries = pkg.binaries.iter().map(|b| engine.bin_path.join(b));
+            let files = pkg.files.iter().map(|f| engine.data_dir.join(f));
+            let mut paths = binaries.chain(files).peekable();
+            paths.peek().is_some() && paths.all(|p| p.symlink_metadata().is_err())
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    receipts.sort();
+
+    Ok(CleanAllResult {
+        leftovers,
+        receipts,
+    })
+}
+
+/// Helper: Staging directories in the cache no process has touched for `STAGING_MAX_AGE`
+fn stale_staging(cache_dir: &Path) -> Vec<PathBuf> {
+    let now = SystemTime::now();
+    entries(cache_dir)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(STAGING_PREFIX))
+        })
+        .filter(|path| {
+            fs::metadata(path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| {
+                    now.duration_since(modified).unwrap_or_default() > STAGING_MAX_AGE
+                })
+        })
+        .collect()
+}
+
+/// Helper: Cached artifacts that no release in the registry, installed package, or
+/// imported bundle has the sha256 of, and `rush run` copies of packages or versions the
+/// registry no longer has. With no synced registry, nothing can be told to be orphaned,
+/// so nothing is.
+fn orphaned_cache(engine: &RushEngine) -> Vec<PathBuf> {
+    let available = query::list_available_packages(engine);
+    if available.is_empty() {
+        return Vec::new();
+    }
+    let mut hashes: HashSet<String> = available
+        .iter()
+        .flat_map(|(_, manifest)| manifest.releases())
+        .flat_map(|(_, targets)| targets.values())
+        .map(|target| target.sha256.clone())
+        .collect();
+    hashes.extend(
+        engine
+            .state
+            .packages
+            .values()
+            .filter_map(|pkg| pkg.provenance.as_ref())
+            .map(|provenance| provenance.sha256.clone()),
+    );
+    hashes.extend(bundle::imported_artifacts(&engine.cache_dir));
+
+    let mut orphaned: Vec<PathBuf> = entries(&engine.cache_dir.join(registry::ARTIFACTS_DIR))
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_none_or(|sha256| !hashes.contains(sha256))
+        })
+        .collect();
+
+    for package_dir in entries(&engine.cache_dir.join(run::RUN_DIR)) {
+        let manifest = package_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|name| available.iter().find(|(n, _)| n == name))
+            .map(|(_, manifest)| manifest);
+        match manifest {
+            None => orphaned.push(package_dir),
+            Some(manifest) => orphaned.extend(entries(&package_dir).into_iter().filter(|dir| {
+                let version = dir.file_name().and_then(|n| n.to_str());
+                !manifest.releases().any(|(v, _)| Some(v) == version)
+            })),
+        }
+    }
+    orphaned
+}
+
+/// Helper: Stored AppImage versions other than the installed one, and those of packages
+/// no longer installed
+fn old_versions(engine: &RushEngine) -> Vec<PathBuf> {
+    let mut old = Vec::new();
+    for app_dir in entries(&engine.apps_dir) {
+        let installed = app_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|name| engine.state.packages.get(name));
+        match installed {
+            None => old.push(app_dir),
+            Some(pkg) => old.extend(
+                entries(&app_dir)
+                    .into_iter()
+                    .filter(|version| version.file_name() != Some(pkg.version.as_ref())),
+            ),
+        }
+    }
+    old
+}
+
+/// Helper: The paths in `dir`, sorted; none if it doesn't exist
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Helper: The bytes in `path`, everything under it for a directory; symlinks aren't followed
+fn disk_size(path: &Path) -> u64 {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return 0;
+    };
+    if metadata.is_dir() {
+        entries(path).iter().map(|p| disk_size(p)).sum()
+    } else {
+        metadata.len()
+    }
+}
+
+/// The temp files in the bin dir whose names start with `prefix`
+pub(crate) fn find_trash(engine: &RushEngine, prefix: &str) -> Result<Vec<PathBuf>> {
+    let mut trash = Vec::new();
+
+    for entry in fs::read_dir(&engine.bin_path)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(prefix))
+        {
+            trash.push(path);
+        }
+    }
+
+    Ok(trash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::InstalledPackage;
+    use crate::test_utils::{MockEnvironment, current_target};
     use tempfile::tempdir;
 
     #[test]
     fn test_clean_trash() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let bin_path = root.join(".local/bin");
+        let bin_path = root.join(".local/share/rush/shims");
 
         // 1. Initialize Engine (creates folders)
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         let real_bin = bin_path.join("ripgrep");
         fs::write(&real_bin, "I am a real program").unwrap();
@@ -60,4 +290,157 @@ mod tests {
         assert!(!trash1.exists(), "Trash file 1 still exists!");
         assert!(!trash2.exists(), "Trash file 2 still exists!");
     }
+
+    #[test]
+    fn test_clean_own_trash_spares_other_processes() {
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let ours = engine.bin_path.join(format!("{}abc", own_trash_prefix()));
+        let theirs = engine.bin_path.join(".rush-tmp-0-abc");
+        fs::write(&ours, "junk").unwrap();
+        fs::write(&theirs, "junk").unwrap();
+
+        let result = engine.clean_own_trash().unwrap();
+        assert_eq!(result.files_cleaned.len(), 1);
+        assert!(!ours.exists());
+        assert!(theirs.exists());
+    }
+
+    #[test]
+    fn test_clean_all_removes_only_leftovers() {
+        let mock = MockEnvironment::new();
+        // 0.9.0 is still offered, as an older release
+        let manifest_path = mock.registry_source.join("packages/t/tool.toml");
+        mock.add_package("tool", "0.9.0", "tool-bin");
+        let older = crate::core::load_manifest(&manifest_path).unwrap();
+        mock.add_package("tool", "1.0.0", "tool-bin");
+        let mut manifest = crate::core::load_manifest(&manifest_path).unwrap();
+        manifest.history = vec![crate::models::Release {
+            version: older.version,
+            targets: older.targets.clone(),
+        }];
+        fs::write(&manifest_path, toml::to_string(&manifest).unwrap()).unwrap();
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let sha256 = manifest.targets[&current_target()].sha256.clone();
+        let older_sha256 = older.targets[&current_target()].sha256.clone();
+        // Installed from an artifact the registry no longer lists, and one a bundle brought
+        let installed_sha256 = "cd".repeat(32);
+        let bundled_sha256 = "ef".repeat(32);
+
+        let package = |version: &str, bin: &str| InstalledPackage {
+            version: version.to_string(),
+            binaries: vec![bin.to_string()],
+            files: Vec::new(),
+            provenance: None,
+            constraint: None,
+            pinned: false,
+            dependency: false,
+        };
+        let mut tool = package("1.0.0", "tool-bin");
+        tool.provenance = Some(crate::models::Provenance {
+            registry: None,
+            url: "https://example.com/tool.tar.gz".to_string(),
+            sha256: installed_sha256.clone(),
+            installed_at: "2025-01-31T12:00:00Z".to_string(),
+        });
+        engine.state.packages.insert("tool".to_string(), tool);
+        engine
+            .state
+            .packages
+            .insert("gone".to_string(), package("2.0.0", "gone-bin"));
+        fs::write(engine.bin_path.join("tool-bin"), "tool").unwrap();
+
+        let write = |path: PathBuf| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "12345").unwrap();
+            path
+        };
+        let cache = engine.cache_dir.clone();
+        let apps = engine.apps_dir.clone();
+        let record = cache.join("bundles/record.lock");
+        fs::create_dir_all(record.parent().unwrap()).unwrap();
+        let lockfile = format!(
+            "[[package]]\nname = \"other\"\nversion = \"1.0.0\"\ntarget = \"{}\"\n\
+             url = \"https://example.com/other.tar.gz\"\nbin = \"other\"\nsha256 = \"{}\"\n",
+            current_target(),
+            bundled_sha256
+        );
+        fs::write(&record, lockfile).unwrap();
+        // Waiting to replace an installed binary, and left behind by one that is gone
+        let waiting = write(engine.bin_path.join("tool-bin.new"));
+        let staged = [
+            write(engine.bin_path.join("dropped-bin.new")),
+            write(engine.bin_path.join("dropped-bin.old")),
+        ];
+        let kept = [
+            engine.bin_path.join("tool-bin"),
+            waiting,
+            record,
+            write(cache.join("artifacts").join(&sha256)),
+            write(cache.join("artifacts").join(&older_sha256)),
+            write(cache.join("artifacts").join(&installed_sha256)),
+            write(cache.join("artifacts").join(&bundled_sha256)),
+            write(cache.join("run/tool/1.0.0/tool-bin")),
+            write(cache.join("run/tool/0.9.0/tool-bin")),
+            write(apps.join("tool/1.0.0/tool.AppImage")),
+            // Fresh, so possibly in use
+            write(cache.join(".tmpAbC/staged")),
+        ];
+        let trash = write(engine.bin_path.join(".rush-tmp-1-x"));
+        let orphans = [
+            write(cache.join("artifacts").join("ab".repeat(32))),
+            write(cache.join("run/tool/0.8.0/tool-bin")),
+            write(cache.join("run/dropped/1.0.0/dropped")),
+        ];
+        let old_versions = [
+            write(apps.join("tool/0.9.0/tool.AppImage")),
+            write(apps.join("other/1.0.0/other.AppImage")),
+        ];
+
+        let preview = engine.find_leftovers().unwrap();
+        let kinds = |kind| {
+            preview
+                .leftovers
+                .iter()
+                .filter(|l| l.kind == kind)
+                .map(|l| l.path.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kinds(LeftoverKind::TempFile), vec![trash.clone()]);
+        assert_eq!(
+            kinds(LeftoverKind::Cache),
+            [
+                orphans[0].clone(),
+                cache.join("run/dropped"),
+                cache.join("run/tool/0.8.0")
+            ]
+        );
+        assert_eq!(
+            kinds(LeftoverKind::OldVersion),
+            [apps.join("other"), apps.join("tool/0.9.0")]
+        );
+        assert_eq!(kinds(LeftoverKind::Staged), staged);
+        assert_eq!(preview.receipts, ["gone"]);
+        assert_eq!(preview.bytes(), 8 * 5);
+        // Only a preview
+        assert!(trash.exists() && engine.state.packages.contains_key("gone"));
+
+        assert_eq!(engine.clean_all().unwrap(), preview);
+        for path in kept {
+            assert!(path.exists(), "{:?} was removed", path);
+        }
+        for path in [trash]
+            .iter()
+            .chain(&orphans)
+            .chain(&old_versions)
+            .chain(&staged)
+        {
+            assert!(!path.exists(), "{:?} is still there", path);
+        }
+        assert!(!engine.state.packages.contains_key("gone"));
+        assert!(mock.engine().unwrap().state.packages.contains_key("tool"));
+        assert!(engine.find_leftovers().unwrap().is_empty());
+    }
 }
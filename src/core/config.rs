@@ -0,0 +1,51 @@
+use crate::models::Config;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Name of the config file inside the config dir
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Loads `config.toml` from `config_dir`; a missing file means the defaults.
+/// A file that exists but doesn't parse is an error, so typos don't go unnoticed.
+pub fn load_config(config_dir: &Path) -> Result<Config> {
+    let path = config_dir.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    toml::from_str(&content).with_context(|| format!("Invalid config file {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_config() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(load_config(temp_dir.path()).unwrap(), Config::default());
+
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "[[scoring.rules]]\nkeyword = \"static\"\nweight = -20\n",
+        )
+        .unwrap();
+        assert_eq!(load_config(temp_dir.path()).unwrap().scoring.rules.len(), 1);
+
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "[http]\npool_max_idle_per_host = 4\nhttp1_only = true\n",
+        )
+        .unwrap();
+        let http = load_config(temp_dir.path()).unwrap().http;
+        assert_eq!(http.max_idle_per_host(), 4);
+        assert_eq!(http.idle_timeout().as_secs(), 90);
+        assert!(http.http1_only);
+
+        std::fs::write(temp_dir.path().join(CONFIG_FILE), "[[scoring.rules]]\n").unwrap();
+        let err = load_config(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("Invalid config file"));
+    }
+}
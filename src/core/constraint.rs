@@ -0,0 +1,198 @@
+//! Version constraints kept with installed packages (`rush pin node "~20"`), and pins
+//! that hold one at its installed version (`rush pin node`).
+//!
+//! The syntax is Cargo's, applied to dotted versions: `=1.2.3`, `>=1.2`, `<2`, `~1.2`
+//! (same minor), `^1.2` or a bare `1.2` (same major, or same minor below 1.0), `1.*`, and
+//! `*`. Comma-separated parts must all hold (`>=1.2, <1.5`).
+
+use crate::core::RushEngine;
+use crate::core::requires::compare_versions;
+use anyhow::{Result, bail};
+use std::cmp::Ordering;
+
+/// Keep upgrades of installed `name` to versions `constraint` allows; false if it isn't
+/// installed
+pub(crate) fn pin(engine: &mut RushEngine, name: &str, constraint: &str) -> Result<bool> {
+    Constraint::parse(constraint)?;
+    let Some(installed) = engine.state.packages.get_mut(name) else {
+        return Ok(false);
+    };
+    installed.constraint = Some(constraint.trim().to_string());
+    installed.pinned = false;
+    engine.save()?;
+    Ok(true)
+}
+
+/// Keep installed `name` at its installed version; false if it isn't installed
+pub(crate) fn hold(engine: &mut RushEngine, name: &str) -> Result<bool> {
+    let Some(installed) = engine.state.packages.get_mut(name) else {
+        return Ok(false);
+    };
+    installed.pinned = true;
+    engine.save()?;
+    Ok(true)
+}
+
+/// Drop installed `name`'s pin and constraint, so upgrades move it to the registry's
+/// version again; false if it isn't installed
+pub(crate) fn unpin(engine: &mut RushEngine, name: &str) -> Result<bool> {
+    let Some(installed) = engine.state.packages.get_mut(name) else {
+        return Ok(false);
+    };
+    installed.pinned = false;
+    installed.constraint = None;
+    engine.save()?;
+    Ok(true)
+}
+
+/// A parsed constraint
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Constraint {
+    bounds: Vec<(Op, Vec<u64>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Constraint {
+    /// Parse `constraint`, e.g. "~20" or ">=1.2, <1.5"
+    pub(crate) fn parse(constraint: &str) -> Result<Self> {
+        let mut bounds = Vec::new();
+        for part in constraint.split(',').map(str::trim) {
+            if part.is_empty() {
+                bail!("Invalid version constraint '{}'", constraint);
+            }
+            if part == "*" {
+                continue;
+            }
+            let (op, version) = ["<=", ">=", "=", "<", ">", "~", "^"]
+                .into_iter()
+                .find_map(|op| part.strip_prefix(op).map(|rest| (op, rest.trim())))
+                .unwrap_or(("^", part));
+            let wildcard = version.ends_with(".*");
+            let parts = parse_parts(version.trim_end_matches(".*"))
+                .ok_or_else(|| anyhow::anyhow!("Invalid version constraint '{}'", constraint))?;
+
+            match op {
+                // 1.2.* stays below 1.3
+                _ if wildcard => {
+                    let bump = parts.len() - 1;
+                    range(&mut bounds, parts, bump)
+                }
+                "=" => bounds.push((Op::Eq, parts)),
+                ">" => bounds.push((Op::Gt, parts)),
+                ">=" => bounds.push((Op::Ge, parts)),
+                "<" => bounds.push((Op::Lt, parts)),
+                "<=" => bounds.push((Op::Le, parts)),
+                // ~1.2.3 and ~1.2 stay below 1.3, ~1 below 2
+                "~" => {
+                    let bump = if parts.len() > 1 { 1 } else { 0 };
+                    range(&mut bounds, parts, bump)
+                }
+                // ^1.2 stays below 2, ^0.2 below 0.3, ^0.0.3 below 0.0.4
+                _ => {
+                    let bump = parts
+                        .iter()
+                        .position(|&p| p != 0)
+                        .unwrap_or(parts.len() - 1);
+                    range(&mut bounds, parts, bump)
+                }
+            }
+        }
+        Ok(Constraint { bounds })
+    }
+
+    /// Whether `version` satisfies every part of the constraint
+    pub(crate) fn matches(&self, version: &str) -> bool {
+        self.bounds.iter().all(|(op, bound)| {
+            let bound = join(bound);
+            let ordering = compare_versions(version, &bound);
+            match op {
+                Op::Eq => ordering == Ordering::Equal,
+                Op::Gt => ordering == Ordering::Greater,
+                Op::Ge => ordering != Ordering::Less,
+                Op::Lt => ordering == Ordering::Less,
+                Op::Le => ordering != Ordering::Greater,
+            }
+        })
+    }
+}
+
+/// Helper: `>= parts, < parts with parts[bump] + 1`
+fn range(bounds: &mut Vec<(Op, Vec<u64>)>, parts: Vec<u64>, bump: usize) {
+    let mut upper: Vec<u64> = parts[..=bump].to_vec();
+    upper[bump] += 1;
+    bounds.push((Op::Ge, parts));
+    bounds.push((Op::Lt, upper));
+}
+
+/// Helper: "1.2.3" (or "v1.2.3") -> [1, 2, 3]; None unless every part is a number
+fn parse_parts(version: &str) -> Option<Vec<u64>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()
+}
+
+/// Helper: [1, 2, 3] -> "1.2.3"
+fn join(parts: &[u64]) -> String {
+    parts
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraint_matches() {
+        let cases = [
+            ("~20", "20.11.1", true),
+            ("~20", "21.0.0", false),
+            ("~1.2", "1.2.9", true),
+            ("~1.2", "1.3.0", false),
+            ("^1.2", "1.9.0", true),
+            ("1.2", "2.0.0", false),
+            ("^0.2.3", "0.2.9", true),
+            ("^0.2.3", "0.3.0", false),
+            ("^0.2.3", "0.2.1", false),
+            ("=1.2.3", "1.2.3", true),
+            ("=1.2.3", "1.2.4", false),
+            (">=1.2, <1.5", "1.4.9", true),
+            (">=1.2, <1.5", "1.5.0", false),
+            ("> 2", "2.0.1", true),
+            ("<= 2.1", "2.1.0", true),
+            ("1.*", "1.99.0", true),
+            ("1.*", "2.0.0", false),
+            ("1.2.*", "1.3.0", false),
+            ("*", "0.0.1", true),
+            ("v20", "20.0.0", true),
+        ];
+        for (constraint, version, expected) in cases {
+            assert_eq!(
+                Constraint::parse(constraint).unwrap().matches(version),
+                expected,
+                "{} against {}",
+                version,
+                constraint
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_constraint() {
+        for constraint in ["~latest", ">=1.x", "1.2,", "=", "^1..2", ""] {
+            assert!(Constraint::parse(constraint).is_err(), "{}", constraint);
+        }
+    }
+}
@@ -0,0 +1,438 @@
+//! Data packages: fonts, color schemes, grammars and the like, copied out of an artifact
+//! into the XDG data directory as the target's `files = [{ src, dest }]` says.
+//!
+//! Every file written is recorded on the installed package (relative to the data dir),
+//! like its binaries, so an upgrade drops the files the new version no longer ships and
+//! an uninstall removes them all. Like a binary, a file belongs to one package: another
+//! package can't write to it.
+
+use crate::core::{CancellationToken, RushEngine, archive, clean};
+use crate::models::{Conflict, DataFile, TargetDefinition};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Copy the target's `files` out of the artifact in `content`, returning what was
+/// written, relative to the data dir. Every `src` must match something, and no `dest`
+/// may overlap files another installed package owns.
+pub(crate) fn install(
+    engine: &RushEngine,
+    name: &str,
+    target: &TargetDefinition,
+    content: &[u8],
+) -> Result<Vec<String>> {
+    if target.files.is_empty() {
+        return Ok(Vec::new());
+    }
+    for file in &target.files {
+        check_dest(&file.dest)?;
+        if let Some((owner, _)) = owner(engine, name, &file.dest) {
+            bail!(
+                "{}",
+                Conflict::DataFileClash {
+                    package: name.to_string(),
+                    file: file.dest.clone(),
+                    owner: owner.to_string(),
+                }
+            );
+        }
+    }
+
+    let mut matched = vec![false; target.files.len()];
+    let mut installed = Vec::new();
+    archive::visit_files::<()>(content, &mut |path, mode, reader| {
+        let Some((i, dest)) = target
+            .files
+            .iter()
+            .enumerate()
+            .find_map(|(i, file)| destination(path, file).map(|dest| (i, dest)))
+        else {
+            return Ok(None);
+        };
+        if !is_relative(&dest) {
+            bail!(
+                "Refusing to install {} outside the data dir",
+                path.display()
+            );
+        }
+
+        write_file(&engine.data_dir.join(&dest), mode, reader, &engine.cancel)?;
+        matched[i] = true;
+        installed.push(dest.to_string_lossy().into_owned());
+        Ok(None)
+    })?;
+
+    all_matched(target, &matched)?;
+    installed.sort();
+    installed.dedup();
+    Ok(installed)
+}
+
+/// Make sure every one of the target's `files` is in the artifact, without writing any
+pub(crate) fn check_sources(target: &TargetDefinition, content: &[u8]) -> Result<()> {
+    let mut matched = vec![false; target.files.len()];
+    archive::visit_files::<()>(content, &mut |path, _, _| {
+        for (i, file) in target.files.iter().enumerate() {
+            matched[i] |= destination(path, file).is_some();
+        }
+        Ok(None)
+    })?;
+    all_matched(target, &matched)
+}
+
+/// The installed package other than `name` owning `dest` (relative to the data dir) or a
+/// file under it, and that file
+pub(crate) fn owner<'a>(
+    engine: &'a RushEngine,
+    name: &str,
+    dest: &str,
+) -> Option<(&'a str, &'a str)> {
+    engine
+        .state
+        .packages
+        .iter()
+        .filter(|(owner, _)| *owner != name)
+        .find_map(|(owner, pkg)| {
+            let file = pkg.files.iter().find(|file| overlaps(file, dest))?;
+            Some((owner.as_str(), file.as_str()))
+        })
+}
+
+/// Whether two paths relative to the data dir are the same, or one is inside the other
+/// (a `dest` can be a directory)
+pub(crate) fn overlaps(a: &str, b: &str) -> bool {
+    let (a, b) = (Path::new(a), Path::new(b));
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// Delete `files` (relative to the data dir) and any directories they leave empty,
+/// returning the ones that were there
+pub(crate) fn remove(engine: &RushEngine, files: &[String]) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for file in files {
+        let path = engine.data_dir.join(file);
+        if !path.is_file() {
+            continue;
+        }
+        fs::remove_file(&path)?;
+        removed.push(file.clone());
+
+        // Stops at the first directory something else still lives in
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| *d != engine.data_dir) {
+            if fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+    Ok(removed)
+}
+
+/// The manual pages among installed `name`'s files (`man/man1/tool.1`, ...), as full
+/// paths; None if it isn't installed
+pub(crate) fn man_pages(engine: &RushEngine, name: &str) -> Option<Vec<PathBuf>> {
+    let installed = engine.state.packages.get(name)?;
+    Some(
+        installed
+            .files
+            .iter()
+            .filter(|file| is_man_page(Path::new(file)))
+            .map(|file| engine.data_dir.join(file))
+            .collect(),
+    )
+}
+
+/// Helper: Whether `path` is `man/man<section>/<page>.<section>` (or `.3p` and the like),
+/// perhaps gzipped
+fn is_man_page(path: &Path) -> bool {
+    let mut components = path.components().map(|c| c.as_os_str().to_string_lossy());
+    let (Some(root), Some(dir), Some(file), None) = (
+        components.next(),
+        components.next(),
+        components.next(),
+        components.next(),
+    ) else {
+        return false;
+    };
+    let Some(section) = dir.strip_prefix("man").filter(|s| !s.is_empty()) else {
+        return false;
+    };
+    let file = file.strip_suffix(".gz").unwrap_or(&file);
+    root == "man"
+        && file
+            .rsplit_once('.')
+            .is_some_and(|(page, ext)| !page.is_empty() && ext.starts_with(section))
+}
+
+/// A `dest` must stay inside the data dir
+pub(crate) fn check_dest(dest: &str) -> Result<()> {
+    if !is_relative(Path::new(dest)) {
+        bail!(
+            "files: dest '{}' must be a relative path inside the data dir",
+            dest
+        );
+    }
+    Ok(())
+}
+
+/// Helper: Where an archive entry goes if `file` takes it, relative to the data dir.
+/// `src` is matched at any depth, so it needn't spell out the archive's top-level directory.
+fn destination(path: &Path, file: &DataFile) -> Option<PathBuf> {
+    let src = Path::new(&file.src);
+    let mut rest = path;
+    loop {
+        if let Ok(inner) = rest.strip_prefix(src) {
+            let dest = Path::new(&file.dest);
+            return Some(if inner.as_os_str().is_empty() {
+                dest.to_path_buf()
+            } else {
+                dest.join(inner)
+            });
+        }
+        let mut components = rest.components();
+        components.next()?;
+        rest = components.as_path();
+        if rest.as_os_str().is_empty() {
+            return None;
+        }
+    }
+}
+
+/// Helper: An error naming the first of the target's `files` nothing matched
+fn all_matched(target: &TargetDefinition, matched: &[bool]) -> Result<()> {
+    match matched.iter().position(|m| !m) {
+        Some(i) => bail!("'{}' not found inside archive", target.files[i].src),
+        None => Ok(()),
+    }
+}
+
+/// Helper: Non-empty, and nothing but plain names (no `/`, `..` or `.`)
+fn is_relative(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Helper: Atomically write `entry` to `dest`, executable if it was in the archive.
+/// It is only moved into place if `cancel` hasn't fired.
+fn write_file(
+    dest: &Path,
+    mode: u32,
+    entry: &mut dyn Read,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let dir = dest.parent().context("Data file has no parent")?;
+    fs::create_dir_all(dir)?;
+
+    let mut temp_file = tempfile::Builder::new()
+        .prefix(&clean::own_trash_prefix())
+        .tempfile_in(dir)?;
+    std::io::copy(entry, &mut temp_file)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = if mode & 0o111 != 0 { 0o755 } else { 0o644 };
+        temp_file
+            .as_file()
+            .set_permissions(fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    cancel.check()?;
+    temp_file.persist(dest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::install::install_from_bytes;
+    use crate::models::ArtifactFormat;
+    use crate::test_utils::tar_gz;
+    use sha2::{Digest, Sha256};
+    use tempfile::tempdir;
+
+    fn data_archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let files: Vec<_> = files
+            .iter()
+            .map(|(path, data)| (*path, 0o644, data.as_bytes()))
+            .collect();
+        tar_gz(&files)
+    }
+
+    fn data_target(content: &[u8], files: &[(&str, &str)]) -> TargetDefinition {
+        TargetDefinition {
+            url: "https://example.com/themes.tar.gz".to_string(),
+            bin: Vec::new(),
+            sha256: hex::encode(Sha256::digest(content)),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::Archive,
+            extract: false,
+            files: files
+                .iter()
+                .map(|(src, dest)| DataFile {
+                    src: src.to_string(),
+                    dest: dest.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_is_man_page() {
+        for path in ["man/man1/rg.1", "man/man5/tool.conf.5.gz", "man/man3/x.3p"] {
+            assert!(is_man_page(Path::new(path)), "{}", path);
+        }
+        for path in [
+            "man/rg.1",
+            "man/man1/README.md",
+            "man/man1/rg.5",
+            "share/man/man1/rg.1",
+            "man/man1/.1",
+        ] {
+            assert!(!is_man_page(Path::new(path)), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_destination() {
+        let file = |src: &str, dest: &str| DataFile {
+            src: src.to_string(),
+            dest: dest.to_string(),
+        };
+        let themes = file("themes", "tool/themes");
+        assert_eq!(
+            destination(Path::new("tool-1.0/themes/dark.toml"), &themes),
+            Some(PathBuf::from("tool/themes/dark.toml"))
+        );
+        assert_eq!(
+            destination(Path::new("./themes/dark.toml"), &themes),
+            Some(PathBuf::from("tool/themes/dark.toml"))
+        );
+        assert_eq!(destination(Path::new("tool-1.0/README"), &themes), None);
+        // Whole components only
+        assert_eq!(destination(Path::new("mythemes/dark.toml"), &themes), None);
+
+        let font = file("Fira.ttf", "fonts/Fira.ttf");
+        assert_eq!(
+            destination(Path::new("dist/Fira.ttf"), &font),
+            Some(PathBuf::from("fonts/Fira.ttf"))
+        );
+
+        assert!(check_dest("fonts/x.ttf").is_ok());
+        for bad in ["", "/etc/passwd", "../.bashrc", "fonts/../../x"] {
+            assert!(check_dest(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_data_package_lifecycle() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let v1 = data_archive(&[
+            ("themes-1.0/themes/dark.toml", "dark"),
+            ("themes-1.0/themes/light.toml", "light"),
+            ("themes-1.0/README.md", "readme"),
+        ]);
+        let target = data_target(&v1, &[("themes", "tool/themes")]);
+        let result =
+            install_from_bytes(&mut engine, "themes", "1.0.0", &target, &v1, None, |_| {}).unwrap();
+        assert_eq!(result.path, engine.data_dir);
+
+        let installed = &engine.state.packages["themes"];
+        assert!(installed.binaries.is_empty());
+        assert_eq!(
+            installed.files,
+            ["tool/themes/dark.toml", "tool/themes/light.toml"]
+        );
+        let dark = engine.data_dir.join("tool/themes/dark.toml");
+        assert_eq!(fs::read_to_string(&dark).unwrap(), "dark");
+
+        // The upgrade no longer ships light.toml
+        let v2 = data_archive(&[("themes-2.0/themes/dark.toml", "darker")]);
+        let target = data_target(&v2, &[("themes", "tool/themes")]);
+        install_from_bytes(&mut engine, "themes", "2.0.0", &target, &v2, None, |_| {}).unwrap();
+        assert_eq!(fs::read_to_string(&dark).unwrap(), "darker");
+        assert!(!engine.data_dir.join("tool/themes/light.toml").exists());
+
+        // A src the archive lacks fails the install
+        let target = data_target(&v2, &[("grammars", "tool/grammars")]);
+        let err = install_from_bytes(&mut engine, "themes", "3.0.0", &target, &v2, None, |_| {})
+            .unwrap_err();
+        assert!(err.to_string().contains("'grammars' not found"));
+
+        let result = engine
+            .uninstall_package("themes", &mut ())
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.files_removed, ["tool/themes/dark.toml"]);
+        assert!(!engine.data_dir.join("tool").exists());
+        assert!(engine.data_dir.join("rush").exists());
+    }
+
+    #[test]
+    fn test_overlapping_data_files_conflict() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let themes = data_archive(&[("themes/dark.toml", "dark")]);
+        let target = data_target(&themes, &[("themes", "tool/themes")]);
+        install_from_bytes(
+            &mut engine,
+            "themes",
+            "1.0.0",
+            &target,
+            &themes,
+            None,
+            |_| {},
+        )
+        .unwrap();
+
+        // Another package writing the same dest (or a directory around it) conflicts
+        let extra = data_archive(&[("dark.toml", "darkest")]);
+        for dest in ["tool/themes/dark.toml", "tool"] {
+            let target = data_target(&extra, &[("dark.toml", dest)]);
+            let mut manifest: crate::models::PackageManifest =
+                toml::from_str("version = \"1.0.0\"\ndescription = \"x\"\n[targets]").unwrap();
+            let slug = engine.target().to_string();
+            manifest.targets.insert(slug.clone(), target.clone());
+
+            let plan = crate::core::plan::resolve_manifest(&engine, "extra", &manifest, &slug);
+            assert!(plan.steps.is_empty());
+            assert_eq!(
+                plan.conflicts,
+                [Conflict::DataFileClash {
+                    package: "extra".to_string(),
+                    file: dest.to_string(),
+                    owner: "themes".to_string(),
+                }]
+            );
+
+            // And is refused when installed directly
+            let err =
+                install_from_bytes(&mut engine, "extra", "1.0.0", &target, &extra, None, |_| {})
+                    .unwrap_err();
+            assert!(err.to_string().contains("belongs to 'themes'"), "{}", err);
+        }
+        let dark = engine.data_dir.join("tool/themes/dark.toml");
+        assert_eq!(fs::read_to_string(dark).unwrap(), "dark");
+        assert!(!engine.state.packages.contains_key("extra"));
+
+        // Its own files are the package's to overwrite
+        install_from_bytes(
+            &mut engine,
+            "themes",
+            "1.0.1",
+            &target,
+            &themes,
+            None,
+            |_| {},
+        )
+        .unwrap();
+    }
+}
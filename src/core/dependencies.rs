@@ -0,0 +1,184 @@
+//! Packages other packages need (`dependencies = ["libfoo"]` in a manifest).
+//!
+//! The planner (`plan::resolve`) installs whatever a package needs that isn't installed
+//! yet, dependencies first, from the registry. Those are marked as dependencies in the
+//! state; installing one by name later makes it explicit.
+
+use crate::core::RushEngine;
+use crate::models::{Conflict, PackageManifest};
+
+/// What installing `name` (from `manifest`) also needs to install, in the order to install
+/// it: every package its dependencies lead to that isn't installed, each after its own
+/// dependencies. A cycle, or a dependency the registry doesn't have, is a conflict.
+pub(crate) fn missing_dependencies(
+    engine: &RushEngine,
+    name: &str,
+    manifest: &PackageManifest,
+) -> Result<Vec<(String, PackageManifest)>, Conflict> {
+    let mut order = Vec::new();
+    visit(engine, manifest, &mut vec![name.to_string()], &mut order)?;
+    Ok(order)
+}
+
+/// The installed packages whose registry manifest lists `name` among its dependencies,
+/// sorted; uninstalling `name` would leave them without it
+pub(crate) fn dependents(engine: &RushEngine, name: &str) -> Vec<String> {
+    let mut dependents: Vec<String> = engine
+        .state
+        .packages
+        .keys()
+        .filter(|installed| *installed != name)
+        .filter(|installed| {
+            engine
+                .find_package(installed)
+                .is_some_and(|manifest| manifest.dependencies.iter().any(|d| d == name))
+        })
+        .cloned()
+        .collect();
+    dependents.sort();
+    dependents
+}
+
+/// Helper: Depth-first over `manifest`'s dependencies; `path` is the chain from the
+/// requested package to `manifest`'s, so a dependency already on it closes a cycle
+fn visit(
+    engine: &RushEngine,
+    manifest: &PackageManifest,
+    path: &mut Vec<String>,
+    order: &mut Vec<(String, PackageManifest)>,
+) -> Result<(), Conflict> {
+    for dependency in &manifest.dependencies {
+        if let Some(start) = path.iter().position(|p| p == dependency) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(dependency.clone());
+            return Err(Conflict::DependencyCycle {
+                package: path[0].clone(),
+                cycle,
+            });
+        }
+        if engine.state.packages.contains_key(dependency)
+            || order.iter().any(|(planned, _)| planned == dependency)
+        {
+            continue;
+        }
+        let Some(needed) = engine.find_package(dependency) else {
+            return Err(Conflict::DependencyNotFound {
+                package: path[0].clone(),
+                dependency: dependency.clone(),
+            });
+        };
+
+        path.push(dependency.clone());
+        visit(engine, &needed, path, order)?;
+        path.pop();
+        order.push((dependency.clone(), needed));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+
+    fn names(order: &[(String, PackageManifest)]) -> Vec<&str> {
+        order.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_missing_dependencies_in_install_order() {
+        let mock = MockEnvironment::new();
+        for name in ["app", "libb", "libc", "libd"] {
+            mock.add_package(name, "1.0.0", name);
+        }
+        mock.add_dependencies("app", &["libb", "libc"]);
+        mock.add_dependencies("libb", &["libd"]);
+        mock.add_dependencies("libc", &["libd"]);
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let app = engine.find_package("app").unwrap();
+        let order = missing_dependencies(&engine, "app", &app).unwrap();
+        assert_eq!(names(&order), ["libd", "libb", "libc"]);
+
+        // Installing it installs them first, marked as dependencies
+        let target = engine.target().to_string();
+        let outcome = engine
+            .install_from_manifest("app", &app, &target, &mut ())
+            .unwrap();
+        assert!(outcome.is_installed());
+        for name in ["libb", "libc", "libd"] {
+            assert!(engine.state.packages[name].dependency, "{}", name);
+            assert!(engine.bin_path.join(name).exists());
+        }
+        assert!(!engine.state.packages["app"].dependency);
+        assert!(
+            missing_dependencies(&engine, "app", &app)
+                .unwrap()
+                .is_empty()
+        );
+
+        // Asking for one by name makes it explicit
+        let libd = engine.find_package("libd").unwrap();
+        engine
+            .install_from_manifest("libd", &libd, &target, &mut ())
+            .unwrap();
+        assert!(!engine.state.packages["libd"].dependency);
+    }
+
+    #[test]
+    fn test_dependency_cycle_and_missing_dependency() {
+        let mock = MockEnvironment::new();
+        for name in ["a", "b", "c", "d"] {
+            mock.add_package(name, "1.0.0", name);
+        }
+        mock.add_dependencies("a", &["b"]);
+        mock.add_dependencies("b", &["a"]);
+        mock.add_dependencies("c", &["d"]);
+        mock.add_dependencies("d", &["ghost"]);
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let a = engine.find_package("a").unwrap();
+        let conflict = missing_dependencies(&engine, "a", &a).unwrap_err();
+        assert_eq!(
+            conflict,
+            Conflict::DependencyCycle {
+                package: "a".to_string(),
+                cycle: vec!["a".to_string(), "b".to_string(), "a".to_string()]
+            }
+        );
+        assert_eq!(conflict.to_string(), "Dependency cycle: a -> b -> a");
+
+        // Reported against the package asked for, however deep the missing one is
+        let c = engine.find_package("c").unwrap();
+        assert_eq!(
+            missing_dependencies(&engine, "c", &c).unwrap_err(),
+            Conflict::DependencyNotFound {
+                package: "c".to_string(),
+                dependency: "ghost".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_dependents() {
+        let mock = MockEnvironment::new();
+        for name in ["app", "tool", "libfoo"] {
+            mock.add_package(name, "1.0.0", name);
+        }
+        mock.add_dependencies("app", &["libfoo"]);
+        mock.add_dependencies("tool", &["libfoo"]);
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let target = engine.target().to_string();
+        let app = engine.find_package("app").unwrap();
+        engine
+            .install_from_manifest("app", &app, &target, &mut ())
+            .unwrap();
+        // Only installed packages count
+        assert_eq!(engine.dependents("libfoo"), ["app"]);
+        assert!(engine.dependents("app").is_empty());
+    }
+}
@@ -1,16 +1,50 @@
-use crate::core::{RushEngine, util};
+mod bump;
+mod checksums;
+mod crates;
+mod github;
+mod gitlab;
+mod links;
+mod lint;
+mod publish;
+mod refresh;
+mod scaffold;
+mod scoring;
+mod serve;
+mod sign;
+mod trial;
+
+pub use bump::bump_package;
+pub(crate) use checksums::{fetch_upstream_checksum, find_checksum_asset, parse_checksum_file};
+pub use crates::import_cargo_dist;
+pub use github::RateLimitError;
+pub use links::check_urls;
+pub use lint::lint_registry;
+pub use publish::publish;
+pub use refresh::refresh_all;
+pub use scaffold::create_package;
+pub use serve::serve_registry;
+pub use sign::{INDEX_FILE, SIGNATURE_FILE, generate_signing_key, sign_registry};
+pub use trial::trial_install;
+
+pub(crate) use scoring::calculate_asset_score;
+use scoring::explain_asset_score;
+
+use crate::Error;
+use crate::core::{RushEngine, Target, archive, datafiles, install, provenance, util};
 use crate::models::{
-    GitHubRelease, ImportCandidate, InstallEvent, PackageManifest, ScoredAsset, TargetDefinition,
-    VerificationFailure, VerifyEvent, VerifyResult,
+    ArtifactFormat, AssetPreview, AssetScore, ChecksumSource, GitHubRelease, ImportCandidate,
+    PackageManifest, ProgressEvent, ProgressKind, ScoreFactor, ScoreReport, ScoredAsset,
+    ScoringRule, TargetDefinition, TargetScores, VerificationFailure, VerifyEvent, VerifyResult,
 };
 use anyhow::{Context, Result};
-use flate2::read::GzDecoder;
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
-use std::path::PathBuf;
-use tar::Archive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// Developer Tool: Create/Update a local package manifest
+/// Developer Tool: Create/Update a local package manifest.
+/// Without `bin_name`, the binary is detected from the archive contents (falling back to
+/// the package name). Returns the binary name that was written.
 pub fn add_package_manual<F>(
     engine: &RushEngine,
     name: String,
@@ -18,35 +52,195 @@ pub fn add_package_manual<F>(
     target_arch: String,
     url: String,
     bin_name: Option<String>,
-    mut on_event: F,
-) -> Result<()>
+    on_event: F,
+) -> Result<String>
 where
-    F: FnMut(InstallEvent),
+    F: FnMut(ProgressEvent),
 {
+    let mut on_event = util::tag_progress(Some(&name), on_event);
+
     // 1. Download to get checksum
-    let content = util::download_url(&engine.client, &url, &mut on_event)?;
+    let content = util::download_url(engine.transport.as_ref(), &url, &mut on_event)?;
 
-    on_event(InstallEvent::VerifyingChecksum);
+    on_event(ProgressKind::VerifyingChecksum);
 
     let mut hasher = Sha256::new();
     hasher.update(&content);
     let sha256 = hex::encode(hasher.finalize());
 
-    // 2. Write to file
+    // 2. Work out the binary
+    let bin_name = match bin_name {
+        Some(bin) => bin,
+        None => detect_bin_name(&content, &name).unwrap_or_else(|| name.clone()),
+    };
+
+    // 3. Write to file
     write_package_manifest(
         &engine.registry_source,
         &name,
         &version,
         &target_arch,
         &url,
-        bin_name,
+        Some(bin_name.clone()),
         &sha256,
-    )
+        Some(content.len() as u64),
+    )?;
+
+    Ok(bin_name)
+}
+
+/// Helper: The file name of the most likely binary in an archive, if it is one
+fn detect_bin_name(content: &[u8], package_name: &str) -> Option<String> {
+    let entries = archive::list_entries(content).ok()?;
+    let idx = archive::detect_binary(&entries, package_name)?;
+    Some(entries[idx].file_name().to_string())
+}
+
+/// Developer Tool: Turn a `dev add` location into a manifest URL.
+/// URLs pass through; local paths become absolute `file://` URLs, optionally after
+/// copying the file into the registry's `artifacts/<name>/<version>/` folder.
+pub fn resolve_artifact_location(
+    engine: &RushEngine,
+    name: &str,
+    version: &str,
+    location: &str,
+    copy: bool,
+) -> Result<String> {
+    if location.contains("://") {
+        if copy {
+            anyhow::bail!("--copy only applies to local files, not '{}'", location);
+        }
+        return Ok(location.to_string());
+    }
+
+    let path = std::fs::canonicalize(location)
+        .with_context(|| format!("Artifact '{}' not found", location))?;
+    if !path.is_file() {
+        anyhow::bail!("Artifact '{}' is not a file", location);
+    }
+
+    let path = if copy {
+        let source_path = ensure_local_registry(&engine.registry_source)?;
+        let dest_dir = source_path.join("artifacts").join(name).join(version);
+        std::fs::create_dir_all(&dest_dir)?;
+
+        let dest = dest_dir.join(path.file_name().context("Artifact has no file name")?);
+        std::fs::copy(&path, &dest)?;
+        std::fs::canonicalize(dest)?
+    } else {
+        path
+    };
+
+    Ok(format!("file://{}", path.display()))
 }
 
-/// Internal helper: Updates the registry file.
+/// Developer Tool: Add an asset picked in the import wizard.
+/// When the release publishes a checksum for it, that hash is used without downloading
+/// the asset (unless `verify` is set); otherwise the asset is downloaded and hashed.
+pub fn add_import_asset<F>(
+    engine: &RushEngine,
+    name: String,
+    version: String,
+    target_arch: String,
+    scored: &ScoredAsset,
+    verify: bool,
+    on_event: F,
+) -> Result<ChecksumSource>
+where
+    F: FnMut(ProgressEvent),
+{
+    let url = &scored.asset.browser_download_url;
+
+    // Guard Clause: Nothing published upstream, fall back to hashing it ourselves
+    let Some(checksum_url) = &scored.checksum_url else {
+        add_package_manual(
+            engine,
+            name,
+            version,
+            target_arch,
+            url.clone(),
+            None,
+            on_event,
+        )?;
+        return Ok(ChecksumSource::Computed);
+    };
+
+    let sha256 = checksums::fetch_upstream_checksum(
+        engine.transport.as_ref(),
+        checksum_url,
+        &scored.asset.name,
+    )?;
+
+    let (source, size) = if verify {
+        let mut on_event = util::tag_progress(Some(&name), on_event);
+        let content = util::download_url(engine.transport.as_ref(), url, &mut on_event)?;
+        on_event(ProgressKind::VerifyingChecksum);
+        util::verify_checksum(&content, &sha256)?;
+        (ChecksumSource::UpstreamVerified, Some(content.len() as u64))
+    } else {
+        (ChecksumSource::Upstream, None)
+    };
+
+    write_package_manifest(
+        &engine.registry_source,
+        &name,
+        &version,
+        &target_arch,
+        url,
+        None,
+        &sha256,
+        size,
+    )?;
+    set_checksum_url(engine, &name, &target_arch, checksum_url)?;
+
+    Ok(source)
+}
+
+/// Developer Tool: Download an asset picked in the import wizard and look inside it,
+/// so the user can confirm which file is the binary before anything is written.
+pub fn preview_import_asset<F>(
+    engine: &RushEngine,
+    package_name: &str,
+    scored: &ScoredAsset,
+    on_event: F,
+) -> Result<AssetPreview>
+where
+    F: FnMut(ProgressEvent),
+{
+    let mut on_event = util::tag_progress(Some(package_name), on_event);
+    let content = util::download_url(
+        engine.transport.as_ref(),
+        &scored.asset.browser_download_url,
+        &mut on_event,
+    )?;
+
+    on_event(ProgressKind::VerifyingChecksum);
+    let sha256 = hex::encode(Sha256::digest(&content));
+    if let Some(checksum_url) = &scored.checksum_url {
+        let upstream = checksums::fetch_upstream_checksum(
+            engine.transport.as_ref(),
+            checksum_url,
+            &scored.asset.name,
+        )?;
+        util::verify_checksum(&content, &upstream)?;
+    }
+
+    on_event(ProgressKind::Extracting);
+    let entries = archive::list_entries(&content)
+        .with_context(|| format!("Can't look inside {}", scored.asset.name))?;
+    let suggested = archive::detect_binary(&entries, package_name);
+
+    Ok(AssetPreview {
+        sha256,
+        entries,
+        suggested,
+    })
+}
+
+/// Internal helper: Updates the registry file, stamping it as `updated` now.
 /// We pass `registry_source` string directly since we don't need the whole engine.
-fn write_package_manifest(
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_package_manifest(
     registry_source: &str,
     name: &str,
     version: &str,
@@ -54,13 +248,12 @@ fn write_package_manifest(
     url: &str,
     bin_name: Option<String>,
     sha256: &str,
+    size: Option<u64>,
 ) -> Result<()> {
     let source_path = ensure_local_registry(registry_source)?;
 
-    // Determine file path: e.g., packages/f/fzf.toml
-    let prefix = name.chars().next().context("Package name empty")?;
-    let package_dir = source_path.join("packages").join(prefix.to_string());
-    let package_path = package_dir.join(format!("{}.toml", name));
+    let package_path = manifest_path(&source_path, name)?;
+    let package_dir = package_path.parent().context("Manifest has no parent")?;
 
     // Load existing or create new manifest
     let mut manifest = if package_path.exists() {
@@ -68,27 +261,62 @@ fn write_package_manifest(
         toml::from_str::<PackageManifest>(&content).unwrap_or_else(|_| PackageManifest {
             version: version.to_string(),
             description: None,
+            source: None,
+            license: None,
+            homepage: None,
+            updated: None,
+            check: None,
+            osv: None,
+            requires: Vec::new(),
+            dependencies: Vec::new(),
             targets: BTreeMap::new(),
+            history: Vec::new(),
         })
     } else {
         if !package_dir.exists() {
-            std::fs::create_dir_all(&package_dir)?;
+            std::fs::create_dir_all(package_dir)?;
         }
         PackageManifest {
             version: version.to_string(),
             description: None,
+            source: None,
+            license: None,
+            homepage: None,
+            updated: None,
+            check: None,
+            osv: None,
+            requires: Vec::new(),
+            dependencies: Vec::new(),
             targets: BTreeMap::new(),
+            history: Vec::new(),
         }
     };
 
-    // Update Struct
+    // Update Struct (a new release of an AppImage or data target is still one, and
+    // ships the same binaries unless told otherwise)
+    let previous = manifest.targets.remove(target_arch);
+    let (bins, format, extract, files) = previous.map_or(
+        (Vec::new(), ArtifactFormat::Archive, false, Vec::new()),
+        |t| (t.bin, t.format, t.extract, t.files),
+    );
+    let bin = match bin_name {
+        Some(bin) => vec![bin],
+        None if !bins.is_empty() => bins,
+        None => vec![name.to_string()],
+    };
     manifest.version = version.to_string();
+    manifest.updated = Some(timestamp());
     manifest.targets.insert(
         target_arch.to_string(),
         TargetDefinition {
             url: url.to_string(),
-            bin: bin_name.unwrap_or(name.to_string()),
+            bin,
             sha256: sha256.to_string(),
+            checksum_url: None,
+            size,
+            format,
+            extract,
+            files,
         },
     );
 
@@ -99,67 +327,269 @@ fn write_package_manifest(
     Ok(())
 }
 
+/// Helper: Now, as a manifest's `updated`
+pub(crate) fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    provenance::rfc3339(secs)
+}
+
+/// Developer Tool: Record where a package's releases come from, so `dev bump` can find them
+pub fn set_package_source(engine: &RushEngine, name: &str, source: &str) -> Result<()> {
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    let package_path = manifest_path(&source_path, name)?;
+
+    let content = std::fs::read_to_string(&package_path)
+        .with_context(|| format!("Package '{}' is not in the local registry", name))?;
+    let mut manifest: PackageManifest = toml::from_str(&content)?;
+    manifest.source = Some(source.to_string());
+
+    std::fs::write(&package_path, toml::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// Developer Tool: Record the upstream checksum file a target's hash came from, so
+/// `rush verify --upstream` can check it again later
+pub fn set_checksum_url(
+    engine: &RushEngine,
+    name: &str,
+    target_arch: &str,
+    checksum_url: &str,
+) -> Result<()> {
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    let package_path = manifest_path(&source_path, name)?;
+
+    let content = std::fs::read_to_string(&package_path)
+        .with_context(|| format!("Package '{}' is not in the local registry", name))?;
+    let mut manifest: PackageManifest = toml::from_str(&content)?;
+    let target = manifest
+        .targets
+        .get_mut(target_arch)
+        .with_context(|| format!("Package '{}' has no target {}", name, target_arch))?;
+    target.checksum_url = Some(checksum_url.to_string());
+
+    std::fs::write(&package_path, toml::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// Helper: Where a package's manifest lives, e.g. packages/f/fzf.toml
+fn manifest_path(source_path: &Path, name: &str) -> Result<PathBuf> {
+    let prefix = name.chars().next().context("Package name empty")?;
+    Ok(source_path
+        .join("packages")
+        .join(prefix.to_string())
+        .join(format!("{}.toml", name)))
+}
+
 /// Ensures registry env variable is set, fails otherwise
 pub fn ensure_local_registry(registry_source: &str) -> Result<PathBuf> {
     let source_path = PathBuf::from(registry_source);
 
     if registry_source.is_empty() || !source_path.exists() || !source_path.is_dir() {
-        anyhow::bail!(
-            "RUSH_REGISTRY_URL must be set to your local git repository path to alter the local registry. \
-             \nTry: \n\texport RUSH_REGISTRY_URL=\"$(pwd)\""
-        );
+        return Err(Error::NotLocalRegistry.into());
     }
 
     Ok(source_path)
 }
 
-/// Developer Tool: Interactive Import wizard from GitHub
+/// Developer Tool: Interactive Import wizard from GitHub (or GitLab, for `gitlab:group/project`)
+/// Imports the release tagged `tag`, or the latest release when `None`.
 pub fn fetch_github_import_candidates(
     engine: &RushEngine,
     repo: &str,
+    tag: Option<&str>,
+    targets: &[String],
 ) -> Result<(String, String, Vec<ImportCandidate>)> {
     ensure_local_registry(&engine.registry_source)?;
+    resolve_import_targets(targets)?;
 
-    let api_url = format!("https://api.github.com/repos/{}/releases/latest", repo);
+    let release = fetch_release(engine, repo, tag, None)?;
 
-    let release: GitHubRelease = engine
-        .client
-        .get(&api_url)
-        .send()?
-        .error_for_status()?
-        .json()?;
+    import_candidates_from_release(engine, repo, &release, targets)
+}
 
-    let package_name = repo.split('/').nth(1).unwrap_or("unknown").to_string();
+/// Helper: Fetch one release (by tag, or the latest) from GitHub or GitLab, giving up
+/// after `timeout` if there is one
+pub(crate) fn fetch_release(
+    engine: &RushEngine,
+    repo: &str,
+    tag: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<GitHubRelease> {
+    if let Some(project) = repo.strip_prefix(gitlab::PREFIX) {
+        return gitlab::fetch_release(&engine.client, project, tag, timeout);
+    }
+
+    github::api_get_json(
+        &engine.client,
+        &engine.cache_dir,
+        &release_api_url(repo, tag),
+        timeout,
+    )?
+    .with_context(|| match tag {
+        Some(tag) => format!("No release '{}' found for {}", tag, repo),
+        None => format!("No releases found for {}", repo),
+    })
+}
+
+/// Developer Tool: List the `limit` most recent releases of a GitHub repo, newest first.
+/// Prereleases (flagged or tagged like "-rc", "nightly") are skipped unless `include_prereleases`.
+pub fn list_github_releases(
+    engine: &RushEngine,
+    repo: &str,
+    limit: usize,
+    include_prereleases: bool,
+) -> Result<Vec<GitHubRelease>> {
+    ensure_local_registry(&engine.registry_source)?;
 
-    let (version, candidates) = build_candidates_from_release(&release);
+    // Fetch a full page when filtering, so nightlies don't crowd out stable releases
+    let per_page = if include_prereleases { limit } else { 100 }.clamp(1, 100);
+
+    let releases = if let Some(project) = repo.strip_prefix(gitlab::PREFIX) {
+        gitlab::list_releases(&engine.client, project, per_page)?
+    } else {
+        let api_url = format!(
+            "https://api.github.com/repos/{}/releases?per_page={}",
+            repo, per_page
+        );
+        github::api_get_json::<Vec<GitHubRelease>>(
+            &engine.client,
+            &engine.cache_dir,
+            &api_url,
+            None,
+        )?
+        .with_context(|| format!("Repository {} not found", repo))?
+    };
+
+    Ok(filter_releases(releases, limit, include_prereleases))
+}
+
+/// Helper: Drop prereleases (unless included) and keep the first `limit`
+fn filter_releases(
+    releases: Vec<GitHubRelease>,
+    limit: usize,
+    include_prereleases: bool,
+) -> Vec<GitHubRelease> {
+    releases
+        .into_iter()
+        .filter(|r| include_prereleases || !r.is_prerelease())
+        .take(limit)
+        .collect()
+}
+
+/// Developer Tool: Build import candidates from an already fetched release.
+/// `targets` selects which system targets to offer (empty = defaults).
+pub fn import_candidates_from_release(
+    engine: &RushEngine,
+    repo: &str,
+    release: &GitHubRelease,
+    targets: &[String],
+) -> Result<(String, String, Vec<ImportCandidate>)> {
+    let package_name = package_name_from_repo(repo);
+
+    let targets = resolve_import_targets(targets)?;
+    let (version, candidates) =
+        build_candidates_from_release(release, &targets, &engine.config.scoring.rules);
 
     Ok((package_name, version, candidates))
 }
 
+/// Helper: The package name is the last path segment ("sharkdp/bat", "gitlab:group/sub/tool")
+fn package_name_from_repo(repo: &str) -> String {
+    let path = repo.strip_prefix(gitlab::PREFIX).unwrap_or(repo);
+    match path.rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => name.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Helper: GitHub API endpoint for a release, by tag or the latest one
+fn release_api_url(repo: &str, tag: Option<&str>) -> String {
+    match tag {
+        Some(tag) => format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            repo, tag
+        ),
+        None => format!("https://api.github.com/repos/{}/releases/latest", repo),
+    }
+}
+
+/// Assets scoring below this are never picked without asking
+pub const IMPORT_MIN_SCORE: i32 = 25;
+
+/// Every target the registry knows about, with a human-readable description
+pub const TARGETS: &[(&str, &str)] = &[
+    ("x86_64-linux", "Linux (x86_64)"),
+    ("aarch64-linux", "Linux (ARM64)"),
+    ("aarch64-macos", "macOS (Apple Silicon)"),
+    ("x86_64-macos", "macOS (Intel)"),
+    ("x86_64-windows", "Windows (x86_64)"),
+    ("aarch64-windows", "Windows (ARM64)"),
+];
+
+/// Targets the import wizard offers when none are requested.
+/// Windows is opt-in since rush can't install there yet.
+const DEFAULT_IMPORT_TARGETS: &[&str] = &[
+    "x86_64-linux",
+    "aarch64-linux",
+    "aarch64-macos",
+    "x86_64-macos",
+];
+
+/// Helper: Resolve requested import targets (empty = defaults), rejecting unknown ones
+fn resolve_import_targets(requested: &[String]) -> Result<Vec<(&'static str, &'static str)>> {
+    if requested.is_empty() {
+        return Ok(TARGETS
+            .iter()
+            .copied()
+            .filter(|(slug, _)| DEFAULT_IMPORT_TARGETS.contains(slug))
+            .collect());
+    }
+
+    requested
+        .iter()
+        .map(|wanted| {
+            let wanted = Target::normalize(wanted);
+            TARGETS
+                .iter()
+                .copied()
+                .find(|(slug, _)| *slug == wanted)
+                .ok_or_else(|| {
+                    Error::UnknownTarget {
+                        target: wanted.clone(),
+                    }
+                    .into()
+                })
+        })
+        .collect()
+}
+
 /// Helper: Transforms a GitHub Release into sorted ImportCandidates
-fn build_candidates_from_release(release: &GitHubRelease) -> (String, Vec<ImportCandidate>) {
+fn build_candidates_from_release(
+    release: &GitHubRelease,
+    targets: &[(&str, &str)],
+    rules: &[ScoringRule],
+) -> (String, Vec<ImportCandidate>) {
     let version = release.tag_name.trim_start_matches('v').to_string();
 
-    let target_defs = vec![
-        ("Linux (x86_64)", "x86_64-linux"),
-        ("macOS (Apple Silicon)", "aarch64-macos"),
-        ("macOS (Intel)", "x86_64-macos"),
-    ];
-
     let mut candidates = Vec::new();
 
-    for (desc, target_key) in target_defs {
+    for &(target_key, desc) in targets {
         let mut scored_assets: Vec<ScoredAsset> = release
             .assets
             .iter()
             .map(|asset| ScoredAsset {
-                score: calculate_asset_score(&asset.name, target_key),
+                score: score_asset(&asset.name, target_key, rules),
                 asset: asset.clone(),
+                checksum_url: checksums::find_checksum_asset(release, &asset.name)
+                    .map(|c| c.browser_download_url.clone()),
             })
             .collect();
 
         // Sort by score descending
-        scored_assets.sort_by(|a, b| b.score.cmp(&a.score));
+        scored_assets.sort_by_key(|a| std::cmp::Reverse(a.score));
 
         candidates.push(ImportCandidate {
             target_desc: desc.to_string(),
@@ -171,160 +601,165 @@ fn build_candidates_from_release(release: &GitHubRelease) -> (String, Vec<Import
     (version, candidates)
 }
 
-/// Helper to rank assets
-fn calculate_asset_score(name: &str, target_arch: &str) -> i32 {
-    let name = name.to_lowercase();
-    let mut score = 0;
+/// Helper: The built-in score plus any configured `[[scoring.rules]]` that fire
+fn score_asset(name: &str, target_arch: &str, rules: &[ScoringRule]) -> i32 {
+    let adjustment: i32 = rules
+        .iter()
+        .filter(|rule| rule.matches(name, target_arch))
+        .map(|rule| rule.weight)
+        .sum();
+    calculate_asset_score(name, target_arch) + adjustment
+}
 
-    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
-        score += 20;
-    }
-    if name.ends_with(".zip") {
-        score -= 10;
-    }
-    if name.ends_with(".deb") || name.ends_with(".rpm") || name.ends_with(".msi") {
-        score -= 100;
-    }
-    if name.contains("sha256") || name.contains("sum") || name.contains("sig") {
-        score -= 100;
-    }
+/// Helper: The factors behind `score_asset`, configured rules included
+fn explain_score(name: &str, target_arch: &str, rules: &[ScoringRule]) -> Vec<ScoreFactor> {
+    let mut factors = explain_asset_score(name, target_arch);
+    factors.extend(
+        rules
+            .iter()
+            .filter(|rule| rule.matches(name, target_arch))
+            .map(|rule| ScoreFactor {
+                reason: format!("config rule '{}'", rule.keyword),
+                points: rule.weight,
+            }),
+    );
+    factors
+}
 
-    match target_arch {
-        "x86_64-linux" => {
-            if name.contains("linux") {
-                score += 10;
-            }
-            if name.contains("x86_64") || name.contains("amd64") {
-                score += 10;
-            }
-            if name.contains("musl") {
-                score += 5;
-            }
-            if name.contains("gnu") {
-                score += 3;
-            }
-            if name.contains("aarch64") || name.contains("arm") {
-                score -= 50;
-            }
-            if name.contains("darwin") || name.contains("apple") || name.contains("macos") {
-                score -= 50;
-            }
-            if name.contains("windows") || name.contains(".exe") {
-                score -= 50;
-            }
-        }
-        "aarch64-macos" => {
-            if name.contains("apple") || name.contains("darwin") || name.contains("macos") {
-                score += 10;
-            }
-            if name.contains("aarch64") || name.contains("arm64") {
-                score += 10;
-            }
-            if name.contains("linux") {
-                score -= 50;
-            }
-            if name.contains("x86_64") || name.contains("amd64") {
-                score -= 50;
-            }
-            if name.contains("windows") || name.contains(".exe") {
-                score -= 50;
-            }
-        }
-        "x86_64-macos" => {
-            if name.contains("apple") || name.contains("darwin") || name.contains("macos") {
-                score += 10;
-            }
-            if name.contains("x86_64") || name.contains("amd64") {
-                score += 10;
-            }
-            if name.contains("linux") {
-                score -= 50;
-            }
-            if name.contains("aarch64") || name.contains("arm") {
-                score -= 50;
-            }
-            if name.contains("windows") || name.contains(".exe") {
-                score -= 50;
+/// Developer Tool: Score every asset of a release for each target, with the reasons,
+/// to debug why the import wizard recommends what it does
+pub fn explain_scores(
+    engine: &RushEngine,
+    repo: &str,
+    tag: Option<&str>,
+    targets: &[String],
+) -> Result<ScoreReport> {
+    let targets = resolve_import_targets(targets)?;
+    let release = fetch_release(engine, repo, tag, None)?;
+    let rules = &engine.config.scoring.rules;
+
+    let targets = targets
+        .iter()
+        .map(|(slug, _)| {
+            let mut assets: Vec<AssetScore> = release
+                .assets
+                .iter()
+                .map(|asset| {
+                    let factors = explain_score(&asset.name, slug, rules);
+                    AssetScore {
+                        name: asset.name.clone(),
+                        score: factors.iter().map(|f| f.points).sum(),
+                        factors,
+                    }
+                })
+                .collect();
+            assets.sort_by_key(|a| std::cmp::Reverse(a.score));
+            TargetScores {
+                target_slug: slug.to_string(),
+                assets,
             }
-        }
-        _ => {}
-    }
-    score
+        })
+        .collect();
+
+    Ok(ScoreReport {
+        repo: repo.to_string(),
+        tag: release.tag_name,
+        min_score: IMPORT_MIN_SCORE,
+        targets,
+    })
 }
 
-/// Iterates through the entire registry, checking downloads, hashes, and binary existence.
-pub fn verify_registry<F>(engine: &RushEngine, mut on_event: F) -> Result<VerifyResult>
+/// Iterates through the registry, checking downloads, hashes, and binary existence.
+/// With `sample`, only that many randomly chosen targets are downloaded.
+pub fn verify_registry<F>(
+    engine: &RushEngine,
+    sample: Option<usize>,
+    mut on_event: F,
+) -> Result<VerifyResult>
 where
-    F: FnMut(VerifyEvent), // <--- Changed from InstallEvent
+    F: FnMut(VerifyEvent),
 {
-    let packages = engine.list_available_packages();
-    let packages_count = packages.len();
+    let mut checks: Vec<(String, String, String, TargetDefinition)> = Vec::new();
+    for (pkg_name, manifest) in engine.packages() {
+        for (target_arch, target_def) in manifest.targets {
+            checks.push((
+                pkg_name.clone(),
+                manifest.version.clone(),
+                target_arch,
+                target_def,
+            ));
+        }
+    }
+
+    if let Some(n) = sample {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        checks = sample_checks(checks, n, seed);
+    }
+
+    let mut packages_seen: Vec<&str> = checks.iter().map(|c| c.0.as_str()).collect();
+    packages_seen.dedup();
+    let packages_count = packages_seen.len();
+
     let mut failures = Vec::new();
     let mut targets_checked = 0;
 
-    for (pkg_name, manifest) in packages {
-        for (target_arch, target_def) in manifest.targets {
-            targets_checked += 1;
+    for (pkg_name, version, target_arch, target_def) in &checks {
+        targets_checked += 1;
 
-            // 1. Notify UI we are starting this target
-            on_event(VerifyEvent::Checking {
-                name: pkg_name.clone(),
-                target: target_arch.clone(),
-            });
-
-            // 2. Create an adapter closure to map InstallEvent -> VerifyEvent::Progress
-            // We need a ref to on_event, so we wrap it.
-            let mut progress_adapter = |evt: InstallEvent| {
-                on_event(VerifyEvent::Progress(evt));
-            };
-
-            let check_result = (|| -> Result<()> {
-                // Pass the adapter to download_url
-                let content =
-                    util::download_url(&engine.client, &target_def.url, &mut progress_adapter)?;
-
-                // Manually trigger the VerifyEvent::Progress for checksum/success steps if needed
-                // (Though download_url mostly handles the heavy lifting)
-
-                // B. Verify Checksum
-                progress_adapter(InstallEvent::VerifyingChecksum);
-                util::verify_checksum(&content, &target_def.sha256)?;
-
-                // C. Verify Binary Exists in Archive
-                progress_adapter(InstallEvent::Extracting);
-                let tar = GzDecoder::new(&content[..]);
-                let mut archive = Archive::new(tar);
-                let mut found = false;
-
-                for entry in archive.entries()? {
-                    let entry = entry?;
-                    let path = entry.path()?;
-                    if let Some(fname) = path.file_name()
-                        && fname == std::ffi::OsStr::new(&target_def.bin)
-                    {
-                        found = true;
-                        break;
-                    }
-                }
+        // 1. Notify UI we are starting this target
+        on_event(VerifyEvent::Checking {
+            name: pkg_name.clone(),
+            target: target_arch.clone(),
+        });
 
-                if !found {
-                    anyhow::bail!("Binary '{}' not found inside archive", target_def.bin);
+        // 2. Create an adapter closure to map progress -> VerifyEvent::Progress
+        // We need a ref to on_event, so we wrap it.
+        let mut progress_adapter =
+            util::tag_progress(Some(pkg_name), |evt| on_event(VerifyEvent::Progress(evt)));
+
+        let check_result = (|| -> Result<()> {
+            // Pass the adapter to download_url
+            let content = util::download_url(
+                engine.transport.as_ref(),
+                &target_def.url,
+                &mut progress_adapter,
+            )?;
+
+            // Manually trigger the VerifyEvent::Progress for checksum/success steps if needed
+            // (Though download_url mostly handles the heavy lifting)
+
+            // B. Verify Checksum
+            progress_adapter(ProgressKind::VerifyingChecksum);
+            util::verify_checksum(&content, &target_def.sha256)?;
+
+            // C. Verify Binary (and data files) Exist in Archive. An AppImage is the binary.
+            progress_adapter(ProgressKind::Extracting);
+            if target_def.format.is_archive() && !target_def.bin.is_empty() {
+                let missing = install::missing_binaries(&content, &target_def.bin)?;
+                if let Some(bin) = missing.first() {
+                    anyhow::bail!("Binary '{}' not found inside archive", bin);
                 }
+            }
+            if target_def.format.is_archive() && !target_def.files.is_empty() {
+                datafiles::check_sources(target_def, &content)?;
+            }
 
-                // Signal success for this specific file (clears the progress bar)
-                progress_adapter(InstallEvent::Success);
+            // Signal success for this specific file (clears the progress bar)
+            progress_adapter(ProgressKind::Success);
 
-                Ok(())
-            })();
+            Ok(())
+        })();
 
-            if let Err(e) = check_result {
-                failures.push(VerificationFailure {
-                    package_name: pkg_name.clone(),
-                    version: manifest.version.clone(),
-                    target: target_arch,
-                    error: e.to_string(),
-                });
-            }
+        if let Err(e) = check_result {
+            failures.push(VerificationFailure {
+                package_name: pkg_name.clone(),
+                version: version.clone(),
+                target: target_arch.clone(),
+                error: e.to_string(),
+            });
         }
     }
 
@@ -335,10 +770,41 @@ where
     })
 }
 
+/// Helper: Picks `n` entries pseudo-randomly (xorshift seeded by `seed`), keeping registry order
+fn sample_checks<T>(checks: Vec<T>, n: usize, seed: u64) -> Vec<T> {
+    if n >= checks.len() {
+        return checks;
+    }
+
+    let mut state = seed | 1;
+    let mut indices: Vec<usize> = (0..checks.len()).collect();
+    // Partial Fisher-Yates: the first `n` slots end up holding the sample
+    for i in 0..n {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = i + (state as usize) % (indices.len() - i);
+        indices.swap(i, j);
+    }
+    indices.truncate(n);
+    indices.sort_unstable();
+
+    let mut picked = vec![false; checks.len()];
+    for i in indices {
+        picked[i] = true;
+    }
+    checks
+        .into_iter()
+        .zip(picked)
+        .filter_map(|(c, keep)| keep.then_some(c))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::GitHubAsset;
+    use crate::test_utils::tar_gz;
     use tempfile::tempdir;
 
     #[test]
@@ -355,6 +821,7 @@ mod tests {
             "http://example.com",
             Some("binary-name".to_string()),
             "fake-hash-123",
+            None,
         )
         .unwrap();
 
@@ -362,6 +829,125 @@ mod tests {
         assert!(expected_path.exists());
     }
 
+    #[test]
+    fn test_set_package_source_survives_rewrites() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(root.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+
+        let write = |target| {
+            write_package_manifest(
+                root.to_str().unwrap(),
+                "tool",
+                "1.0.0",
+                target,
+                "https://example.com/tool.tar.gz",
+                None,
+                "abc",
+                None,
+            )
+            .unwrap()
+        };
+        write("x86_64-linux");
+        set_package_source(&engine, "tool", "owner/tool").unwrap();
+        write("aarch64-macos");
+
+        let content = std::fs::read_to_string(root.join("packages/t/tool.toml")).unwrap();
+        let manifest: PackageManifest = toml::from_str(&content).unwrap();
+        assert_eq!(manifest.source.as_deref(), Some("owner/tool"));
+        assert_eq!(manifest.targets.len(), 2);
+
+        assert!(set_package_source(&engine, "missing", "owner/missing").is_err());
+    }
+
+    #[test]
+    fn test_resolve_artifact_location() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(root.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        let artifact = root.join("dist/tool.tar.gz");
+        std::fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        std::fs::write(&artifact, "bytes").unwrap();
+        let resolve = |location: &str, copy| {
+            resolve_artifact_location(&engine, "tool", "1.0.0", location, copy)
+        };
+
+        // URLs are left alone
+        let url = "https://example.com/tool.tar.gz";
+        assert_eq!(resolve(url, false).unwrap(), url);
+        assert!(resolve(url, true).is_err());
+
+        // Plain paths become file:// URLs
+        let expected = format!("file://{}", artifact.canonicalize().unwrap().display());
+        assert_eq!(
+            resolve(artifact.to_str().unwrap(), false).unwrap(),
+            expected
+        );
+
+        // --copy stages the file next to the packages
+        let copied = resolve(artifact.to_str().unwrap(), true).unwrap();
+        let staged = root.join("artifacts/tool/1.0.0/tool.tar.gz");
+        assert_eq!(
+            copied,
+            format!("file://{}", staged.canonicalize().unwrap().display())
+        );
+        assert_eq!(std::fs::read_to_string(staged).unwrap(), "bytes");
+
+        assert!(resolve("./does/not/exist.tar.gz", false).is_err());
+    }
+
+    #[test]
+    fn test_add_package_manual_detects_binary() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let archive_path = root.join("ripgrep.tar.gz");
+
+        let tarball = tar_gz(&[
+            ("rg-14.0/README.md", 0o644, b"data"),
+            ("rg-14.0/rg", 0o755, b"data"),
+        ]);
+        std::fs::write(&archive_path, tarball).unwrap();
+
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(root.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        let url = format!("file://{}", archive_path.to_str().unwrap());
+        let add = |bin: Option<&str>| {
+            add_package_manual(
+                &engine,
+                "ripgrep".to_string(),
+                "14.0.0".to_string(),
+                "x86_64-linux".to_string(),
+                url.clone(),
+                bin.map(String::from),
+                |_| {},
+            )
+            .unwrap()
+        };
+
+        assert_eq!(add(None), "rg");
+        let manifest = std::fs::read_to_string(root.join("packages/r/ripgrep.toml")).unwrap();
+        assert!(manifest.contains("bin = \"rg\""));
+        // The artifact's size and the time of the write are recorded for `search --sort`
+        let manifest: PackageManifest = toml::from_str(&manifest).unwrap();
+        let size = std::fs::metadata(&archive_path).unwrap().len();
+        assert_eq!(manifest.targets["x86_64-linux"].size, Some(size));
+        assert!(manifest.updated.is_some_and(|t| t.ends_with('Z')));
+
+        // An explicit --bin always wins
+        assert_eq!(add(Some("ripgrep")), "ripgrep");
+    }
+
     #[test]
     fn test_write_package_manifest_invalid_path() {
         // Pass a non-existent path
@@ -373,6 +959,7 @@ mod tests {
             "url",
             None,
             "hash",
+            None,
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("must be set"));
@@ -392,6 +979,7 @@ mod tests {
             "url",
             None,
             "hash",
+            None,
         );
         assert!(result.is_err());
         assert!(
@@ -417,6 +1005,7 @@ mod tests {
             "http://linux.tar.gz",
             None,
             "hash1",
+            None,
         )
         .unwrap();
 
@@ -429,6 +1018,7 @@ mod tests {
             "http://mac.tar.gz",
             None,
             "hash2",
+            None,
         )
         .unwrap();
 
@@ -472,15 +1062,76 @@ mod tests {
     fn fetch_import_candidates_requires_local_registry() {
         let temp_dir = tempdir().unwrap();
 
-        let engine =
-            RushEngine::with_root_and_registry(temp_dir.path().to_path_buf(), "".to_string())
-                .unwrap();
+        let engine = RushEngine::builder()
+            .root(temp_dir.path().to_path_buf())
+            .registry_source("".to_string())
+            .build()
+            .unwrap();
 
-        let err = fetch_github_import_candidates(&engine, "sharkdp/bat").unwrap_err();
+        let err = fetch_github_import_candidates(&engine, "sharkdp/bat", None, &[]).unwrap_err();
 
         assert!(err.to_string().contains("RUSH_REGISTRY_URL must be set"));
     }
 
+    #[test]
+    fn test_resolve_import_targets() {
+        let defaults = resolve_import_targets(&[]).unwrap();
+        assert_eq!(defaults.len(), DEFAULT_IMPORT_TARGETS.len());
+        assert!(defaults.iter().all(|(slug, _)| !slug.ends_with("windows")));
+
+        let picked = resolve_import_targets(&["x86_64-windows".to_string()]).unwrap();
+        assert_eq!(picked, vec![("x86_64-windows", "Windows (x86_64)")]);
+
+        let err = resolve_import_targets(&["sparc-solaris".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("Unknown target 'sparc-solaris'"));
+    }
+
+    #[test]
+    fn test_package_name_from_repo() {
+        assert_eq!(package_name_from_repo("sharkdp/bat"), "bat");
+        assert_eq!(package_name_from_repo("gitlab:group/sub/tool"), "tool");
+        assert_eq!(package_name_from_repo("bat"), "unknown");
+    }
+
+    #[test]
+    fn test_filter_releases() {
+        let release = |tag: &str, prerelease| GitHubRelease {
+            tag_name: tag.to_string(),
+            published_at: None,
+            prerelease,
+            assets: vec![],
+        };
+        let releases = || {
+            vec![
+                release("nightly", false),
+                release("v2.0.0-rc.1", true),
+                release("v1.9.0", false),
+                release("v1.8.0", false),
+            ]
+        };
+
+        let stable: Vec<_> = filter_releases(releases(), 10, false)
+            .into_iter()
+            .map(|r| r.tag_name)
+            .collect();
+        assert_eq!(stable, vec!["v1.9.0", "v1.8.0"]);
+
+        assert_eq!(filter_releases(releases(), 3, true).len(), 3);
+        assert_eq!(filter_releases(releases(), 1, false)[0].tag_name, "v1.9.0");
+    }
+
+    #[test]
+    fn test_release_api_url() {
+        assert_eq!(
+            release_api_url("sharkdp/bat", None),
+            "https://api.github.com/repos/sharkdp/bat/releases/latest"
+        );
+        assert_eq!(
+            release_api_url("sharkdp/bat", Some("v0.24.0")),
+            "https://api.github.com/repos/sharkdp/bat/releases/tags/v0.24.0"
+        );
+    }
+
     #[test]
     fn test_calculate_asset_score() {
         // CASE 1: Linux x86_64
@@ -495,8 +1146,17 @@ mod tests {
         // Good match: tar.gz (+20), linux (+10), amd64 (+10) = 40
         assert_eq!(calculate_asset_score("app-linux-amd64.tar.gz", target), 40);
 
-        // Okay match: zip (-10), linux (+10), amd64 (+10) = 10
-        assert_eq!(calculate_asset_score("app-linux-amd64.zip", target), 10);
+        // Good match: zip (+15), linux (+10), amd64 (+10) = 35
+        assert_eq!(calculate_asset_score("app-linux-amd64.zip", target), 35);
+        assert!(
+            calculate_asset_score("app-linux-amd64.tar.gz", target)
+                > calculate_asset_score("app-linux-amd64.zip", target)
+        );
+
+        // Other compressions count as tarballs too
+        assert_eq!(calculate_asset_score("app-linux-amd64.tar.xz", target), 40);
+        assert_eq!(calculate_asset_score("app-linux-amd64.tar.zst", target), 40);
+        assert_eq!(calculate_asset_score("app-linux-amd64.tar.bz2", target), 40);
 
         // Wrong Arch: tar.gz (+20), linux (+10), arm64 (-50) = -20
         assert_eq!(calculate_asset_score("app-linux-arm64.tar.gz", target), -20);
@@ -508,7 +1168,11 @@ mod tests {
         );
 
         // Garbage: deb (-100), amd64 (+10) = -90
+        #[cfg(not(feature = "distro-packages"))]
         assert_eq!(calculate_asset_score("app_amd64.deb", target), -90);
+        // ...unless rush can unpack it: deb (-5), amd64 (+10) = 5
+        #[cfg(feature = "distro-packages")]
+        assert_eq!(calculate_asset_score("app_amd64.deb", target), 5);
 
         // CASE 2: macOS ARM
         let target = "aarch64-macos";
@@ -540,7 +1204,37 @@ mod tests {
             -20
         );
 
-        // CASE 4: Global filters
+        // CASE 4: Linux ARM64
+        let target = "aarch64-linux";
+
+        // Perfect match: tar.gz (+20), linux (+10), aarch64 (+10), musl (+5) = 45
+        assert_eq!(
+            calculate_asset_score("app-aarch64-unknown-linux-musl.tar.gz", target),
+            45
+        );
+
+        // 32-bit ARM: tar.gz (+20), linux (+10), gnu (+3), arm (-50) = -17
+        assert_eq!(
+            calculate_asset_score("app-armv7-unknown-linux-gnueabihf.tar.gz", target),
+            -17
+        );
+
+        // CASE 5: Windows x86_64
+        let target = "x86_64-windows";
+
+        // Perfect match: zip (+15), windows (+10), x86_64 (+10), msvc (+5) = 40
+        assert_eq!(
+            calculate_asset_score("app-x86_64-pc-windows-msvc.zip", target),
+            40
+        );
+
+        // Wrong OS: tar.gz (+20), linux (-50), x86_64 (+10) = -20
+        assert_eq!(
+            calculate_asset_score("app-x86_64-unknown-linux-musl.tar.gz", target),
+            -20
+        );
+
+        // CASE 6: Global filters
         // Checksums should be heavily penalized regardless of platform
         assert!(calculate_asset_score("app-linux-amd64.tar.gz.sha256", "x86_64-linux") < -50);
     }
@@ -550,6 +1244,8 @@ mod tests {
         // 1. Create a Fake Release with a mix of good and bad assets
         let release = GitHubRelease {
             tag_name: "v1.2.3".to_string(),
+            published_at: None,
+            prerelease: false,
             assets: vec![
                 GitHubAsset {
                     name: "app.deb".to_string(),
@@ -566,12 +1262,12 @@ mod tests {
                 GitHubAsset {
                     name: "app-linux.zip".to_string(),
                     browser_download_url: "url".to_string(),
-                }, // Mediocre (+10)
+                }, // Good (+25)
             ],
         };
 
         // 2. Run logic
-        let (version, candidates) = build_candidates_from_release(&release);
+        let (version, candidates) = build_candidates_from_release(&release, TARGETS, &[]);
 
         assert_eq!(version, "1.2.3");
 
@@ -593,13 +1289,215 @@ mod tests {
             "Best match should be first"
         );
         assert_eq!(
-            filenames[1], "app.tar.gz",
-            "Generic tarball should be second"
+            filenames[1], "app-linux.zip",
+            "A zip for the right OS should beat a generic tarball"
+        );
+        assert_eq!(
+            filenames[2], "app.tar.gz",
+            "Generic tarball should be third"
         );
-        assert_eq!(filenames[2], "app-linux.zip", "Zip should be third");
         assert_eq!(filenames[3], "app.deb", "Deb should be last");
     }
 
+    #[test]
+    fn test_configured_rules_adjust_score() {
+        let rules = vec![
+            ScoringRule {
+                keyword: "-static".to_string(),
+                weight: -20,
+                targets: vec![],
+            },
+            ScoringRule {
+                keyword: "acme".to_string(),
+                weight: 15,
+                targets: vec!["aarch64-macos".to_string()],
+            },
+        ];
+        let target = "x86_64-linux";
+        let base = calculate_asset_score("app-x86_64-linux-static.tar.gz", target);
+
+        assert_eq!(
+            score_asset("app-x86_64-linux-static.tar.gz", target, &rules),
+            base - 20
+        );
+        // Rules scoped to another target don't fire
+        assert_eq!(
+            score_asset("app-x86_64-linux-acme.tar.gz", target, &rules),
+            calculate_asset_score("app-x86_64-linux-acme.tar.gz", target)
+        );
+        assert_eq!(
+            score_asset("app-x86_64-linux-static.tar.gz", target, &[]),
+            base
+        );
+    }
+
+    #[test]
+    fn test_explain_score_matches_score() {
+        let rules = vec![ScoringRule {
+            keyword: "musl".to_string(),
+            weight: 7,
+            targets: vec![],
+        }];
+        let name = "app-x86_64-unknown-linux-musl.tar.gz";
+
+        let factors = explain_score(name, "x86_64-linux", &rules);
+        assert_eq!(
+            factors.iter().map(|f| f.points).sum::<i32>(),
+            score_asset(name, "x86_64-linux", &rules)
+        );
+        assert_eq!(factors.last().unwrap().reason, "config rule 'musl'");
+    }
+
+    #[test]
+    fn test_sample_checks() {
+        let checks: Vec<u32> = (0..10).collect();
+
+        let sampled = sample_checks(checks.clone(), 3, 42);
+        assert_eq!(sampled.len(), 3);
+        // Registry order is preserved and there are no duplicates
+        assert!(sampled.windows(2).all(|w| w[0] < w[1]));
+
+        // Asking for more than exists returns everything
+        assert_eq!(sample_checks(checks.clone(), 50, 42), checks);
+        assert!(sample_checks(checks, 0, 42).is_empty());
+    }
+
+    #[test]
+    fn test_best_asset_respects_threshold() {
+        let release = GitHubRelease {
+            tag_name: "v1.0.0".to_string(),
+            published_at: None,
+            prerelease: false,
+            assets: vec![
+                GitHubAsset {
+                    name: "app-x86_64-unknown-linux-musl.tar.gz".to_string(),
+                    browser_download_url: "url".to_string(),
+                },
+                GitHubAsset {
+                    name: "app-x86_64-pc-windows-msvc.zip".to_string(),
+                    browser_download_url: "url".to_string(),
+                },
+            ],
+        };
+
+        let (_, candidates) = build_candidates_from_release(&release, TARGETS, &[]);
+        let linux = candidates
+            .iter()
+            .find(|c| c.target_slug == "x86_64-linux")
+            .unwrap();
+        let mac = candidates
+            .iter()
+            .find(|c| c.target_slug == "aarch64-macos")
+            .unwrap();
+
+        assert_eq!(
+            linux.best_asset(25).unwrap().asset.name,
+            "app-x86_64-unknown-linux-musl.tar.gz"
+        );
+        // Nothing for macOS scores well enough, so --auto skips the target
+        assert!(mac.best_asset(25).is_none());
+    }
+
+    #[test]
+    fn test_add_import_asset_uses_upstream_checksum() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let source_dir = root.join("source");
+        std::fs::create_dir_all(&source_dir).unwrap();
+
+        let asset_path = source_dir.join("tool.tar.gz");
+        std::fs::write(&asset_path, "archive bytes").unwrap();
+        let sha256 = hex::encode(sha2::Sha256::digest(b"archive bytes"));
+        let sums_path = source_dir.join("SHA256SUMS");
+        std::fs::write(&sums_path, format!("{}  tool.tar.gz\n", sha256)).unwrap();
+
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        let mut scored = ScoredAsset {
+            score: 40,
+            asset: GitHubAsset {
+                name: "tool.tar.gz".to_string(),
+                browser_download_url: format!("file://{}", asset_path.to_str().unwrap()),
+            },
+            checksum_url: Some(format!("file://{}", sums_path.to_str().unwrap())),
+        };
+        let add = |scored: &ScoredAsset, verify| {
+            add_import_asset(
+                &engine,
+                "tool".to_string(),
+                "1.0.0".to_string(),
+                "x86_64-linux".to_string(),
+                scored,
+                verify,
+                |_| {},
+            )
+        };
+
+        // 1. Trusted without downloading
+        assert_eq!(add(&scored, false).unwrap(), ChecksumSource::Upstream);
+        let manifest = std::fs::read_to_string(source_dir.join("packages/t/tool.toml")).unwrap();
+        assert!(manifest.contains(&sha256));
+        // ...and it remembers where, for `rush verify --upstream`
+        assert!(manifest.contains("SHA256SUMS"));
+
+        // 2. Confirmed by a download
+        assert_eq!(
+            add(&scored, true).unwrap(),
+            ChecksumSource::UpstreamVerified
+        );
+
+        // 3. A download that disagrees with upstream is rejected
+        std::fs::write(&asset_path, "tampered").unwrap();
+        let err = add(&scored, true).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::ChecksumMismatch { .. })
+        ));
+
+        // 4. Nothing published: hash it ourselves
+        scored.checksum_url = None;
+        assert_eq!(add(&scored, false).unwrap(), ChecksumSource::Computed);
+    }
+
+    #[test]
+    fn test_preview_import_asset_suggests_binary() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let archive_path = root.join("tool.tar.gz");
+
+        let tarball = tar_gz(&[
+            ("tool-1.0/README.md", 0o644, b"data"),
+            ("tool-1.0/tool", 0o755, b"data"),
+        ]);
+        std::fs::write(&archive_path, tarball).unwrap();
+
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(String::new())
+            .build()
+            .unwrap();
+        let scored = ScoredAsset {
+            score: 40,
+            asset: GitHubAsset {
+                name: "tool.tar.gz".to_string(),
+                browser_download_url: format!("file://{}", archive_path.to_str().unwrap()),
+            },
+            checksum_url: None,
+        };
+
+        let preview = preview_import_asset(&engine, "tool", &scored, |_| {}).unwrap();
+
+        assert_eq!(preview.entries.len(), 2);
+        assert_eq!(preview.suggested, Some(1));
+        assert_eq!(preview.entries[1].file_name(), "tool");
+        let bytes = std::fs::read(&archive_path).unwrap();
+        assert_eq!(preview.sha256, hex::encode(sha2::Sha256::digest(&bytes)));
+    }
+
     #[test]
     fn test_verify_registry_detects_bad_hash() {
         let temp_dir = tempdir().unwrap();
@@ -632,12 +1530,14 @@ mod tests {
         .unwrap();
 
         // 4. Run Verify
-        let engine =
-            RushEngine::with_root_and_registry(root, source_dir.to_str().unwrap().to_string())
-                .unwrap();
-        engine.update_registry(|_| {}).unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
-        let result = engine.verify_registry(|_| {}).unwrap();
+        let result = engine.verify_registry(None, &mut ()).unwrap();
 
         assert!(!result.failures.is_empty());
         assert!(result.failures[0].error.contains("Checksum mismatch"));
@@ -652,16 +1552,8 @@ mod tests {
         // 1. Create a VALID tarball (valid GZIP), but EMPTY of the binary we want
         std::fs::create_dir_all(&source_dir).unwrap();
         let archive_path = source_dir.join("empty.tar.gz");
-        let f = std::fs::File::create(&archive_path).unwrap();
-        let enc = flate2::write::GzEncoder::new(f, flate2::Compression::default());
-        let mut tar = tar::Builder::new(enc);
-        let mut header = tar::Header::new_gnu();
-        header.set_path("other-file.txt").unwrap();
-        header.set_size(0);
-        header.set_cksum();
-        tar.append_data(&mut header, "other-file.txt", "".as_bytes())
-            .unwrap();
-        tar.into_inner().unwrap().finish().unwrap();
+        let tarball = tar_gz(&[("other-file.txt", 0o644, b"")]);
+        std::fs::write(&archive_path, tarball).unwrap();
 
         // 2. Calculate VALID hash for that tarball
         let bytes = std::fs::read(&archive_path).unwrap();
@@ -689,12 +1581,14 @@ mod tests {
         .unwrap();
 
         // 4. Run Verify
-        let engine =
-            RushEngine::with_root_and_registry(root, source_dir.to_str().unwrap().to_string())
-                .unwrap();
-        engine.update_registry(|_| {}).unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
-        let result = engine.verify_registry(|_| {}).unwrap();
+        let result = engine.verify_registry(None, &mut ()).unwrap();
 
         assert!(!result.failures.is_empty());
         assert!(
@@ -714,18 +1608,8 @@ mod tests {
         let archive_path = source_dir.join("good.tar.gz");
         std::fs::create_dir_all(&source_dir).unwrap();
 
-        let f = std::fs::File::create(&archive_path).unwrap();
-        let enc = flate2::write::GzEncoder::new(f, flate2::Compression::default());
-        let mut tar = tar::Builder::new(enc);
-
-        // Add a fake binary file
-        let mut header = tar::Header::new_gnu();
-        header.set_path("my-bin").unwrap();
-        header.set_size(4);
-        header.set_cksum();
-        tar.append_data(&mut header, "my-bin", "bin!".as_bytes())
-            .unwrap();
-        tar.into_inner().unwrap().finish().unwrap();
+        // Holding a fake binary
+        std::fs::write(&archive_path, tar_gz(&[("my-bin", 0o755, b"bin!")])).unwrap();
 
         // 2. Calculate VALID hash
         let bytes = std::fs::read(&archive_path).unwrap();
@@ -753,12 +1637,14 @@ mod tests {
         .unwrap();
 
         // 4. Run Verify
-        let engine =
-            RushEngine::with_root_and_registry(root, source_dir.to_str().unwrap().to_string())
-                .unwrap();
-        engine.update_registry(|_| {}).unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
-        let result = engine.verify_registry(|_| {}).unwrap();
+        let result = engine.verify_registry(None, &mut ()).unwrap();
 
         assert!(result.failures.is_empty());
         assert_eq!(result.packages_checked, 1);
@@ -786,12 +1672,14 @@ mod tests {
         .unwrap();
 
         // 2. Run Verify
-        let engine =
-            RushEngine::with_root_and_registry(root, source_dir.to_str().unwrap().to_string())
-                .unwrap();
-        engine.update_registry(|_| {}).unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
-        let result = engine.verify_registry(|_| {}).unwrap();
+        let result = engine.verify_registry(None, &mut ()).unwrap();
 
         assert!(!result.failures.is_empty());
         assert!(
@@ -806,7 +1694,7 @@ mod tests {
         let root = temp_dir.path().to_path_buf();
         let source_dir = root.join("source");
 
-        // 1. Create a file that is NOT a gzip (just text)
+        // 1. Create a file that is NOT an archive (just text)
         let archive_path = source_dir.join("broken.tar.gz");
         std::fs::create_dir_all(&source_dir).unwrap();
         std::fs::write(&archive_path, "this is not a tarball").unwrap();
@@ -837,18 +1725,20 @@ mod tests {
         .unwrap();
 
         // 4. Run Verify
-        let engine =
-            RushEngine::with_root_and_registry(root, source_dir.to_str().unwrap().to_string())
-                .unwrap();
-        engine.update_registry(|_| {}).unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
-        let result = engine.verify_registry(|_| {}).unwrap();
+        let result = engine.verify_registry(None, &mut ()).unwrap();
 
         assert!(!result.failures.is_empty());
-        // It might fail at GzDecoder or Archive handling
         assert!(
-            result.failures[0].error.contains("invalid gzip header")
-                || result.failures[0].error.contains("corrupt")
+            result.failures[0]
+                .error
+                .contains("Not a recognized archive")
         );
     }
 }
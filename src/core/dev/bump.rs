@@ -0,0 +1,360 @@
+use super::{
+    IMPORT_MIN_SCORE, checksums, ensure_local_registry, fetch_release, gitlab, manifest_path,
+    score_asset, timestamp,
+};
+use crate::core::cancel::{Deadline, DeadlineTransport};
+use crate::core::requires::compare_versions;
+use crate::core::{RushEngine, Target, Transport, util};
+use crate::models::{
+    BumpEvent, BumpResult, GitHubAsset, GitHubRelease, PackageManifest, ScoringRule,
+};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use toml_edit::{DocumentMut, value};
+
+/// Developer Tool: Move a package in the local registry to its newest upstream release.
+/// Every target is re-resolved and re-hashed; the manifest is only rewritten if all succeed,
+/// and then only the fields that changed. A release older than the manifest's version is
+/// refused unless `force` is set.
+pub fn bump_package<F>(
+    engine: &RushEngine,
+    name: &str,
+    force: bool,
+    on_event: F,
+) -> Result<BumpResult>
+where
+    F: FnMut(BumpEvent),
+{
+    bump_package_by(engine, name, force, None, on_event)
+}
+
+/// `bump_package`, giving up with `rush::Error::TimedOut` once `deadline` passes
+pub(crate) fn bump_package_by<F>(
+    engine: &RushEngine,
+    name: &str,
+    force: bool,
+    deadline: Option<Deadline>,
+    mut on_event: F,
+) -> Result<BumpResult>
+where
+    F: FnMut(BumpEvent),
+{
+    let check_deadline = || deadline.as_ref().map_or(Ok(()), Deadline::check);
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    let package_path = manifest_path(&source_path, name)?;
+
+    let content = std::fs::read_to_string(&package_path)
+        .with_context(|| format!("Package '{}' is not in the local registry", name))?;
+    let manifest: PackageManifest = toml::from_str(&content)?;
+
+    // 1. Find the upstream
+    let source = manifest
+        .source
+        .clone()
+        .or_else(|| manifest.targets.values().find_map(|t| infer_source(&t.url)))
+        .with_context(|| {
+            format!(
+                "Can't tell where '{}' is released; add `source = \"owner/repo\"` to its manifest",
+                name
+            )
+        })?;
+
+    check_deadline()?;
+    let release = fetch_release(engine, &source, None, deadline.map(|d| d.remaining()))?;
+    let new_version = release.tag_name.trim_start_matches('v').to_string();
+
+    let mut result = BumpResult {
+        package_name: name.to_string(),
+        old_version: manifest.version.clone(),
+        new_version: new_version.clone(),
+        source: source.clone(),
+        updated: Vec::new(),
+        failures: Vec::new(),
+    };
+
+    // Guard Clause: Nothing to do
+    if new_version == manifest.version {
+        return Ok(result);
+    }
+    // Guard Clause: Upstream went backwards (a yanked release, a retagged one)
+    if !force && compare_versions(&new_version, &manifest.version) != Ordering::Greater {
+        anyhow::bail!(
+            "The newest release of '{}' ({}) is not newer than {} (use --force to bump anyway)",
+            name,
+            new_version,
+            manifest.version
+        );
+    }
+
+    // 2. Re-resolve and re-hash every target
+    let mut bumped = manifest.clone();
+    bumped.version = new_version.clone();
+    bumped.source = Some(source);
+
+    let rules = &engine.config.scoring.rules;
+    let limited;
+    let transport: &dyn Transport = match deadline {
+        Some(deadline) => {
+            limited = DeadlineTransport {
+                inner: engine.transport.as_ref(),
+                deadline,
+            };
+            &limited
+        }
+        None => engine.transport.as_ref(),
+    };
+    for (target, def) in bumped.targets.iter_mut() {
+        check_deadline()?;
+        let Some(asset) = matching_asset(
+            &release,
+            &def.url,
+            &manifest.version,
+            &new_version,
+            target,
+            rules,
+        ) else {
+            result
+                .failures
+                .push((target.clone(), "No matching asset in release".to_string()));
+            continue;
+        };
+
+        on_event(BumpEvent::Downloading {
+            target: target.clone(),
+            url: asset.browser_download_url.clone(),
+        });
+        let mut on_progress = util::tag_progress(Some(name), |e| on_event(BumpEvent::Progress(e)));
+        let content =
+            match util::download_url(transport, &asset.browser_download_url, &mut on_progress) {
+                Ok(c) => c,
+                Err(e) => {
+                    result.failures.push((target.clone(), e.to_string()));
+                    continue;
+                }
+            };
+
+        def.url = asset.browser_download_url.clone();
+        def.sha256 = hex::encode(Sha256::digest(&content));
+        def.size = Some(content.len() as u64);
+        def.checksum_url = checksums::find_checksum_asset(&release, &asset.name)
+            .map(|c| c.browser_download_url.clone());
+        result.updated.push(target.clone());
+    }
+
+    // 3. Never leave a manifest with a mix of old and new URLs
+    if result.failures.is_empty() {
+        bumped.updated = Some(timestamp());
+        std::fs::write(&package_path, edit_manifest(&content, &bumped)?)?;
+    }
+
+    Ok(result)
+}
+
+/// Helper: `content` with the fields a bump changes set to `bumped`'s, leaving the rest
+/// of the manifest (comments, key order, formatting) as it was
+fn edit_manifest(content: &str, bumped: &PackageManifest) -> Result<String> {
+    let mut doc: DocumentMut = content.parse()?;
+    doc["version"] = value(&bumped.version);
+    if let Some(source) = &bumped.source {
+        doc["source"] = value(source);
+    }
+    if let Some(updated) = &bumped.updated {
+        doc["updated"] = value(updated);
+    }
+
+    let targets = doc
+        .get_mut("targets")
+        .and_then(|t| t.as_table_like_mut())
+        .context("Manifest has no [targets] table")?;
+    for (key, item) in targets.iter_mut() {
+        let (Some(def), Some(table)) = (
+            bumped.targets.get(&Target::normalize(&key)),
+            item.as_table_like_mut(),
+        ) else {
+            continue;
+        };
+        table.insert("url", value(&def.url));
+        table.insert("sha256", value(&def.sha256));
+        if let Some(size) = def.size {
+            table.insert("size", value(size as i64));
+        }
+        match &def.checksum_url {
+            Some(url) => table.insert("checksum_url", value(url)),
+            None => table.remove("checksum_url"),
+        };
+    }
+    Ok(doc.to_string())
+}
+
+/// Helper: Guess the `dev import` source from a release download URL
+fn infer_source(url: &str) -> Option<String> {
+    if let Some(rest) = url.strip_prefix("https://github.com/") {
+        let mut parts = rest.split('/');
+        let (owner, repo) = (parts.next()?, parts.next()?);
+        return (parts.next()? == "releases").then(|| format!("{}/{}", owner, repo));
+    }
+
+    if let Some(rest) = url.strip_prefix("https://gitlab.com/") {
+        let (project, _) = rest.split_once("/-/")?;
+        return Some(format!("{}{}", gitlab::PREFIX, project));
+    }
+
+    None
+}
+
+/// Helper: The new release's equivalent of the asset at `old_url`.
+/// Prefers the same file name with the version swapped, then the best-scoring asset.
+fn matching_asset<'a>(
+    release: &'a GitHubRelease,
+    old_url: &str,
+    old_version: &str,
+    new_version: &str,
+    target: &str,
+    rules: &[ScoringRule],
+) -> Option<&'a GitHubAsset> {
+    let old_name = old_url.rsplit('/').next().unwrap_or_default();
+    if !old_version.is_empty() && old_name.contains(old_version) {
+        let renamed = old_name.replace(old_version, new_version);
+        if let Some(asset) = release.assets.iter().find(|a| a.name == renamed) {
+            return Some(asset);
+        }
+    }
+
+    release
+        .assets
+        .iter()
+        .map(|a| (score_asset(&a.name, target, rules), a))
+        .filter(|(score, _)| *score >= IMPORT_MIN_SCORE)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, a)| a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(names: &[&str]) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: "v1.1.0".to_string(),
+            published_at: None,
+            prerelease: false,
+            assets: names
+                .iter()
+                .map(|n| GitHubAsset {
+                    name: n.to_string(),
+                    browser_download_url: format!("https://example.com/{}", n),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_edit_manifest_keeps_the_rest() {
+        let content = r#"# Maintained by hand
+version = "1.0.0"
+description = "A tool" # keep this
+
+[targets.linux-x86_64]
+url = "https://example.com/tool-1.0.0.tar.gz"
+bin = "tool"
+sha256 = "old"
+checksum_url = "https://example.com/tool-1.0.0.sha256"
+
+[targets.aarch64-macos]
+url = "https://example.com/tool-1.0.0-mac.tar.gz"
+bin = "tool"
+sha256 = "old"
+"#;
+        let mut bumped: PackageManifest = toml::from_str(content).unwrap();
+        bumped.version = "1.1.0".to_string();
+        bumped.source = Some("owner/tool".to_string());
+        let linux = bumped.targets.get_mut("x86_64-linux").unwrap();
+        linux.url = "https://example.com/tool-1.1.0.tar.gz".to_string();
+        linux.sha256 = "new".to_string();
+        linux.size = Some(42);
+        linux.checksum_url = None;
+
+        let edited = edit_manifest(content, &bumped).unwrap();
+        assert!(edited.starts_with("# Maintained by hand\nversion = \"1.1.0\"\n"));
+        assert!(edited.contains("description = \"A tool\" # keep this"));
+        // Target keys keep their spelling; only the changed fields differ
+        assert!(edited.contains(
+            "[targets.linux-x86_64]\nurl = \"https://example.com/tool-1.1.0.tar.gz\"\nbin = \"tool\"\nsha256 = \"new\"\nsize = 42\n"
+        ));
+        assert!(!edited.contains("checksum_url"));
+        assert!(edited.contains("url = \"https://example.com/tool-1.0.0-mac.tar.gz\""));
+        let parsed: PackageManifest = toml::from_str(&edited).unwrap();
+        assert_eq!(parsed.source, bumped.source);
+        assert_eq!(parsed.targets, bumped.targets);
+    }
+
+    #[test]
+    fn test_infer_source() {
+        assert_eq!(
+            infer_source(
+                "https://github.com/sharkdp/bat/releases/download/v0.24.0/bat-v0.24.0-x86_64-unknown-linux-musl.tar.gz"
+            )
+            .as_deref(),
+            Some("sharkdp/bat")
+        );
+        assert_eq!(
+            infer_source("https://gitlab.com/group/sub/tool/-/releases/v1/downloads/tool.tar.gz")
+                .as_deref(),
+            Some("gitlab:group/sub/tool")
+        );
+        assert_eq!(infer_source("https://example.com/tool.tar.gz"), None);
+        assert_eq!(
+            infer_source("https://github.com/owner/repo/archive/main.tar.gz"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_matching_asset_swaps_version_in_name() {
+        let release = release(&[
+            "tool-1.1.0-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-1.1.0-x86_64-unknown-linux-musl.tar.gz",
+        ]);
+
+        // The gnu build was chosen before, even though musl scores higher
+        let asset = matching_asset(
+            &release,
+            "https://example.com/tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz",
+            "1.0.0",
+            "1.1.0",
+            "x86_64-linux",
+            &[],
+        );
+        assert_eq!(
+            asset.unwrap().name,
+            "tool-1.1.0-x86_64-unknown-linux-gnu.tar.gz"
+        );
+
+        // Naming scheme changed: fall back to scoring
+        let asset = matching_asset(
+            &release,
+            "https://example.com/tool_linux_amd64.tar.gz",
+            "1.0.0",
+            "1.1.0",
+            "x86_64-linux",
+            &[],
+        );
+        assert_eq!(
+            asset.unwrap().name,
+            "tool-1.1.0-x86_64-unknown-linux-musl.tar.gz"
+        );
+
+        assert!(
+            matching_asset(
+                &release,
+                "https://example.com/tool-1.0.0-aarch64-apple-darwin.tar.gz",
+                "1.0.0",
+                "1.1.0",
+                "aarch64-macos",
+                &[],
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::core::Transport;
+use crate::models::{GitHubAsset, GitHubRelease};
+use anyhow::{Context, Result};
+use std::io::Read;
+
+/// Release-wide checksum files, compared case-insensitively
+const SHARED_CHECKSUM_FILES: &[&str] = &[
+    "sha256sums",
+    "sha256sums.txt",
+    "sha256sum.txt",
+    "checksums.txt",
+    "checksums.sha256",
+];
+
+/// Finds the checksum file covering `asset_name`: its own `<asset>.sha256` if published,
+/// otherwise a release-wide file like `SHA256SUMS` or goreleaser's `<tool>_checksums.txt`.
+pub fn find_checksum_asset<'a>(
+    release: &'a GitHubRelease,
+    asset_name: &str,
+) -> Option<&'a GitHubAsset> {
+    let own = [
+        format!("{}.sha256", asset_name),
+        format!("{}.sha256sum", asset_name),
+    ];
+    if let Some(asset) = release.assets.iter().find(|a| own.contains(&a.name)) {
+        return Some(asset);
+    }
+
+    release.assets.iter().find(|a| {
+        let name = a.name.to_lowercase();
+        SHARED_CHECKSUM_FILES.contains(&name.as_str())
+            || name.ends_with("_checksums.txt")
+            || name.ends_with("-checksums.txt")
+    })
+}
+
+/// Downloads a checksum file and returns the sha256 it lists for `asset_name`
+pub fn fetch_upstream_checksum(
+    transport: &dyn Transport,
+    url: &str,
+    asset_name: &str,
+) -> Result<String> {
+    let mut content = String::new();
+    transport.get(url)?.body.read_to_string(&mut content)?;
+
+    parse_checksum_file(&content, asset_name)
+        .with_context(|| format!("No sha256 for '{}' in {}", asset_name, url))
+}
+
+/// Parses `sha256sum` output ("<hash>  <file>" or "<hash> *<file>").
+/// A file holding a single bare hash (e.g. `<asset>.sha256`) applies to any asset.
+pub fn parse_checksum_file(content: &str, asset_name: &str) -> Option<String> {
+    let mut entries = Vec::new();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(hash) = parts.next() else {
+            continue;
+        };
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+
+        match parts.next().map(|f| f.trim_start_matches('*')) {
+            // Some files list paths like "./dist/tool.tar.gz"
+            Some(file) if file.rsplit('/').next() == Some(asset_name) => {
+                return Some(hash.to_lowercase());
+            }
+            Some(_) => {}
+            None => entries.push(hash),
+        }
+    }
+
+    match entries.as_slice() {
+        [only] => Some(only.to_lowercase()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_A: &str = "0dcd8ac79732c0d5b136f11f4ee00e581440e16a44eab5b3105b611bbf2cf191";
+    const HASH_B: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn asset(name: &str) -> GitHubAsset {
+        GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+        }
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        let sums = format!("{HASH_A}  tool-linux.tar.gz\n{HASH_B} *tool-macos.tar.gz\n");
+        assert_eq!(
+            parse_checksum_file(&sums, "tool-macos.tar.gz").as_deref(),
+            Some(HASH_B)
+        );
+        assert_eq!(parse_checksum_file(&sums, "tool-windows.zip"), None);
+        assert_eq!(
+            parse_checksum_file(&format!("{HASH_A}  other.tar.gz"), "tool.tar.gz"),
+            None
+        );
+
+        // A lone hash (e.g. "<asset>.sha256") applies to whatever it was published for
+        assert_eq!(
+            parse_checksum_file(&format!("{}\n", HASH_A.to_uppercase()), "x").as_deref(),
+            Some(HASH_A)
+        );
+        assert_eq!(
+            parse_checksum_file(&format!("{HASH_A}  ./dist/tool.tar.gz"), "tool.tar.gz").as_deref(),
+            Some(HASH_A)
+        );
+    }
+
+    #[test]
+    fn test_find_checksum_asset_prefers_own_file() {
+        let release = GitHubRelease {
+            tag_name: "v1.0.0".to_string(),
+            published_at: None,
+            prerelease: false,
+            assets: vec![
+                asset("tool-linux.tar.gz"),
+                asset("tool-linux.tar.gz.sha256"),
+                asset("tool-macos.tar.gz"),
+                asset("tool_1.0.0_checksums.txt"),
+            ],
+        };
+
+        assert_eq!(
+            find_checksum_asset(&release, "tool-linux.tar.gz").map(|a| a.name.as_str()),
+            Some("tool-linux.tar.gz.sha256")
+        );
+        assert_eq!(
+            find_checksum_asset(&release, "tool-macos.tar.gz").map(|a| a.name.as_str()),
+            Some("tool_1.0.0_checksums.txt")
+        );
+    }
+}
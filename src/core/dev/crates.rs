@@ -0,0 +1,306 @@
+use super::{
+    checksums, ensure_local_registry, github, release_api_url, resolve_import_targets,
+    set_checksum_url, set_package_source, write_package_manifest,
+};
+use crate::core::RushEngine;
+use crate::models::{
+    CratesIoResponse, DistArtifact, DistImportResult, DistManifest, GitHubRelease,
+};
+use anyhow::{Context, Result};
+
+/// Imports a crate's cargo-dist release artifacts without downloading them:
+/// targets, binary names, and hashes all come from the release's `dist-manifest.json`.
+/// Uses the latest stable version on crates.io unless `version` is given.
+pub fn import_cargo_dist(
+    engine: &RushEngine,
+    crate_name: &str,
+    version: Option<&str>,
+    targets: &[String],
+) -> Result<DistImportResult> {
+    ensure_local_registry(&engine.registry_source)?;
+    let wanted = resolve_import_targets(targets)?;
+
+    // 1. Find the repository and version on crates.io
+    let info: CratesIoResponse = engine
+        .client
+        .get(format!("https://crates.io/api/v1/crates/{}", crate_name))
+        .send()?
+        .error_for_status()
+        .with_context(|| format!("Crate '{}' not found on crates.io", crate_name))?
+        .json()?;
+    let krate = info.krate;
+
+    let version = match version {
+        Some(v) => v.to_string(),
+        None => krate.max_stable_version.unwrap_or(krate.max_version),
+    };
+    let repository = krate
+        .repository
+        .with_context(|| format!("Crate '{}' has no repository link", crate_name))?;
+    let repo = github::repo_from_url(&repository).with_context(|| {
+        format!(
+            "Only GitHub repositories are supported (found '{}')",
+            repository
+        )
+    })?;
+
+    // 2. Find the matching release and its dist-manifest.json
+    let release = find_release(engine, &repo, &krate.name, &version)?;
+    let manifest_url = asset_url(&release, "dist-manifest.json").with_context(|| {
+        format!(
+            "Release {} of {} has no dist-manifest.json (not built with cargo-dist?)",
+            release.tag_name, repo
+        )
+    })?;
+    let dist: DistManifest = engine
+        .client
+        .get(manifest_url)
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    // 3. Write one target per matching artifact
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (slug, _) in wanted {
+        let Some((artifact_name, artifact)) = select_artifact(&dist, slug) else {
+            skipped.push((slug.to_string(), "No .tar.gz artifact".to_string()));
+            continue;
+        };
+        let Some(url) = asset_url(&release, artifact_name) else {
+            skipped.push((
+                slug.to_string(),
+                format!("'{}' not in release", artifact_name),
+            ));
+            continue;
+        };
+        let Some(bin) = executable_name(artifact) else {
+            skipped.push((slug.to_string(), "No executable in artifact".to_string()));
+            continue;
+        };
+
+        let checksum_url = artifact
+            .checksum
+            .as_deref()
+            .and_then(|name| asset_url(&release, name));
+        let sha256 = match artifact.checksums.get("sha256") {
+            Some(hash) => hash.clone(),
+            None => match fetch_checksum_file(engine, &release, artifact) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    skipped.push((slug.to_string(), e.to_string()));
+                    continue;
+                }
+            },
+        };
+
+        write_package_manifest(
+            &engine.registry_source,
+            &krate.name,
+            &version,
+            slug,
+            url,
+            Some(bin),
+            &sha256,
+            None,
+        )?;
+        if let Some(checksum_url) = checksum_url {
+            set_checksum_url(engine, &krate.name, slug, checksum_url)?;
+        }
+        imported.push(slug.to_string());
+    }
+
+    if !imported.is_empty() {
+        set_package_source(engine, &krate.name, &repo)?;
+    }
+
+    Ok(DistImportResult {
+        package_name: krate.name,
+        version,
+        imported,
+        skipped,
+    })
+}
+
+/// Helper: cargo-dist tags releases "v1.2.3", or "<crate>-v1.2.3" in workspaces
+fn find_release(
+    engine: &RushEngine,
+    repo: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<GitHubRelease> {
+    let tags = [
+        format!("v{}", version),
+        format!("{}-v{}", crate_name, version),
+        version.to_string(),
+    ];
+
+    for tag in &tags {
+        let url = release_api_url(repo, Some(tag));
+        if let Some(release) = github::api_get_json(&engine.client, &engine.cache_dir, &url, None)?
+        {
+            return Ok(release);
+        }
+    }
+
+    anyhow::bail!(
+        "No release of {} found for version {} (tried {})",
+        repo,
+        version,
+        tags.join(", ")
+    )
+}
+
+/// Helper: Download URL of the release asset called `name`
+fn asset_url<'a>(release: &'a GitHubRelease, name: &str) -> Option<&'a str> {
+    release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .map(|a| a.browser_download_url.as_str())
+}
+
+/// Helper: Picks the installable archive for `target`, preferring static (musl) builds
+fn select_artifact<'a>(
+    dist: &'a DistManifest,
+    target: &str,
+) -> Option<(&'a str, &'a DistArtifact)> {
+    dist.artifacts
+        .iter()
+        .filter(|(name, a)| {
+            a.kind == "executable-zip" && (name.ends_with(".tar.gz") || name.ends_with(".tgz"))
+        })
+        .filter_map(|(name, a)| {
+            let best = a
+                .target_triples
+                .iter()
+                .filter(|t| target_from_triple(t).as_deref() == Some(target))
+                .map(|t| if t.ends_with("musl") { 2 } else { 1 })
+                .max()?;
+            Some((best, name.as_str(), a))
+        })
+        .max_by_key(|(rank, _, _)| *rank)
+        .map(|(_, name, a)| (name, a))
+}
+
+/// Helper: "x86_64-unknown-linux-musl" -> "x86_64-linux"
+fn target_from_triple(triple: &str) -> Option<String> {
+    let arch = triple.split('-').next()?;
+    if arch != "x86_64" && arch != "aarch64" {
+        return None;
+    }
+
+    let os = if triple.contains("-linux") {
+        "linux"
+    } else if triple.contains("-apple-darwin") {
+        "macos"
+    } else if triple.contains("-windows") {
+        "windows"
+    } else {
+        return None;
+    };
+
+    Some(format!("{}-{}", arch, os))
+}
+
+/// Helper: Name of the (first) executable shipped in an artifact
+fn executable_name(artifact: &DistArtifact) -> Option<String> {
+    let name = artifact
+        .assets
+        .iter()
+        .find(|a| a.kind == "executable")?
+        .name
+        .as_deref()?;
+    Some(name.trim_end_matches(".exe").to_string())
+}
+
+/// Helper: Reads the hash from the artifact's companion `.sha256` file
+fn fetch_checksum_file(
+    engine: &RushEngine,
+    release: &GitHubRelease,
+    artifact: &DistArtifact,
+) -> Result<String> {
+    let checksum_name = artifact
+        .checksum
+        .as_deref()
+        .context("No checksum published")?;
+    let url = asset_url(release, checksum_name)
+        .with_context(|| format!("'{}' not in release", checksum_name))?;
+    let artifact_name = checksum_name.trim_end_matches(".sha256");
+
+    checksums::fetch_upstream_checksum(engine.transport.as_ref(), url, artifact_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIST_MANIFEST: &str = r#"
+    {
+        "dist_version": "0.22.1",
+        "announcement_tag": "v1.0.0",
+        "artifacts": {
+            "tool-x86_64-unknown-linux-gnu.tar.gz": {
+                "name": "tool-x86_64-unknown-linux-gnu.tar.gz",
+                "kind": "executable-zip",
+                "target_triples": ["x86_64-unknown-linux-gnu"],
+                "assets": [
+                    { "name": "README.md", "path": "README.md", "kind": "readme" },
+                    { "name": "tool", "path": "tool", "kind": "executable" }
+                ],
+                "checksum": "tool-x86_64-unknown-linux-gnu.tar.gz.sha256"
+            },
+            "tool-x86_64-unknown-linux-musl.tar.gz": {
+                "name": "tool-x86_64-unknown-linux-musl.tar.gz",
+                "kind": "executable-zip",
+                "target_triples": ["x86_64-unknown-linux-musl"],
+                "assets": [{ "name": "tool", "path": "tool", "kind": "executable" }],
+                "checksums": { "sha256": "0dcd8ac79732c0d5b136f11f4ee00e581440e16a44eab5b3105b611bbf2cf191" }
+            },
+            "tool-aarch64-apple-darwin.tar.xz": {
+                "name": "tool-aarch64-apple-darwin.tar.xz",
+                "kind": "executable-zip",
+                "target_triples": ["aarch64-apple-darwin"],
+                "assets": [{ "name": "tool", "path": "tool", "kind": "executable" }]
+            },
+            "tool-x86_64-unknown-linux-gnu.tar.gz.sha256": {
+                "kind": "checksum",
+                "target_triples": ["x86_64-unknown-linux-gnu"]
+            },
+            "tool-installer.sh": { "kind": "installer" }
+        }
+    }
+    "#;
+
+    #[test]
+    fn test_select_artifact_prefers_musl_tarballs() {
+        let dist: DistManifest = serde_json::from_str(DIST_MANIFEST).unwrap();
+
+        let (name, artifact) = select_artifact(&dist, "x86_64-linux").unwrap();
+        assert_eq!(name, "tool-x86_64-unknown-linux-musl.tar.gz");
+        assert_eq!(executable_name(artifact).as_deref(), Some("tool"));
+        assert!(artifact.checksums.contains_key("sha256"));
+
+        // Only a .tar.xz exists for macOS, which we can't install yet
+        assert!(select_artifact(&dist, "aarch64-macos").is_none());
+        assert!(select_artifact(&dist, "aarch64-linux").is_none());
+    }
+
+    #[test]
+    fn test_target_from_triple() {
+        assert_eq!(
+            target_from_triple("aarch64-unknown-linux-musl").as_deref(),
+            Some("aarch64-linux")
+        );
+        assert_eq!(
+            target_from_triple("x86_64-apple-darwin").as_deref(),
+            Some("x86_64-macos")
+        );
+        assert_eq!(
+            target_from_triple("x86_64-pc-windows-msvc").as_deref(),
+            Some("x86_64-windows")
+        );
+        assert_eq!(target_from_triple("i686-unknown-linux-gnu"), None);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::Error;
+use anyhow::Result;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use reqwest::header::{AUTHORIZATION, ETAG, HeaderMap, IF_NONE_MATCH, RETRY_AFTER};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variables checked (in order) for a GitHub API token
+const TOKEN_VARS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// The GitHub API refused a request because the rate limit is used up
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitError {
+    /// When the limit resets, as seconds since the Unix epoch (if GitHub said)
+    pub reset_at: Option<u64>,
+    /// Whether the request carried a token (authenticated limits are much higher)
+    pub authenticated: bool,
+}
+
+impl RateLimitError {
+    /// How long until the limit resets (zero if unknown or already past)
+    pub fn wait_time(&self) -> Duration {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Duration::from_secs(self.reset_at.unwrap_or(now).saturating_sub(now))
+    }
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GitHub API rate limit exceeded")?;
+        if self.reset_at.is_some() {
+            let wait = self.wait_time().as_secs();
+            write!(f, "; it resets in {}m {}s", wait / 60, wait % 60)?;
+        }
+        if !self.authenticated {
+            write!(
+                f,
+                ". Set GITHUB_TOKEN to raise the limit from 60 to 5000 requests per hour"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RateLimitError {}
+
+/// The GitHub API token from the environment, if one is set
+pub fn token() -> Option<String> {
+    TOKEN_VARS
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()))
+}
+
+/// GET a GitHub API URL and parse the JSON body, or Ok(None) on 404.
+/// Responses are cached under `cache_dir` by URL along with their ETag; repeat requests are
+/// conditional, and a 304 answer (which doesn't count against the rate limit) reuses the cache.
+/// Rate-limit rejections become a `RateLimitError`. With a `timeout`, the request is given
+/// up after it.
+pub fn api_get_json<T: DeserializeOwned>(
+    client: &Client,
+    cache_dir: &Path,
+    url: &str,
+    timeout: Option<Duration>,
+) -> Result<Option<T>> {
+    let token = token();
+    let cache_path = cache_path(cache_dir, url);
+    let cached = read_cached(&cache_path, url);
+
+    let mut request = client.get(url);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    if let Some(token) = &token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    if let Some(etag) = cached.as_ref().map(|c| &c.etag) {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    let response = request.send()?;
+
+    if let Some(reset_at) = rate_limit_reset(response.status(), response.headers()) {
+        return Err(Error::RateLimited(RateLimitError {
+            reset_at,
+            authenticated: token.is_some(),
+        })
+        .into());
+    }
+
+    let body = match (response.status(), cached) {
+        (StatusCode::NOT_MODIFIED, Some(cached)) => cached.body,
+        (StatusCode::NOT_FOUND, _) => return Ok(None),
+        _ => {
+            let response = response.error_for_status()?;
+            let etag = response
+                .headers()
+                .get(ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = response.text()?;
+            if let Some(etag) = etag {
+                // A cache we can't write is only a missed optimization
+                let _ = write_cached(&cache_path, url, &etag, &body);
+            }
+            body
+        }
+    };
+
+    Ok(Some(serde_json::from_str(&body)?))
+}
+
+/// "https://github.com/owner/repo(.git)", "git@github.com:owner/repo.git", ... -> "owner/repo"
+pub fn repo_from_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/').trim_end_matches(".git");
+    let path = [
+        "https://github.com/",
+        "http://github.com/",
+        "ssh://git@github.com/",
+        "git@github.com:",
+    ]
+    .iter()
+    .find_map(|prefix| url.strip_prefix(prefix))?;
+
+    let mut parts = path.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+    Some(format!("{}/{}", owner, repo.trim_end_matches(".git")))
+}
+
+/// A cached API response body and the ETag it was served with
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    etag: String,
+    body: String,
+}
+
+/// Helper: Where the response for `url` is cached
+fn cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir
+        .join("github")
+        .join(format!("{}.json", hex::encode(Sha256::digest(url))))
+}
+
+/// Helper: The cached response for `url`, ignoring unreadable or mismatched entries
+fn read_cached(path: &Path, url: &str) -> Option<CachedResponse> {
+    let content = fs::read_to_string(path).ok()?;
+    let cached: CachedResponse = serde_json::from_str(&content).ok()?;
+    (cached.url == url).then_some(cached)
+}
+
+/// Helper: Stores a response for later conditional requests
+fn write_cached(path: &Path, url: &str, etag: &str, body: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let cached = CachedResponse {
+        url: url.to_string(),
+        etag: etag.to_string(),
+        body: body.to_string(),
+    };
+    fs::write(path, serde_json::to_string(&cached)?)?;
+    Ok(())
+}
+
+/// Helper: Some(reset time) if the response is a rate-limit rejection.
+/// GitHub answers 403 (or 429) with `x-ratelimit-remaining: 0` for the primary limit,
+/// and with `retry-after` for secondary (abuse) limits.
+fn rate_limit_reset(status: StatusCode, headers: &HeaderMap) -> Option<Option<u64>> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let header_u64 = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+
+    if let Some(seconds) = header_u64(RETRY_AFTER.as_str()) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        return Some(Some(now + seconds));
+    }
+    if header_u64("x-ratelimit-remaining") == Some(0) {
+        return Some(header_u64("x-ratelimit-reset"));
+    }
+
+    // A plain 403 (e.g. a private repo) is not a rate limit
+    (status == StatusCode::TOO_MANY_REQUESTS).then_some(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, HeaderValue::from_static(v));
+        }
+        map
+    }
+
+    #[test]
+    fn test_rate_limit_reset() {
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1700000000"),
+        ]);
+        assert_eq!(
+            rate_limit_reset(StatusCode::FORBIDDEN, &exhausted),
+            Some(Some(1700000000))
+        );
+        assert_eq!(rate_limit_reset(StatusCode::OK, &exhausted), None);
+
+        // Forbidden for other reasons
+        let remaining = headers(&[("x-ratelimit-remaining", "42")]);
+        assert_eq!(rate_limit_reset(StatusCode::FORBIDDEN, &remaining), None);
+
+        // Secondary limits only say how long to back off
+        let retry = headers(&[("retry-after", "30")]);
+        assert!(matches!(
+            rate_limit_reset(StatusCode::FORBIDDEN, &retry),
+            Some(Some(_))
+        ));
+    }
+
+    #[test]
+    fn test_repo_from_url() {
+        assert_eq!(
+            repo_from_url("https://github.com/BurntSushi/ripgrep").as_deref(),
+            Some("BurntSushi/ripgrep")
+        );
+        assert_eq!(
+            repo_from_url("https://github.com/owner/repo.git/").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            repo_from_url("https://github.com/owner/repo/tree/main/crates/x").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            repo_from_url("git@github.com:owner/repo.git").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            repo_from_url("ssh://git@github.com/owner/repo").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(repo_from_url("https://gitlab.com/owner/repo"), None);
+    }
+
+    #[test]
+    fn test_response_cache_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let url = "https://api.github.com/repos/owner/repo/releases/latest";
+        let path = cache_path(temp_dir.path(), url);
+
+        assert!(read_cached(&path, url).is_none());
+        write_cached(&path, url, "\"abc\"", "{\"tag_name\":\"v1\"}").unwrap();
+
+        let cached = read_cached(&path, url).unwrap();
+        assert_eq!(cached.etag, "\"abc\"");
+        assert_eq!(cached.body, "{\"tag_name\":\"v1\"}");
+
+        // Each URL gets its own entry
+        let other = "https://api.github.com/repos/owner/other/releases/latest";
+        assert_ne!(cache_path(temp_dir.path(), other), path);
+        assert!(read_cached(&path, other).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_error_message() {
+        let err = RateLimitError {
+            reset_at: None,
+            authenticated: false,
+        };
+        assert!(err.to_string().contains("GITHUB_TOKEN"));
+        assert_eq!(err.wait_time(), Duration::ZERO);
+
+        let err = RateLimitError {
+            reset_at: Some(0),
+            authenticated: true,
+        };
+        assert!(err.to_string().contains("resets in 0m 0s"));
+        assert!(!err.to_string().contains("GITHUB_TOKEN"));
+    }
+}
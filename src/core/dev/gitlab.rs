@@ -0,0 +1,79 @@
+use crate::models::{GitHubRelease, GitLabRelease};
+use anyhow::Result;
+use reqwest::blocking::Client;
+use std::time::Duration;
+
+/// Prefix that marks a `dev import` source as a GitLab project
+pub const PREFIX: &str = "gitlab:";
+
+const API_BASE: &str = "https://gitlab.com/api/v4";
+
+/// Fetches the release tagged `tag`, or the latest one when `None`; with a `timeout`, the
+/// request is given up after it
+pub fn fetch_release(
+    client: &Client,
+    project: &str,
+    tag: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<GitHubRelease> {
+    let mut request = client.get(release_api_url(project, tag));
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let release: GitLabRelease = request.send()?.error_for_status()?.json()?;
+
+    Ok(release.into())
+}
+
+/// The `limit` most recent releases of `project`, newest first
+pub fn list_releases(client: &Client, project: &str, limit: usize) -> Result<Vec<GitHubRelease>> {
+    let api_url = format!(
+        "{}/projects/{}/releases?per_page={}",
+        API_BASE,
+        encode_path(project),
+        limit.clamp(1, 100)
+    );
+
+    let releases: Vec<GitLabRelease> = client.get(&api_url).send()?.error_for_status()?.json()?;
+
+    Ok(releases.into_iter().map(Into::into).collect())
+}
+
+/// Helper: GitLab API endpoint for a release, by tag or the latest one
+fn release_api_url(project: &str, tag: Option<&str>) -> String {
+    let project = encode_path(project);
+    match tag {
+        Some(tag) => format!(
+            "{}/projects/{}/releases/{}",
+            API_BASE,
+            project,
+            encode_path(tag)
+        ),
+        None => format!(
+            "{}/projects/{}/releases/permalink/latest",
+            API_BASE, project
+        ),
+    }
+}
+
+/// GitLab addresses projects (and tags) by their URL-encoded path, e.g. `group%2Fproject`
+fn encode_path(path: &str) -> String {
+    path.replace('%', "%25").replace('/', "%2F")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_api_url() {
+        assert_eq!(
+            release_api_url("group/sub/project", None),
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Fproject/releases/permalink/latest"
+        );
+        assert_eq!(
+            release_api_url("group/project", Some("release/1.0")),
+            "https://gitlab.com/api/v4/projects/group%2Fproject/releases/release%2F1.0"
+        );
+    }
+}
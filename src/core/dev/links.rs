@@ -0,0 +1,280 @@
+use crate::core::RushEngine;
+use crate::models::{LinkCheck, LinkCheckEvent, LinkCheckResult, LinkStatus};
+use anyhow::Result;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use reqwest::header::{LOCATION, RANGE};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// How many redirects we follow before calling a link dead
+const MAX_REDIRECTS: usize = 10;
+
+/// Issues a HEAD request for every artifact URL in the registry, `jobs` at a time.
+pub fn check_urls<F>(engine: &RushEngine, jobs: usize, mut on_event: F) -> Result<LinkCheckResult>
+where
+    F: FnMut(LinkCheckEvent),
+{
+    let mut pending = Vec::new();
+    for (pkg_name, manifest) in engine.packages() {
+        for (target, def) in manifest.targets {
+            pending.push(LinkCheck {
+                package_name: pkg_name.clone(),
+                target,
+                url: def.url,
+                status: LinkStatus::Ok,
+            });
+        }
+    }
+
+    on_event(LinkCheckEvent::Started {
+        total: pending.len(),
+    });
+
+    // Redirects are followed by hand so we can tell permanent moves from CDN hops
+    let client = Client::builder()
+        .user_agent(concat!("rush/", env!("CARGO_PKG_VERSION")))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel::<(usize, LinkStatus)>();
+    let mut statuses: Vec<Option<LinkStatus>> = vec![None; pending.len()];
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1).min(pending.len()) {
+            let tx = tx.clone();
+            let (client, next, pending) = (&client, &next, &pending);
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(check) = pending.get(i) else {
+                        break;
+                    };
+                    if tx.send((i, check_url(client, &check.url))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Report on this thread so the callback doesn't need to be Send
+        for (i, status) in rx {
+            let mut check = pending[i].clone();
+            check.status = status.clone();
+            on_event(LinkCheckEvent::Checked(check));
+            statuses[i] = Some(status);
+        }
+    });
+
+    let checks = pending
+        .into_iter()
+        .zip(statuses)
+        .map(|(mut check, status)| {
+            check.status = status.unwrap_or(LinkStatus::Dead {
+                reason: "Not checked".to_string(),
+            });
+            check
+        })
+        .collect();
+
+    Ok(LinkCheckResult { checks })
+}
+
+/// Checks one URL, following redirects manually
+fn check_url(client: &Client, url: &str) -> LinkStatus {
+    if let Some(path) = url.strip_prefix("file://") {
+        return if std::path::Path::new(path).is_file() {
+            LinkStatus::Ok
+        } else {
+            LinkStatus::Dead {
+                reason: "File not found".to_string(),
+            }
+        };
+    }
+
+    let mut current = url.to_string();
+    let mut moved_to: Option<String> = None;
+
+    for _ in 0..MAX_REDIRECTS {
+        let response = match probe(client, &current) {
+            Ok(r) => r,
+            Err(e) => {
+                return LinkStatus::Dead {
+                    reason: e.to_string(),
+                };
+            }
+        };
+        let status = response.status();
+
+        if status.is_redirection() {
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .and_then(|l| response.url().join(l).ok())
+            else {
+                return LinkStatus::Dead {
+                    reason: format!("{} without a Location header", status),
+                };
+            };
+
+            // Only the first permanent hop matters; later hops are usually CDN internals
+            if moved_to.is_none()
+                && matches!(
+                    status,
+                    StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+                )
+            {
+                moved_to = Some(location.to_string());
+            }
+            current = location.to_string();
+            continue;
+        }
+
+        if !status.is_success() {
+            return LinkStatus::Dead {
+                reason: status.to_string(),
+            };
+        }
+
+        return match moved_to {
+            Some(location) => LinkStatus::Redirected { location },
+            None => LinkStatus::Ok,
+        };
+    }
+
+    LinkStatus::Dead {
+        reason: "Too many redirects".to_string(),
+    }
+}
+
+/// HEAD request, falling back to a one-byte GET for servers that reject HEAD
+fn probe(client: &Client, url: &str) -> reqwest::Result<reqwest::blocking::Response> {
+    let response = client.head(url).send()?;
+    if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+        return client.get(url).header(RANGE, "bytes=0-0").send();
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use tempfile::tempdir;
+
+    /// Serves canned responses keyed by request path, one connection at a time
+    fn serve(routes: Vec<(&'static str, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let response = routes
+                    .iter()
+                    .find(|(p, _)| *p == path)
+                    .map(|(_, r)| *r)
+                    .unwrap_or("HTTP/1.1 404 Not Found\r\n");
+                let mut stream = &stream;
+                let _ = write!(
+                    stream,
+                    "{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    response
+                );
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn no_redirect_client() -> Client {
+        Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_check_url_classifies_responses() {
+        let base = serve(vec![
+            ("/ok", "HTTP/1.1 200 OK\r\n"),
+            ("/cdn", "HTTP/1.1 302 Found\r\nLocation: /ok\r\n"),
+            (
+                "/moved",
+                "HTTP/1.1 301 Moved Permanently\r\nLocation: /cdn\r\n",
+            ),
+            ("/loop", "HTTP/1.1 302 Found\r\nLocation: /loop\r\n"),
+        ]);
+        let client = no_redirect_client();
+
+        assert_eq!(check_url(&client, &format!("{base}/ok")), LinkStatus::Ok);
+        // Temporary redirects (like GitHub -> CDN) are fine
+        assert_eq!(check_url(&client, &format!("{base}/cdn")), LinkStatus::Ok);
+        assert_eq!(
+            check_url(&client, &format!("{base}/moved")),
+            LinkStatus::Redirected {
+                location: format!("{base}/cdn")
+            }
+        );
+        assert!(matches!(
+            check_url(&client, &format!("{base}/missing")),
+            LinkStatus::Dead { reason } if reason.contains("404")
+        ));
+        assert!(matches!(
+            check_url(&client, &format!("{base}/loop")),
+            LinkStatus::Dead { reason } if reason.contains("Too many redirects")
+        ));
+    }
+
+    #[test]
+    fn test_check_urls_over_registry() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let source_dir = root.join("source");
+        let pkg_dir = source_dir.join("packages/t");
+        std::fs::create_dir_all(&pkg_dir).unwrap();
+
+        let artifact = source_dir.join("tool.tar.gz");
+        std::fs::write(&artifact, "data").unwrap();
+        std::fs::write(
+            pkg_dir.join("tool.toml"),
+            format!(
+                r#"
+            version = "1.0.0"
+            [targets.x86_64-linux]
+            url = "file://{}"
+            bin = "tool"
+            sha256 = "abc"
+            [targets.aarch64-macos]
+            url = "file:///path/to/nowhere.tar.gz"
+            bin = "tool"
+            sha256 = "abc"
+            "#,
+                artifact.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let mut events = 0;
+        let result = check_urls(&engine, 4, |_| events += 1).unwrap();
+
+        assert_eq!(result.checks.len(), 2);
+        assert_eq!(events, 3, "One Started plus one Checked per URL");
+
+        let problems: Vec<_> = result.problems().collect();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].target, "aarch64-macos");
+    }
+}
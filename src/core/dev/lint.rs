@@ -0,0 +1,371 @@
+use super::{TARGETS, ensure_local_registry};
+use crate::core::{RushEngine, datafiles};
+use crate::models::{LintIssue, LintResult, LintSeverity, PackageManifest};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Parses every manifest in the local registry source and checks it for mistakes.
+/// Unlike `list_available_packages`, broken files are reported instead of skipped.
+pub fn lint_registry(engine: &RushEngine) -> Result<LintResult> {
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    Ok(lint_packages_dir(&source_path.join("packages")))
+}
+
+fn lint_packages_dir(packages_dir: &Path) -> LintResult {
+    let mut issues = Vec::new();
+    let mut packages_checked = 0;
+    // bin name -> packages that install it
+    let mut bin_owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    if !packages_dir.exists() {
+        return LintResult {
+            packages_checked,
+            issues,
+        };
+    }
+
+    let mut entries: Vec<_> = WalkDir::new(packages_dir)
+        .min_depth(1)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .collect();
+    entries.sort_by(|a, b| a.path().cmp(b.path()));
+
+    for entry in entries {
+        let path = entry.path();
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut report = |severity: LintSeverity, message: String| {
+            issues.push(LintIssue {
+                package_name: name.clone(),
+                severity,
+                message,
+            });
+        };
+
+        // Guard Clause 1: Only manifests belong in packages/
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            report(
+                LintSeverity::Warning,
+                format!("Unexpected file {:?} in packages directory", path),
+            );
+            continue;
+        }
+
+        packages_checked += 1;
+
+        // Guard Clause 2: Must live in packages/<first letter>/ or find_package can't see it
+        let parent = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|p| p.to_str());
+        let expected_prefix = name.chars().next().map(|c| c.to_string());
+        if parent != expected_prefix.as_deref() || entry.depth() != 2 {
+            report(
+                LintSeverity::Error,
+                format!(
+                    "Manifest is in the wrong directory (expected packages/{}/{}.toml)",
+                    expected_prefix.unwrap_or_default(),
+                    name
+                ),
+            );
+        }
+
+        // Guard Clause 3: Must parse
+        let manifest = match fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|c| toml::from_str::<PackageManifest>(&c).map_err(anyhow::Error::from))
+        {
+            Ok(m) => m,
+            Err(e) => {
+                report(
+                    LintSeverity::Error,
+                    format!("Failed to parse manifest: {}", e),
+                );
+                continue;
+            }
+        };
+
+        for (severity, message) in lint_manifest(&manifest) {
+            report(severity, message);
+        }
+
+        for bin in manifest.targets.values().flat_map(|t| &t.bin) {
+            let owners = bin_owners.entry(bin.clone()).or_default();
+            if !owners.contains(&name) {
+                owners.push(name.clone());
+            }
+        }
+    }
+
+    for (bin, owners) in bin_owners {
+        if owners.len() > 1 {
+            issues.push(LintIssue {
+                package_name: owners.join(", "),
+                severity: LintSeverity::Error,
+                message: format!("Binary '{}' is installed by multiple packages", bin),
+            });
+        }
+    }
+
+    LintResult {
+        packages_checked,
+        issues,
+    }
+}
+
+/// Checks a single parsed manifest for missing or malformed fields
+fn lint_manifest(manifest: &PackageManifest) -> Vec<(LintSeverity, String)> {
+    let mut problems = Vec::new();
+
+    if manifest.version.trim().is_empty() {
+        problems.push((LintSeverity::Error, "Version is empty".to_string()));
+    }
+    if manifest.description.is_none() {
+        problems.push((LintSeverity::Warning, "Missing description".to_string()));
+    }
+    if manifest.targets.is_empty() {
+        problems.push((LintSeverity::Error, "No targets defined".to_string()));
+    }
+
+    for (key, target) in &manifest.targets {
+        if !TARGETS.iter().any(|(slug, _)| slug == key) {
+            let known: Vec<&str> = TARGETS.iter().map(|(slug, _)| *slug).collect();
+            problems.push((
+                LintSeverity::Error,
+                format!(
+                    "Unrecognized target '{}' (expected one of: {})",
+                    key,
+                    known.join(", ")
+                ),
+            ));
+        }
+        if !(target.url.starts_with("https://")
+            || target.url.starts_with("http://")
+            || target.url.starts_with("file://"))
+        {
+            problems.push((
+                LintSeverity::Error,
+                format!(
+                    "[{}] URL '{}' is not an http(s) or file URL",
+                    key, target.url
+                ),
+            ));
+        }
+        // A data package installs only `files`
+        if target.bin.iter().all(|b| b.trim().is_empty()) && target.files.is_empty() {
+            problems.push((
+                LintSeverity::Error,
+                format!("[{}] Binary name is empty", key),
+            ));
+        }
+        for file in &target.files {
+            if let Err(e) = datafiles::check_dest(&file.dest) {
+                problems.push((LintSeverity::Error, format!("[{}] {}", key, e)));
+            }
+        }
+        if !target.files.is_empty() && !target.format.is_archive() {
+            problems.push((
+                LintSeverity::Error,
+                format!("[{}] files can only come from an archive", key),
+            ));
+        }
+        if target.bin.len() > 1 && !target.format.is_archive() {
+            problems.push((
+                LintSeverity::Error,
+                format!("[{}] only an archive can hold several binaries", key),
+            ));
+        }
+        if target.sha256.len() != 64 || !target.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            problems.push((
+                LintSeverity::Error,
+                format!(
+                    "[{}] sha256 '{}' is not a 64-character hex digest",
+                    key, target.sha256
+                ),
+            ));
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const GOOD_HASH: &str = "0dcd8ac79732c0d5b136f11f4ee00e581440e16a44eab5b3105b611bbf2cf191";
+
+    fn write_manifest(packages_dir: &Path, name: &str, content: &str) {
+        let dir = packages_dir.join(&name[..1]);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}.toml", name)), content).unwrap();
+    }
+
+    fn manifest_toml(target: &str, bin: &str, sha256: &str) -> String {
+        format!(
+            r#"
+            version = "1.0.0"
+            description = "A test tool"
+            [targets.{target}]
+            url = "https://example.com/tool.tar.gz"
+            bin = "{bin}"
+            sha256 = "{sha256}"
+            "#
+        )
+    }
+
+    #[test]
+    fn test_lint_clean_registry() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        write_manifest(
+            &packages,
+            "tool",
+            &manifest_toml("x86_64-linux", "tool", GOOD_HASH),
+        );
+
+        let result = lint_packages_dir(&packages);
+
+        assert_eq!(result.packages_checked, 1);
+        assert!(result.issues.is_empty(), "{:?}", result.issues);
+    }
+
+    #[test]
+    fn test_lint_reports_unparseable_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        // Missing the required `sha256` field
+        write_manifest(
+            &packages,
+            "broken",
+            r#"
+            version = "1.0.0"
+            [targets.x86_64-linux]
+            url = "https://example.com/tool.tar.gz"
+            bin = "tool"
+            "#,
+        );
+
+        let result = lint_packages_dir(&packages);
+
+        assert_eq!(result.error_count(), 1);
+        assert!(result.issues[0].message.contains("sha256"));
+    }
+
+    #[test]
+    fn test_lint_flags_bad_fields() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        write_manifest(
+            &packages,
+            "odd",
+            &manifest_toml("sparc-solaris", "", "deadbeef"),
+        );
+
+        let result = lint_packages_dir(&packages);
+        let messages: Vec<&str> = result.issues.iter().map(|i| i.message.as_str()).collect();
+
+        assert_eq!(result.error_count(), 3, "{:?}", messages);
+        assert!(messages.iter().any(|m| m.contains("Unrecognized target")));
+        assert!(messages.iter().any(|m| m.contains("Binary name is empty")));
+        assert!(messages.iter().any(|m| m.contains("64-character")));
+    }
+
+    #[test]
+    fn test_lint_data_package() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        let data_toml = |dest: &str| {
+            format!(
+                r#"
+                version = "1.0.0"
+                description = "Color schemes"
+                [targets.x86_64-linux]
+                url = "https://example.com/themes.tar.gz"
+                sha256 = "{GOOD_HASH}"
+                files = [{{ src = "themes", dest = "{dest}" }}]
+                "#
+            )
+        };
+        write_manifest(&packages, "themes", &data_toml("tool/themes"));
+        // Two data packages don't clash over an empty bin
+        write_manifest(&packages, "fonts", &data_toml("fonts/tool"));
+        assert!(lint_packages_dir(&packages).issues.is_empty());
+
+        write_manifest(&packages, "themes", &data_toml("../themes"));
+        let result = lint_packages_dir(&packages);
+        assert_eq!(result.error_count(), 1);
+        assert!(result.issues[0].message.contains("relative path"));
+    }
+
+    #[test]
+    fn test_lint_flags_duplicate_bins() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        write_manifest(
+            &packages,
+            "alpha",
+            &manifest_toml("x86_64-linux", "tool", GOOD_HASH),
+        );
+        write_manifest(
+            &packages,
+            "beta",
+            &manifest_toml("x86_64-linux", "tool", GOOD_HASH),
+        );
+        // One of several binaries counts too
+        let several = manifest_toml("x86_64-linux", "gamma", GOOD_HASH)
+            .replace(r#"bin = "gamma""#, r#"bin = ["gamma", "tool"]"#);
+        write_manifest(&packages, "gamma", &several);
+
+        let result = lint_packages_dir(&packages);
+
+        assert_eq!(result.error_count(), 1);
+        assert_eq!(result.issues[0].package_name, "alpha, beta, gamma");
+        assert!(result.issues[0].message.contains("multiple packages"));
+
+        // Only an archive has several files to take them from
+        let appimage = several.replace("sha256 =", "format = \"appimage\"\nsha256 =");
+        write_manifest(&packages, "gamma", &appimage);
+        let result = lint_packages_dir(&packages);
+        assert!(result.issues.iter().any(|i| {
+            i.message
+                .contains("only an archive can hold several binaries")
+        }));
+
+        // So is naming one binary twice
+        let twice = several.replace(r#"["gamma", "tool"]"#, r#"["gamma", "gamma"]"#);
+        write_manifest(&packages, "gamma", &twice);
+        let result = lint_packages_dir(&packages);
+        assert!(result.issues.iter().any(|i| {
+            i.package_name == "gamma" && i.message.contains("'gamma' is listed more than once")
+        }));
+    }
+
+    #[test]
+    fn test_lint_flags_misplaced_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        let dir = packages.join("x");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("tool.toml"),
+            manifest_toml("x86_64-linux", "tool", GOOD_HASH),
+        )
+        .unwrap();
+
+        let result = lint_packages_dir(&packages);
+
+        assert_eq!(result.error_count(), 1);
+        assert!(result.issues[0].message.contains("wrong directory"));
+    }
+}
@@ -0,0 +1,297 @@
+use super::{ensure_local_registry, github};
+use crate::core::RushEngine;
+use crate::models::{PackageChange, PackageManifest, PublishResult, PublishedPackage};
+use anyhow::{Context, Result};
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+use std::path::Path;
+use std::process::Command;
+
+/// Developer Tool: Commit the changed manifests in the local registry checkout to a new
+/// branch, push it to `remote`, and open a pull request against `base`. The checkout is
+/// left on the branch it started on, even if publishing fails part way.
+pub fn publish(
+    engine: &RushEngine,
+    remote: &str,
+    base: &str,
+    branch: Option<&str>,
+) -> Result<PublishResult> {
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+
+    // 1. Check everything we need before touching the checkout
+    let token = github::token().context("Set GITHUB_TOKEN to open pull requests")?;
+    let remote_url = git(&source_path, &["remote", "get-url", remote])?;
+    let repo = github::repo_from_url(&remote_url)
+        .with_context(|| format!("Remote '{}' ({}) is not on GitHub", remote, remote_url))?;
+
+    let packages = changed_packages(&source_path)?;
+    if packages.is_empty() {
+        anyhow::bail!("No changed manifests in packages/ to publish");
+    }
+
+    let branch = branch
+        .map(str::to_string)
+        .unwrap_or_else(|| default_branch_name(&packages));
+    let title = pr_title(&packages);
+
+    #[derive(Deserialize)]
+    struct PullRequest {
+        html_url: String,
+    }
+
+    let pr = on_new_branch(&source_path, &branch, || {
+        // 2. Commit, push
+        let mut add = vec!["add", "--all", "--"];
+        add.extend(packages.iter().map(|p| p.path.as_str()));
+        git(&source_path, &add)?;
+        git(&source_path, &["commit", "-m", &title])?;
+        git(&source_path, &["push", "-u", remote, &branch])?;
+
+        // 3. Open the pull request
+        let response = engine
+            .client
+            .post(format!("https://api.github.com/repos/{}/pulls", repo))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .json(&serde_json::json!({
+                "title": title,
+                "head": branch,
+                "base": base,
+                "body": pr_body(&packages),
+            }))
+            .send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!(
+                "Pushed '{}' but GitHub refused the pull request ({}): {}",
+                branch,
+                status,
+                response.text().unwrap_or_default()
+            );
+        }
+        Ok(response.json::<PullRequest>()?)
+    })?;
+
+    Ok(PublishResult {
+        branch,
+        pr_url: pr.html_url,
+        packages,
+    })
+}
+
+/// Helper: Run `f` on a new `branch` of the checkout in `dir`, then switch back to the
+/// branch (or detached commit) it was on, whether `f` succeeded or not
+fn on_new_branch<T>(dir: &Path, branch: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let started_on = git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .or_else(|_| git(dir, &["rev-parse", "HEAD"]))?;
+    git(dir, &["checkout", "--quiet", "-b", branch])?;
+
+    let result = f();
+    let restored = git(dir, &["checkout", "--quiet", &started_on]);
+    let value = result?;
+    restored.with_context(|| format!("Couldn't switch the checkout back to '{}'", started_on))?;
+    Ok(value)
+}
+
+/// Helper: Runs git in `dir`, returning stdout without the trailing newline
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    // Only trim the end: porcelain status lines start with a significant space
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Helper: Manifests under packages/ that differ from HEAD
+fn changed_packages(source_path: &Path) -> Result<Vec<PublishedPackage>> {
+    let status = git(
+        source_path,
+        &[
+            "status",
+            "--porcelain",
+            "--untracked-files=all",
+            "--",
+            "packages",
+        ],
+    )?;
+
+    let mut packages = Vec::new();
+    for line in status.lines() {
+        let (code, path) = (
+            line.get(..2).unwrap_or_default(),
+            line.get(3..).unwrap_or_default(),
+        );
+        let Some(name) = path
+            .strip_suffix(".toml")
+            .and_then(|p| p.rsplit('/').next())
+        else {
+            continue;
+        };
+
+        let change = match code {
+            "??" | "A " => PackageChange::Added,
+            c if c.contains('D') => PackageChange::Removed,
+            _ => PackageChange::Updated,
+        };
+        let manifest = std::fs::read_to_string(source_path.join(path))
+            .ok()
+            .and_then(|c| toml::from_str::<PackageManifest>(&c).ok());
+
+        packages.push(PublishedPackage {
+            name: name.to_string(),
+            path: path.to_string(),
+            version: manifest.as_ref().map(|m| m.version.clone()),
+            targets: manifest
+                .map(|m| m.targets.into_keys().collect())
+                .unwrap_or_default(),
+            change,
+        });
+    }
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Helper: "rush/bat-0.24.0" for one package, "rush/update-3-packages" for several
+fn default_branch_name(packages: &[PublishedPackage]) -> String {
+    match packages {
+        [only] => match &only.version {
+            Some(version) => format!("rush/{}-{}", only.name, version),
+            None => format!("rush/remove-{}", only.name),
+        },
+        _ => format!("rush/update-{}-packages", packages.len()),
+    }
+}
+
+/// Helper: Commit message and pull request title
+fn pr_title(packages: &[PublishedPackage]) -> String {
+    match packages {
+        [only] => match (&only.change, &only.version) {
+            (PackageChange::Added, Some(v)) => format!("Add {} {}", only.name, v),
+            (PackageChange::Updated, Some(v)) => format!("Update {} to {}", only.name, v),
+            _ => format!("Remove {}", only.name),
+        },
+        _ => {
+            let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+            format!("Update {}", names.join(", "))
+        }
+    }
+}
+
+/// Helper: Markdown table of what changed
+fn pr_body(packages: &[PublishedPackage]) -> String {
+    let mut body = String::from(
+        "Registry update generated by `rush dev publish`.\n\n\
+         | Package | Change | Version | Targets |\n\
+         |---|---|---|---|\n",
+    );
+    for p in packages {
+        let change = match p.change {
+            PackageChange::Added => "added",
+            PackageChange::Updated => "updated",
+            PackageChange::Removed => "removed",
+        };
+        body.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            p.name,
+            change,
+            p.version.as_deref().unwrap_or("-"),
+            p.targets.join(", ")
+        ));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest(version: &str) -> String {
+        format!(
+            r#"
+            version = "{version}"
+            [targets.x86_64-linux]
+            url = "https://example.com/tool.tar.gz"
+            bin = "tool"
+            sha256 = "abc"
+            "#
+        )
+    }
+
+    #[test]
+    fn test_changed_packages_from_git_status() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path();
+        git(repo, &["init", "-q"]).unwrap();
+        git(repo, &["config", "user.name", "Test"]).unwrap();
+        git(repo, &["config", "user.email", "test@example.com"]).unwrap();
+
+        std::fs::create_dir_all(repo.join("packages/b")).unwrap();
+        std::fs::write(repo.join("packages/b/bat.toml"), manifest("0.23.0")).unwrap();
+        std::fs::write(repo.join("README.md"), "registry").unwrap();
+        git(repo, &["add", "."]).unwrap();
+        git(repo, &["commit", "-q", "-m", "init"]).unwrap();
+
+        // One update, one new package, and an unrelated change that must be ignored
+        std::fs::write(repo.join("packages/b/bat.toml"), manifest("0.24.0")).unwrap();
+        std::fs::create_dir_all(repo.join("packages/f")).unwrap();
+        std::fs::write(repo.join("packages/f/fzf.toml"), manifest("0.56.3")).unwrap();
+        std::fs::write(repo.join("README.md"), "changed").unwrap();
+
+        let packages = changed_packages(repo).unwrap();
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "bat");
+        assert_eq!(packages[0].change, PackageChange::Updated);
+        assert_eq!(packages[0].version.as_deref(), Some("0.24.0"));
+        assert_eq!(packages[1].name, "fzf");
+        assert_eq!(packages[1].change, PackageChange::Added);
+        assert_eq!(packages[1].targets, vec!["x86_64-linux"]);
+
+        assert_eq!(pr_title(&packages), "Update bat, fzf");
+        assert_eq!(default_branch_name(&packages), "rush/update-2-packages");
+        assert_eq!(pr_title(&packages[..1]), "Update bat to 0.24.0");
+        assert_eq!(default_branch_name(&packages[1..]), "rush/fzf-0.56.3");
+
+        let body = pr_body(&packages);
+        assert!(body.contains("| fzf | added | 0.56.3 | x86_64-linux |"));
+
+        // Publishing goes back to the branch it started on, whether it worked or not
+        let started_on = git(repo, &["symbolic-ref", "--short", "HEAD"]).unwrap();
+        let failed = on_new_branch(repo, "rush/failed", || -> Result<()> {
+            git(repo, &["add", "--all"])?;
+            anyhow::bail!("push refused")
+        });
+        assert!(failed.is_err());
+        assert_eq!(
+            git(repo, &["symbolic-ref", "--short", "HEAD"]).unwrap(),
+            started_on
+        );
+        assert_eq!(changed_packages(repo).unwrap().len(), 2);
+
+        on_new_branch(repo, "rush/update-2-packages", || {
+            git(repo, &["commit", "-q", "-m", "update"])
+        })
+        .unwrap();
+        assert_eq!(
+            git(repo, &["symbolic-ref", "--short", "HEAD"]).unwrap(),
+            started_on
+        );
+        assert!(changed_packages(repo).unwrap().is_empty());
+        assert!(git(repo, &["rev-parse", "--verify", "rush/update-2-packages"]).is_ok());
+    }
+}
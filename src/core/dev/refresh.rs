@@ -0,0 +1,188 @@
+use super::bump::bump_package_by;
+use super::ensure_local_registry;
+use crate::core::RushEngine;
+use crate::core::cancel::Deadline;
+use crate::models::{BumpResult, PackageManifest, RefreshEvent, RefreshResult};
+use anyhow::{Result, anyhow};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// Developer Tool: Bump every package with a recorded `source`, `jobs` at a time.
+/// Each package gets `timeout`; one that runs over it, can't be reached, or whose release
+/// can't be read is reported in `failed`, and the rest are still checked.
+pub fn refresh_all<F>(
+    engine: &RushEngine,
+    jobs: usize,
+    timeout: Duration,
+    mut on_event: F,
+) -> Result<RefreshResult>
+where
+    F: FnMut(RefreshEvent),
+{
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    let names = sourced_packages(&source_path.join("packages"));
+
+    on_event(RefreshEvent::Started { total: names.len() });
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel::<(String, Result<BumpResult>)>();
+    let mut result = RefreshResult::default();
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.max(1).min(names.len()) {
+            let tx = tx.clone();
+            let (next, names) = (&next, &names);
+            scope.spawn(move || {
+                while let Some(name) = names.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let deadline = Deadline::after(timeout);
+                    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                        bump_package_by(engine, name, false, Some(deadline), |_| {})
+                    }))
+                    .unwrap_or_else(|panic| Err(anyhow!("crashed: {}", panic_message(&*panic))));
+                    if tx.send((name.clone(), outcome)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Report on this thread so the callback doesn't need to be Send
+        for (name, outcome) in rx {
+            on_event(RefreshEvent::Checked { name: name.clone() });
+            match outcome {
+                Ok(bump) if !bump.failures.is_empty() => {
+                    let reasons: Vec<String> = bump
+                        .failures
+                        .iter()
+                        .map(|(target, reason)| format!("{}: {}", target, reason))
+                        .collect();
+                    result.failed.push((name, reasons.join("; ")));
+                }
+                Ok(bump) if bump.old_version == bump.new_version => result.up_to_date.push(name),
+                Ok(bump) => result.bumped.push(bump),
+                Err(e) => result.failed.push((name, format!("{:#}", e))),
+            }
+        }
+    });
+
+    result
+        .bumped
+        .sort_by(|a, b| a.package_name.cmp(&b.package_name));
+    result.up_to_date.sort();
+    result.failed.sort();
+
+    Ok(result)
+}
+
+/// Helper: What a caught panic said
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Helper: Names of the manifests that record an upstream `source`
+fn sourced_packages(packages_dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = WalkDir::new(packages_dir)
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            let manifest: PackageManifest = toml::from_str(&content).ok()?;
+            manifest.source?;
+            Some(e.path().file_stem()?.to_str()?.to_string())
+        })
+        .collect();
+
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_refresh_reports_timeouts_and_carries_on() {
+        let mock = MockEnvironment::new();
+        for name in ["alpha", "beta"] {
+            mock.add_package(name, "1.0.0", name);
+            let path = mock
+                .registry_source
+                .join(format!("packages/{}/{}.toml", &name[..1], name));
+            let manifest = std::fs::read_to_string(&path).unwrap();
+            let sourced = format!("source = \"owner/{}\"\n{}", name, manifest);
+            std::fs::write(&path, sourced).unwrap();
+        }
+        let engine = mock.engine().unwrap();
+
+        // No time at all: both are given up on before any request is made
+        let mut checked = 0;
+        let result = refresh_all(&engine, 1, Duration::ZERO, |e| {
+            if let RefreshEvent::Checked { .. } = e {
+                checked += 1;
+            }
+        })
+        .unwrap();
+        assert_eq!(checked, 2);
+        assert_eq!(
+            result.failed,
+            [
+                ("alpha".to_string(), "Gave up after 0s".to_string()),
+                ("beta".to_string(), "Gave up after 0s".to_string())
+            ]
+        );
+        assert!(result.bumped.is_empty() && result.up_to_date.is_empty());
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let caught = panic::catch_unwind(|| panic!("bad release {}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*caught), "bad release 42");
+        let caught = panic::catch_unwind(|| panic!("plain")).unwrap_err();
+        assert_eq!(panic_message(&*caught), "plain");
+    }
+
+    #[test]
+    fn test_sourced_packages() {
+        let temp_dir = tempdir().unwrap();
+        let packages = temp_dir.path().join("packages");
+        std::fs::create_dir_all(packages.join("a")).unwrap();
+        std::fs::create_dir_all(packages.join("m")).unwrap();
+
+        let manifest = |source: &str| {
+            format!(
+                r#"
+                version = "1.0.0"
+                {source}
+                [targets.x86_64-linux]
+                url = "https://example.com/tool.tar.gz"
+                bin = "tool"
+                sha256 = "abc"
+                "#
+            )
+        };
+        std::fs::write(
+            packages.join("a/alpha.toml"),
+            manifest(r#"source = "owner/alpha""#),
+        )
+        .unwrap();
+        std::fs::write(packages.join("m/manual.toml"), manifest("")).unwrap();
+        std::fs::write(packages.join("m/broken.toml"), "not toml").unwrap();
+
+        assert_eq!(sourced_packages(&packages), vec!["alpha"]);
+    }
+}
@@ -0,0 +1,154 @@
+use super::{TARGETS, detect_bin_name, ensure_local_registry, manifest_path, timestamp};
+use crate::Error;
+use crate::core::{RushEngine, Target, util};
+use crate::models::{ArtifactFormat, BumpEvent, NewPackage, PackageManifest, TargetDefinition};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Developer Tool: Write a complete manifest for a package that isn't in the registry yet.
+/// Every target's artifact is downloaded and hashed; nothing is written unless all succeed.
+/// Returns the path of the new manifest.
+pub fn create_package<F>(engine: &RushEngine, spec: &NewPackage, mut on_event: F) -> Result<PathBuf>
+where
+    F: FnMut(BumpEvent),
+{
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+
+    // 1. Validate before downloading anything
+    spec.validate().map_err(anyhow::Error::msg)?;
+
+    let package_path = manifest_path(&source_path, &spec.name)?;
+    if package_path.exists() {
+        return Err(Error::PackageExists {
+            name: spec.name.clone(),
+        }
+        .into());
+    }
+
+    for target in &spec.targets {
+        if !TARGETS
+            .iter()
+            .any(|(slug, _)| *slug == Target::normalize(&target.target))
+        {
+            return Err(Error::UnknownTarget {
+                target: target.target.clone(),
+            }
+            .into());
+        }
+    }
+
+    // 2. Download and hash every artifact
+    let mut targets = BTreeMap::new();
+    for target in &spec.targets {
+        on_event(BumpEvent::Downloading {
+            target: target.target.clone(),
+            url: target.url.clone(),
+        });
+        let mut on_progress =
+            util::tag_progress(Some(&spec.name), |e| on_event(BumpEvent::Progress(e)));
+        let content = util::download_url(engine.transport.as_ref(), &target.url, &mut on_progress)
+            .with_context(|| format!("Failed to download {}", target.url))?;
+
+        let bin = match &target.bin {
+            Some(bin) => bin.clone(),
+            None => detect_bin_name(&content, &spec.name).unwrap_or_else(|| spec.name.clone()),
+        };
+        targets.insert(
+            Target::normalize(&target.target),
+            TargetDefinition {
+                url: target.url.clone(),
+                bin: vec![bin],
+                sha256: hex::encode(Sha256::digest(&content)),
+                checksum_url: None,
+                size: Some(content.len() as u64),
+                format: ArtifactFormat::Archive,
+                extract: false,
+                files: Vec::new(),
+            },
+        );
+    }
+
+    // 3. Write the manifest
+    let manifest = PackageManifest {
+        version: spec.version.clone(),
+        description: spec.description.clone(),
+        source: None,
+        license: spec.license.clone(),
+        homepage: spec.homepage.clone(),
+        updated: Some(timestamp()),
+        check: None,
+        osv: None,
+        requires: Vec::new(),
+        dependencies: Vec::new(),
+        targets,
+        history: Vec::new(),
+    };
+    if let Some(dir) = package_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&package_path, toml::to_string_pretty(&manifest)?)?;
+
+    Ok(package_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NewTarget;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_create_package_writes_complete_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let registry = temp_dir.path().join("registry");
+        std::fs::create_dir_all(&registry).unwrap();
+        let artifact = temp_dir.path().join("tool.bin");
+        std::fs::write(&artifact, "binary").unwrap();
+
+        let engine = RushEngine::builder()
+            .root(temp_dir.path().join("root"))
+            .registry_source(registry.to_string_lossy().into_owned())
+            .build()
+            .unwrap();
+
+        let mut spec = NewPackage {
+            name: "tool".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("A tool".to_string()),
+            license: Some("MIT".to_string()),
+            homepage: Some("https://example.com".to_string()),
+            targets: vec![NewTarget {
+                target: "x86_64-linux".to_string(),
+                url: format!("file://{}", artifact.display()),
+                bin: Some("tool".to_string()),
+            }],
+        };
+
+        let path = create_package(&engine, &spec, |_| {}).unwrap();
+        let manifest: PackageManifest =
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(path, registry.join("packages/t/tool.toml"));
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+        assert_eq!(manifest.homepage.as_deref(), Some("https://example.com"));
+        assert_eq!(
+            manifest.targets["x86_64-linux"].sha256,
+            hex::encode(Sha256::digest(b"binary"))
+        );
+
+        // Never overwrites an existing package
+        let err = create_package(&engine, &spec, |_| {}).unwrap_err();
+        assert!(err.to_string().contains("already in the registry"));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::PackageExists { .. })
+        ));
+
+        // Invalid input is rejected before anything is written
+        spec.name = "other".to_string();
+        spec.targets[0].target = "sparc-solaris".to_string();
+        assert!(create_package(&engine, &spec, |_| {}).is_err());
+        assert!(!registry.join("packages/o/other.toml").exists());
+    }
+}
@@ -0,0 +1,226 @@
+//! Ranks release assets against a target by parsing the platform out of their names.
+//!
+//! Asset names are split into tokens ("app-v1.0-x86_64-unknown-linux-musl.tar.gz" ->
+//! app, v1, 0, x86_64, unknown, linux, musl) and each token is mapped onto an OS,
+//! architecture, or libc/ABI. The many spellings of each (`arm64`/`aarch64`,
+//! `darwin`/`macos`/`apple`, `pc-windows-msvc`, ...) collapse to one canonical value, which
+//! is compared with the target's. The spellings are shared with `Target::parse`.
+
+use crate::core::target::{Arch, Libc, Os, Target, parse_arch, parse_libc, parse_os, tokenize};
+use crate::models::ScoreFactor;
+
+/// Everything the name says about where an asset runs
+#[derive(Debug, Default, PartialEq)]
+struct AssetPlatform {
+    os: Option<Os>,
+    arch: Option<Arch>,
+    libc: Option<Libc>,
+}
+
+/// Ranks an asset for `target` (e.g. "x86_64-linux"); higher is a better match
+pub fn calculate_asset_score(name: &str, target: &str) -> i32 {
+    explain_asset_score(name, target)
+        .iter()
+        .map(|f| f.points)
+        .sum()
+}
+
+/// The individual factors behind `calculate_asset_score`
+pub fn explain_asset_score(name: &str, target: &str) -> Vec<ScoreFactor> {
+    let name = name.to_lowercase();
+    let mut factors = Vec::new();
+    let mut add = |points: i32, reason: String| factors.push(ScoreFactor { reason, points });
+
+    // 1. Format
+    let (stem, extension) = split_extension(&name);
+    match extension {
+        Some(
+            ext @ ("tar.gz" | "tgz" | "tar.xz" | "txz" | "tar.zst" | "tzst" | "tar.bz2" | "tbz2"),
+        ) => add(20, format!("tarball (.{})", ext)),
+        Some("tar") => add(15, "uncompressed tarball (.tar)".to_string()),
+        // Installs just as well; a tarball of the same build still wins, as it always
+        // keeps permission bits
+        Some("zip") => add(15, "zip archive (.zip)".to_string()),
+        // Usable when rush can unpack them, but a tarball of the same build is simpler
+        Some(ext @ ("deb" | "rpm")) if cfg!(feature = "distro-packages") => {
+            add(-5, format!("distro package (.{})", ext))
+        }
+        Some(ext @ ("deb" | "rpm" | "msi" | "pkg" | "dmg" | "apk")) => {
+            add(-100, format!("installer package (.{})", ext))
+        }
+        _ => {}
+    }
+    let tokens = tokenize(stem);
+    let is_metadata = matches!(
+        extension,
+        Some("sha256" | "sha512" | "sig" | "asc" | "minisig" | "pem" | "sbom" | "intoto")
+    ) || tokens.iter().any(|t| {
+        t.starts_with("sha256")
+            || t.starts_with("sha512")
+            || t.starts_with("checksum")
+            || t.ends_with("sums")
+    });
+    if is_metadata {
+        add(-100, "checksum or signature file".to_string());
+    }
+
+    // 2. Platform
+    let Some(Target {
+        arch: want_arch,
+        os: want_os,
+        ..
+    }) = Target::parse(target)
+    else {
+        return factors;
+    };
+    let mut platform = parse_platform(&tokens);
+    if extension == Some("exe") {
+        platform.os.get_or_insert(Os::Windows);
+    }
+
+    match platform.os {
+        Some(os) if os == want_os => add(10, format!("OS matches ({:?})", os)),
+        Some(os) => add(-50, format!("wrong OS ({:?})", os)),
+        None => {}
+    }
+    match platform.arch {
+        Some(arch) if arch == want_arch => add(10, format!("architecture matches ({:?})", arch)),
+        Some(Arch::Universal) if want_os == Os::Mac => add(5, "universal macOS binary".to_string()),
+        Some(arch) => add(-50, format!("wrong architecture ({:?})", arch)),
+        None => {}
+    }
+    match (want_os, platform.libc) {
+        (Os::Linux, Some(Libc::Musl)) => add(5, "static musl build".to_string()),
+        (Os::Linux, Some(Libc::Gnu)) => add(3, "glibc build".to_string()),
+        (Os::Windows, Some(Libc::Msvc)) => add(5, "MSVC build".to_string()),
+        _ => {}
+    }
+
+    factors
+}
+
+/// Helper: "app.tar.gz" -> ("app", Some("tar.gz")). Only known extensions are split off,
+/// so version dots ("app-1.2.3-linux") stay part of the stem.
+fn split_extension(name: &str) -> (&str, Option<&str>) {
+    const EXTENSIONS: &[&str] = &[
+        "tar.gz", "tgz", "tar.xz", "txz", "tar.zst", "tzst", "tar.bz2", "tbz2", "tar", "zip",
+        "deb", "rpm", "msi", "pkg", "dmg", "apk", "exe", "sha256", "sha512", "sig", "asc",
+        "minisig", "pem", "sbom", "intoto", "txt", "json",
+    ];
+
+    // Checksums of archives ("app.tar.gz.sha256") take the outer extension
+    EXTENSIONS
+        .iter()
+        .filter_map(|ext| {
+            let stem = name.strip_suffix(ext)?.strip_suffix('.')?;
+            Some((stem, Some(*ext)))
+        })
+        .min_by_key(|(stem, _)| std::cmp::Reverse(stem.len()))
+        .unwrap_or((name, None))
+}
+
+/// Helper: What the tokens say about OS, architecture, and libc
+fn parse_platform(tokens: &[String]) -> AssetPlatform {
+    let mut platform = AssetPlatform::default();
+    for token in tokens {
+        if let Some(os) = parse_os(token) {
+            platform.os.get_or_insert(os);
+        }
+        if let Some(arch) = parse_arch(token) {
+            platform.arch.get_or_insert(arch);
+        }
+        if let Some(libc) = parse_libc(token) {
+            platform.libc.get_or_insert(libc);
+        }
+    }
+    platform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(name: &str) -> AssetPlatform {
+        let (stem, _) = split_extension(name);
+        parse_platform(&tokenize(stem))
+    }
+
+    #[test]
+    fn test_parse_platform_spellings() {
+        let linux_arm = AssetPlatform {
+            os: Some(Os::Linux),
+            arch: Some(Arch::Aarch64),
+            libc: Some(Libc::Gnu),
+        };
+        assert_eq!(platform("tool-aarch64-unknown-linux-gnu.tar.gz"), linux_arm);
+        assert_eq!(platform("tool_1.0_linux_arm64_gnu.tar.gz"), linux_arm);
+
+        let mac = platform("tool-v2.1.0-x86_64-apple-darwin.tar.gz");
+        assert_eq!(mac.os, Some(Os::Mac));
+        assert_eq!(mac.arch, Some(Arch::X86_64));
+
+        let windows = platform("tool-x86_64-pc-windows-msvc.zip");
+        assert_eq!(windows.os, Some(Os::Windows));
+        assert_eq!(windows.libc, Some(Libc::Msvc));
+
+        // "x86-64" written with a dash, 32-bit ARM hidden in the ABI
+        assert_eq!(platform("tool-linux-x86-64.tgz").arch, Some(Arch::X86_64));
+        assert_eq!(
+            platform("tool-armv7-unknown-linux-gnueabihf.tar.gz").arch,
+            Some(Arch::Arm32)
+        );
+
+        // Substrings don't count: "armor" is not ARM, "darwinian" is not macOS
+        assert_eq!(platform("armor-darwinian.tar.gz"), AssetPlatform::default());
+    }
+
+    #[test]
+    fn test_split_extension() {
+        assert_eq!(
+            split_extension("app-1.2.3.tar.gz"),
+            ("app-1.2.3", Some("tar.gz"))
+        );
+        assert_eq!(
+            split_extension("app.tar.gz.sha256"),
+            ("app.tar.gz", Some("sha256"))
+        );
+        assert_eq!(
+            split_extension("app-1.2.3-linux"),
+            ("app-1.2.3-linux", None)
+        );
+    }
+
+    #[test]
+    fn test_scoring_edge_cases() {
+        // Universal macOS binaries work on both Macs, but native builds win
+        let universal =
+            calculate_asset_score("tool-universal-apple-darwin.tar.gz", "aarch64-macos");
+        let native = calculate_asset_score("tool-aarch64-apple-darwin.tar.gz", "aarch64-macos");
+        assert!(universal > 0 && universal < native);
+        assert!(calculate_asset_score("tool-universal-apple-darwin.tar.gz", "x86_64-linux") < 0);
+
+        // Other Unixes and 32-bit builds are never a match
+        assert!(calculate_asset_score("tool-x86_64-unknown-freebsd.tar.gz", "x86_64-linux") < 0);
+        assert!(calculate_asset_score("tool-i686-unknown-linux-musl.tar.gz", "x86_64-linux") < 0);
+
+        // The old keyword matching mistook these for wrong-platform builds
+        assert_eq!(
+            calculate_asset_score("armor-x86_64-unknown-linux-musl.tar.gz", "x86_64-linux"),
+            45
+        );
+
+        // Unknown targets only get format scoring
+        assert_eq!(
+            calculate_asset_score("tool-linux-amd64.tar.gz", "riscv64-linux"),
+            20
+        );
+
+        let factors = explain_asset_score("tool-x86_64-unknown-linux-musl.tar.gz", "x86_64-linux");
+        assert_eq!(factors.len(), 4);
+        assert!(
+            factors
+                .iter()
+                .any(|f| f.reason.contains("musl") && f.points == 5)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use super::ensure_local_registry;
+use crate::core::RushEngine;
+use crate::models::ServeEvent;
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+
+/// Path of the registry tarball, as `rush update` expects it
+const REGISTRY_TARBALL: &str = "/registry.tar.gz";
+
+/// Directories served over HTTP
+struct ServeRoots {
+    registry: PathBuf,
+    artifacts: PathBuf,
+}
+
+/// A response ready to be written
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn not_found() -> Self {
+        Response {
+            status: 404,
+            content_type: "text/plain",
+            body: b"Not Found".to_vec(),
+        }
+    }
+}
+
+/// Developer Tool: Serve the local registry over HTTP on localhost until interrupted.
+///
+/// - `/registry.tar.gz` is built from `packages/` on every request, so edits show up on the
+///   next `rush update` without restarting.
+/// - `/packages/...` serves individual manifests.
+/// - `/artifacts/...` serves `artifacts_dir` (default: the registry's `artifacts/`, where
+///   `dev add --copy` puts files).
+pub fn serve_registry<F>(
+    engine: &RushEngine,
+    port: u16,
+    artifacts_dir: Option<&Path>,
+    mut on_event: F,
+) -> Result<()>
+where
+    F: FnMut(ServeEvent),
+{
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+    let roots = ServeRoots {
+        artifacts: artifacts_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| source_path.join("artifacts")),
+        registry: source_path,
+    };
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Failed to listen on port {}", port))?;
+    let address = listener.local_addr()?;
+
+    on_event(ServeEvent::Listening {
+        registry_url: format!("http://{}{}", address, REGISTRY_TARBALL),
+        artifacts_url: format!("http://{}/artifacts/", address),
+    });
+
+    for stream in listener.incoming() {
+        // A client hanging up mid-request shouldn't stop the server
+        let Ok(stream) = stream else { continue };
+        if let Ok((method, path, status)) = handle_connection(stream, &roots) {
+            on_event(ServeEvent::Request {
+                method,
+                path,
+                status,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Helper: Reads one request, writes the response, returns (method, path, status)
+fn handle_connection(mut stream: TcpStream, roots: &ServeRoots) -> Result<(String, String, u16)> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Drain the headers; nothing in them changes the response
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let response = match method.as_str() {
+        "GET" | "HEAD" => route(roots, &path),
+        _ => Response {
+            status: 405,
+            content_type: "text/plain",
+            body: b"Method Not Allowed".to_vec(),
+        },
+    };
+
+    let reason = match response.status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    )?;
+    if method != "HEAD" {
+        stream.write_all(&response.body)?;
+    }
+    stream.flush()?;
+
+    Ok((method, path, response.status))
+}
+
+/// Helper: Maps a request path to a response
+fn route(roots: &ServeRoots, path: &str) -> Response {
+    if path == REGISTRY_TARBALL {
+        return match registry_tarball(&roots.registry) {
+            Ok(body) => Response {
+                status: 200,
+                content_type: "application/gzip",
+                body,
+            },
+            Err(e) => Response {
+                status: 500,
+                content_type: "text/plain",
+                body: e.to_string().into_bytes(),
+            },
+        };
+    }
+
+    let file = if let Some(rest) = path.strip_prefix("/artifacts/") {
+        safe_join(&roots.artifacts, rest)
+    } else if path.starts_with("/packages/") {
+        safe_join(&roots.registry, path.trim_start_matches('/'))
+    } else {
+        None
+    };
+
+    match file.and_then(|f| std::fs::read(&f).ok().map(|body| (f, body))) {
+        Some((file, body)) => Response {
+            status: 200,
+            content_type: match file.extension().and_then(|e| e.to_str()) {
+                Some("toml") => "text/plain; charset=utf-8",
+                _ => "application/octet-stream",
+            },
+            body,
+        },
+        None => Response::not_found(),
+    }
+}
+
+/// Helper: `root/relative`, refusing anything that could escape `root`
+fn safe_join(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let is_plain = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)));
+    (is_plain && relative.components().next().is_some()).then(|| root.join(relative))
+}
+
+/// Helper: A .tar.gz of `packages/`, in the layout `rush update` unpacks
+fn registry_tarball(registry: &Path) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let packages = registry.join("packages");
+    if packages.is_dir() {
+        builder.append_dir_all("packages", &packages)?;
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn roots(root: &Path) -> ServeRoots {
+        std::fs::create_dir_all(root.join("packages/b")).unwrap();
+        std::fs::write(root.join("packages/b/bat.toml"), "version = \"1.0.0\"").unwrap();
+        std::fs::create_dir_all(root.join("artifacts/bat")).unwrap();
+        std::fs::write(root.join("artifacts/bat/bat.tar.gz"), "binary").unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+
+        ServeRoots {
+            registry: root.to_path_buf(),
+            artifacts: root.join("artifacts"),
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        let temp_dir = tempdir().unwrap();
+        let roots = roots(temp_dir.path());
+
+        let manifest = route(&roots, "/packages/b/bat.toml");
+        assert_eq!(manifest.status, 200);
+        assert_eq!(manifest.body, b"version = \"1.0.0\"");
+
+        let artifact = route(&roots, "/artifacts/bat/bat.tar.gz");
+        assert_eq!(artifact.status, 200);
+        assert_eq!(artifact.body, b"binary");
+
+        assert_eq!(route(&roots, "/packages/../secret.txt").status, 404);
+        assert_eq!(route(&roots, "/artifacts/../secret.txt").status, 404);
+        assert_eq!(route(&roots, "/secret.txt").status, 404);
+        assert_eq!(route(&roots, "/artifacts/").status, 404);
+    }
+
+    #[test]
+    fn test_registry_tarball_over_http() {
+        let temp_dir = tempdir().unwrap();
+        let roots = roots(temp_dir.path());
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let url = format!(
+            "http://{}{}",
+            listener.local_addr().unwrap(),
+            REGISTRY_TARBALL
+        );
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_connection(stream, &roots).unwrap()
+        });
+
+        let body = reqwest::blocking::get(&url).unwrap().bytes().unwrap();
+        assert_eq!(
+            server.join().unwrap(),
+            ("GET".to_string(), REGISTRY_TARBALL.to_string(), 200)
+        );
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&body[..]));
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert!(paths.contains(&"packages/b/bat.toml".to_string()));
+        assert!(!paths.iter().any(|p| p.contains("secret")));
+    }
+}
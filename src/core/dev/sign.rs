@@ -0,0 +1,184 @@
+use super::ensure_local_registry;
+use crate::core::{RushEngine, clean};
+use crate::models::{KeygenResult, SignResult};
+use anyhow::{Context, Result};
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Index of every manifest's sha256, written to the registry root
+pub const INDEX_FILE: &str = "index.sha256";
+/// Detached ed25519 signature of `INDEX_FILE`, hex-encoded
+pub const SIGNATURE_FILE: &str = "index.sha256.sig";
+
+/// Developer Tool: Create a maintainer signing key in the config dir.
+/// Refuses to replace an existing key unless `force` is set.
+pub fn generate_signing_key(engine: &RushEngine, force: bool) -> Result<KeygenResult> {
+    let key_path = engine.config_dir.join("signing.key");
+    if key_path.exists() && !force {
+        anyhow::bail!(
+            "A signing key already exists at {:?} (use --force to replace it)",
+            key_path
+        );
+    }
+
+    let mut secret = [0u8; 32];
+    getrandom::getrandom(&mut secret)
+        .map_err(|e| anyhow::anyhow!("Failed to gather randomness: {}", e))?;
+    let key = SigningKey::from_bytes(&secret);
+    let public_key = hex::encode(key.verifying_key().as_bytes());
+
+    // A temp file is only readable by its owner from the start, so the key never is by
+    // anyone else, even while it is being written
+    fs::create_dir_all(&engine.config_dir)?;
+    let mut temp_file = tempfile::Builder::new()
+        .prefix(&clean::own_trash_prefix())
+        .tempfile_in(&engine.config_dir)?;
+    temp_file.write_all(hex::encode(secret).as_bytes())?;
+    if force {
+        temp_file.persist(&key_path)?;
+    } else {
+        temp_file.persist_noclobber(&key_path)?;
+    }
+    fs::write(engine.config_dir.join("signing.pub"), &public_key)?;
+
+    Ok(KeygenResult {
+        key_path,
+        public_key,
+    })
+}
+
+/// Developer Tool: Write a signed index of the local registry's manifests.
+/// Uses the key from `dev keygen` unless `key_path` points elsewhere.
+pub fn sign_registry(engine: &RushEngine, key_path: Option<&Path>) -> Result<SignResult> {
+    let source_path = ensure_local_registry(&engine.registry_source)?;
+
+    let default_key = engine.config_dir.join("signing.key");
+    let key_path = key_path.unwrap_or(&default_key);
+    let key = read_signing_key(key_path)?;
+
+    let (index, packages_signed) = build_index(&source_path)?;
+    let signature = key.sign(index.as_bytes());
+
+    let index_path = source_path.join(INDEX_FILE);
+    let signature_path = source_path.join(SIGNATURE_FILE);
+    fs::write(&index_path, &index)?;
+    fs::write(&signature_path, hex::encode(signature.to_bytes()))?;
+
+    Ok(SignResult {
+        index_path,
+        signature_path,
+        packages_signed,
+        public_key: hex::encode(key.verifying_key().as_bytes()),
+    })
+}
+
+/// Helper: Loads a hex-encoded ed25519 secret key
+fn read_signing_key(path: &Path) -> Result<SigningKey> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("No signing key at {:?} (run `rush dev keygen` first)", path))?;
+    let bytes: [u8; 32] = hex::decode(content.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .with_context(|| format!("{:?} is not a valid signing key", path))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Helper: "<sha256>  packages/x/name.toml" for every manifest, sorted by path
+fn build_index(source_path: &Path) -> Result<(String, usize)> {
+    let mut paths: Vec<_> = WalkDir::new(source_path.join("packages"))
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "toml"))
+        .map(|e| e.into_path())
+        .collect();
+    paths.sort();
+
+    let mut index = String::new();
+    for path in &paths {
+        let relative = path.strip_prefix(source_path)?.to_string_lossy();
+        let hash = hex::encode(Sha256::digest(fs::read(path)?));
+        index.push_str(&format!("{}  {}\n", hash, relative));
+    }
+
+    Ok((index, paths.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_keygen_and_sign_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let registry = temp_dir.path().join("registry");
+        fs::create_dir_all(registry.join("packages/b")).unwrap();
+        fs::write(registry.join("packages/b/bat.toml"), "version = \"1.0.0\"").unwrap();
+        fs::write(registry.join("packages/b/notes.txt"), "ignored").unwrap();
+
+        let engine = RushEngine::builder()
+            .root(temp_dir.path().join("root"))
+            .registry_source(registry.to_string_lossy().into_owned())
+            .build()
+            .unwrap();
+
+        let keygen = generate_signing_key(&engine, false).unwrap();
+        assert!(generate_signing_key(&engine, false).is_err());
+
+        let result = sign_registry(&engine, None).unwrap();
+        assert_eq!(result.packages_signed, 1);
+        assert_eq!(result.public_key, keygen.public_key);
+
+        let index = fs::read_to_string(&result.index_path).unwrap();
+        assert_eq!(
+            index,
+            format!(
+                "{}  packages/b/bat.toml\n",
+                hex::encode(Sha256::digest(b"version = \"1.0.0\""))
+            )
+        );
+
+        // Anyone with the public key can check the index
+        let public: [u8; 32] = hex::decode(&keygen.public_key).unwrap().try_into().unwrap();
+        let signature: [u8; 64] = hex::decode(fs::read_to_string(&result.signature_path).unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public).unwrap();
+        let signature = Signature::from_bytes(&signature);
+        assert!(verifying_key.verify(index.as_bytes(), &signature).is_ok());
+        assert!(verifying_key.verify(b"tampered index", &signature).is_err());
+
+        // Replacing it writes a new key, only its owner can read
+        let replaced = generate_signing_key(&engine, true).unwrap();
+        assert_ne!(replaced.public_key, keygen.public_key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&replaced.key_path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_sign_without_key_fails() {
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder()
+            .root(temp_dir.path().join("root"))
+            .registry_source(temp_dir.path().to_string_lossy().into_owned())
+            .build()
+            .unwrap();
+
+        let err = sign_registry(&engine, None).unwrap_err();
+        assert!(err.to_string().contains("rush dev keygen"));
+    }
+}
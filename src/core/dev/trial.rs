@@ -0,0 +1,107 @@
+use super::{ensure_local_registry, manifest_path};
+use crate::core::{RushEngine, Transport, TransportResponse, load_manifest};
+use crate::models::{Reporter, TrialResult};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Developer Tool: Install `package` (a local registry package, or a path to a manifest
+/// ending in `.toml`) into a throwaway root, the way `install_package` would, then run its
+/// `check`. Nothing outside the temporary root is touched: no hooks, hash pins, or state.
+pub fn trial_install<R>(engine: &RushEngine, package: &str, reporter: &mut R) -> Result<TrialResult>
+where
+    R: Reporter,
+{
+    // 1. Find the manifest
+    let (name, path) = if package.ends_with(".toml") {
+        let path = Path::new(package).to_path_buf();
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(package);
+        (stem.to_string(), path)
+    } else {
+        let source_path = ensure_local_registry(&engine.registry_source)?;
+        (package.to_string(), manifest_path(&source_path, package)?)
+    };
+    let manifest = load_manifest(&path)
+        .with_context(|| format!("Package '{}' is not in the local registry", name))?;
+
+    // 2. A fresh engine in a temporary root, downloading the way this one does
+    let root = tempfile::tempdir()?;
+    let mut trial = RushEngine::builder()
+        .root(root.path())
+        .registry_source(engine.registry_source.clone())
+        .transport(Shared(Arc::clone(&engine.transport)))
+        .target(engine.target())
+        .offline(engine.offline)
+        .cancellation_token(engine.cancellation_token())
+        .build()?;
+
+    // 3. Download, verify, extract, and install
+    trial.install_from_manifest(&name, &manifest, engine.target(), reporter)?;
+    let installed = trial.state.packages[&name].clone();
+
+    // 4. Smoke-test it, if it can run here
+    let (check, check_skipped) = if manifest.check.is_none() {
+        (None, None)
+    } else if !trial.targets_host() {
+        let reason = format!("built for {}, not this machine", engine.target());
+        (None, Some(reason))
+    } else {
+        match trial.check_package(&name, &manifest) {
+            Ok(check) => (check, None),
+            Err(e) => (None, Some(e.to_string())),
+        }
+    };
+
+    Ok(TrialResult {
+        package: name,
+        version: manifest.version,
+        target: engine.target().to_string(),
+        binaries: installed.binaries,
+        files: installed.files,
+        check,
+        check_skipped,
+    })
+}
+
+/// The engine's transport, shared with the trial engine
+struct Shared(Arc<dyn Transport>);
+
+impl Transport for Shared {
+    fn get(&self, url: &str) -> Result<TransportResponse> {
+        self.0.get(url)
+    }
+
+    fn head(&self, url: &str) -> Result<Option<u64>> {
+        self.0.head(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_trial_install_leaves_nothing_behind() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool-bin");
+        let engine = mock.engine().unwrap();
+
+        let result = trial_install(&engine, "tool", &mut ()).unwrap();
+        assert_eq!(result.package, "tool");
+        assert_eq!(result.version, "1.0.0");
+        assert_eq!(result.target, current_target());
+        assert_eq!(result.binaries, ["tool-bin"]);
+        assert!(result.check.is_none() && result.check_skipped.is_none());
+        assert!(result.succeeded());
+
+        assert!(engine.state.packages.is_empty());
+        assert!(!engine.bin_path.join("tool-bin").exists());
+
+        // A manifest path works too; a missing package doesn't
+        let path = mock.registry_source.join("packages/t/tool.toml");
+        let result = trial_install(&engine, path.to_str().unwrap(), &mut ()).unwrap();
+        assert_eq!(result.package, "tool");
+        assert!(trial_install(&engine, "ghost", &mut ()).is_err());
+    }
+}
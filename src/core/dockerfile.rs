@@ -0,0 +1,283 @@
+//! `rush export --format dockerfile`: the installed set as a Dockerfile.
+//!
+//! A busybox stage fetches each artifact rush installed with `ADD --checksum` (so the
+//! build fails if it no longer hashes to what was installed), unpacks it, and lays the
+//! binaries and data files out under `/rush`. The final stage, on any base image, copies
+//! that into `/usr/local`. The unpacking stage runs on the build machine's platform; only
+//! the final one is pinned to the target's.
+
+use crate::core::bundle::locked_artifact;
+use crate::core::hooks::shell_quote;
+use crate::core::{Arch, Os, RushEngine, Target};
+use crate::models::{
+    ArtifactFormat, ExportResult, InstalledPackage, SkippedExport, TargetDefinition,
+};
+use anyhow::{Result, bail};
+use std::fmt::Write;
+
+/// Base image of the final stage unless `--build-arg BASE_IMAGE=...` says otherwise
+const DEFAULT_BASE_IMAGE: &str = "debian:bookworm-slim";
+
+/// The installed packages' artifacts for `target` as a Dockerfile. Images run Linux, so
+/// any other target is an error. Packages whose artifact isn't known, or that busybox
+/// can't unpack, are left out.
+pub fn export_dockerfile(engine: &RushEngine, target: &str) -> Result<ExportResult> {
+    let Some(platform) = Target::parse(target).and_then(|t| docker_platform(&t)) else {
+        bail!(
+            "Container images need Linux binaries, but packages are installed for {} (set RUSH_TARGET to a Linux target)",
+            target
+        );
+    };
+
+    let mut names: Vec<&String> = engine.state.packages.keys().collect();
+    names.sort();
+
+    let mut dockerfile = format!(
+        "# syntax=docker/dockerfile:1.6\n\
+         # Generated by `rush export --format dockerfile` for {}\n\
+         ARG BASE_IMAGE={}\n\n\
+         FROM --platform=$BUILDPLATFORM busybox:stable AS rush\n\
+         RUN mkdir -p /rush/bin /rush/share\n",
+        target, DEFAULT_BASE_IMAGE
+    );
+    let mut packages = Vec::new();
+    let mut skipped = Vec::new();
+    let mut appimages = false;
+    for name in names {
+        let installed = &engine.state.packages[name];
+        let manifest = engine.find_package(name);
+        let skip = |reason: &str| SkippedExport {
+            package: name.clone(),
+            reason: reason.to_string(),
+        };
+        let Some(artifact) = locked_artifact(installed, manifest.as_ref(), target) else {
+            skipped.push(skip(
+                "its artifact isn't recorded, and the registry no longer has it",
+            ));
+            continue;
+        };
+
+        let steps = match artifact.format {
+            ArtifactFormat::AppImage => {
+                appimages = true;
+                as_is(installed)
+            }
+            ArtifactFormat::Binary => as_is(installed),
+            _ => match unpack_command(&artifact.url) {
+                Some(unpack) => archive(installed, &artifact, unpack),
+                None => {
+                    skipped.push(skip("busybox can't unpack its artifact"));
+                    continue;
+                }
+            },
+        };
+        let work_dir = format!("/tmp/rush/{}", name);
+        let _ = write!(
+            dockerfile,
+            "\n# {} {}\nADD --checksum=sha256:{} {} {}/artifact\nRUN cd {} \\\n{} \\\n && cd / && rm -rf {}\n",
+            name,
+            installed.version,
+            artifact.sha256,
+            artifact.url.replace('$', "\\$"),
+            work_dir,
+            work_dir,
+            steps.join(" \\\n"),
+            work_dir
+        );
+        packages.push(name.clone());
+    }
+
+    let _ = write!(
+        dockerfile,
+        "\nFROM --platform={} ${{BASE_IMAGE}}\nCOPY --from=rush /rush/ /usr/local/\n",
+        platform
+    );
+    if appimages {
+        // There is no FUSE in a container to mount the images with
+        dockerfile.push_str("ENV APPIMAGE_EXTRACT_AND_RUN=1\n");
+    }
+
+    Ok(ExportResult {
+        contents: dockerfile,
+        packages,
+        skipped,
+    })
+}
+
+/// Helper: Shell steps that unpack `artifact` and install its binaries and data files
+fn archive(installed: &InstalledPackage, artifact: &TargetDefinition, unpack: &str) -> Vec<String> {
+    let mut steps = vec![format!(" && {} artifact && rm artifact", unpack)];
+    for bin in &installed.binaries {
+        steps.push(format!(
+            " && install -D -m 755 \"$(find . -type f -name {} | head -n 1)\" /rush/bin/{}",
+            shell_quote(bin),
+            shell_quote(bin)
+        ));
+    }
+    for file in &artifact.files {
+        let dest = format!("/rush/share/{}", shell_quote(&file.dest));
+        steps.push(format!(
+            " && mkdir -p \"$(dirname {dest})\" && cp -r \"$(find . -path {} | head -n 1)\" {dest}",
+            shell_quote(&format!("*/{}", file.src)),
+        ));
+    }
+    steps
+}
+
+/// Helper: An AppImage or bare binary is installed as it is, under the binary's name
+fn as_is(installed: &InstalledPackage) -> Vec<String> {
+    installed
+        .binaries
+        .iter()
+        .map(|bin| {
+            format!(
+                " && install -D -m 755 artifact /rush/bin/{}",
+                shell_quote(bin)
+            )
+        })
+        .collect()
+}
+
+/// Helper: The busybox command that unpacks an artifact, by its extension. A .deb or
+/// .rpm, or a tarball nested in a zip, would need more than that.
+fn unpack_command(url: &str) -> Option<&'static str> {
+    let file = url.rsplit('/').next().unwrap_or(url);
+    [
+        (".tar.gz", "tar -xzf"),
+        (".tgz", "tar -xzf"),
+        (".tar.xz", "tar -xJf"),
+        (".tar.bz2", "tar -xjf"),
+        (".zip", "unzip -q"),
+    ]
+    .into_iter()
+    .find(|(ext, _)| file.ends_with(ext))
+    .map(|(_, command)| command)
+}
+
+/// Helper: The `--platform` of a Linux target; None for any other OS
+fn docker_platform(target: &Target) -> Option<&'static str> {
+    if target.os != Os::Linux {
+        return None;
+    }
+    match target.arch {
+        Arch::X86_64 => Some("linux/amd64"),
+        Arch::Aarch64 => Some("linux/arm64"),
+        Arch::Arm32 => Some("linux/arm/v7"),
+        Arch::X86 => Some("linux/386"),
+        Arch::Universal | Arch::Other => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DataFile, PackageManifest};
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_export_dockerfile() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let target = |url: &str, format| TargetDefinition {
+            url: url.to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: "cd".repeat(32),
+            checksum_url: None,
+            size: None,
+            format,
+            extract: false,
+            files: Vec::new(),
+        };
+        let mut tool = target("https://example.com/tool.tar.gz", ArtifactFormat::Archive);
+        tool.files = vec![DataFile {
+            src: "completions".to_string(),
+            dest: "fish/vendor_completions.d".to_string(),
+        }];
+        let packages = [
+            ("tool", tool),
+            (
+                "viewer",
+                target(
+                    "https://example.com/Viewer.AppImage",
+                    ArtifactFormat::AppImage,
+                ),
+            ),
+            (
+                "debonly",
+                target("https://example.com/debonly.deb", ArtifactFormat::Archive),
+            ),
+            (
+                "bare",
+                target("https://example.com/bare-linux", ArtifactFormat::Binary),
+            ),
+        ];
+        for (name, target) in packages {
+            let manifest = PackageManifest {
+                version: "1.0.0".to_string(),
+                description: None,
+                source: None,
+                license: None,
+                homepage: None,
+                updated: None,
+                check: None,
+                osv: None,
+                requires: Vec::new(),
+                dependencies: Vec::new(),
+                targets: BTreeMap::from([("x86_64-linux".to_string(), target)]),
+                history: Vec::new(),
+            };
+            let dir = engine.registry_dir.join("packages").join(&name[..1]);
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(format!("{}.toml", name));
+            std::fs::write(path, toml::to_string(&manifest).unwrap()).unwrap();
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: "1.0.0".to_string(),
+                    binaries: vec![name.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let export = export_dockerfile(&engine, "x86_64-linux").unwrap();
+        assert_eq!(export.packages, ["bare", "tool", "viewer"]);
+        assert_eq!(export.skipped.len(), 1);
+        assert_eq!(export.skipped[0].package, "debonly");
+
+        let dockerfile = &export.contents;
+        assert!(dockerfile.starts_with("# syntax=docker/dockerfile:1.6\n"));
+        assert!(dockerfile.contains(&format!(
+            "ADD --checksum=sha256:{} https://example.com/tool.tar.gz /tmp/rush/tool/artifact\n",
+            "cd".repeat(32)
+        )));
+        assert!(dockerfile.contains(" && tar -xzf artifact && rm artifact \\\n"));
+        assert!(dockerfile.contains(
+            "install -D -m 755 \"$(find . -type f -name 'tool' | head -n 1)\" /rush/bin/'tool'"
+        ));
+        assert!(dockerfile.contains("/rush/share/'fish/vendor_completions.d'"));
+        assert!(dockerfile.contains("install -D -m 755 artifact /rush/bin/'viewer'"));
+        assert!(dockerfile.contains("install -D -m 755 artifact /rush/bin/'bare'"));
+        assert!(dockerfile.ends_with(
+            "FROM --platform=linux/amd64 ${BASE_IMAGE}\nCOPY --from=rush /rush/ /usr/local/\nENV APPIMAGE_EXTRACT_AND_RUN=1\n"
+        ));
+
+        let err = export_dockerfile(&engine, "aarch64-macos").unwrap_err();
+        assert!(err.to_string().contains("need Linux binaries"));
+    }
+
+    #[test]
+    fn test_unpack_command() {
+        assert_eq!(unpack_command("https://x/t-1.0.tgz"), Some("tar -xzf"));
+        assert_eq!(unpack_command("https://x/t.tar.xz"), Some("tar -xJf"));
+        assert_eq!(unpack_command("https://x/t.zip"), Some("unzip -q"));
+        assert_eq!(unpack_command("https://x/t.deb"), None);
+        assert_eq!(unpack_command("https://x/t"), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! `rush find`: fuzzy matching over the registry index, for an incremental finder.
+//!
+//! A query matches when its characters appear in order in a package's name or
+//! description (case-insensitively, gaps allowed), as in skim or fzf. Matches are ranked
+//! by how tightly they fit: runs of consecutive characters and characters starting a word
+//! score higher, gaps lower, and a match in the name beats one in the description.
+
+use crate::core::RushEngine;
+use crate::core::query::packages;
+use crate::models::FinderEntry;
+
+/// Points for each query character matched
+const MATCH: i64 = 16;
+/// Extra points for a character right after the previous match
+const CONSECUTIVE: i64 = 12;
+/// Extra points for a character starting a word (`rip` in `ripgrep`, `g` in `fd-find`)
+const WORD_START: i64 = 10;
+/// Points lost per skipped character, up to `MAX_GAP_PENALTY` per gap
+const GAP: i64 = 1;
+const MAX_GAP_PENALTY: i64 = 8;
+
+/// The registry's packages that have a build for the engine's target, in name order
+pub fn finder_entries(engine: &RushEngine) -> Vec<FinderEntry> {
+    packages(engine)
+        .filter(|(_, manifest)| manifest.target(engine.target()).is_some())
+        .map(|(name, manifest)| FinderEntry {
+            name,
+            version: manifest.version,
+            description: manifest.description,
+        })
+        .collect()
+}
+
+/// The entries matching `query`, best first (ties keep name order); an empty query
+/// matches everything
+pub fn fuzzy_filter<'a>(entries: &'a [FinderEntry], query: &str) -> Vec<&'a FinderEntry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return entries.iter().collect();
+    }
+    let mut scored: Vec<(i64, &FinderEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let name = fuzzy_score(query, &entry.name).map(|s| s * 2);
+            let description = entry
+                .description
+                .as_deref()
+                .and_then(|d| fuzzy_score(query, d));
+            name.max(description).map(|score| (score, entry))
+        })
+        .collect();
+    scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// How well `query` fits `text`; None if its characters don't all appear in order.
+/// Every starting point for the first character is tried, and the best kept.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let first = *query.first()?;
+
+    (0..text.len())
+        .filter(|&start| text[start] == first)
+        .filter_map(|start| score_from(&query, &text, start))
+        .max()
+}
+
+/// Helper: The score of matching `query` into `text` greedily, its first character at `start`
+fn score_from(query: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut position = start;
+    for &c in query {
+        let found = position + text[position..].iter().position(|&t| t == c)?;
+        score += MATCH;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START;
+        }
+        match previous {
+            Some(p) if found == p + 1 => score += CONSECUTIVE,
+            Some(p) => score -= ((found - p - 1) as i64 * GAP).min(MAX_GAP_PENALTY),
+            None => {}
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+
+    fn entry(name: &str, description: &str) -> FinderEntry {
+        FinderEntry {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: Some(description.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("rg", "ripgrep").is_some());
+        assert!(fuzzy_score("RG", "ripgrep").is_some());
+        assert!(fuzzy_score("gr", "rg").is_none());
+        assert!(fuzzy_score("", "anything").is_none());
+
+        // Consecutive and word-start matches beat scattered ones
+        assert!(fuzzy_score("fd", "fd-find") > fuzzy_score("fd", "fzf-dir"));
+        assert!(fuzzy_score("bat", "bat") > fuzzy_score("bat", "bandwhich-tool"));
+        // The best start is found, not just the first
+        assert_eq!(fuzzy_score("grep", "gg grep"), fuzzy_score("grep", "grep"));
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let entries = vec![
+            entry("bat", "A cat clone with wings"),
+            entry("fd", "A simple, fast alternative to find"),
+            entry("ripgrep", "Search files recursively with a regex"),
+        ];
+        let names = |query| -> Vec<&str> {
+            fuzzy_filter(&entries, query)
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect()
+        };
+        assert_eq!(names(""), ["bat", "fd", "ripgrep"]);
+        assert_eq!(names("rg"), ["ripgrep"]);
+        // Descriptions match too, below names
+        assert_eq!(names("find"), ["fd"]);
+        assert_eq!(names("regex"), ["ripgrep"]);
+        assert!(names("zzz").is_empty());
+    }
+
+    #[test]
+    fn test_finder_entries() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let entries = finder_entries(&engine);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "tool");
+        assert_eq!(entries[0].version, "1.0.0");
+    }
+}
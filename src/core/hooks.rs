@@ -0,0 +1,128 @@
+use crate::Error;
+use crate::core::RushEngine;
+use crate::models::{HookEvent, HookPoint};
+use anyhow::Result;
+use std::process::Command;
+
+/// Run the `[hooks]` command configured for `point`, if any, and report it to `on_event`.
+/// A failing "pre" hook is an error (the caller must not go ahead); a failing "post" hook
+/// is only reported.
+pub fn run_hook<F>(
+    engine: &RushEngine,
+    point: HookPoint,
+    package: &str,
+    version: &str,
+    mut on_event: F,
+) -> Result<()>
+where
+    F: FnMut(HookEvent),
+{
+    let Some(template) = engine.config.hooks.command(point) else {
+        return Ok(());
+    };
+
+    let bin_dir = engine.bin_path.to_string_lossy();
+    let command = template
+        .replace("{pkg}", &shell_quote(package))
+        .replace("{version}", &shell_quote(version))
+        .replace("{bin_dir}", &shell_quote(&bin_dir));
+
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .env("RUSH_HOOK", point.to_string())
+        .env("RUSH_PACKAGE", package)
+        .env("RUSH_VERSION", version)
+        .env("RUSH_BIN_DIR", bin_dir.as_ref())
+        .output();
+
+    let (exit_code, stderr) = match output {
+        Ok(output) => (
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ),
+        Err(e) => (None, e.to_string()),
+    };
+    let event = HookEvent {
+        point,
+        package: package.to_string(),
+        command,
+        exit_code,
+        stderr,
+    };
+    let succeeded = event.succeeded();
+    on_event(event);
+
+    if point.is_pre() && !succeeded {
+        return Err(Error::HookFailed {
+            point,
+            package: package.to_string(),
+            exit_code,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Helper: Single-quote `value` for `sh`, so names can't inject commands
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HooksConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_hooks_run_and_report() {
+        let temp_dir = tempdir().unwrap();
+        let log = temp_dir.path().join("hooks.log");
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+        engine.config.hooks = HooksConfig {
+            post_install: Some(format!(
+                "echo {{pkg}} {{version}} $RUSH_HOOK >> '{}'",
+                log.display()
+            )),
+            pre_uninstall: Some("echo no >&2; exit 3".to_string()),
+            ..Default::default()
+        };
+
+        // Unconfigured points do nothing
+        let mut events = Vec::new();
+        run_hook(&engine, HookPoint::PreInstall, "fzf", "1.0", |e| {
+            events.push(e)
+        })
+        .unwrap();
+        assert!(events.is_empty());
+
+        run_hook(
+            &engine,
+            HookPoint::PostInstall,
+            "it's; rm -rf",
+            "1.0",
+            |e| events.push(e),
+        )
+        .unwrap();
+        assert!(events[0].succeeded());
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap(),
+            "it's; rm -rf 1.0 post_install\n"
+        );
+
+        let err = run_hook(&engine, HookPoint::PreUninstall, "fzf", "1.0", |e| {
+            events.push(e)
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::HookFailed {
+                point: HookPoint::PreUninstall,
+                package: "fzf".to_string(),
+                exit_code: Some(3),
+            })
+        );
+        assert_eq!(events[1].stderr, "no");
+    }
+}
@@ -0,0 +1,91 @@
+//! `rush init`: put the bin dir on `PATH` for good, by adding the line that does it to
+//! the user's shell startup file. Which line and which file depend on the shell; the CLI
+//! picks them.
+
+use super::RushEngine;
+use super::schedule::home;
+use crate::models::InitResult;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Comment written above the line, so it's clear where it came from
+const MARKER: &str = "# Added by `rush init`: put rush's binaries on PATH";
+
+/// Append `line` to `rc_file` (relative to the home directory), creating it if needed.
+/// A file that already has the line is left alone.
+pub fn init_shell(engine: &RushEngine, rc_file: &Path, line: &str) -> Result<InitResult> {
+    let path = home(engine)?.join(rc_file);
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", path)),
+    };
+    if existing.lines().any(|l| l.trim() == line) {
+        return Ok(InitResult {
+            rc_file: path,
+            added: false,
+        });
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| write!(file, "{}\n{}\n{}\n", separator, MARKER, line))
+        .with_context(|| format!("Failed to write {:?}", path))?;
+
+    Ok(InitResult {
+        rc_file: path,
+        added: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_init_shell_appends_once() {
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+        let line = r#"eval "$(rush env)""#;
+
+        let bashrc = temp_dir.path().join(".bashrc");
+        fs::write(&bashrc, "alias ll='ls -l'").unwrap();
+        let result = engine.init_shell(Path::new(".bashrc"), line).unwrap();
+        assert_eq!(result.rc_file, bashrc);
+        assert!(result.added);
+        assert_eq!(
+            fs::read_to_string(&bashrc).unwrap(),
+            format!("alias ll='ls -l'\n\n{}\n{}\n", MARKER, line)
+        );
+
+        // Already there
+        assert!(!engine.init_shell(Path::new(".bashrc"), line).unwrap().added);
+        assert_eq!(
+            fs::read_to_string(&bashrc).unwrap().matches(line).count(),
+            1
+        );
+
+        // Directories of a missing file are made
+        let fish = Path::new(".config/fish/config.fish");
+        assert!(
+            engine
+                .init_shell(fish, "rush env --shell fish | source")
+                .unwrap()
+                .added
+        );
+        assert!(temp_dir.path().join(fish).is_file());
+    }
+}
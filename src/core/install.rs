@@ -1,76 +1,323 @@
-use crate::core::{RushEngine, util};
-use crate::models::{InstallEvent, InstallResult, InstalledPackage, TargetDefinition};
+use crate::Error;
+use crate::core::{
+    CancellationToken, RushEngine, Target, appimage, archive, clean, datafiles, lock, pending,
+    pins, provenance, registry, util,
+};
+use crate::models::{
+    ArtifactFormat, InstallResult, InstallSummary, InstalledPackage, PackageManifest,
+    ProgressEvent, ProgressKind, TargetDefinition,
+};
 use anyhow::Result;
-use flate2::read::GzDecoder;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use tar::Archive;
+use std::time::{Duration, Instant};
 
+#[tracing::instrument(skip_all, fields(package = name, version, url = %target.url))]
 pub fn install_package<F>(
     engine: &mut RushEngine,
     name: &str,
     version: &str,
     target: &TargetDefinition,
+    on_event: F,
+) -> Result<InstallResult>
+where
+    F: FnMut(ProgressEvent),
+{
+    let mut on_event = util::tag_progress(Some(name), on_event);
+
+    // 1. Download using shared utility, unless the artifact is already here
+    let (content, download_time) = match local_artifact(engine, target) {
+        Some(content) => (content, None),
+        None => {
+            engine.ensure_online(&target.url)?;
+            let started = Instant::now();
+            let content =
+                util::download_url(engine.transport.as_ref(), &target.url, &mut on_event)?;
+            (content, Some(started.elapsed()))
+        }
+    };
+
+    install_from_bytes(
+        engine,
+        name,
+        version,
+        target,
+        &content,
+        download_time,
+        on_event,
+    )
+}
+
+/// A copy of `target`'s artifact already on this machine: the registry's own (a mirror),
+/// or one in the artifact cache (from `rush import-bundle`). Works offline; the checksum
+/// is still verified like a download's.
+pub(crate) fn local_artifact(engine: &RushEngine, target: &TargetDefinition) -> Option<Vec<u8>> {
+    let path = local_artifact_path(engine, target)?;
+    tracing::debug!(path = %path.display(), "using local artifact");
+    std::fs::read(path).ok()
+}
+
+/// Where `local_artifact` would read from, if anywhere
+pub(crate) fn local_artifact_path(
+    engine: &RushEngine,
+    target: &TargetDefinition,
+) -> Option<PathBuf> {
+    engine.registry.artifact_path(&target.sha256).or_else(|| {
+        registry::artifact_file(&engine.cache_dir, &target.sha256).filter(|p| p.is_file())
+    })
+}
+
+/// The manifest's entry for `target_key` (in any spelling, see `PackageManifest::target`),
+/// or `Error::NoCompatibleTarget`
+pub(crate) fn manifest_target<'a>(
+    name: &str,
+    manifest: &'a PackageManifest,
+    target_key: &str,
+) -> Result<&'a TargetDefinition> {
+    match manifest.target(target_key) {
+        Some((_, target)) => Ok(target),
+        None => Err(Error::NoCompatibleTarget {
+            package: name.to_string(),
+            target: Target::normalize(target_key),
+        }
+        .into()),
+    }
+}
+
+/// Verify, extract, and record an artifact that has already been downloaded.
+/// Stages are reported to `on_event` as part of the download's operation, ending in a
+/// `Summary` that counts `content` as downloaded if `download_time` is given (None: it was
+/// already on this machine).
+#[tracing::instrument(level = "debug", skip_all, fields(package = name, bytes = content.len()))]
+pub(crate) fn install_from_bytes<F>(
+    engine: &mut RushEngine,
+    name: &str,
+    version: &str,
+    target: &TargetDefinition,
+    content: &[u8],
+    download_time: Option<Duration>,
     mut on_event: F,
 ) -> Result<InstallResult>
 where
-    F: FnMut(InstallEvent),
+    F: FnMut(ProgressKind),
 {
-    // 1. Download using shared utility
-    let content = util::download_url(&engine.client, &target.url, &mut on_event)?;
+    engine.cancel.check()?;
+
+    // 1. The hash must be the one this version had when first seen
+    pins::check_install(engine, name, version, target)?;
 
     // 2. Verify Checksum using shared utility
-    on_event(InstallEvent::VerifyingChecksum);
-    util::verify_checksum(&content, &target.sha256)?;
-
-    // 3. Extract
-    on_event(InstallEvent::Extracting);
-    let tar = GzDecoder::new(&content[..]);
-    let mut archive = Archive::new(tar);
-    let mut found = false;
-    let mut final_path = PathBuf::new();
-
-    for entry in archive.entries()? {
-        let mut entry = entry?;
-        // Call the local helper function
-        if let Some(dest) = try_extract_binary(&mut entry, &engine.bin_path, &target.bin)? {
-            final_path = dest;
-            found = true;
-            break;
+    on_event(ProgressKind::VerifyingChecksum);
+    let started = Instant::now();
+    util::verify_checksum(content, &target.sha256)?;
+    let verify_time = started.elapsed();
+
+    // 3. Extract the binaries and data files, or store an AppImage behind its shim
+    //    (other engines sharing the bin dir wait for these binaries; always taken in the
+    //    same order, so two installs can't each hold one the other needs)
+    on_event(ProgressKind::Extracting);
+    let started = Instant::now();
+    let mut bins: Vec<&String> = target.bin.iter().collect();
+    bins.sort();
+    bins.dedup();
+    let _locks: Vec<_> = bins
+        .into_iter()
+        .map(|bin| lock::lock_binary(&engine.bin_path, bin))
+        .collect();
+    let main_bin = target.main_bin();
+    let mut staged = false;
+    let (final_path, binaries, files) = match target.format {
+        ArtifactFormat::Archive => {
+            // A data-only package has no binary to point at
+            let path = if target.bin.is_empty() {
+                engine.data_dir.clone()
+            } else {
+                // Written beside them if old binaries are running; still where they will be
+                let paths =
+                    extract_binaries_into(content, &engine.bin_path, &target.bin, &engine.cancel)?;
+                staged = paths
+                    .iter()
+                    .zip(&target.bin)
+                    .any(|(path, bin)| *path != engine.bin_path.join(bin));
+                engine.bin_path.join(main_bin)
+            };
+            let files = datafiles::install(engine, name, target, content)?;
+            // A previous version may have been an AppImage
+            appimage::remove(engine, name)?;
+            (path, target.bin.clone(), files)
         }
-    }
+        ArtifactFormat::AppImage => (
+            appimage::install(engine, name, version, target, content)?,
+            vec![main_bin.to_string()],
+            Vec::new(),
+        ),
+        ArtifactFormat::Binary => {
+            let dest = engine.bin_path.join(main_bin);
+            staged =
+                write_binary_into(content, &engine.bin_path, main_bin, &engine.cancel)? != dest;
+            appimage::remove(engine, name)?;
+            (dest, vec![main_bin.to_string()], Vec::new())
+        }
+    };
 
-    if !found {
-        anyhow::bail!("Binary '{}' not found in archive", target.bin);
+    // Binaries and data files the previous version had and this one doesn't
+    if let Some(previous) = engine.state.packages.get(name) {
+        let stale: Vec<String> = previous
+            .files
+            .iter()
+            .filter(|f| !files.contains(f))
+            .cloned()
+            .collect();
+        let stale_bins: Vec<String> = previous
+            .binaries
+            .iter()
+            .filter(|b| !binaries.contains(b))
+            .cloned()
+            .collect();
+        datafiles::remove(engine, &stale)?;
+        for bin in &stale_bins {
+            pending::remove_binary(&engine.bin_path, bin)?;
+        }
     }
 
-    // 4. Update State
+    // 4. Update State (an upgrade keeps the package's constraint or pin, and whether it
+    // was installed as a dependency)
+    let provenance = provenance::record(engine, name, target);
+    let previous = engine.state.packages.get(name);
+    let constraint = previous.and_then(|previous| previous.constraint.clone());
+    let pinned = previous.is_some_and(|previous| previous.pinned);
+    let dependency = previous.is_some_and(|previous| previous.dependency);
     engine.state.packages.insert(
         name.to_string(),
         InstalledPackage {
             version: version.to_string(),
-            binaries: vec![target.bin.clone()],
+            binaries,
+            files,
+            provenance: Some(provenance),
+            constraint,
+            pinned,
+            dependency,
         },
     );
     engine.save()?;
+    let install_time = started.elapsed();
 
-    on_event(InstallEvent::Success);
+    on_event(ProgressKind::Success);
+    on_event(ProgressKind::Summary(InstallSummary {
+        version: version.to_string(),
+        downloaded_bytes: download_time.map_or(0, |_| content.len() as u64),
+        download_time: download_time.unwrap_or_default(),
+        verify_time,
+        install_time,
+        path: final_path.clone(),
+    }));
 
     Ok(InstallResult {
         package_name: name.to_string(),
         version: version.to_string(),
         path: final_path,
+        staged,
     })
 }
 
+/// Atomically place the file named `bin` from an artifact (a tarball or .zip, possibly
+/// wrapped in another, or with the `distro-packages` feature a .deb or .rpm) into
+/// `bin_dir`, returning its final path (`<bin>.new` if the old one is running and can't be
+/// replaced). No state is touched; this is the extraction step of an install.
+pub fn extract_binary(archive: &[u8], bin: &str, bin_dir: &Path) -> Result<PathBuf> {
+    let _lock = lock::lock_binary(bin_dir, bin);
+    extract_binary_into(archive, bin_dir, bin, &CancellationToken::default())
+}
+
+/// Helper: `extract_binary`, giving up before the rename if `cancel` fires
+pub(crate) fn extract_binary_into(
+    content: &[u8],
+    bin_dir: &Path,
+    bin: &str,
+    cancel: &CancellationToken,
+) -> Result<PathBuf> {
+    let mut paths = extract_binaries_into(content, bin_dir, &[bin.to_string()], cancel)?;
+    Ok(paths.remove(0))
+}
+
+/// Helper: `extract_binary_into` for each of `bins` (the first file named like each), in
+/// one pass over the archive; their final paths, in the same order. If any is missing,
+/// none is written.
+#[tracing::instrument(
+    name = "extract_binary",
+    level = "debug",
+    skip(content, cancel),
+    fields(bytes = content.len())
+)]
+pub(crate) fn extract_binaries_into(
+    content: &[u8],
+    bin_dir: &Path,
+    bins: &[String],
+    cancel: &CancellationToken,
+) -> Result<Vec<PathBuf>> {
+    // A lone binary can't be half-installed, so only several need looking for first
+    if bins.len() > 1
+        && let Some(bin) = missing_binaries(content, bins)?.first()
+    {
+        return Err(Error::BinaryNotFound { bin: bin.clone() }.into());
+    }
+
+    let mut placed: Vec<Option<PathBuf>> = vec![None; bins.len()];
+    archive::visit_files(content, &mut |path, _, reader| {
+        let Some(i) = (0..bins.len()).find(|&i| {
+            placed[i].is_none() && path.file_name() == Some(std::ffi::OsStr::new(&bins[i]))
+        }) else {
+            return Ok(None);
+        };
+        placed[i] = try_extract_binary(path, reader, bin_dir, &bins[i], cancel)?;
+        Ok(placed.iter().all(Option::is_some).then_some(()))
+    })?;
+
+    bins.iter()
+        .zip(placed)
+        .map(|(bin, dest)| {
+            let dest = dest.ok_or_else(|| Error::BinaryNotFound { bin: bin.clone() })?;
+            tracing::debug!(path = %dest.display(), "binary in place");
+            Ok(dest)
+        })
+        .collect()
+}
+
+/// Which of `bins` no file in an archive is named like
+pub(crate) fn missing_binaries(content: &[u8], bins: &[String]) -> Result<Vec<String>> {
+    let mut missing = bins.to_vec();
+    archive::visit_files(content, &mut |path, _, _| {
+        missing.retain(|bin| path.file_name() != Some(std::ffi::OsStr::new(bin)));
+        Ok(missing.is_empty().then_some(()))
+    })?;
+    Ok(missing)
+}
+
+/// Helper: `extract_binary_into` for an artifact that is the binary itself
+pub(crate) fn write_binary_into(
+    content: &[u8],
+    bin_dir: &Path,
+    bin: &str,
+    cancel: &CancellationToken,
+) -> Result<PathBuf> {
+    let dest = try_extract_binary(Path::new(bin), &mut &content[..], bin_dir, bin, cancel)?;
+    // Only None when `bin` has no file name
+    let dest = dest.ok_or_else(|| Error::BinaryNotFound {
+        bin: bin.to_string(),
+    })?;
+    Ok(dest)
+}
+
 /// Helper: Returns Some(path) if successful, None if skipped
 /// We pass `bin_path` explicitly here instead of `&self`
-fn try_extract_binary<R: std::io::Read>(
-    entry: &mut tar::Entry<R>,
+/// The binary is only moved into place if `cancel` hasn't fired by then.
+fn try_extract_binary(
+    path: &Path,
+    entry: &mut dyn Read,
     bin_path: &Path,
     target_bin_name: &str,
+    cancel: &CancellationToken,
 ) -> Result<Option<PathBuf>> {
-    let path = entry.path()?;
-
     // Guard Clause 1: Check if filename exists
     let fname = match path.file_name() {
         Some(f) => f,
@@ -86,7 +333,7 @@ fn try_extract_binary<R: std::io::Read>(
     let dest = bin_path.join(target_bin_name);
 
     let mut temp_file = tempfile::Builder::new()
-        .prefix(".rush-tmp-")
+        .prefix(&clean::own_trash_prefix())
         .tempfile_in(bin_path)?;
 
     std::io::copy(entry, &mut temp_file)?;
@@ -99,22 +346,26 @@ fn try_extract_binary<R: std::io::Read>(
         temp_file.as_file().set_permissions(p)?;
     }
 
-    temp_file.persist(&dest)?;
-
-    Ok(Some(dest))
+    // Dropping the temp file deletes it
+    cancel.check()?;
+    Ok(Some(pending::persist(temp_file, &dest)?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::transport::mock::{MockResponse, MockTransport};
+    use crate::test_utils::tar_gz;
+    use sha2::Digest;
     use std::io::Cursor;
+    use tar::Archive;
     use tempfile::tempdir;
 
     #[test]
     fn test_try_extract_binary_success() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         let mut header = tar::Header::new_gnu();
         header.set_size(12);
@@ -133,17 +384,25 @@ mod tests {
         let mut entries = archive.entries().unwrap();
         let mut entry = entries.next().unwrap().unwrap();
 
-        let result = try_extract_binary(&mut entry, &engine.bin_path, "test-bin").unwrap();
+        let path = entry.path().unwrap().into_owned();
+        let result = try_extract_binary(
+            &path,
+            &mut entry,
+            &engine.bin_path,
+            "test-bin",
+            &engine.cancel,
+        )
+        .unwrap();
 
         assert!(result.is_some(), "Should have extracted the binary");
-        assert!(root.join(".local/bin/test-bin").exists());
+        assert!(root.join(".local/share/rush/shims/test-bin").exists());
     }
 
     #[test]
     fn test_try_extract_binary_mismatch() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         // Create a tarball with a different filename
         let mut header = tar::Header::new_gnu();
@@ -163,13 +422,21 @@ mod tests {
         let mut entries = archive.entries().unwrap();
         let mut entry = entries.next().unwrap().unwrap();
 
-        let result = try_extract_binary(&mut entry, &engine.bin_path, "test-bin").unwrap();
+        let path = entry.path().unwrap().into_owned();
+        let result = try_extract_binary(
+            &path,
+            &mut entry,
+            &engine.bin_path,
+            "test-bin",
+            &engine.cancel,
+        )
+        .unwrap();
 
         assert!(
             result.is_none(),
             "Should not have extracted mismatched filename"
         );
-        assert!(!root.join(".local/bin/test-bin").exists());
+        assert!(!root.join(".local/share/rush/shims/test-bin").exists());
     }
 
     #[test]
@@ -177,7 +444,7 @@ mod tests {
     fn test_install_fails_gracefully_if_binary_missing() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         // 1. Create a tarball that contains "wrong_file", NOT "target_file"
         let mut header = tar::Header::new_gnu();
@@ -200,9 +467,16 @@ mod tests {
         for entry in archive.entries().unwrap() {
             let mut entry = entry.unwrap();
 
-            if try_extract_binary(&mut entry, &engine.bin_path, "target_file")
-                .unwrap()
-                .is_some()
+            let path = entry.path().unwrap().into_owned();
+            if try_extract_binary(
+                &path,
+                &mut entry,
+                &engine.bin_path,
+                "target_file",
+                &engine.cancel,
+            )
+            .unwrap()
+            .is_some()
             {
                 found = true;
                 break;
@@ -212,4 +486,185 @@ mod tests {
         // 3. Assert failure
         assert!(!found, "Should not have found binary");
     }
+
+    #[test]
+    fn test_install_over_mocked_network() {
+        let temp_dir = tempdir().unwrap();
+        let data = tar_gz(&[("tool", 0o755, b"#!sh")]);
+        let target = |url: &str| TargetDefinition {
+            url: url.to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: hex::encode(sha2::Sha256::digest(&data)),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::Archive,
+            extract: false,
+            files: Vec::new(),
+        };
+
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .transport(
+                MockTransport::default()
+                    .with("https://dl/tool.tar.gz", MockResponse::Body(data.clone()))
+                    .with(
+                        "https://dl/cut.tar.gz",
+                        MockResponse::Truncated {
+                            body: data[..10].to_vec(),
+                            length: data.len() as u64,
+                        },
+                    ),
+            )
+            .build()
+            .unwrap();
+
+        let mut summary = None;
+        let result = install_package(
+            &mut engine,
+            "tool",
+            "1.0",
+            &target("https://dl/tool.tar.gz"),
+            |e| {
+                if let ProgressKind::Summary(s) = e.kind {
+                    summary = Some((e.package, s));
+                }
+            },
+        );
+        let path = result.unwrap().path;
+        assert!(path.exists());
+        // The last event has everything needed to report the install
+        let (package, summary) = summary.unwrap();
+        assert_eq!(package.as_deref(), Some("tool"));
+        assert_eq!(summary.version, "1.0");
+        assert_eq!(summary.downloaded_bytes, data.len() as u64);
+        assert_eq!(summary.path, path);
+
+        // 404s and dropped connections fail before anything is recorded
+        engine.state.packages.clear();
+        for url in ["https://dl/missing.tar.gz", "https://dl/cut.tar.gz"] {
+            assert!(install_package(&mut engine, "tool", "1.0", &target(url), |_| {}).is_err());
+        }
+        assert!(engine.state.packages.is_empty());
+    }
+
+    #[test]
+    fn test_install_bare_binary() {
+        let temp_dir = tempdir().unwrap();
+        let binary = b"\x7fELF pretend binary".to_vec();
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .transport(
+                MockTransport::default()
+                    .with("https://dl/tool-linux", MockResponse::Body(binary.clone())),
+            )
+            .build()
+            .unwrap();
+        let target = TargetDefinition {
+            url: "https://dl/tool-linux".to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: hex::encode(sha2::Sha256::digest(&binary)),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::Binary,
+            extract: false,
+            files: Vec::new(),
+        };
+
+        let path = install_package(&mut engine, "tool", "1.0", &target, |_| {})
+            .unwrap()
+            .path;
+        assert_eq!(path, engine.bin_path.join("tool"));
+        assert_eq!(std::fs::read(&path).unwrap(), binary);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+        assert_eq!(engine.state.packages["tool"].binaries, ["tool"]);
+
+        // The checksum is still checked
+        engine.state.packages.clear();
+        let tampered = TargetDefinition {
+            sha256: "00".repeat(32),
+            ..target
+        };
+        assert!(install_package(&mut engine, "tool", "1.0", &tampered, |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_install_several_binaries() {
+        let temp_dir = tempdir().unwrap();
+        let data = tar_gz(&[
+            ("uv-1.0/uv", 0o755, b"uv"),
+            ("uv-1.0/README.md", 0o755, b"# uv"),
+            ("uv-1.0/uvx", 0o755, b"uvx"),
+        ]);
+
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .transport(
+                MockTransport::default()
+                    .with("https://dl/uv.tar.gz", MockResponse::Body(data.clone())),
+            )
+            .build()
+            .unwrap();
+        let target = |bins: &[&str]| TargetDefinition {
+            url: "https://dl/uv.tar.gz".to_string(),
+            bin: bins.iter().map(|b| b.to_string()).collect(),
+            sha256: hex::encode(sha2::Sha256::digest(&data)),
+            checksum_url: None,
+            size: None,
+            format: ArtifactFormat::Archive,
+            extract: false,
+            files: Vec::new(),
+        };
+
+        let result = install_package(&mut engine, "uv", "1.0", &target(&["uv", "uvx"]), |_| {});
+        assert_eq!(result.unwrap().path, engine.bin_path.join("uv"));
+        assert_eq!(std::fs::read(engine.bin_path.join("uvx")).unwrap(), b"uvx");
+        assert_eq!(engine.state.packages["uv"].binaries, ["uv", "uvx"]);
+
+        // A binary the archive lacks fails the install before any is written
+        let err = install_package(&mut engine, "uv", "1.1", &target(&["uvx", "uvw"]), |_| {});
+        assert!(err.unwrap_err().to_string().contains("uvw"));
+        assert_eq!(engine.state.packages["uv"].version, "1.0");
+
+        // A version that drops one takes it away
+        install_package(&mut engine, "uv", "1.1", &target(&["uv"]), |_| {}).unwrap();
+        assert!(!engine.bin_path.join("uvx").exists());
+        assert_eq!(engine.state.packages["uv"].binaries, ["uv"]);
+
+        // A name listed twice fails instead of waiting on its own lock
+        assert!(install_package(&mut engine, "uv", "1.2", &target(&["uv", "uv"]), |_| {}).is_err());
+        assert_eq!(engine.state.packages["uv"].version, "1.1");
+    }
+
+    #[test]
+    fn test_install_from_gnu_key_on_host() {
+        let mock = crate::test_utils::MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        let path = mock.registry_source.join("packages/t/tool.toml");
+        let host = crate::test_utils::current_target();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let gnu = format!("[targets.{}-gnu]", host);
+        let content = content.replace(&format!("[targets.{}]", host), &gnu);
+        std::fs::write(&path, content).unwrap();
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let manifest = engine.find_package("tool").unwrap();
+        assert_eq!(
+            manifest.targets.keys().collect::<Vec<_>>(),
+            [&format!("{}-gnu", host)]
+        );
+        let target = engine.target().to_string();
+        assert!(
+            engine
+                .install_from_manifest("tool", &manifest, &target, &mut ())
+                .unwrap()
+                .is_installed()
+        );
+        assert!(engine.bin_path.join("tool").exists());
+    }
 }
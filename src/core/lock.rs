@@ -0,0 +1,156 @@
+//! In-process locks on the bin dir.
+//!
+//! Several engines (or threads driving `AsyncRushEngine`s) can share one bin dir. Writing
+//! or removing a binary takes that binary's lock plus a shared hold on the dir, so
+//! different packages still install in parallel; sweeping the dir for trash takes the dir
+//! exclusively, so it never deletes a temp file an install is about to move into place.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+#[derive(Default)]
+struct DirState {
+    /// Someone holds the whole dir
+    exclusive: bool,
+    /// Binaries currently being written or removed
+    busy: HashSet<String>,
+}
+
+#[derive(Default)]
+struct Locks {
+    dirs: Mutex<HashMap<PathBuf, DirState>>,
+    changed: Condvar,
+}
+
+fn locks() -> &'static Locks {
+    static LOCKS: std::sync::OnceLock<Locks> = std::sync::OnceLock::new();
+    LOCKS.get_or_init(Locks::default)
+}
+
+/// The same dir spelled differently must map to the same lock
+fn key(bin_dir: &Path) -> PathBuf {
+    bin_dir
+        .canonicalize()
+        .unwrap_or_else(|_| bin_dir.to_path_buf())
+}
+
+/// Wait until `ready` holds for `dir`'s state, then apply `take` to it
+fn acquire(dir: &Path, ready: impl Fn(&DirState) -> bool, take: impl FnOnce(&mut DirState)) {
+    let locks = locks();
+    let mut dirs = lock_map(locks);
+    loop {
+        let state = dirs.entry(dir.to_path_buf()).or_default();
+        if ready(state) {
+            take(state);
+            return;
+        }
+        dirs = locks.changed.wait(dirs).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+fn release(dir: &Path, give_back: impl FnOnce(&mut DirState)) {
+    let locks = locks();
+    let mut dirs = lock_map(locks);
+    if let Some(state) = dirs.get_mut(dir) {
+        give_back(state);
+        if !state.exclusive && state.busy.is_empty() {
+            dirs.remove(dir);
+        }
+    }
+    locks.changed.notify_all();
+}
+
+fn lock_map(locks: &Locks) -> MutexGuard<'_, HashMap<PathBuf, DirState>> {
+    locks.dirs.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Held while one binary in a bin dir is written or removed
+pub(crate) struct BinaryLock {
+    dir: PathBuf,
+    bin: String,
+}
+
+/// Block until no one else is writing `bin` in `bin_dir` and the dir isn't being swept
+pub(crate) fn lock_binary(bin_dir: &Path, bin: &str) -> BinaryLock {
+    let dir = key(bin_dir);
+    acquire(
+        &dir,
+        |state| !state.exclusive && !state.busy.contains(bin),
+        |state| {
+            state.busy.insert(bin.to_string());
+        },
+    );
+    BinaryLock {
+        dir,
+        bin: bin.to_string(),
+    }
+}
+
+impl Drop for BinaryLock {
+    fn drop(&mut self) {
+        release(&self.dir, |state| {
+            state.busy.remove(&self.bin);
+        });
+    }
+}
+
+/// Held while the whole bin dir is swept
+pub(crate) struct DirLock {
+    dir: PathBuf,
+}
+
+/// Block until every binary lock on `bin_dir` is released, and keep new ones out
+pub(crate) fn lock_dir(bin_dir: &Path) -> DirLock {
+    let dir = key(bin_dir);
+    acquire(
+        &dir,
+        |state| !state.exclusive && state.busy.is_empty(),
+        |state| state.exclusive = true,
+    );
+    DirLock { dir }
+}
+
+impl Drop for DirLock {
+    fn drop(&mut self) {
+        release(&self.dir, |state| state.exclusive = false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sweep_waits_for_writers() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+
+        let fzf = lock_binary(&dir, "fzf");
+        // A different binary in the same dir doesn't wait
+        drop(lock_binary(&dir, "bat"));
+
+        let swept = Arc::new(AtomicBool::new(false));
+        let sweeper = {
+            let (dir, swept) = (dir.clone(), swept.clone());
+            std::thread::spawn(move || {
+                let _dir = lock_dir(&dir);
+                swept.store(true, Ordering::SeqCst);
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!swept.load(Ordering::SeqCst));
+
+        drop(fzf);
+        sweeper.join().unwrap();
+        assert!(swept.load(Ordering::SeqCst));
+
+        // Everything released: the dir's entry is gone
+        assert!(lock_map(locks()).get(&key(&dir)).is_none());
+    }
+}
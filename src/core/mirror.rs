@@ -0,0 +1,211 @@
+//! Copying the registry and its artifacts somewhere rush can install from without a network.
+//!
+//! A mirror directory is a local registry (`packages/`, plus the signed index if the
+//! source has one) with every artifact for the chosen targets in `artifacts/<sha256>`.
+//! With `RUSH_REGISTRY_URL` pointing at it, `rush update` syncs from it and installs read
+//! the artifacts from it instead of downloading them, so even an offline engine can install.
+
+use crate::core::dev::{INDEX_FILE, SIGNATURE_FILE};
+use crate::core::registry::{self, ARTIFACTS_DIR};
+use crate::core::{RushEngine, update, util};
+use crate::models::{MirrorFailure, MirrorResult, ProgressEvent, ProgressKind, TargetDefinition};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Sync the registry, then write it and its artifacts for `targets` into `dir`.
+/// Manifests are rewritten on every run, so packages dropped from the registry drop out
+/// of the mirror; artifacts already there are kept and only checked.
+#[tracing::instrument(skip(engine, on_event), fields(dir = %dir.display()))]
+pub fn mirror<F>(
+    engine: &RushEngine,
+    dir: &Path,
+    targets: &[String],
+    mut on_event: F,
+) -> Result<MirrorResult>
+where
+    F: FnMut(ProgressEvent),
+{
+    update::update_registry(engine, &mut on_event)?;
+
+    registry::reset_dir(&dir.join("packages"))?;
+    fs::create_dir_all(dir.join(ARTIFACTS_DIR))?;
+    for file in [INDEX_FILE, SIGNATURE_FILE] {
+        let synced = engine.registry_dir.join(file);
+        if synced.is_file() {
+            fs::copy(&synced, dir.join(file))?;
+        } else if dir.join(file).is_file() {
+            // A signature left from an earlier run would vouch for the wrong manifests
+            fs::remove_file(dir.join(file))?;
+        }
+    }
+
+    let mut result = MirrorResult {
+        dir: dir.to_path_buf(),
+        packages: 0,
+        downloaded: 0,
+        reused: 0,
+        unsupported: Vec::new(),
+        failures: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+
+    for (name, manifest) in engine.packages() {
+        write_manifest(engine, dir, &name, &manifest)?;
+        result.packages += 1;
+
+        for target in targets {
+            let Some((_, artifact)) = manifest.target(target) else {
+                result.unsupported.push((name.clone(), target.clone()));
+                continue;
+            };
+            // Targets (and packages) can share an artifact; one that failed is tried again
+            // from the next URL it is listed under
+            if seen.contains(&artifact.sha256) {
+                continue;
+            }
+            engine.cancel.check()?;
+
+            let mut on_event = util::tag_progress(Some(&name), &mut on_event);
+            let fetched = fetch_artifact(engine, dir, artifact, &mut on_event);
+            if fetched.is_ok() {
+                seen.insert(artifact.sha256.clone());
+            }
+            match fetched {
+                Ok(true) => result.downloaded += 1,
+                Ok(false) => result.reused += 1,
+                Err(e) => result.failures.push(MirrorFailure {
+                    package: name.clone(),
+                    target: target.clone(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Helper: Write `name`'s manifest into the mirror, byte for byte as synced where
+/// possible, so hashes in the signed index still match it
+fn write_manifest(
+    engine: &RushEngine,
+    dir: &Path,
+    name: &str,
+    manifest: &crate::models::PackageManifest,
+) -> Result<()> {
+    let Some(prefix) = name.chars().next() else {
+        return Ok(());
+    };
+    let relative = format!("packages/{}/{}.toml", prefix, name);
+    let dest = dir.join(&relative);
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match fs::read(engine.registry_dir.join(&relative)) {
+        Ok(content) => fs::write(dest, content)?,
+        // A custom backend may not keep a local copy
+        Err(_) => fs::write(dest, toml::to_string(manifest)?)?,
+    }
+    Ok(())
+}
+
+/// Helper: Make sure the mirror has `artifact`, downloading it unless a copy with the
+/// right checksum is already there. `true` if it was downloaded.
+fn fetch_artifact<F>(
+    engine: &RushEngine,
+    dir: &Path,
+    artifact: &TargetDefinition,
+    on_event: &mut F,
+) -> Result<bool>
+where
+    F: FnMut(ProgressKind),
+{
+    let dest = dir.join(ARTIFACTS_DIR).join(&artifact.sha256);
+    if let Ok(existing) = fs::read(&dest)
+        && util::verify_checksum(&existing, &artifact.sha256).is_ok()
+    {
+        return Ok(false);
+    }
+
+    engine.ensure_online(&artifact.url)?;
+    let content = util::download_url(engine.transport.as_ref(), &artifact.url, on_event)?;
+    on_event(ProgressKind::VerifyingChecksum);
+    util::verify_checksum(&content, &artifact.sha256)?;
+
+    // Written aside and renamed, so an interrupted run never leaves a truncated artifact
+    let partial = dest.with_extension("partial");
+    fs::write(&partial, &content)?;
+    fs::rename(&partial, &dest)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_mirror_installs_offline() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        mock.add_malicious_package("evil", "1.0.0", "evil");
+
+        let engine = mock.engine().unwrap();
+        let mirror_dir = mock.home.join("mirror");
+        let targets = vec![current_target(), "sparc-solaris".to_string()];
+
+        let result = mirror(&engine, &mirror_dir, &targets, |_| {}).unwrap();
+        assert_eq!(result.packages, 2);
+        assert_eq!(result.downloaded, 1);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].package, "evil");
+        assert_eq!(result.unsupported.len(), 2);
+        assert!(mirror_dir.join("packages/t/tool.toml").is_file());
+
+        // A second run only checks what it already has
+        let result = mirror(&engine, &mirror_dir, &targets, |_| {}).unwrap();
+        assert_eq!((result.downloaded, result.reused), (0, 1));
+
+        // With the source gone, the mirror alone is enough to install from, offline
+        fs::remove_dir_all(&mock.registry_source).unwrap();
+        let mut offline = RushEngine::builder()
+            .root(mock.home.join("isolated"))
+            .registry_source(mirror_dir.to_str().unwrap())
+            .offline(true)
+            .build()
+            .unwrap();
+        offline.update_registry(&mut ()).unwrap();
+        let manifest = offline.find_package("tool").unwrap();
+        let target = &manifest.targets[&current_target()];
+        offline
+            .install_package("tool", &manifest.version, target, &mut ())
+            .unwrap();
+        assert!(offline.bin_path.join("tool").exists());
+    }
+
+    #[test]
+    fn test_mirror_retries_a_shared_artifact_that_failed() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        // The same artifact, listed first under a URL that doesn't work
+        let manifest = fs::read_to_string(mock.registry_source.join("packages/t/tool.toml"))
+            .unwrap()
+            .replace("file://", "file:///nonexistent");
+        fs::create_dir_all(mock.registry_source.join("packages/a")).unwrap();
+        fs::write(
+            mock.registry_source.join("packages/a/aa-tool.toml"),
+            manifest,
+        )
+        .unwrap();
+
+        let engine = mock.engine().unwrap();
+        let mirror_dir = mock.home.join("mirror");
+        let result = mirror(&engine, &mirror_dir, &[current_target()], |_| {}).unwrap();
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].package, "aa-tool");
+        assert_eq!(result.downloaded, 1);
+    }
+}
@@ -0,0 +1,311 @@
+//! `rush export --format nix`: the installed set as a Nix expression.
+//!
+//! Each package becomes a derivation that fetches the exact artifact rush installed
+//! (`fetchurl` with the recorded sha256) and copies its binaries and data files into
+//! `$out`, so the same set can be built with Nix alongside or instead of rush. The
+//! expression is a function of `pkgs` returning an attribute set, one attribute per package.
+
+use crate::core::bundle::locked_artifact;
+use crate::core::hooks::shell_quote;
+use crate::core::{Os, RushEngine, Target};
+use crate::models::{
+    ArtifactFormat, ExportResult, InstalledPackage, SkippedExport, TargetDefinition,
+};
+use std::fmt::Write;
+use std::path::Path;
+
+/// The installed packages' artifacts for `target` as a Nix expression. Packages whose
+/// artifact isn't known, or that Nix's stock unpacking can't handle, are left out.
+pub fn export_nix(engine: &RushEngine, target: &str) -> ExportResult {
+    let mut names: Vec<&String> = engine.state.packages.keys().collect();
+    names.sort();
+    let linux = Target::parse(target).is_some_and(|t| t.os == Os::Linux);
+
+    let mut expression = format!(
+        "# Generated by `rush export --format nix` for {}\n{{ pkgs ? import <nixpkgs> {{ }} }}:\n\n{{\n",
+        target
+    );
+    let mut packages = Vec::new();
+    let mut skipped = Vec::new();
+    for name in names {
+        let installed = &engine.state.packages[name];
+        let manifest = engine.find_package(name);
+        let skip = |reason: &str| SkippedExport {
+            package: name.clone(),
+            reason: reason.to_string(),
+        };
+        let Some(artifact) = locked_artifact(installed, manifest.as_ref(), target) else {
+            skipped.push(skip(
+                "its artifact isn't recorded, and the registry no longer has it",
+            ));
+            continue;
+        };
+
+        let derivation = match artifact.format {
+            ArtifactFormat::AppImage => appimage(name, installed, &artifact),
+            ArtifactFormat::Binary => archive(name, installed, &artifact, linux),
+            _ if !unpacks(&artifact.url) => {
+                skipped.push(skip("Nix can't unpack its artifact"));
+                continue;
+            }
+            _ => archive(name, installed, &artifact, linux),
+        };
+        let _ = writeln!(expression, "  {} = {};", attribute(name), derivation);
+        packages.push(name.clone());
+    }
+    expression.push_str("}\n");
+
+    ExportResult {
+        contents: expression,
+        packages,
+        skipped,
+    }
+}
+
+/// Helper: A derivation copying the binaries and data files out of an archive, or a bare
+/// binary as it is
+fn archive(
+    name: &str,
+    installed: &InstalledPackage,
+    artifact: &TargetDefinition,
+    linux: bool,
+) -> String {
+    let bare = artifact.format == ArtifactFormat::Binary;
+    let mut install = String::new();
+    for bin in &installed.binaries {
+        let source = if bare {
+            "$src".to_string()
+        } else {
+            format!("$(find . -type f -name {} -print -quit)", shell_quote(bin))
+        };
+        let _ = writeln!(
+            install,
+            "      install -Dm755 \"{}\" \"$out/bin/\"{}",
+            source,
+            shell_quote(bin)
+        );
+    }
+    for file in &artifact.files {
+        let dest = format!("$out/share/{}", shell_quote(&file.dest));
+        let _ = writeln!(
+            install,
+            "      mkdir -p \"$(dirname {dest})\"\n      cp -r \"$(find . -path {} -print -quit)\" {dest}",
+            shell_quote(&format!("*/{}", file.src)),
+        );
+    }
+
+    let mut inputs = Vec::new();
+    if artifact.url.ends_with(".zip") {
+        inputs.push("pkgs.unzip");
+    }
+    // Prebuilt binaries expect an FHS layout; point them at the Nix store instead
+    if linux {
+        inputs.push("pkgs.autoPatchelfHook");
+    }
+
+    let unpack = if bare {
+        "dontUnpack = true;"
+    } else {
+        "sourceRoot = \".\";"
+    };
+    format!(
+        "pkgs.stdenv.mkDerivation {{\n    pname = {};\n    version = {};\n    src = {};\n    {}\n    nativeBuildInputs = [ {} ];\n    dontConfigure = true;\n    dontBuild = true;\n    installPhase = ''\n      runHook preInstall\n{}      runHook postInstall\n    '';\n  }}",
+        string(name),
+        string(&installed.version),
+        fetchurl(artifact),
+        unpack,
+        inputs.join(" "),
+        escape_indented(&install)
+    )
+}
+
+/// Helper: An AppImage, wrapped to run from the store
+fn appimage(name: &str, installed: &InstalledPackage, artifact: &TargetDefinition) -> String {
+    // wrapType2 names the wrapper $out/bin/<pname>, so that is the installed binary's name
+    let bin = installed.binaries.first().map_or(name, String::as_str);
+    format!(
+        "pkgs.appimageTools.wrapType2 {{\n    pname = {};\n    version = {};\n    src = {};\n  }}",
+        string(bin),
+        string(&installed.version),
+        fetchurl(artifact)
+    )
+}
+
+/// Helper: `pkgs.fetchurl` of the artifact, pinned to its hash (Nix takes it in hex)
+fn fetchurl(artifact: &TargetDefinition) -> String {
+    format!(
+        "pkgs.fetchurl {{\n      url = {};\n      sha256 = {};\n    }}",
+        string(&artifact.url),
+        string(&artifact.sha256)
+    )
+}
+
+/// Helper: Archives stdenv unpacks by itself (or with unzip). A tarball nested in a
+/// zip, or a .deb or .rpm, would need more than a copy.
+fn unpacks(url: &str) -> bool {
+    let file = Path::new(url)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(url);
+    [".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".zip"]
+        .iter()
+        .any(|ext| file.ends_with(ext))
+}
+
+/// Helper: A Nix string literal
+fn string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace("${", "\\${")
+    )
+}
+
+/// Helper: `value` made safe inside a `'' ... ''` string, where `''` and `${` are special
+fn escape_indented(value: &str) -> String {
+    value.replace("''", "'''").replace("${", "''${")
+}
+
+/// Helper: An attribute name, quoted unless it is a plain Nix identifier
+fn attribute(name: &str) -> String {
+    let identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '\''));
+    if identifier {
+        name.to_string()
+    } else {
+        string(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DataFile, PackageManifest};
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    fn target(url: &str, format: ArtifactFormat) -> TargetDefinition {
+        TargetDefinition {
+            url: url.to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: "ab".repeat(32),
+            checksum_url: None,
+            size: None,
+            format,
+            extract: false,
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(string("a\"b${c}"), r#""a\"b\${c}""#);
+        assert_eq!(escape_indented("it''s ${x}"), "it'''s ''${x}");
+        assert_eq!(attribute("ripgrep"), "ripgrep");
+        assert_eq!(attribute("fd-find"), "fd-find");
+        assert_eq!(attribute("7zip"), "\"7zip\"");
+        assert_eq!(attribute("dotnet.sdk"), "\"dotnet.sdk\"");
+        assert!(unpacks("https://x/tool-1.0-x86_64-linux.tar.gz"));
+        assert!(unpacks("https://x/tool.zip"));
+        assert!(!unpacks("https://x/tool_1.0_amd64.deb"));
+    }
+
+    #[test]
+    fn test_export_nix() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let manifest = |target: TargetDefinition| PackageManifest {
+            version: "1.0.0".to_string(),
+            description: None,
+            source: None,
+            license: None,
+            homepage: None,
+            updated: None,
+            check: None,
+            osv: None,
+            requires: Vec::new(),
+            dependencies: Vec::new(),
+            targets: BTreeMap::from([("x86_64-linux".to_string(), target)]),
+            history: Vec::new(),
+        };
+        let mut tool = target("https://example.com/tool.zip", ArtifactFormat::Archive);
+        tool.files = vec![DataFile {
+            src: "man".to_string(),
+            dest: "man/man1".to_string(),
+        }];
+        let packages = [
+            ("tool", tool),
+            (
+                "viewer",
+                target(
+                    "https://example.com/Viewer.AppImage",
+                    ArtifactFormat::AppImage,
+                ),
+            ),
+            (
+                "debonly",
+                target("https://example.com/debonly.deb", ArtifactFormat::Archive),
+            ),
+            (
+                "bare",
+                target("https://example.com/bare-linux", ArtifactFormat::Binary),
+            ),
+        ];
+        for (name, target) in packages {
+            let dir = engine.registry_dir.join("packages").join(&name[..1]);
+            std::fs::create_dir_all(&dir).unwrap();
+            let manifest = toml::to_string(&manifest(target)).unwrap();
+            std::fs::write(dir.join(format!("{}.toml", name)), manifest).unwrap();
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: "1.0.0".to_string(),
+                    binaries: vec![name.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+        engine.state.packages.insert(
+            "gone".to_string(),
+            InstalledPackage {
+                version: "0.1.0".to_string(),
+                binaries: vec!["gone".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+
+        let export = export_nix(&engine, "x86_64-linux");
+        assert_eq!(export.packages, ["bare", "tool", "viewer"]);
+        let skipped: Vec<&str> = export.skipped.iter().map(|s| s.package.as_str()).collect();
+        assert_eq!(skipped, ["debonly", "gone"]);
+
+        let nix = &export.contents;
+        assert!(nix.starts_with("# Generated by `rush export --format nix` for x86_64-linux\n"));
+        assert!(nix.contains("  tool = pkgs.stdenv.mkDerivation {\n"));
+        assert!(nix.contains(&format!("sha256 = \"{}\";", "ab".repeat(32))));
+        assert!(nix.contains("nativeBuildInputs = [ pkgs.unzip pkgs.autoPatchelfHook ];"));
+        assert!(nix.contains(
+            "install -Dm755 \"$(find . -type f -name 'tool' -print -quit)\" \"$out/bin/\"'tool'"
+        ));
+        assert!(
+            nix.contains("cp -r \"$(find . -path '*/man' -print -quit)\" $out/share/'man/man1'")
+        );
+        assert!(nix.contains("    dontUnpack = true;\n"));
+        assert!(nix.contains("install -Dm755 \"$src\" \"$out/bin/\"'bare'"));
+        assert!(nix.contains("  viewer = pkgs.appimageTools.wrapType2 {\n    pname = \"viewer\";"));
+        assert!(nix.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,362 @@
+//! Async front end for `RushEngine` (behind the `async` feature).
+//!
+//! This is a wrapper around the blocking engine, not the other way round: the blocking
+//! `RushEngine` stays the source of truth for all logic, and this type only replaces how
+//! bytes are fetched. Network I/O runs on async reqwest; the disk work (extracting,
+//! copying the registry, saving state) is handed to tokio's blocking pool so it never
+//! stalls the executor.
+//!
+//! Calls are serialized: every handle shares one engine behind one mutex, so only one
+//! call's engine work runs at a time, and the others wait for it on the blocking pool.
+//! Only the downloads themselves overlap.
+
+use crate::core::{
+    RushEngine, RushEngineBuilder, hooks, install, pins, registry, transport, update, util,
+};
+use crate::models::{
+    HookEvent, HookPoint, InstallResult, PackageManifest, ProgressEvent, ProgressKind, Reporter,
+    State, StateChange, SubscriptionId, TargetDefinition, UninstallResult, UpdateResult,
+};
+use anyhow::{Context, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// An async handle to a `RushEngine`. Cheap to clone; clones share the engine, and
+/// take turns using it.
+#[derive(Clone)]
+pub struct AsyncRushEngine {
+    engine: Arc<Mutex<RushEngine>>,
+    client: reqwest::Client,
+}
+
+impl AsyncRushEngine {
+    /// Standard constructor: reads HOME and env vars, like `RushEngine::new()`
+    pub async fn new() -> Result<Self> {
+        let engine = tokio::task::spawn_blocking(RushEngine::new).await??;
+        Self::from_engine(engine)
+    }
+
+    /// Build the engine from a configured `RushEngine::builder()`
+    pub async fn from_builder(builder: RushEngineBuilder) -> Result<Self> {
+        let engine = tokio::task::spawn_blocking(move || builder.build()).await??;
+        Self::from_engine(engine)
+    }
+
+    /// Wrap an engine that was built elsewhere
+    pub fn from_engine(engine: RushEngine) -> Result<Self> {
+        // Pooled like the blocking client, one pool for every download this engine makes
+        let http = &engine.config.http;
+        let mut builder = reqwest::Client::builder()
+            .user_agent(concat!("rush/", env!("CARGO_PKG_VERSION")))
+            .pool_max_idle_per_host(http.max_idle_per_host())
+            .pool_idle_timeout(http.idle_timeout())
+            .tcp_keepalive(transport::TCP_KEEPALIVE);
+        if http.http1_only {
+            builder = builder.http1_only();
+        }
+        let client = builder.build()?;
+
+        Ok(Self {
+            engine: Arc::new(Mutex::new(engine)),
+            client,
+        })
+    }
+
+    /// Run any blocking `RushEngine` call on the blocking pool, inside the caller's span,
+    /// once no other call is using the engine
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut RushEngine) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let engine = self.engine.clone();
+        let span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
+            let mut engine = engine
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Engine poisoned by an earlier panic"))?;
+            f(&mut engine)
+        })
+        .await
+        .context("Engine task panicked")?
+    }
+
+    /// Download and install a package, running the configured install hooks around it.
+    /// The reporter moves to whichever thread is doing the work, hence owned and `Send`.
+    #[tracing::instrument(skip_all, fields(package = name, version, url = %target.url))]
+    pub async fn install_package<R>(
+        &self,
+        name: &str,
+        version: &str,
+        target: &TargetDefinition,
+        reporter: R,
+    ) -> Result<InstallResult>
+    where
+        R: Reporter + Send + 'static,
+    {
+        let reporter = SharedReporter(Arc::new(Mutex::new(reporter)));
+
+        let pre_target = target.clone();
+        let (pre_name, pre_version, mut pre_reporter) =
+            (name.to_string(), version.to_string(), reporter.clone());
+        let (cancel, local) = self
+            .run(move |engine| {
+                engine.cancel.check()?;
+                let local = install::local_artifact(engine, &pre_target);
+                if local.is_none() {
+                    engine.ensure_online(&pre_target.url)?;
+                }
+                hooks::run_hook(
+                    engine,
+                    HookPoint::PreInstall,
+                    &pre_name,
+                    &pre_version,
+                    |e| pre_reporter.hook(e),
+                )?;
+                Ok((engine.cancel.clone(), local))
+            })
+            .await?;
+
+        let mut progress_reporter = reporter.clone();
+        let mut on_event = util::tag_progress(Some(name), move |e| progress_reporter.progress(e));
+        let (content, download_time) = match local {
+            Some(content) => (content, None),
+            None => {
+                let started = Instant::now();
+                let content =
+                    util::download_url_async(&self.client, &target.url, &cancel, &mut on_event)
+                        .await?;
+                (content, Some(started.elapsed()))
+            }
+        };
+
+        let (name, version, target) = (name.to_string(), version.to_string(), target.clone());
+        let mut reporter = reporter;
+        self.run(move |engine| {
+            let result = install::install_from_bytes(
+                engine,
+                &name,
+                &version,
+                &target,
+                &content,
+                download_time,
+                on_event,
+            )?;
+            hooks::run_hook(engine, HookPoint::PostInstall, &name, &version, |e| {
+                reporter.hook(e)
+            })?;
+            Ok(result)
+        })
+        .await
+    }
+
+    /// Uninstall a package, running the configured uninstall hooks around it.
+    pub async fn uninstall_package<R>(
+        &self,
+        name: &str,
+        reporter: R,
+    ) -> Result<Option<UninstallResult>>
+    where
+        R: Reporter + Send + 'static,
+    {
+        let name = name.to_string();
+        let mut reporter = reporter;
+        self.run(move |engine| engine.uninstall_package(&name, &mut reporter))
+            .await
+    }
+
+    /// Download the registry from the internet OR copy it from a local directory
+    #[tracing::instrument(skip_all, fields(source))]
+    pub async fn update_registry<R>(&self, reporter: R) -> Result<UpdateResult>
+    where
+        R: Reporter + Send + 'static,
+    {
+        let (source, archive_url, cancel) = self
+            .run(|engine| {
+                engine.cancel.check()?;
+                let archive_url = engine.registry.archive_url().map(str::to_string);
+                Ok((
+                    engine.registry.source().to_string(),
+                    archive_url,
+                    engine.cancel.clone(),
+                ))
+            })
+            .await?;
+        tracing::Span::current().record("source", source.as_str());
+        let mut reporter = reporter;
+        let mut on_event = util::tag_progress(None, move |e| reporter.progress(e));
+        on_event(ProgressKind::FetchingRegistry {
+            source: source.clone(),
+        });
+
+        let Some(url) = archive_url else {
+            // Local directories, git, ...: the backend's own sync, off the executor
+            let hash_changes = self
+                .run(move |engine| {
+                    if engine.registry.is_remote() {
+                        engine.ensure_online(engine.registry.source())?;
+                    }
+                    engine.registry.sync(
+                        &engine.registry_dir,
+                        engine.transport.as_ref(),
+                        &mut on_event,
+                    )?;
+                    pins::observe_registry(engine)
+                })
+                .await?;
+            return Ok(UpdateResult {
+                source,
+                hash_changes,
+            });
+        };
+
+        // Download before wiping, so a failed fetch keeps the old registry usable
+        let online_url = url.clone();
+        self.run(move |engine| engine.ensure_online(&online_url))
+            .await?;
+        let content = util::download_url_async(&self.client, &url, &cancel, &mut on_event).await?;
+
+        let hash_changes = self
+            .run(move |engine| {
+                engine.cancel.check()?;
+                update::verify_archive(engine, &url, &content)?;
+                on_event(ProgressKind::Unpacking);
+                registry::reset_dir(&engine.registry_dir)?;
+                registry::unpack_packages(&content, &engine.registry_dir)?;
+                pins::observe_registry(engine)
+            })
+            .await?;
+
+        Ok(UpdateResult {
+            source,
+            hash_changes,
+        })
+    }
+
+    /// Look up a specific package in the installed registry
+    pub async fn find_package(&self, name: &str) -> Result<Option<PackageManifest>> {
+        let name = name.to_string();
+        self.run(move |engine| Ok(engine.find_package(&name))).await
+    }
+
+    /// All packages in the installed registry
+    pub async fn list_available_packages(&self) -> Result<Vec<(String, PackageManifest)>> {
+        self.run(|engine| Ok(engine.list_available_packages()))
+            .await
+    }
+
+    /// Every package name in the installed registry, without parsing any manifests
+    pub async fn package_names(&self) -> Result<Vec<String>> {
+        self.run(|engine| Ok(engine.package_names())).await
+    }
+
+    /// Call `callback` for every package installed, removed, or upgraded from now on
+    /// (it runs on whichever thread made the change)
+    pub async fn subscribe<F>(&self, callback: F) -> Result<SubscriptionId>
+    where
+        F: Fn(&StateChange) + Send + Sync + 'static,
+    {
+        self.run(move |engine| Ok(engine.subscribe(callback))).await
+    }
+
+    /// A snapshot of what is installed
+    pub async fn state(&self) -> Result<State> {
+        self.run(|engine| Ok(engine.state.clone())).await
+    }
+}
+
+/// One reporter fed from several blocking tasks in turn
+struct SharedReporter<R>(Arc<Mutex<R>>);
+
+impl<R> Clone for SharedReporter<R> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<R: Reporter> SharedReporter<R> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, R> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<R: Reporter> Reporter for SharedReporter<R> {
+    fn progress(&mut self, event: ProgressEvent) {
+        self.lock().progress(event)
+    }
+
+    fn hook(&mut self, event: HookEvent) {
+        self.lock().hook(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::tar_gz;
+    use sha2::{Digest, Sha256};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_async_update_install_uninstall() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source");
+        let artifact = temp_dir.path().join("tool.tar.gz");
+        let archive = tar_gz(&[("tool", 0o755, b"#!sh")]);
+        std::fs::write(&artifact, &archive).unwrap();
+
+        std::fs::create_dir_all(source.join("packages/t")).unwrap();
+        std::fs::write(
+            source.join("packages/t/tool.toml"),
+            format!(
+                "version = \"1.0.0\"\n[targets.x86_64-linux]\nurl = \"file://{}\"\nbin = \"tool\"\nsha256 = \"{}\"\n",
+                artifact.display(),
+                hex::encode(Sha256::digest(&archive))
+            ),
+        )
+        .unwrap();
+
+        let engine = AsyncRushEngine::from_builder(
+            RushEngine::builder()
+                .root(temp_dir.path().join("root"))
+                .registry_source(source.to_string_lossy()),
+        )
+        .await
+        .unwrap();
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        engine
+            .subscribe(move |change| sink.lock().unwrap().push(change.clone()))
+            .await
+            .unwrap();
+
+        engine.update_registry(()).await.unwrap();
+        let manifest = engine.find_package("tool").await.unwrap().unwrap();
+        assert_eq!(engine.list_available_packages().await.unwrap().len(), 1);
+        assert_eq!(engine.package_names().await.unwrap(), ["tool"]);
+
+        let target = &manifest.targets["x86_64-linux"];
+        let result = engine
+            .install_package("tool", &manifest.version, target, ())
+            .await
+            .unwrap();
+        assert!(result.path.exists());
+        assert!(engine.state().await.unwrap().packages.contains_key("tool"));
+
+        engine.uninstall_package("tool", ()).await.unwrap().unwrap();
+        assert!(!result.path.exists());
+        assert_eq!(changes.lock().unwrap().len(), 2);
+
+        // Bad checksums are still caught
+        let mut tampered = target.clone();
+        tampered.sha256 = "0".repeat(64);
+        assert!(
+            engine
+                .install_package("tool", "1.0.0", &tampered, ())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Replacing a binary that is running. Unix lets a running executable be renamed over,
+//! but Windows, and some network filesystems, refuse. Rather than fail the install, the
+//! new binary waits next to the old one as `<bin>.new`, and the next rush run swaps it in.
+
+use super::lock;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// Appended to a binary's name while it waits to replace a running copy
+pub(crate) const PENDING_SUFFIX: &str = ".new";
+
+/// Appended to a binary moved out of the way on Windows, until it is no longer running
+const OLD_SUFFIX: &str = ".old";
+
+/// Where the new version of `dest` waits while `dest` is in use
+pub(crate) fn pending_path(dest: &Path) -> PathBuf {
+    with_suffix(dest, PENDING_SUFFIX)
+}
+
+/// Move `temp_file` to `dest`, or to its pending path if `dest` is a running program that
+/// can't be replaced. Returns where it went.
+pub(crate) fn persist(temp_file: NamedTempFile, dest: &Path) -> Result<PathBuf> {
+    let pending = pending_path(dest);
+    match temp_file.persist(dest) {
+        Ok(_) => {
+            // An older pending version would undo this one on the next run
+            let _ = fs::remove_file(&pending);
+            Ok(dest.to_path_buf())
+        }
+        Err(e) if in_use(&e.error) => {
+            tracing::warn!(path = %dest.display(), error = %e.error, "in use; the new version waits as .new");
+            e.file.persist(&pending)?;
+            Ok(pending)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Swap in the binaries an earlier run left pending, now that they may have stopped.
+/// Only `binaries` (the names state says are rush's) are looked at; one still in use stays
+/// pending. Returns the names swapped in.
+pub(crate) fn finish_pending<'a>(
+    bin_dir: &Path,
+    binaries: impl IntoIterator<Item = &'a String>,
+) -> Vec<String> {
+    let mut finished = Vec::new();
+    for bin in binaries {
+        let dest = bin_dir.join(bin);
+        let _ = fs::remove_file(with_suffix(&dest, OLD_SUFFIX));
+
+        let pending = pending_path(&dest);
+        if !pending.is_file() {
+            continue;
+        }
+        // Not while another rush is installing it
+        let _lock = lock::lock_binary(bin_dir, bin);
+        match swap(&pending, &dest) {
+            Ok(()) => {
+                tracing::info!(bin = %bin, "swapped in the version that was waiting");
+                finished.push(bin.clone());
+            }
+            Err(e) => tracing::debug!(bin = %bin, error = %e, "still in use"),
+        }
+    }
+    finished
+}
+
+/// Delete `bin` from `bin_dir` along with the `.new` copy waiting to replace it and the
+/// `.old` one it replaced. Returns whether `bin` itself was there.
+pub(crate) fn remove_binary(bin_dir: &Path, bin: &str) -> Result<bool> {
+    let dest = bin_dir.join(bin);
+    let existed = dest.exists();
+    if existed {
+        fs::remove_file(&dest)?;
+    }
+    if let Err(e) = fs::remove_file(pending_path(&dest))
+        && e.kind() != ErrorKind::NotFound
+    {
+        return Err(e.into());
+    }
+    // Still running on Windows, it goes when `clean --all` next finds it orphaned
+    let _ = fs::remove_file(with_suffix(&dest, OLD_SUFFIX));
+    Ok(existed)
+}
+
+/// The `.new` and `.old` copies in `bin_dir` beside binaries that aren't in `binaries`
+/// (every installed package's), sorted
+pub(crate) fn orphaned(bin_dir: &Path, binaries: &HashSet<&str>) -> Vec<PathBuf> {
+    let mut orphans: Vec<PathBuf> = fs::read_dir(bin_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                return false;
+            };
+            let bin = name
+                .strip_suffix(PENDING_SUFFIX)
+                .or_else(|| name.strip_suffix(OLD_SUFFIX));
+            bin.is_some_and(|bin| !bin.is_empty() && !binaries.contains(bin)) && path.is_file()
+        })
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Rename `new` over `exe`. A running binary can be renamed over on Unix (the running
+/// process keeps the old file); Windows only lets it be renamed away first, and the old
+/// copy is left to be removed on a later run.
+pub(crate) fn swap(new: &Path, exe: &Path) -> Result<()> {
+    #[cfg(windows)]
+    {
+        let old = with_suffix(exe, OLD_SUFFIX);
+        let _ = fs::remove_file(&old);
+        if exe.exists() {
+            fs::rename(exe, &old)?;
+        }
+        if let Err(e) = fs::rename(new, exe) {
+            let _ = fs::rename(&old, exe);
+            return Err(e.into());
+        }
+    }
+    #[cfg(not(windows))]
+    fs::rename(new, exe)?;
+    Ok(())
+}
+
+/// Helper: Whether a failed rename was refused because the file is a running program
+/// (`ETXTBSY`, `EBUSY`; on Windows, a sharing violation). Access denied isn't counted: it
+/// is as likely a permissions problem, which waiting wouldn't fix.
+fn in_use(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::ExecutableFileBusy | ErrorKind::ResourceBusy
+    ) || (cfg!(windows) && e.raw_os_error() == Some(32))
+}
+
+/// Helper: `path` with `suffix` added to its file name ("rg" -> "rg.new")
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_in_use() {
+        assert!(in_use(&ErrorKind::ExecutableFileBusy.into()));
+        assert!(in_use(&ErrorKind::ResourceBusy.into()));
+        assert!(!in_use(&ErrorKind::NotFound.into()));
+        // Access denied (ERROR_ACCESS_DENIED) is a permissions problem everywhere
+        assert!(!in_use(&io::Error::from_raw_os_error(5)));
+    }
+
+    #[test]
+    fn test_persist_clears_an_older_pending_version() {
+        let temp_dir = tempdir().unwrap();
+        let dest = temp_dir.path().join("tool");
+        fs::write(pending_path(&dest), "v1").unwrap();
+
+        let mut temp_file = NamedTempFile::new_in(temp_dir.path()).unwrap();
+        io::Write::write_all(&mut temp_file, b"v2").unwrap();
+        assert_eq!(persist(temp_file, &dest).unwrap(), dest);
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "v2");
+        assert!(!pending_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_finish_pending() {
+        let temp_dir = tempdir().unwrap();
+        let bin_dir = temp_dir.path();
+        fs::write(bin_dir.join("tool"), "v1").unwrap();
+        fs::write(bin_dir.join("tool.new"), "v2").unwrap();
+        fs::write(bin_dir.join("tool.old"), "v0").unwrap();
+        // Not rush's, so not touched
+        fs::write(bin_dir.join("mine.new"), "mine").unwrap();
+
+        let binaries = ["tool".to_string(), "other".to_string()];
+        assert_eq!(finish_pending(bin_dir, &binaries), ["tool"]);
+        assert_eq!(fs::read_to_string(bin_dir.join("tool")).unwrap(), "v2");
+        assert!(!bin_dir.join("tool.new").exists());
+        assert!(!bin_dir.join("tool.old").exists());
+        assert!(bin_dir.join("mine.new").exists());
+
+        // Nothing left to do
+        assert!(finish_pending(bin_dir, &binaries).is_empty());
+    }
+
+    #[test]
+    fn test_remove_binary_and_orphans() {
+        let temp_dir = tempdir().unwrap();
+        let bin_dir = temp_dir.path();
+        for name in [
+            "tool", "tool.new", "tool.old", "gone.new", "gone.old", "kept.new",
+        ] {
+            fs::write(bin_dir.join(name), name).unwrap();
+        }
+
+        assert!(remove_binary(bin_dir, "tool").unwrap());
+        for name in ["tool", "tool.new", "tool.old"] {
+            assert!(!bin_dir.join(name).exists(), "{}", name);
+        }
+        assert!(!remove_binary(bin_dir, "tool").unwrap());
+
+        // A pending copy of an installed binary is still to be swapped in
+        let binaries = HashSet::from(["kept"]);
+        assert_eq!(
+            orphaned(bin_dir, &binaries),
+            [bin_dir.join("gone.new"), bin_dir.join("gone.old")]
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Every artifact hash rush has seen, so a release re-published with different bytes
+//! doesn't slip through.
+//!
+//! Each (package, version, target) is pinned to the sha256 it had the first time a
+//! registry update or an install saw it. A registry that later lists another hash for it,
+//! whatever its URL, is reported by `update_registry`, and installing it is refused
+//! (`Error::HashChanged`) until the change is accepted with `accept_hash_changes`.
+
+use crate::Error;
+use crate::core::{RushEngine, query, target};
+use crate::models::{HashChange, PackageManifest, TargetDefinition};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Name of the pin file in the state dir
+pub(crate) const KNOWN_HASHES_FILE: &str = "known-hashes.json";
+
+/// package -> version -> target -> sha256. Pin files from before targets were the key
+/// have artifact URLs there instead; `pin` moves those over as it meets them.
+type KnownHashes = BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>>;
+
+/// Pin every artifact in the synced registry that hasn't been seen before, and return the
+/// ones whose hash differs from their pin. Those keep their old pin.
+/// Only manifests in the local copy are looked at; a sparse registry's are pinned as
+/// they are installed.
+pub(crate) fn observe_registry(engine: &RushEngine) -> Result<Vec<HashChange>> {
+    let mut known = load(&engine.known_hashes_path)?;
+    let mut changes = Vec::new();
+    let mut pinned = false;
+
+    for name in query::scan_names(&engine.registry_dir) {
+        let Some(manifest) = query::read_manifest(&engine.registry_dir, &name) else {
+            continue;
+        };
+        for (target, artifact) in &manifest.targets {
+            match pin(&mut known, &name, &manifest.version, target, artifact) {
+                Some(previous) => changes.push(HashChange {
+                    package: name.clone(),
+                    version: manifest.version.clone(),
+                    target: target.clone(),
+                    url: artifact.url.clone(),
+                    known: previous,
+                    now: artifact.sha256.clone(),
+                }),
+                None => pinned = true,
+            }
+        }
+    }
+
+    if pinned {
+        save(&engine.known_hashes_path, &known)?;
+    }
+    Ok(changes)
+}
+
+/// Refuse `artifact` (for the engine's target) if it is pinned to another hash; otherwise
+/// pin it
+pub(crate) fn check_install(
+    engine: &RushEngine,
+    name: &str,
+    version: &str,
+    artifact: &TargetDefinition,
+) -> Result<()> {
+    let mut known = load(&engine.known_hashes_path)?;
+    if let Some(previous) = pin(&mut known, name, version, engine.target(), artifact) {
+        return Err(Error::HashChanged {
+            package: name.to_string(),
+            version: version.to_string(),
+            known: previous,
+            now: artifact.sha256.clone(),
+        }
+        .into());
+    }
+    save(&engine.known_hashes_path, &known)
+}
+
+/// Re-pin every artifact of `manifest` (the registry's current `name`) to the hash it
+/// has now, returning the pins that changed
+pub(crate) fn accept(
+    engine: &RushEngine,
+    name: &str,
+    manifest: &PackageManifest,
+) -> Result<Vec<HashChange>> {
+    let mut known = load(&engine.known_hashes_path)?;
+    let mut accepted = Vec::new();
+
+    for (target, artifact) in &manifest.targets {
+        let targets = known
+            .entry(name.to_string())
+            .or_default()
+            .entry(manifest.version.clone())
+            .or_default();
+        let previous = targets
+            .insert(target.clone(), artifact.sha256.clone())
+            .or_else(|| targets.remove(&artifact.url));
+        if let Some(previous) = previous.filter(|p| *p != artifact.sha256) {
+            accepted.push(HashChange {
+                package: name.to_string(),
+                version: manifest.version.clone(),
+                target: target.clone(),
+                url: artifact.url.clone(),
+                known: previous,
+                now: artifact.sha256.clone(),
+            });
+        }
+    }
+
+    save(&engine.known_hashes_path, &known)?;
+    Ok(accepted)
+}
+
+/// Helper: Pin `artifact` (the `target` build of `name` `version`) if it is new. If it is
+/// pinned to another hash, leave the pin and return the pinned hash: a new URL doesn't
+/// make it a new artifact.
+fn pin(
+    known: &mut KnownHashes,
+    name: &str,
+    version: &str,
+    target: &str,
+    artifact: &TargetDefinition,
+) -> Option<String> {
+    let targets = known
+        .entry(name.to_string())
+        .or_default()
+        .entry(version.to_string())
+        .or_default();
+    // The host's key finds a pin made under the manifest's (e.g. "x86_64-linux-gnu")
+    let target =
+        target::lookup(targets, target).map_or_else(|| target.to_string(), |(key, _)| key.clone());
+    // A pin from an older pin file, keyed by the URL
+    if !targets.contains_key(&target)
+        && let Some(sha256) = targets.remove(&artifact.url)
+    {
+        targets.insert(target.clone(), sha256);
+    }
+    match targets.get(&target) {
+        Some(sha256) if !sha256.eq_ignore_ascii_case(&artifact.sha256) => Some(sha256.clone()),
+        Some(_) => None,
+        None => {
+            targets.insert(target, artifact.sha256.clone());
+            None
+        }
+    }
+}
+
+/// Helper: The pins at `path`; none if the file doesn't exist yet. An unreadable file is
+/// an error rather than a fresh start, which would silently forget every pin.
+fn load(path: &Path) -> Result<KnownHashes> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Corrupt hash pin file {:?}", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(KnownHashes::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Helper: Write the pins to `path`, atomically
+fn save(path: &Path, known: &KnownHashes) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(known)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_republished_artifact_needs_approval() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+
+        let mut engine = mock.engine().unwrap();
+        assert!(
+            engine
+                .update_registry(&mut ())
+                .unwrap()
+                .hash_changes
+                .is_empty()
+        );
+        let original = engine.find_package("tool").unwrap();
+
+        // Same version, same URL, different bytes
+        mock.add_malicious_package("tool", "1.0.0", "tool");
+        let result = engine.update_registry(&mut ()).unwrap();
+        let target = current_target();
+        assert_eq!(result.hash_changes.len(), 1);
+        let change = &result.hash_changes[0];
+        assert_eq!(change.package, "tool");
+        assert_eq!(change.target, target);
+        assert_eq!(change.known, original.targets[&target].sha256);
+
+        // Still flagged on the next update, and installs are refused
+        let result = engine.update_registry(&mut ()).unwrap();
+        assert_eq!(result.hash_changes.len(), 1);
+        let republished = engine.find_package("tool").unwrap();
+        let err = engine
+            .install_package("tool", "1.0.0", &republished.targets[&target], &mut ())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::HashChanged { package, .. }) if package == "tool"
+        ));
+
+        let accepted = engine.accept_hash_changes(&["tool".to_string()]).unwrap();
+        assert_eq!(accepted.len(), 1);
+        assert!(
+            engine
+                .update_registry(&mut ())
+                .unwrap()
+                .hash_changes
+                .is_empty()
+        );
+        // Accepted, the install goes ahead (and here fails on the bad checksum instead)
+        let err = engine
+            .install_package("tool", "1.0.0", &republished.targets[&target], &mut ())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_new_url_and_hash_for_known_version_is_flagged() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let original = engine.find_package("tool").unwrap();
+
+        // Same version, moved to another URL with other bytes
+        mock.add_package("tool", "1.0.1", "tool");
+        let path = mock.registry_source.join("packages/t/tool.toml");
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.replace("\"1.0.1\"", "\"1.0.0\"")).unwrap();
+
+        let result = engine.update_registry(&mut ()).unwrap();
+        let target = current_target();
+        assert_eq!(result.hash_changes.len(), 1);
+        let change = &result.hash_changes[0];
+        assert!(change.url.ends_with("tool-1.0.1.tar.gz"));
+        assert_eq!(change.known, original.targets[&target].sha256);
+
+        let moved = engine.find_package("tool").unwrap();
+        assert_ne!(moved.targets[&target].url, original.targets[&target].url);
+        let err = engine
+            .install_package("tool", "1.0.0", &moved.targets[&target], &mut ())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::HashChanged { .. })
+        ));
+    }
+
+    #[test]
+    fn test_url_keyed_pins_carry_over() {
+        let mut known = KnownHashes::new();
+        let artifact = TargetDefinition {
+            url: "https://example.com/tool.tar.gz".to_string(),
+            bin: vec!["tool".to_string()],
+            sha256: "b".repeat(64),
+            checksum_url: None,
+            size: None,
+            format: crate::models::ArtifactFormat::Archive,
+            extract: false,
+            files: Vec::new(),
+        };
+        known
+            .entry("tool".to_string())
+            .or_default()
+            .entry("1.0.0".to_string())
+            .or_default()
+            .insert(artifact.url.clone(), "a".repeat(64));
+
+        let previous = pin(&mut known, "tool", "1.0.0", "x86_64-linux", &artifact);
+        assert_eq!(previous, Some("a".repeat(64)));
+        assert_eq!(
+            known["tool"]["1.0.0"].keys().collect::<Vec<_>>(),
+            ["x86_64-linux"]
+        );
+    }
+}
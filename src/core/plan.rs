@@ -0,0 +1,1073 @@
+//! Working out what an install or upgrade would do, separately from doing it.
+//!
+//! `resolve` only reads: the state, the registry, and (online) the advertised size of each
+//! download. Nothing in the bin dir is touched until `apply` runs the plan's steps, with
+//! the same hooks and checks as `RushEngine::install_package`, so a plan can be shown,
+//! confirmed, or saved first.
+
+use crate::Error;
+use crate::core::constraint::Constraint;
+use crate::core::requires::compare_versions;
+use crate::core::{RushEngine, Target, datafiles, dependencies, hooks, install, util};
+use crate::models::{
+    Conflict, HookPoint, InstallOutcome, OperationId, PackageManifest, Plan, PlannedInstall,
+    ProgressEvent, Reporter, ResolveRequest, SkipReason, SkippedPackage, TargetDefinition,
+};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+/// Compute the plan for `request` against the installed state and the registry.
+/// Packages it needs (`dependencies`) that aren't installed are planned as steps of their
+/// own, before the package that needs them.
+#[tracing::instrument(level = "debug", skip(engine))]
+pub fn resolve(engine: &RushEngine, request: &ResolveRequest) -> Plan {
+    let (names, target, upgrading) = match request {
+        ResolveRequest::Install { packages, target } => (packages.clone(), target, false),
+        ResolveRequest::Upgrade { packages, target } if packages.is_empty() => {
+            let mut installed: Vec<String> = engine.state.packages.keys().cloned().collect();
+            installed.sort();
+            (installed, target, true)
+        }
+        ResolveRequest::Upgrade { packages, target } => (packages.clone(), target, true),
+    };
+
+    let mut resolver = Resolver::new(engine, target);
+    for name in names {
+        let installed = engine.state.packages.get(&name).map(|p| p.version.clone());
+        if upgrading && installed.is_none() {
+            resolver
+                .plan
+                .conflicts
+                .push(Conflict::NotInstalled { package: name });
+            continue;
+        }
+        if let (false, Some(version)) = (upgrading, &installed) {
+            resolver.plan.skipped.push(SkippedPackage {
+                package: name,
+                reason: SkipReason::AlreadyInstalled {
+                    version: version.clone(),
+                },
+            });
+            continue;
+        }
+        // Already planned, as a dependency of a package before it
+        if let Some(step) = resolver.plan.steps.iter_mut().find(|s| s.package == name) {
+            step.dependency = false;
+            continue;
+        }
+
+        let Some(manifest) = engine.find_package(&name) else {
+            resolver
+                .plan
+                .conflicts
+                .push(Conflict::NotFound { package: name });
+            continue;
+        };
+        // A pinned package stays where it is
+        let pinned = engine.state.packages.get(&name).is_some_and(|p| p.pinned);
+        if let (true, true, Some(version)) = (upgrading, pinned, &installed) {
+            let reason = if *version == manifest.version {
+                SkipReason::UpToDate {
+                    version: version.clone(),
+                }
+            } else {
+                SkipReason::Pinned {
+                    latest: manifest.version.clone(),
+                }
+            };
+            resolver.plan.skipped.push(SkippedPackage {
+                package: name,
+                reason,
+            });
+            continue;
+        }
+        // A constrained package moves to the newest release its constraint allows
+        let constraint = engine
+            .state
+            .packages
+            .get(&name)
+            .and_then(|p| p.constraint.clone())
+            .filter(|_| upgrading);
+        let release = match (&constraint, &installed) {
+            (Some(constraint), Some(installed)) => {
+                match allowed_release(&manifest, constraint, installed) {
+                    Ok(release) => release,
+                    Err(e) => {
+                        resolver.plan.skipped.push(SkippedPackage {
+                            package: name,
+                            reason: SkipReason::BadConstraint {
+                                constraint: constraint.clone(),
+                                error: format!("{:#}", e),
+                            },
+                        });
+                        continue;
+                    }
+                }
+            }
+            _ => Some((manifest.version.as_str(), &manifest.targets)),
+        };
+        let Some((version, targets)) =
+            release.filter(|(version, _)| installed.as_deref() != Some(*version))
+        else {
+            let reason = match (constraint, installed) {
+                (Some(constraint), Some(version)) if version != manifest.version => {
+                    SkipReason::Held {
+                        constraint,
+                        latest: manifest.version.clone(),
+                    }
+                }
+                (_, installed) => SkipReason::UpToDate {
+                    version: installed.unwrap_or_else(|| manifest.version.clone()),
+                },
+            };
+            resolver.plan.skipped.push(SkippedPackage {
+                package: name,
+                reason,
+            });
+            continue;
+        };
+        let release = PackageManifest {
+            version: version.to_string(),
+            targets: targets.clone(),
+            ..manifest.clone()
+        };
+        resolver.add(&name, &release, installed);
+    }
+
+    resolver.plan
+}
+
+/// `resolve` installing `name` from `manifest`, which needn't come from the registry (a
+/// local file, or an older release); its dependencies do. Another installed version of
+/// `name` is replaced.
+pub(crate) fn resolve_manifest(
+    engine: &RushEngine,
+    name: &str,
+    manifest: &PackageManifest,
+    target: &str,
+) -> Plan {
+    let mut resolver = Resolver::new(engine, target);
+    match engine.state.packages.get(name).map(|p| p.version.clone()) {
+        Some(version) if version == manifest.version => {
+            resolver.plan.skipped.push(SkippedPackage {
+                package: name.to_string(),
+                reason: SkipReason::AlreadyInstalled { version },
+            });
+        }
+        installed => resolver.add(name, manifest, installed),
+    }
+    resolver.plan
+}
+
+/// Helper: A failed `apply` of a one-package plan as its caller expects it: a conflict
+/// that is just `name` having nothing for the target fails as `Error::NoCompatibleTarget`
+pub(crate) fn conflicts_error(name: &str, err: anyhow::Error) -> anyhow::Error {
+    match err.downcast_ref::<Error>() {
+        Some(Error::PlanConflicts(conflicts)) => match conflicts.as_slice() {
+            [Conflict::NoCompatibleTarget { package, target }] if package == name => {
+                Error::NoCompatibleTarget {
+                    package: package.clone(),
+                    target: Target::normalize(target),
+                }
+                .into()
+            }
+            _ => err,
+        },
+        _ => err,
+    }
+}
+
+/// A plan being worked out, and who each binary and data file will belong to once it is
+/// applied
+struct Resolver<'a> {
+    engine: &'a RushEngine,
+    plan: Plan,
+    /// Binary -> the package it belongs to, installed or planned
+    owners: HashMap<String, String>,
+    /// The data dir `dest`s planned steps write to, and their package
+    files: Vec<(String, String)>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(engine: &'a RushEngine, target: &str) -> Self {
+        let mut owners = HashMap::new();
+        for (name, pkg) in &engine.state.packages {
+            for bin in &pkg.binaries {
+                owners.insert(bin.clone(), name.clone());
+            }
+        }
+        Resolver {
+            engine,
+            plan: Plan {
+                target: target.to_string(),
+                steps: Vec::new(),
+                skipped: Vec::new(),
+                conflicts: Vec::new(),
+            },
+            owners,
+            files: Vec::new(),
+        }
+    }
+
+    /// Plan `name` at `manifest`'s version (replacing `from`), after whatever it needs that
+    /// isn't installed or planned yet. If any of that can't be planned, none of it is, and
+    /// the reason is recorded as a conflict.
+    fn add(&mut self, name: &str, manifest: &PackageManifest, from: Option<String>) {
+        let mark = self.plan.steps.len();
+        let needed = match dependencies::missing_dependencies(self.engine, name, manifest) {
+            Ok(needed) => needed,
+            Err(conflict) => {
+                self.plan.conflicts.push(conflict);
+                return;
+            }
+        };
+        for (dependency, needed) in needed {
+            if self.plan.steps.iter().any(|s| s.package == dependency) {
+                continue;
+            }
+            if !self.step(&dependency, &needed, None, true) {
+                self.rollback(mark);
+                self.plan.conflicts.push(Conflict::DependencyConflict {
+                    package: name.to_string(),
+                    dependency,
+                });
+                return;
+            }
+        }
+        if !self.step(name, manifest, from, false) {
+            self.rollback(mark);
+        }
+    }
+
+    /// Helper: Plan just `name`, or record why it can't be
+    fn step(
+        &mut self,
+        name: &str,
+        manifest: &PackageManifest,
+        from: Option<String>,
+        dependency: bool,
+    ) -> bool {
+        let Some((_, artifact)) = manifest.target(&self.plan.target) else {
+            self.plan.conflicts.push(Conflict::NoCompatibleTarget {
+                package: name.to_string(),
+                target: self.plan.target.clone(),
+            });
+            return false;
+        };
+
+        let clash = artifact.bin.iter().find_map(|bin| {
+            self.owners
+                .get(bin)
+                .filter(|owner| *owner != name)
+                .map(|owner| (bin.clone(), owner.clone()))
+        });
+        if let Some((bin, owner)) = clash {
+            self.plan.conflicts.push(Conflict::BinaryClash {
+                package: name.to_string(),
+                bin,
+                owner,
+            });
+            return false;
+        }
+        let clash = artifact.files.iter().find_map(|file| {
+            let planned = self
+                .files
+                .iter()
+                .find(|(dest, owner)| owner != name && datafiles::overlaps(dest, &file.dest))
+                .map(|(_, owner)| owner.as_str());
+            let owner = planned.or_else(|| {
+                datafiles::owner(self.engine, name, &file.dest).map(|(owner, _)| owner)
+            })?;
+            Some((file.dest.clone(), owner.to_string()))
+        });
+        if let Some((file, owner)) = clash {
+            self.plan.conflicts.push(Conflict::DataFileClash {
+                package: name.to_string(),
+                file,
+                owner,
+            });
+            return false;
+        }
+        for bin in &artifact.bin {
+            self.owners.insert(bin.clone(), name.to_string());
+        }
+        for file in &artifact.files {
+            self.files.push((file.dest.clone(), name.to_string()));
+        }
+
+        self.plan.steps.push(PlannedInstall {
+            download_size: artifact
+                .size
+                .or_else(|| download_size(self.engine, &artifact.url)),
+            package: name.to_string(),
+            from,
+            to: manifest.version.clone(),
+            artifact: artifact.clone(),
+            dependency,
+        });
+        true
+    }
+
+    /// Helper: Drop the steps planned since `mark`, and their claim on their binaries and
+    /// data files
+    fn rollback(&mut self, mark: usize) {
+        for step in self.plan.steps.drain(mark..) {
+            for bin in &step.artifact.bin {
+                self.owners.remove(bin);
+            }
+            self.files.retain(|(_, owner)| *owner != step.package);
+        }
+        // Binaries they were taking over go back to whoever has them installed
+        for (name, pkg) in &self.engine.state.packages {
+            for bin in &pkg.binaries {
+                self.owners
+                    .entry(bin.clone())
+                    .or_insert_with(|| name.clone());
+            }
+        }
+    }
+}
+
+/// Helper: The newest of `manifest`'s releases that `constraint` allows, unless it is older
+/// than `installed` (a constraint never downgrades). An error if `constraint` can't be
+/// parsed (e.g. a state file edited by hand).
+fn allowed_release<'a>(
+    manifest: &'a PackageManifest,
+    constraint: &str,
+    installed: &str,
+) -> Result<Option<(&'a str, &'a BTreeMap<String, TargetDefinition>)>> {
+    let constraint = Constraint::parse(constraint)?;
+    Ok(manifest
+        .releases()
+        .filter(|(version, _)| constraint.matches(version))
+        .max_by(|a, b| compare_versions(a.0, b.0))
+        .filter(|(version, _)| compare_versions(version, installed) != Ordering::Less))
+}
+
+/// `resolve` every installed package for an upgrade. Packages the registry dropped, whose
+/// new version doesn't support `target`, would overwrite another package's binary or data
+/// files, or needs a package that can't be installed, are skipped rather than conflicting,
+/// so one of them doesn't hold back the rest.
+pub fn plan_upgrade(engine: &RushEngine, target: &str) -> Plan {
+    let mut plan = resolve(
+        engine,
+        &ResolveRequest::Upgrade {
+            packages: Vec::new(),
+            target: target.to_string(),
+        },
+    );
+
+    for conflict in std::mem::take(&mut plan.conflicts) {
+        let (package, reason) = match conflict {
+            Conflict::NotFound { package } => (package, SkipReason::NotInRegistry),
+            Conflict::NoCompatibleTarget { package, target } => {
+                (package, SkipReason::NoCompatibleTarget { target })
+            }
+            Conflict::BinaryClash {
+                package,
+                bin,
+                owner,
+            } => (package, SkipReason::BinaryClash { bin, owner }),
+            Conflict::DataFileClash {
+                package,
+                file,
+                owner,
+            } => (package, SkipReason::DataFileClash { file, owner }),
+            Conflict::DependencyNotFound {
+                package,
+                dependency,
+            }
+            | Conflict::DependencyConflict {
+                package,
+                dependency,
+            } => (package, SkipReason::UnmetDependency { dependency }),
+            Conflict::DependencyCycle { package, cycle } => {
+                (package, SkipReason::DependencyCycle { cycle })
+            }
+            other => {
+                plan.conflicts.push(other);
+                continue;
+            }
+        };
+        // A new dependency's own conflict: the package needing it is skipped for it
+        if !engine.state.packages.contains_key(&package) {
+            continue;
+        }
+        plan.skipped.push(SkippedPackage { package, reason });
+    }
+    plan
+}
+
+/// Run a plan's installs in order, then report its skipped packages, so every requested
+/// package has an outcome. A plan with conflicts is refused as a whole; if an install
+/// fails, the ones before it stay installed.
+///
+/// Downloads run on a thread of their own, one artifact ahead: the next step's artifact
+/// downloads while this one is verified and extracted. A step's `pre_install` hook runs
+/// before its download starts, so a hook that vetoes it saves the download (the step
+/// before it is still installed first). Each step reports its download and its install
+/// as one operation, and the reporter is only called from this thread.
+pub fn apply<R>(
+    engine: &mut RushEngine,
+    plan: &Plan,
+    reporter: &mut R,
+) -> Result<Vec<InstallOutcome>>
+where
+    R: Reporter,
+{
+    if !plan.conflicts.is_empty() {
+        return Err(Error::PlanConflicts(plan.conflicts.clone()).into());
+    }
+
+    let sources: Vec<Result<Source>> = plan
+        .steps
+        .iter()
+        .map(|step| source(engine, &step.artifact))
+        .collect();
+    let operations: Vec<OperationId> = plan.steps.iter().map(|_| OperationId::next()).collect();
+    let transport = Arc::clone(&engine.transport);
+
+    let mut outcomes = Vec::new();
+    std::thread::scope(|scope| -> Result<()> {
+        let (tx, rx) = mpsc::channel::<Fetched>();
+        let (permit_tx, permit_rx) = mpsc::channel::<()>();
+        let (steps, operations) = (&plan.steps, &operations);
+        scope.spawn(move || {
+            for (i, source) in sources.into_iter().enumerate() {
+                // Step i waits until its pre_install hook has passed, and step i - 1 has
+                // been handed over
+                if permit_rx.recv().is_err() {
+                    break;
+                }
+                let events = tx.clone();
+                let mut on_event =
+                    util::tag_operation(operations[i], Some(&steps[i].package), move |e| {
+                        let _ = events.send(Fetched::Progress(e));
+                    });
+                let content = source.and_then(|source| match source {
+                    Source::Local(path) => Ok((fs::read(path)?, None)),
+                    Source::Remote(url) => {
+                        let started = Instant::now();
+                        let content = util::download_url(transport.as_ref(), &url, &mut on_event)?;
+                        Ok((content, Some(started.elapsed())))
+                    }
+                });
+                if tx.send(Fetched::Artifact(content)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let pre_install = |engine: &RushEngine, step: &PlannedInstall, reporter: &mut R| {
+            engine.cancel.check()?;
+            hooks::run_hook(
+                engine,
+                HookPoint::PreInstall,
+                &step.package,
+                &step.to,
+                |e| reporter.hook(e),
+            )
+        };
+        if let Some(first) = plan.steps.first() {
+            pre_install(engine, first, reporter)?;
+            let _ = permit_tx.send(());
+        }
+
+        // An artifact that arrived while the step before it was installing
+        let mut early = None;
+        for (i, step) in plan.steps.iter().enumerate() {
+            reporter.step(step);
+            let content = match early.take() {
+                Some(content) => content,
+                None => loop {
+                    match rx.recv()? {
+                        Fetched::Progress(e) => reporter.progress(e),
+                        Fetched::Artifact(content) => break content,
+                    }
+                },
+            };
+            let (content, download_time) = engine.cancel.settle(content)?;
+            // A veto of the next step stops the plan once this one is installed
+            let next = match plan.steps.get(i + 1) {
+                Some(next) => pre_install(engine, next, reporter),
+                None => Ok(()),
+            };
+            if next.is_ok() {
+                let _ = permit_tx.send(());
+            }
+
+            let on_event = util::tag_operation(operations[i], Some(&step.package), |e| {
+                reporter.progress(e);
+                // Keep the next download's progress coming while this one installs
+                while let Ok(fetched) = rx.try_recv() {
+                    match fetched {
+                        Fetched::Progress(e) => reporter.progress(e),
+                        Fetched::Artifact(content) => early = Some(content),
+                    }
+                }
+            });
+            let result = install::install_from_bytes(
+                engine,
+                &step.package,
+                &step.to,
+                &step.artifact,
+                &content,
+                download_time,
+                on_event,
+            );
+            let result = engine.cancel.settle(result)?;
+            hooks::run_hook(
+                engine,
+                HookPoint::PostInstall,
+                &step.package,
+                &step.to,
+                |e| reporter.hook(e),
+            )?;
+            if step.dependency {
+                if let Some(installed) = engine.state.packages.get_mut(&step.package) {
+                    installed.dependency = true;
+                }
+                engine.save()?;
+            }
+            outcomes.push(InstallOutcome::Installed(result));
+            next?;
+        }
+        Ok(())
+    })?;
+
+    // Asked for by name, an installed package no longer only serves as a dependency
+    let mut explicit = false;
+    for skipped in &plan.skipped {
+        if let SkipReason::AlreadyInstalled { .. } = skipped.reason
+            && let Some(installed) = engine.state.packages.get_mut(&skipped.package)
+        {
+            explicit |= std::mem::take(&mut installed.dependency);
+        }
+    }
+    if explicit {
+        engine.save()?;
+    }
+    outcomes.extend(plan.skipped.iter().cloned().map(InstallOutcome::from));
+    Ok(outcomes)
+}
+
+/// Where a step's artifact comes from, worked out before the downloads start
+enum Source {
+    /// Already on this machine (a mirror, or the artifact cache)
+    Local(PathBuf),
+    Remote(String),
+}
+
+/// What the download thread sends back
+enum Fetched {
+    Progress(ProgressEvent),
+    /// The next step's artifact, and how long it took to download (None: it was local)
+    Artifact(Result<(Vec<u8>, Option<Duration>)>),
+}
+
+/// Helper: A step's `Source`; an error if it would need the network and the engine is
+/// offline
+fn source(engine: &RushEngine, artifact: &TargetDefinition) -> Result<Source> {
+    if let Some(path) = install::local_artifact_path(engine, artifact) {
+        return Ok(Source::Local(path));
+    }
+    engine.ensure_online(&artifact.url)?;
+    Ok(Source::Remote(artifact.url.clone()))
+}
+
+/// Helper: The advertised size of `url`, from a HEAD request
+fn download_size(engine: &RushEngine, url: &str) -> Option<u64> {
+    engine.ensure_online(url).ok()?;
+    engine.transport.head(url).ok()?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{InstalledPackage, ProgressKind};
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_resolve_then_apply() {
+        let mock = MockEnvironment::new();
+        mock.add_package("fresh", "1.0.0", "fresh-bin");
+        mock.add_package("newer", "2.0.0", "newer-bin");
+        mock.add_package("current", "1.0.0", "current-bin");
+        mock.add_package("thief", "1.0.0", "current-bin");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        for (name, version) in [("newer", "1.0.0"), ("current", "1.0.0")] {
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: version.to_string(),
+                    binaries: vec![format!("{}-bin", name)],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec![
+                "fresh".into(),
+                "current".into(),
+                "thief".into(),
+                "ghost".into(),
+            ],
+            target: current_target(),
+        });
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].package, "fresh");
+        assert!(plan.steps[0].download_size.is_some());
+        assert_eq!(
+            plan.skipped,
+            [SkippedPackage {
+                package: "current".to_string(),
+                reason: SkipReason::AlreadyInstalled {
+                    version: "1.0.0".to_string()
+                },
+            }]
+        );
+        assert_eq!(
+            plan.conflicts,
+            [
+                Conflict::BinaryClash {
+                    package: "thief".to_string(),
+                    bin: "current-bin".to_string(),
+                    owner: "current".to_string(),
+                },
+                Conflict::NotFound {
+                    package: "ghost".to_string()
+                },
+            ]
+        );
+
+        // Resolving wrote nothing, and a conflicted plan isn't applied at all
+        assert!(!engine.bin_path.join("fresh-bin").exists());
+        let err = engine.apply(&plan, &mut ()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::PlanConflicts(conflicts)) if conflicts.len() == 2
+        ));
+        assert!(!engine.bin_path.join("fresh-bin").exists());
+
+        let plan = engine.resolve(&ResolveRequest::Upgrade {
+            packages: vec![],
+            target: current_target(),
+        });
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].from.as_deref(), Some("1.0.0"));
+        assert_eq!(plan.steps[0].to, "2.0.0");
+        assert_eq!(plan.skipped[0].package, "current");
+
+        let outcomes = engine.apply(&plan, &mut ()).unwrap();
+        assert!(matches!(&outcomes[0], InstallOutcome::Installed(r) if r.version == "2.0.0"));
+        assert!(matches!(
+            &outcomes[1],
+            InstallOutcome::UpToDate { package, version } if package == "current" && version == "1.0.0"
+        ));
+        assert_eq!(engine.state.packages["newer"].version, "2.0.0");
+    }
+
+    #[test]
+    fn test_resolve_plans_dependencies_first() {
+        let mock = MockEnvironment::new();
+        mock.add_package("app", "1.0.0", "app");
+        mock.add_package("libfoo", "1.0.0", "libfoo");
+        mock.add_package("greedy", "1.0.0", "greedy");
+        mock.add_package("libgrab", "1.0.0", "app");
+        mock.add_dependencies("app", &["libfoo"]);
+        mock.add_dependencies("greedy", &["libgrab"]);
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["app".into(), "greedy".into()],
+            target: current_target(),
+        });
+        let steps: Vec<_> = plan
+            .steps
+            .iter()
+            .map(|s| (s.package.as_str(), s.dependency))
+            .collect();
+        assert_eq!(steps, [("libfoo", true), ("app", false)]);
+        // A dependency is clash-checked like any step, and takes its dependent down with it
+        assert_eq!(
+            plan.conflicts,
+            [
+                Conflict::BinaryClash {
+                    package: "libgrab".to_string(),
+                    bin: "app".to_string(),
+                    owner: "app".to_string(),
+                },
+                Conflict::DependencyConflict {
+                    package: "greedy".to_string(),
+                    dependency: "libgrab".to_string(),
+                },
+            ]
+        );
+
+        // Asked for by name too, it is no longer just a dependency
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["app".into(), "libfoo".into()],
+            target: current_target(),
+        });
+        assert_eq!(plan.steps.len(), 2);
+        assert!(plan.steps.iter().all(|s| !s.dependency));
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["app".into()],
+            target: current_target(),
+        });
+        engine.apply(&plan, &mut ()).unwrap();
+        assert!(engine.state.packages["libfoo"].dependency);
+        assert!(!engine.state.packages["app"].dependency);
+    }
+
+    #[test]
+    fn test_plan_upgrade_installs_new_dependencies() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "2.0.0", "tool");
+        mock.add_package("libnew", "1.0.0", "libnew");
+        mock.add_package("needy", "2.0.0", "needy");
+        mock.add_dependencies("tool", &["libnew"]);
+        mock.add_dependencies("needy", &["ghost"]);
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        for name in ["tool", "needy"] {
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: "1.0.0".to_string(),
+                    binaries: vec![name.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.conflicts.is_empty());
+        let steps: Vec<_> = plan.steps.iter().map(|s| s.package.as_str()).collect();
+        assert_eq!(steps, ["libnew", "tool"]);
+        assert_eq!(
+            plan.skipped,
+            [SkippedPackage {
+                package: "needy".to_string(),
+                reason: SkipReason::UnmetDependency {
+                    dependency: "ghost".to_string()
+                },
+            }]
+        );
+
+        engine.apply_upgrade(&plan, &mut ()).unwrap();
+        assert_eq!(engine.state.packages["tool"].version, "2.0.0");
+        assert!(engine.state.packages["libnew"].dependency);
+        assert!(engine.bin_path.join("libnew").exists());
+    }
+
+    #[test]
+    fn test_apply_reports_each_step_as_one_operation() {
+        let mock = MockEnvironment::new();
+        for name in ["a", "b", "c"] {
+            mock.add_package(name, "1.0.0", name);
+        }
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        /// Records each event's package and operation, and whether it was the summary
+        #[derive(Default)]
+        struct Events(Vec<(String, OperationId, bool)>);
+        impl Reporter for Events {
+            fn progress(&mut self, event: ProgressEvent) {
+                let done = matches!(event.kind, ProgressKind::Summary(_));
+                self.0.push((event.package.unwrap(), event.operation, done));
+            }
+        }
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["a".into(), "b".into(), "c".into()],
+            target: current_target(),
+        });
+        let mut events = Events::default();
+        let outcomes = engine.apply(&plan, &mut events).unwrap();
+        assert_eq!(outcomes.len(), 3);
+        for name in ["a", "b", "c"] {
+            assert!(engine.bin_path.join(name).is_file());
+            let ops: Vec<_> = events.0.iter().filter(|(p, ..)| p == name).collect();
+            // Download and install share the step's operation, which ends in its summary
+            assert!(ops.iter().all(|(_, op, _)| *op == ops[0].1));
+            assert!(ops.last().unwrap().2);
+        }
+        let a = events.0.iter().find(|(p, ..)| p == "a").unwrap().1;
+        let b = events.0.iter().find(|(p, ..)| p == "b").unwrap().1;
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_pre_install_runs_before_the_download() {
+        let mock = MockEnvironment::new();
+        for name in ["a", "b", "c"] {
+            mock.add_package(name, "1.0.0", name);
+        }
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        engine.config.hooks = crate::models::HooksConfig {
+            pre_install: Some("test {pkg} != b".to_string()),
+            ..Default::default()
+        };
+
+        /// Records which packages were downloaded
+        #[derive(Default)]
+        struct Downloads(Vec<String>);
+        impl Reporter for Downloads {
+            fn progress(&mut self, event: ProgressEvent) {
+                if let ProgressKind::Downloading { .. } = event.kind {
+                    self.0.push(event.package.unwrap());
+                }
+            }
+        }
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["a".into(), "b".into(), "c".into()],
+            target: current_target(),
+        });
+        let mut downloads = Downloads::default();
+        let err = engine.apply(&plan, &mut downloads).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::HookFailed { package, .. }) if package == "b"
+        ));
+        // The step before the veto is installed; the vetoed one is never fetched
+        assert_eq!(downloads.0, ["a"]);
+        assert!(engine.state.packages.contains_key("a"));
+        assert!(!engine.state.packages.contains_key("b"));
+        assert!(!engine.state.packages.contains_key("c"));
+    }
+
+    #[test]
+    fn test_plan_upgrade_skips_what_it_cannot_upgrade() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "2.0.0", "tool");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        for name in ["tool", "dropped"] {
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: "1.0.0".to_string(),
+                    binaries: vec![name.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let plan = engine.plan_upgrade("sparc-solaris");
+        assert!(plan.steps.is_empty());
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(
+            plan.skipped,
+            [
+                SkippedPackage {
+                    package: "dropped".to_string(),
+                    reason: SkipReason::NotInRegistry,
+                },
+                SkippedPackage {
+                    package: "tool".to_string(),
+                    reason: SkipReason::NoCompatibleTarget {
+                        target: "sparc-solaris".to_string()
+                    },
+                },
+            ]
+        );
+
+        /// Records which upgrades `apply_upgrade` announced
+        #[derive(Default)]
+        struct Steps(Vec<(String, Option<String>, String)>);
+        impl Reporter for Steps {
+            fn step(&mut self, step: &PlannedInstall) {
+                self.0
+                    .push((step.package.clone(), step.from.clone(), step.to.clone()));
+            }
+        }
+
+        let plan = engine.plan_upgrade(&current_target());
+        let mut steps = Steps::default();
+        let outcomes = engine.apply_upgrade(&plan, &mut steps).unwrap();
+        assert_eq!(
+            steps.0,
+            [(
+                "tool".to_string(),
+                Some("1.0.0".to_string()),
+                "2.0.0".to_string()
+            )]
+        );
+        assert_eq!(plan.skipped.len(), 1);
+        assert!(matches!(
+            &outcomes[1],
+            InstallOutcome::Skipped {
+                package,
+                reason: SkipReason::NotInRegistry
+            } if package == "dropped"
+        ));
+    }
+
+    #[test]
+    fn test_plan_upgrade_skips_binary_clash() {
+        let mock = MockEnvironment::new();
+        // tool's new version ships a binary `other` already installed
+        mock.add_package("tool", "2.0.0", "shared");
+        mock.add_package("fresh", "2.0.0", "fresh");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        for (name, bin) in [("tool", "tool"), ("other", "shared"), ("fresh", "fresh")] {
+            engine.state.packages.insert(
+                name.to_string(),
+                InstalledPackage {
+                    version: "1.0.0".to_string(),
+                    binaries: vec![bin.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].package, "fresh");
+        assert!(plan.skipped.contains(&SkippedPackage {
+            package: "tool".to_string(),
+            reason: SkipReason::BinaryClash {
+                bin: "shared".to_string(),
+                owner: "other".to_string(),
+            },
+        }));
+
+        // The clash doesn't hold back the other upgrade
+        engine.apply_upgrade(&plan, &mut ()).unwrap();
+        assert_eq!(engine.state.packages["fresh"].version, "2.0.0");
+        assert_eq!(engine.state.packages["tool"].version, "1.0.0");
+    }
+
+    #[test]
+    fn test_plan_upgrade_follows_constraint() {
+        let mock = MockEnvironment::new();
+        let manifest_path = mock.registry_source.join("packages/n/node.toml");
+        let release = |version: &str| {
+            mock.add_package("node", version, "node");
+            let manifest = crate::core::load_manifest(&manifest_path).unwrap();
+            crate::models::Release {
+                version: manifest.version,
+                targets: manifest.targets,
+            }
+        };
+        let history = vec![release("20.2.0"), release("19.0.0")];
+        mock.add_package("node", "21.0.0", "node");
+        let mut manifest = crate::core::load_manifest(&manifest_path).unwrap();
+        manifest.history = history;
+        fs::write(&manifest_path, toml::to_string(&manifest).unwrap()).unwrap();
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        assert!(!engine.pin_package("node", "~20").unwrap());
+        engine.state.packages.insert(
+            "node".to_string(),
+            InstalledPackage {
+                version: "20.1.0".to_string(),
+                binaries: vec!["node".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+        assert!(engine.pin_package("node", "latest").is_err());
+        assert!(engine.pin_package("node", "~20").unwrap());
+
+        let plan = engine.plan_upgrade(&current_target());
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].to, "20.2.0");
+        assert!(plan.steps[0].artifact.url.ends_with("node-20.2.0.tar.gz"));
+        engine.apply_upgrade(&plan, &mut ()).unwrap();
+        let installed = &engine.state.packages["node"];
+        assert_eq!(installed.version, "20.2.0");
+        assert_eq!(installed.constraint.as_deref(), Some("~20"));
+
+        // Nothing newer is allowed
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.steps.is_empty());
+        assert_eq!(
+            plan.skipped,
+            [SkippedPackage {
+                package: "node".to_string(),
+                reason: SkipReason::Held {
+                    constraint: "~20".to_string(),
+                    latest: "21.0.0".to_string(),
+                },
+            }]
+        );
+
+        // A constraint the installed version is already past never downgrades
+        engine.pin_package("node", "^19").unwrap();
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.steps.is_empty());
+        assert!(matches!(plan.skipped[0].reason, SkipReason::Held { .. }));
+
+        // One that can't be read (a hand-edited state) says why
+        let node = engine.state.packages.get_mut("node").unwrap();
+        node.constraint = Some("~twenty".to_string());
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.steps.is_empty());
+        assert!(matches!(
+            &plan.skipped[0].reason,
+            SkipReason::BadConstraint { constraint, error }
+                if constraint == "~twenty" && !error.is_empty()
+        ));
+        engine.pin_package("node", "^19").unwrap();
+
+        // A pin holds it where it is; unpinning lets it move to the latest
+        assert!(engine.hold_package("node").unwrap());
+        let plan = engine.plan_upgrade(&current_target());
+        assert!(plan.steps.is_empty());
+        assert_eq!(
+            plan.skipped[0].reason,
+            SkipReason::Pinned {
+                latest: "21.0.0".to_string()
+            }
+        );
+        assert!(engine.unpin_package("node").unwrap());
+        assert!(!engine.unpin_package("ghost").unwrap());
+        let installed = &engine.state.packages["node"];
+        assert!(!installed.pinned && installed.constraint.is_none());
+        let plan = engine.plan_upgrade(&current_target());
+        assert_eq!(plan.steps[0].to, "21.0.0");
+    }
+}
@@ -0,0 +1,200 @@
+//! The `preview_*` variants of the engine's mutating operations.
+//!
+//! Each works out what its operation would download, write, delete, and record, from the
+//! same state and paths the real one uses, without changing anything.
+
+use crate::Error;
+use crate::core::{RushEngine, clean};
+use crate::models::{ChangeSet, HookPoint, Plan, PlannedHook, StateChange, TargetDefinition};
+use anyhow::Result;
+
+/// What `install_package` would do
+pub fn preview_install(
+    engine: &RushEngine,
+    name: &str,
+    version: &str,
+    target: &TargetDefinition,
+) -> ChangeSet {
+    let state = match engine.state.packages.get(name) {
+        None => Some(StateChange::Installed {
+            package: name.to_string(),
+            version: version.to_string(),
+        }),
+        Some(installed) if installed.version != version => Some(StateChange::Upgraded {
+            package: name.to_string(),
+            from: installed.version.clone(),
+            to: version.to_string(),
+        }),
+        Some(_) => None,
+    };
+
+    ChangeSet {
+        downloads: vec![target.url.clone()],
+        writes: target
+            .bin
+            .iter()
+            .map(|bin| engine.bin_path.join(bin))
+            // A data file's dest, or the directory a data dir goes into
+            .chain(target.files.iter().map(|f| engine.data_dir.join(&f.dest)))
+            .collect(),
+        deletes: Vec::new(),
+        state: state.into_iter().collect(),
+        hooks: hooks(
+            engine,
+            [HookPoint::PreInstall, HookPoint::PostInstall],
+            name,
+        ),
+    }
+}
+
+/// What `apply` would do with `plan`; a plan with conflicts fails as `apply` would
+pub fn preview_apply(engine: &RushEngine, plan: &Plan) -> Result<ChangeSet> {
+    if !plan.conflicts.is_empty() {
+        return Err(Error::PlanConflicts(plan.conflicts.clone()).into());
+    }
+
+    let mut changes = ChangeSet::default();
+    for step in &plan.steps {
+        changes.extend(preview_install(
+            engine,
+            &step.package,
+            &step.to,
+            &step.artifact,
+        ));
+    }
+    Ok(changes)
+}
+
+/// What `uninstall_package` would do; None if `name` isn't installed
+pub fn preview_uninstall(engine: &RushEngine, name: &str) -> Option<ChangeSet> {
+    let pkg = engine.state.packages.get(name)?;
+
+    Some(ChangeSet {
+        deletes: pkg
+            .binaries
+            .iter()
+            .map(|bin| engine.bin_path.join(bin))
+            .chain(pkg.files.iter().map(|f| engine.data_dir.join(f)))
+            .filter(|path| path.exists())
+            .collect(),
+        state: vec![StateChange::Removed {
+            package: name.to_string(),
+            version: pkg.version.clone(),
+        }],
+        hooks: hooks(
+            engine,
+            [HookPoint::PreUninstall, HookPoint::PostUninstall],
+            name,
+        ),
+        ..Default::default()
+    })
+}
+
+/// What `update_registry` would do: replace the local copy from the source
+pub fn preview_update_registry(engine: &RushEngine) -> ChangeSet {
+    let mut changes = ChangeSet {
+        writes: vec![engine.registry_dir.clone()],
+        ..Default::default()
+    };
+    if engine.registry_dir.exists() {
+        changes.deletes.push(engine.registry_dir.clone());
+    }
+    if engine.registry.is_remote() {
+        let source = engine
+            .registry
+            .archive_url()
+            .unwrap_or(engine.registry.source());
+        changes.downloads.push(source.to_string());
+    }
+    changes
+}
+
+/// What `clean_trash` would do
+pub fn preview_clean(engine: &RushEngine) -> Result<ChangeSet> {
+    Ok(ChangeSet {
+        deletes: clean::find_trash(engine, clean::TRASH_PREFIX)?,
+        ..Default::default()
+    })
+}
+
+/// Helper: The points among `points` that have a hook configured
+fn hooks<const N: usize>(
+    engine: &RushEngine,
+    points: [HookPoint; N],
+    package: &str,
+) -> Vec<PlannedHook> {
+    points
+        .into_iter()
+        .filter(|point| engine.config.hooks.command(*point).is_some())
+        .map(|point| PlannedHook {
+            point,
+            package: package.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HooksConfig, ResolveRequest};
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_previews_match_what_happens() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool-bin");
+        let mut engine = mock.engine().unwrap();
+        engine.config.hooks = HooksConfig {
+            post_install: Some("true".to_string()),
+            ..Default::default()
+        };
+
+        let update = preview_update_registry(&engine);
+        assert_eq!(update.writes, [engine.registry_dir.clone()]);
+        assert!(update.downloads.is_empty());
+        engine.update_registry(&mut ()).unwrap();
+
+        let plan = engine.resolve(&ResolveRequest::Install {
+            packages: vec!["tool".to_string()],
+            target: current_target(),
+        });
+        let install = preview_apply(&engine, &plan).unwrap();
+        let bin = engine.bin_path.join("tool-bin");
+        assert_eq!(install.writes, vec![bin.clone()]);
+        assert_eq!(
+            install.state,
+            [StateChange::Installed {
+                package: "tool".to_string(),
+                version: "1.0.0".to_string()
+            }]
+        );
+        assert_eq!(
+            install.hooks,
+            [PlannedHook {
+                point: HookPoint::PostInstall,
+                package: "tool".to_string()
+            }]
+        );
+        // Previewing wrote nothing
+        assert!(!bin.exists());
+        assert!(engine.state.packages.is_empty());
+
+        engine.apply(&plan, &mut ()).unwrap();
+        assert!(bin.exists());
+
+        let uninstall = preview_uninstall(&engine, "tool").unwrap();
+        assert!(preview_uninstall(&engine, "ghost").is_none());
+        let result = engine.uninstall_package("tool", &mut ()).unwrap().unwrap();
+        let removed: Vec<_> = result
+            .binaries_removed
+            .iter()
+            .map(|bin| engine.bin_path.join(bin))
+            .collect();
+        assert_eq!(uninstall.deletes, removed);
+
+        std::fs::write(engine.bin_path.join(".rush-tmp-1-x"), "").unwrap();
+        let clean = preview_clean(&engine).unwrap();
+        assert_eq!(clean.deletes, [engine.bin_path.join(".rush-tmp-1-x")]);
+        assert!(engine.bin_path.join(".rush-tmp-1-x").exists());
+    }
+}
@@ -0,0 +1,266 @@
+//! Project toolchains: a `rush.toml` at a project's root lists the tools, by version,
+//! that working on it takes.
+//!
+//! They are fetched into the project's own `.rush/tools/<package>/<version>/`, with the
+//! same checks as `rush run`, and never recorded as installed. `.rush/bin` holds exactly
+//! the listed binaries, so putting it first on PATH (as `rush shell` does) gives the
+//! project's versions precedence over anything installed globally.
+
+use crate::core::{RushEngine, install, run};
+use crate::models::{
+    NewPackage, ProgressEvent, ProjectCheck, ProjectEnv, ProjectManifest, ProjectToolCheck,
+    ProjectToolStatus,
+};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project file
+pub const PROJECT_FILE: &str = "rush.toml";
+/// Where a project's tools are kept, next to its `rush.toml`
+const PROJECT_DIR: &str = ".rush";
+/// The version that follows the registry
+const LATEST: &str = "latest";
+
+/// The nearest directory holding a `rush.toml`, from `start` upwards
+pub fn find_project(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(PROJECT_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Loads the `rush.toml` in `root`
+pub fn load_project(root: &Path) -> Result<ProjectManifest> {
+    let path = root.join(PROJECT_FILE);
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("Invalid project file {:?}", path))
+}
+
+/// Fetch the tools of the project `start` is in that aren't there yet, and lay out its
+/// bin dir
+pub fn sync_project<F>(engine: &RushEngine, start: &Path, mut on_event: F) -> Result<ProjectEnv>
+where
+    F: FnMut(ProgressEvent),
+{
+    let root = project_root(start)?;
+    let manifest = load_project(&root)?;
+    if !engine.targets_host() {
+        bail!(
+            "Can't run binaries built for {} on this machine",
+            engine.target()
+        );
+    }
+
+    let dir = root.join(PROJECT_DIR);
+    let tools_dir = dir.join("tools");
+    fs::create_dir_all(&tools_dir)?;
+    // Fetched binaries are per machine, not part of the project's source
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        fs::write(&ignore, "*\n")?;
+    }
+
+    let mut binaries = Vec::new();
+    let mut tools = Vec::new();
+    for (name, version) in &manifest.tools {
+        engine.cancel.check()?;
+        let wanted = (version != LATEST).then_some(version.as_str());
+        let path = run::fetch_version(engine, name, wanted, &tools_dir.join(name), &mut on_event)
+            .with_context(|| format!("Failed to fetch {} for the project", name))?;
+        let version = path
+            .parent()
+            .and_then(Path::file_name)
+            .map_or_else(|| version.clone(), |v| v.to_string_lossy().into_owned());
+        tools.push((name.clone(), version));
+        binaries.push(path);
+    }
+    for entry in fs::read_dir(&tools_dir)?.flatten() {
+        let listed = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| manifest.tools.contains_key(name));
+        if !listed {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+
+    // Rebuilt each time, so a tool dropped from rush.toml drops off PATH
+    let bin_dir = dir.join("bin");
+    if bin_dir.exists() {
+        fs::remove_dir_all(&bin_dir)?;
+    }
+    fs::create_dir_all(&bin_dir)?;
+    for binary in binaries {
+        if let Some(file_name) = binary.file_name() {
+            link(&binary, &bin_dir.join(file_name))?;
+        }
+    }
+
+    Ok(ProjectEnv {
+        root,
+        bin_dir,
+        tools,
+    })
+}
+
+/// Whether every tool the project `start` is in lists is fetched at its version and in
+/// its bin dir, as `sync_project` leaves them; nothing is fetched or changed
+pub fn check_project(engine: &RushEngine, start: &Path) -> Result<ProjectCheck> {
+    let root = project_root(start)?;
+    let manifest = load_project(&root)?;
+    let dir = root.join(PROJECT_DIR);
+    let tools = manifest
+        .tools
+        .iter()
+        .map(|(name, version)| check_tool(engine, &dir, name, version))
+        .collect();
+    Ok(ProjectCheck { root, tools })
+}
+
+/// Helper: Where `name` at the `rush.toml` version `wanted` stands in the project's `dir`
+fn check_tool(engine: &RushEngine, dir: &Path, name: &str, wanted: &str) -> ProjectToolCheck {
+    let check = |version: &str, status| ProjectToolCheck {
+        package: name.to_string(),
+        version: version.to_string(),
+        status,
+    };
+    let manifest = NewPackage::validate_name(name)
+        .ok()
+        .and_then(|_| engine.find_package(name));
+    let Some(manifest) = manifest else {
+        return check(wanted, ProjectToolStatus::Unavailable);
+    };
+    let version = if wanted == LATEST {
+        manifest.version.as_str()
+    } else {
+        wanted
+    };
+    let target = match install::manifest_target(name, &manifest, engine.target()) {
+        Ok(target) if !target.bin.is_empty() => target,
+        _ => return check(version, ProjectToolStatus::Unavailable),
+    };
+
+    let path = dir
+        .join("tools")
+        .join(name)
+        .join(version)
+        .join(target.main_bin());
+    if run::validate_version(version).is_err() || !path.is_file() {
+        return check(version, ProjectToolStatus::Missing);
+    }
+    let link = dir.join("bin").join(path.file_name().unwrap_or_default());
+    #[cfg(unix)]
+    let linked = fs::read_link(&link).is_ok_and(|target| target == path);
+    #[cfg(not(unix))]
+    let linked = link.is_file();
+    if linked {
+        check(version, ProjectToolStatus::Ready)
+    } else {
+        check(version, ProjectToolStatus::NotLinked)
+    }
+}
+
+/// Helper: The root of the project `start` is in
+fn project_root(start: &Path) -> Result<PathBuf> {
+    match find_project(start) {
+        Some(root) => Ok(root),
+        None => bail!(
+            "No {} in {} or any directory above it",
+            PROJECT_FILE,
+            start.display()
+        ),
+    }
+}
+
+/// Helper: Put `binary` at `link`: a symlink where there are symlinks, a copy elsewhere
+fn link(binary: &Path, link: &Path) -> Result<()> {
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(binary, link)?;
+    #[cfg(not(unix))]
+    fs::copy(binary, link)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+
+    #[test]
+    fn test_sync_project() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        mock.add_package("other", "2.0.0", "other");
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let root = mock.home.join("project");
+        let nested = root.join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+        let err = sync_project(&engine, &nested, |_| {}).unwrap_err();
+        assert!(err.to_string().contains("No rush.toml"));
+
+        fs::write(
+            root.join(PROJECT_FILE),
+            "[tools]\ntool = \"1.0.0\"\nother = \"latest\"\n",
+        )
+        .unwrap();
+        let env = sync_project(&engine, &nested, |_| {}).unwrap();
+        assert_eq!(env.root, root);
+        assert_eq!(env.bin_dir, root.join(".rush/bin"));
+        assert_eq!(
+            env.tools,
+            [
+                ("other".to_string(), "2.0.0".to_string()),
+                ("tool".to_string(), "1.0.0".to_string())
+            ]
+        );
+        assert!(env.bin_dir.join("tool").is_file());
+        assert!(env.bin_dir.join("other").is_file());
+        assert!(root.join(".rush/.gitignore").is_file());
+        // Nothing is installed globally
+        assert!(engine.state.packages.is_empty());
+        assert!(!engine.bin_path.join("tool").exists());
+
+        // A dropped tool leaves the bin dir
+        fs::write(root.join(PROJECT_FILE), "[tools]\ntool = \"1.0.0\"\n").unwrap();
+        let env = sync_project(&engine, &root, |_| {}).unwrap();
+        assert_eq!(env.tools.len(), 1);
+        assert!(!env.bin_dir.join("other").exists());
+        assert!(!root.join(".rush/tools/other").exists());
+
+        // Checking changes nothing
+        fs::write(
+            root.join(PROJECT_FILE),
+            "[tools]\ntool = \"1.0.0\"\nother = \"latest\"\nghost = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let check = check_project(&engine, &root).unwrap();
+        let statuses: Vec<_> = check
+            .tools
+            .iter()
+            .map(|t| (t.package.as_str(), t.version.as_str(), t.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("ghost", "1.0.0", ProjectToolStatus::Unavailable),
+                ("other", "2.0.0", ProjectToolStatus::Missing),
+                ("tool", "1.0.0", ProjectToolStatus::Ready),
+            ]
+        );
+        assert_eq!(check.failed().count(), 2);
+        assert!(!root.join(".rush/tools/other").exists());
+        fs::remove_file(root.join(".rush/bin/tool")).unwrap();
+        let check = check_project(&engine, &root).unwrap();
+        assert_eq!(check.tools[2].status, ProjectToolStatus::NotLinked);
+
+        // Only the registry's version can be fetched, and names must be package names
+        fs::write(root.join(PROJECT_FILE), "[tools]\nother = \"1.0.0\"\n").unwrap();
+        assert!(sync_project(&engine, &root, |_| {}).is_err());
+        fs::write(root.join(PROJECT_FILE), "[tools]\n\"../x\" = \"1.0.0\"\n").unwrap();
+        assert!(sync_project(&engine, &root, |_| {}).is_err());
+    }
+}
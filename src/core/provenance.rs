@@ -0,0 +1,181 @@
+//! What gets recorded about where an installed binary came from.
+//!
+//! At install time the artifact's URL and sha256 are known; the registry side (which
+//! manifest listed it, at which commit, and whether the registry's signed index vouched
+//! for that manifest) is read back from the synced copy.
+
+use crate::core::dev::{INDEX_FILE, SIGNATURE_FILE};
+use crate::core::registry::REVISION_FILE;
+use crate::core::{RushEngine, util};
+use crate::models::{
+    PackageManifest, Provenance, RegistryProvenance, SignatureStatus, TargetDefinition,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Provenance for installing `target` of `name` now
+pub(crate) fn record(engine: &RushEngine, name: &str, target: &TargetDefinition) -> Provenance {
+    let installed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    Provenance {
+        registry: registry_provenance(engine, name, target),
+        url: target.url.clone(),
+        sha256: target.sha256.clone(),
+        installed_at: rfc3339(installed_at),
+    }
+}
+
+/// Helper: The synced manifest of `name`, if it lists this artifact (same URL and hash)
+fn registry_provenance(
+    engine: &RushEngine,
+    name: &str,
+    target: &TargetDefinition,
+) -> Option<RegistryProvenance> {
+    let relative = format!("packages/{}/{}.toml", name.chars().next()?, name);
+    let content = fs::read(engine.registry_dir.join(&relative)).ok()?;
+    let manifest: PackageManifest = toml::from_str(std::str::from_utf8(&content).ok()?).ok()?;
+    if !manifest
+        .targets
+        .values()
+        .any(|t| t.url == target.url && t.sha256 == target.sha256)
+    {
+        return None;
+    }
+
+    let manifest_sha256 = hex::encode(Sha256::digest(&content));
+    let revision = fs::read_to_string(engine.registry_dir.join(REVISION_FILE))
+        .ok()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+
+    Some(RegistryProvenance {
+        source: engine.registry.source().to_string(),
+        revision,
+        signature: signature_status(engine, &relative, &manifest_sha256),
+        manifest_sha256,
+    })
+}
+
+/// Helper: What the synced signed index says about the manifest at `relative`
+fn signature_status(engine: &RushEngine, relative: &str, manifest_sha256: &str) -> SignatureStatus {
+    let index = fs::read_to_string(engine.registry_dir.join(INDEX_FILE));
+    let signature = fs::read_to_string(engine.registry_dir.join(SIGNATURE_FILE));
+    let (Ok(index), Ok(signature)) = (index, signature) else {
+        return SignatureStatus::Unsigned;
+    };
+
+    // Lines are "<sha256>  packages/x/name.toml", as `rush dev sign` writes them
+    let listed = index
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .any(|(hash, path)| path == relative && hash == manifest_sha256);
+    if !listed {
+        return SignatureStatus::Invalid;
+    }
+
+    let Some(public_key) = &engine.config.registry.public_key else {
+        return SignatureStatus::Unverified;
+    };
+    if util::verify_signature(public_key, index.as_bytes(), &signature) {
+        SignatureStatus::Verified
+    } else {
+        SignatureStatus::Invalid
+    }
+}
+
+/// Helper: Unix seconds as an RFC 3339 UTC timestamp
+pub(crate) fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(rfc3339(1_738_324_799), "2025-01-31T11:59:59Z");
+    }
+
+    #[test]
+    fn test_install_records_provenance() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool-bin");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        let manifest = engine.find_package("tool").unwrap();
+        let target = manifest.targets[&current_target()].clone();
+
+        engine
+            .install_package("tool", "1.0.0", &target, &mut ())
+            .unwrap();
+        let provenance = engine.state.packages["tool"].provenance.clone().unwrap();
+        assert_eq!(provenance.url, target.url);
+        assert_eq!(provenance.sha256, target.sha256);
+        let registry = provenance.registry.unwrap();
+        assert_eq!(registry.source, engine.registry.source());
+        assert_eq!(registry.signature, SignatureStatus::Unsigned);
+        let synced = fs::read(engine.registry_dir.join("packages/t/tool.toml")).unwrap();
+        assert_eq!(
+            registry.manifest_sha256,
+            hex::encode(Sha256::digest(&synced))
+        );
+
+        // A signed index vouches for the manifest once its key is trusted
+        let index = format!("{}  packages/t/tool.toml\n", registry.manifest_sha256);
+        let key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        use ed25519_dalek::Signer;
+        fs::write(engine.registry_dir.join(INDEX_FILE), &index).unwrap();
+        fs::write(
+            engine.registry_dir.join(SIGNATURE_FILE),
+            hex::encode(key.sign(index.as_bytes()).to_bytes()),
+        )
+        .unwrap();
+        let status = |engine: &RushEngine| {
+            signature_status(engine, "packages/t/tool.toml", &registry.manifest_sha256)
+        };
+        assert_eq!(status(&engine), SignatureStatus::Unverified);
+
+        engine.config.registry.public_key = Some(hex::encode(key.verifying_key().as_bytes()));
+        assert_eq!(status(&engine), SignatureStatus::Verified);
+
+        let other = ed25519_dalek::SigningKey::from_bytes(&[8; 32]);
+        engine.config.registry.public_key = Some(hex::encode(other.verifying_key().as_bytes()));
+        assert_eq!(status(&engine), SignatureStatus::Invalid);
+
+        // An artifact no synced manifest lists has no registry side
+        let mut local = target.clone();
+        local.url = "file:///elsewhere/tool.tar.gz".to_string();
+        assert!(record(&engine, "tool", &local).registry.is_none());
+    }
+}
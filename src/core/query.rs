@@ -1,14 +1,153 @@
-use crate::core::RushEngine;
-use crate::models::PackageManifest;
+use crate::core::{RushEngine, Transport, TransportResponse};
+use crate::models::{OutdatedPackage, PackageManifest, PackageOrder};
+use anyhow::{Context, Result};
 use std::fs;
+use std::path::Path;
 use walkdir::WalkDir;
 
-/// Look up a specific package file (e.g. .../registry/packages/f/fzf.toml)
+/// Look up a specific package through the engine's registry backend
+#[tracing::instrument(level = "debug", skip(engine), fields(found))]
 pub fn find_package(engine: &RushEngine, name: &str) -> Option<PackageManifest> {
+    let manifest = engine
+        .registry
+        .find_package(&engine.registry_dir, &OnlineGuard(engine), name);
+    tracing::Span::current().record("found", manifest.is_some());
+    manifest
+}
+
+/// `find_package`, as of `version` (the latest if None); None if the registry doesn't
+/// offer that version
+pub fn find_package_version(
+    engine: &RushEngine,
+    name: &str,
+    version: Option<&str>,
+) -> Option<PackageManifest> {
+    let manifest = find_package(engine, name)?;
+    match version {
+        Some(version) => manifest.release(version),
+        None => Some(manifest),
+    }
+}
+
+/// List all available packages through the engine's registry backend
+#[tracing::instrument(level = "debug", skip_all, fields(count))]
+pub fn list_available_packages(engine: &RushEngine) -> Vec<(String, PackageManifest)> {
+    let packages: Vec<_> = packages(engine).collect();
+    tracing::Span::current().record("count", packages.len());
+    packages
+}
+
+/// Installed packages the registry has another version of, sorted by name. Packages the
+/// registry no longer has aren't listed.
+pub fn outdated(engine: &RushEngine) -> Vec<OutdatedPackage> {
+    let mut names: Vec<&String> = engine.state.packages.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let installed = &engine.state.packages[name];
+            let manifest = find_package(engine, name)?;
+            (manifest.version != installed.version).then(|| OutdatedPackage {
+                package: name.clone(),
+                installed: installed.version.clone(),
+                available: manifest.version,
+            })
+        })
+        .collect()
+}
+
+/// Reorder `packages` (as listed, by name) by `order`; sizes are of `target`'s artifact.
+/// Packages that tie, or that lack the metadata, keep their order.
+pub fn sort_packages(
+    packages: &mut [(String, PackageManifest)],
+    order: PackageOrder,
+    target: &str,
+) {
+    match order {
+        PackageOrder::Name => packages.sort_by(|a, b| a.0.cmp(&b.0)),
+        // RFC 3339 UTC timestamps sort as strings; None sorts after Some once reversed
+        PackageOrder::Updated => {
+            packages.sort_by(|a, b| b.1.updated.as_ref().cmp(&a.1.updated.as_ref()))
+        }
+        PackageOrder::Size => packages.sort_by_key(|(_, manifest)| {
+            manifest
+                .target(target)
+                .and_then(|(_, artifact)| artifact.size)
+                .map_or((1, 0), |size| (0, size))
+        }),
+    }
+}
+
+/// Every package name in the registry, sorted, without reading any manifests
+pub fn package_names(engine: &RushEngine) -> Vec<String> {
+    engine
+        .registry
+        .package_names(&engine.registry_dir, &OnlineGuard(engine))
+}
+
+/// Every package in the registry, sorted by name and parsed as the iterator advances
+pub fn packages(engine: &RushEngine) -> Packages<'_> {
+    Packages {
+        engine,
+        names: package_names(engine).into_iter(),
+    }
+}
+
+/// Iterator returned by `RushEngine::packages()`.
+/// Each manifest is only read when reached, so stopping early (`take`, `find`, ...)
+/// skips the rest; packages whose manifest can't be read are skipped.
+pub struct Packages<'a> {
+    engine: &'a RushEngine,
+    names: std::vec::IntoIter<String>,
+}
+
+impl Iterator for Packages<'_> {
+    type Item = (String, PackageManifest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let engine = self.engine;
+        self.names.by_ref().find_map(|name| {
+            let manifest =
+                engine
+                    .registry
+                    .find_package(&engine.registry_dir, &OnlineGuard(engine), &name)?;
+            Some((name, manifest))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.names.size_hint().1)
+    }
+}
+
+/// The engine's transport, refusing network lookups in offline mode
+struct OnlineGuard<'a>(&'a RushEngine);
+
+impl Transport for OnlineGuard<'_> {
+    fn get(&self, url: &str) -> Result<TransportResponse> {
+        self.0.ensure_online(url)?;
+        self.0.transport.get(url)
+    }
+
+    fn head(&self, url: &str) -> Result<Option<u64>> {
+        self.0.ensure_online(url)?;
+        self.0.transport.head(url)
+    }
+}
+
+/// Read a manifest file outside the registry (e.g. one being written), for
+/// `RushEngine::install_from_manifest`
+pub fn load_manifest(path: &Path) -> Result<PackageManifest> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Invalid manifest {}", path.display()))
+}
+
+/// Read a specific package file from a registry copy (e.g. .../registry/packages/f/fzf.toml)
+pub(crate) fn read_manifest(registry_dir: &Path, name: &str) -> Option<PackageManifest> {
     let prefix = name.chars().next()?;
 
-    let path = engine
-        .registry_dir
+    let path = registry_dir
         .join("packages")
         .join(prefix.to_string())
         .join(format!("{}.toml", name));
@@ -19,13 +158,13 @@ pub fn find_package(engine: &RushEngine, name: &str) -> Option<PackageManifest>
         .and_then(|content| toml::from_str(&content).ok())
 }
 
-/// Scan the folder structure to list all available packages
-pub fn list_available_packages(engine: &RushEngine) -> Vec<(String, PackageManifest)> {
-    let mut results = Vec::new();
-    let packages_dir = engine.registry_dir.join("packages");
+/// Scan the folder structure of a registry copy for package names (packages/f/fzf.toml)
+pub(crate) fn scan_names(registry_dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let packages_dir = registry_dir.join("packages");
 
     if !packages_dir.exists() {
-        return results;
+        return names;
     }
 
     for entry in WalkDir::new(packages_dir)
@@ -34,26 +173,28 @@ pub fn list_available_packages(engine: &RushEngine) -> Vec<(String, PackageManif
         .into_iter()
         .flatten()
     {
-        // Guard Clause 1: Must be a file
-        if !entry.file_type().is_file() {
+        // Guard Clause 1: Must be a .toml file
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|ext| ext != "toml") {
             continue;
         }
 
         // Guard Clause 2: Must have a valid filename
-        let Some(stem) = entry.path().file_stem().and_then(|s| s.to_str()) else {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
             continue;
         };
 
-        // Attempt to read and parse
-        // We use unwrap_or_default/ok logic to skip bad files silently
-        let content = fs::read_to_string(entry.path()).unwrap_or_default();
-        if let Ok(manifest) = toml::from_str::<PackageManifest>(&content) {
-            results.push((stem.to_string(), manifest));
+        // Guard Clause 3: Must be where `read_manifest` looks for it
+        let prefix = path.parent().and_then(|p| p.file_name());
+        if prefix.and_then(|p| p.to_str()) != stem.chars().next().map(String::from).as_deref() {
+            continue;
         }
+
+        names.push(stem.to_string());
     }
 
-    results.sort_by(|a, b| a.0.cmp(&b.0));
-    results
+    names.sort();
+    names
 }
 
 #[cfg(test)]
@@ -65,7 +206,7 @@ mod tests {
     fn test_find_package_success() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         // Setup: Create a fake package 'test-pkg' inside registry/packages/t/
         let prefix_dir = engine.registry_dir.join("packages").join("t");
@@ -95,11 +236,85 @@ mod tests {
         assert_eq!(m.description, Some("A test package".to_string()));
     }
 
+    #[test]
+    fn test_find_package_version() {
+        use crate::test_utils::MockEnvironment;
+
+        let mock = MockEnvironment::new();
+        let manifest_path = mock.registry_source.join("packages/t/tool.toml");
+        mock.add_package("tool", "1.0.0", "tool");
+        let old = crate::core::load_manifest(&manifest_path).unwrap();
+        mock.add_package("tool", "2.0.0", "tool");
+        let mut manifest = crate::core::load_manifest(&manifest_path).unwrap();
+        manifest.history = vec![crate::models::Release {
+            version: old.version,
+            targets: old.targets,
+        }];
+        fs::write(&manifest_path, toml::to_string(&manifest).unwrap()).unwrap();
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let latest = find_package_version(&engine, "tool", None).unwrap();
+        assert_eq!(latest.version, "2.0.0");
+        assert_eq!(latest.history.len(), 1);
+
+        let older = find_package_version(&engine, "tool", Some("1.0.0")).unwrap();
+        assert_eq!(older.version, "1.0.0");
+        assert!(older.history.is_empty());
+        let target = &older.targets[&crate::test_utils::current_target()];
+        assert!(target.url.ends_with("tool-1.0.0.tar.gz"));
+
+        assert!(find_package_version(&engine, "tool", Some("1.5.0")).is_none());
+        assert!(find_package_version(&engine, "ghost", None).is_none());
+
+        // Installing the older release records it
+        let target = engine.target().to_string();
+        engine
+            .install_from_manifest("tool", &older, &target, &mut ())
+            .unwrap();
+        assert_eq!(engine.state.packages["tool"].version, "1.0.0");
+    }
+
+    #[test]
+    fn test_outdated() {
+        use crate::test_utils::MockEnvironment;
+
+        let mock = MockEnvironment::new();
+        mock.add_package("old", "2.0.0", "old");
+        mock.add_package("current", "1.0.0", "current");
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+        for (name, version) in [("old", "1.0.0"), ("current", "1.0.0"), ("gone", "0.1.0")] {
+            engine.state.packages.insert(
+                name.to_string(),
+                crate::models::InstalledPackage {
+                    version: version.to_string(),
+                    binaries: vec![name.to_string()],
+                    files: Vec::new(),
+                    provenance: None,
+                    constraint: None,
+                    pinned: false,
+                    dependency: false,
+                },
+            );
+        }
+
+        let outdated = outdated(&engine);
+        assert_eq!(
+            outdated,
+            [OutdatedPackage {
+                package: "old".to_string(),
+                installed: "1.0.0".to_string(),
+                available: "2.0.0".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn test_find_package_missing() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root).unwrap();
+        let engine = RushEngine::builder().root(root).build().unwrap();
 
         let manifest = find_package(&engine, "ghost-pkg");
         assert!(manifest.is_none());
@@ -109,7 +324,7 @@ mod tests {
     fn test_list_available_packages() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root).unwrap();
+        let engine = RushEngine::builder().root(root).build().unwrap();
 
         // Setup: Create packages 'a-pkg' and 'b-pkg'
         let packages_root = engine.registry_dir.join("packages");
@@ -150,11 +365,51 @@ mod tests {
         assert_eq!(list[1].0, "b-pkg");
     }
 
+    #[test]
+    fn test_sort_packages() {
+        let manifest = |updated: &str, size: &str| -> PackageManifest {
+            toml::from_str(&format!(
+                r#"version = "1.0"
+                   {updated}
+                   [targets.x86_64-linux]
+                   url = ""
+                   sha256 = ""
+                   {size}"#
+            ))
+            .unwrap()
+        };
+        let mut packages = vec![
+            ("a".to_string(), manifest("", "size = 300")),
+            (
+                "b".to_string(),
+                manifest(r#"updated = "2025-03-01T00:00:00Z""#, ""),
+            ),
+            (
+                "c".to_string(),
+                manifest(r#"updated = "2025-06-01T00:00:00Z""#, "size = 20"),
+            ),
+        ];
+        let names = |packages: &[(String, PackageManifest)]| -> Vec<String> {
+            packages.iter().map(|(name, _)| name.clone()).collect()
+        };
+
+        sort_packages(&mut packages, PackageOrder::Updated, "x86_64-linux");
+        assert_eq!(names(&packages), ["c", "b", "a"]);
+        sort_packages(&mut packages, PackageOrder::Name, "x86_64-linux");
+        assert_eq!(names(&packages), ["a", "b", "c"]);
+        sort_packages(&mut packages, PackageOrder::Size, "x86_64-linux");
+        assert_eq!(names(&packages), ["c", "a", "b"]);
+        // Sizes are of the given target's artifact
+        sort_packages(&mut packages, PackageOrder::Name, "x86_64-linux");
+        sort_packages(&mut packages, PackageOrder::Size, "aarch64-macos");
+        assert_eq!(names(&packages), ["a", "b", "c"]);
+    }
+
     #[test]
     fn test_find_package_empty_string() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root).unwrap();
+        let engine = RushEngine::builder().root(root).build().unwrap();
 
         // Should return None safely, not panic on .chars().next()
         assert!(find_package(&engine, "").is_none());
@@ -164,7 +419,7 @@ mod tests {
     fn test_find_package_corrupted_file() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root.clone()).unwrap();
+        let engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         let prefix_dir = engine.registry_dir.join("packages").join("c");
         fs::create_dir_all(&prefix_dir).unwrap();
@@ -180,7 +435,7 @@ mod tests {
     fn test_list_skips_bad_files() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root).unwrap();
+        let engine = RushEngine::builder().root(root).build().unwrap();
 
         let packages_root = engine.registry_dir.join("packages");
 
@@ -209,11 +464,72 @@ mod tests {
         assert_eq!(list[0].0, "good");
     }
 
+    #[test]
+    fn test_packages_parse_lazily() {
+        use crate::core::RegistryBackend;
+        use crate::models::ProgressKind;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Lists three names and counts how many manifests are asked for
+        struct Counting(Arc<AtomicUsize>);
+        impl RegistryBackend for Counting {
+            fn source(&self) -> &str {
+                "counting://"
+            }
+            fn is_remote(&self) -> bool {
+                false
+            }
+            fn sync(
+                &self,
+                _: &Path,
+                _: &dyn Transport,
+                _: &mut dyn FnMut(ProgressKind),
+            ) -> Result<()> {
+                Ok(())
+            }
+            fn package_names(&self, _: &Path, _: &dyn Transport) -> Vec<String> {
+                vec!["a".to_string(), "broken".to_string(), "c".to_string()]
+            }
+            fn find_package(
+                &self,
+                _: &Path,
+                _: &dyn Transport,
+                name: &str,
+            ) -> Option<PackageManifest> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                (name != "broken")
+                    .then(|| toml::from_str("version = \"1.0.0\"\n[targets]\n").unwrap())
+            }
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let reads = Arc::new(AtomicUsize::new(0));
+        let engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .registry_backend(Counting(reads.clone()))
+            .build()
+            .unwrap();
+
+        // Names alone never touch a manifest
+        assert_eq!(engine.package_names(), ["a", "broken", "c"]);
+        assert_eq!(reads.load(Ordering::SeqCst), 0);
+
+        // Stopping after the first package reads one manifest
+        let first: Vec<_> = engine.packages().take(1).collect();
+        assert_eq!(first[0].0, "a");
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+        // Unreadable manifests are skipped, as in `list_available_packages`
+        let names: Vec<_> = engine.packages().map(|(name, _)| name).collect();
+        assert_eq!(names, ["a", "c"]);
+    }
+
     #[test]
     fn test_list_empty_registry_dir() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let engine = RushEngine::with_root(root).unwrap();
+        let engine = RushEngine::builder().root(root).build().unwrap();
 
         // Do not create the 'packages' directory here
         // simulating a fresh install before 'rush update'
@@ -0,0 +1,558 @@
+//! Where the package registry comes from.
+//!
+//! A `RegistryBackend` fills the engine's local copy of the registry (`sync`) and answers
+//! lookups. The built-in backends all mirror `packages/` into that directory and read it
+//! back; a backend that fetches manifests on demand can override the lookups instead.
+
+use crate::core::dev::{INDEX_FILE, SIGNATURE_FILE};
+use crate::core::{Transport, query, util};
+use crate::models::{PackageManifest, ProgressKind};
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tar::Archive;
+use walkdir::WalkDir;
+
+/// A source of package manifests
+pub trait RegistryBackend: Send + Sync {
+    /// Where the registry comes from, as shown to the user
+    fn source(&self) -> &str;
+
+    /// Whether `sync` needs the network (refused in offline mode)
+    fn is_remote(&self) -> bool;
+
+    /// Replace the local copy in `dir` with the current registry.
+    /// On failure the old copy should be left usable where possible.
+    fn sync(
+        &self,
+        dir: &Path,
+        transport: &dyn Transport,
+        on_event: &mut dyn FnMut(ProgressKind),
+    ) -> Result<()>;
+
+    /// The `.tar.gz` URL `sync` downloads, if fetching one archive is all it does.
+    /// The engine then downloads it itself instead of calling `sync`, so it can check the
+    /// archive against its `[registry.archives]` pin first (and, in `AsyncRushEngine`, use
+    /// async I/O rather than the blocking pool).
+    fn archive_url(&self) -> Option<&str> {
+        None
+    }
+
+    /// Look up one package; by default in the local copy (`dir/packages/f/fzf.toml`).
+    /// `transport` is for backends that fetch manifests on demand.
+    fn find_package(
+        &self,
+        dir: &Path,
+        _transport: &dyn Transport,
+        name: &str,
+    ) -> Option<PackageManifest> {
+        query::read_manifest(dir, name)
+    }
+
+    /// Every package name, sorted; by default from the local copy's file names.
+    /// Manifests are only read when `find_package` is asked for them.
+    fn package_names(&self, dir: &Path, _transport: &dyn Transport) -> Vec<String> {
+        query::scan_names(dir)
+    }
+
+    /// A copy of the artifact with this sha256 the registry carries itself (as a
+    /// `rush mirror` directory does), so installing it needs no download
+    fn artifact_path(&self, _sha256: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Picks the backend for a `RUSH_REGISTRY_URL`-style source:
+/// `git+<url>[#branch]` is a git repository, `sparse+<url>` is a sparse HTTP registry,
+/// `http(s)://` and `file://` URLs are tarballs, and anything else is a local directory.
+pub fn backend_for_source(source: &str) -> Box<dyn RegistryBackend> {
+    if let Some(url) = source.strip_prefix("git+") {
+        Box::new(GitBackend::new(source, url))
+    } else if let Some(url) = source.strip_prefix("sparse+") {
+        Box::new(SparseHttpBackend {
+            source: source.to_string(),
+            base: url.trim_end_matches('/').to_string(),
+        })
+    } else if source.starts_with("http") || source.starts_with("file://") {
+        Box::new(TarballBackend {
+            url: source.to_string(),
+        })
+    } else {
+        Box::new(LocalDirBackend {
+            path: source.to_string(),
+        })
+    }
+}
+
+/// A registry checkout on this machine (what `rush dev` commands edit)
+pub struct LocalDirBackend {
+    path: String,
+}
+
+impl RegistryBackend for LocalDirBackend {
+    fn source(&self) -> &str {
+        &self.path
+    }
+
+    fn is_remote(&self) -> bool {
+        false
+    }
+
+    fn sync(&self, dir: &Path, _: &dyn Transport, _: &mut dyn FnMut(ProgressKind)) -> Result<()> {
+        let source_path = PathBuf::from(&self.path);
+        if !source_path.exists() {
+            return Err(crate::Error::RegistryNotFound { path: source_path }.into());
+        }
+
+        reset_dir(dir)?;
+        copy_packages(&source_path, dir)?;
+        record_revision(&source_path, dir);
+        Ok(())
+    }
+
+    fn artifact_path(&self, sha256: &str) -> Option<PathBuf> {
+        artifact_file(Path::new(&self.path), sha256).filter(|path| path.is_file())
+    }
+}
+
+/// A `.tar.gz` of the registry repository (e.g. GitHub's branch archive)
+pub struct TarballBackend {
+    url: String,
+}
+
+impl RegistryBackend for TarballBackend {
+    fn source(&self) -> &str {
+        &self.url
+    }
+
+    fn is_remote(&self) -> bool {
+        !self.url.starts_with("file://")
+    }
+
+    fn archive_url(&self) -> Option<&str> {
+        Some(&self.url)
+    }
+
+    fn sync(
+        &self,
+        dir: &Path,
+        transport: &dyn Transport,
+        on_event: &mut dyn FnMut(ProgressKind),
+    ) -> Result<()> {
+        // Download before wiping, so a failed fetch keeps the old registry usable
+        let content = util::download_url(transport, &self.url, &mut |kind| on_event(kind))?;
+
+        on_event(ProgressKind::Unpacking);
+        reset_dir(dir)?;
+        unpack_packages(&content, dir)
+    }
+}
+
+/// A git repository, cloned shallowly on every sync (`git+https://host/repo.git#branch`)
+pub struct GitBackend {
+    source: String,
+    url: String,
+    branch: Option<String>,
+}
+
+impl GitBackend {
+    fn new(source: &str, url: &str) -> Self {
+        let (url, branch) = match url.split_once('#') {
+            Some((url, branch)) => (url, Some(branch.to_string())),
+            None => (url, None),
+        };
+        Self {
+            source: source.to_string(),
+            url: url.to_string(),
+            branch,
+        }
+    }
+}
+
+impl RegistryBackend for GitBackend {
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn is_remote(&self) -> bool {
+        !self.url.starts_with("file://")
+    }
+
+    fn sync(&self, dir: &Path, _: &dyn Transport, _: &mut dyn FnMut(ProgressKind)) -> Result<()> {
+        let checkout = tempfile::tempdir()?;
+
+        let mut command = Command::new("git");
+        command.args(["clone", "--quiet", "--depth", "1"]);
+        if let Some(branch) = &self.branch {
+            command.args(["--branch", branch]);
+        }
+        // `--` so a URL starting with `-` can't be taken for an option
+        let output = command
+            .arg("--")
+            .arg(&self.url)
+            .arg(checkout.path())
+            .output()
+            .context("Failed to run git (is it installed?)")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git clone {} failed: {}",
+                self.url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        reset_dir(dir)?;
+        copy_packages(checkout.path(), dir)?;
+        record_revision(checkout.path(), dir);
+        Ok(())
+    }
+}
+
+/// A registry served file by file (`sparse+https://host/registry`).
+/// `sync` only fetches `<base>/index`, one package name per line; each manifest
+/// (`<base>/packages/f/fzf.toml`) is downloaded the first time it is looked up and then
+/// cached in the local copy until the next sync.
+pub struct SparseHttpBackend {
+    source: String,
+    base: String,
+}
+
+impl SparseHttpBackend {
+    /// Package names from the synced index (empty before the first sync), leaving out any
+    /// that isn't a plain file name
+    fn index(dir: &Path) -> Vec<String> {
+        fs::read_to_string(dir.join("index"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty() && !name.starts_with('#'))
+            .filter(|name| Self::is_plain_name(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Helper: Whether `name` can be joined to the local copy without leaving it: no `/`,
+    /// `\` or `..`, just one plain component
+    fn is_plain_name(name: &str) -> bool {
+        let mut components = Path::new(name).components();
+        !name.contains('\\')
+            && matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            )
+    }
+}
+
+impl RegistryBackend for SparseHttpBackend {
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn is_remote(&self) -> bool {
+        !self.base.starts_with("file://")
+    }
+
+    fn sync(
+        &self,
+        dir: &Path,
+        transport: &dyn Transport,
+        on_event: &mut dyn FnMut(ProgressKind),
+    ) -> Result<()> {
+        let index = util::download_url(transport, &format!("{}/index", self.base), &mut |kind| {
+            on_event(kind)
+        })?;
+
+        // Dropping the cached manifests makes the next lookups fetch fresh ones
+        reset_dir(dir)?;
+        fs::write(dir.join("index"), index)?;
+        Ok(())
+    }
+
+    fn find_package(
+        &self,
+        dir: &Path,
+        transport: &dyn Transport,
+        name: &str,
+    ) -> Option<PackageManifest> {
+        if !Self::is_plain_name(name) {
+            return None;
+        }
+        if let Some(manifest) = query::read_manifest(dir, name) {
+            return Some(manifest);
+        }
+        if !Self::index(dir).iter().any(|known| known == name) {
+            return None;
+        }
+
+        let prefix = name.chars().next()?;
+        let relative = format!("packages/{}/{}.toml", prefix, name);
+        let content = util::download_url(
+            transport,
+            &format!("{}/{}", self.base, relative),
+            &mut |_| {},
+        )
+        .ok()?;
+        let manifest = toml::from_str(std::str::from_utf8(&content).ok()?).ok()?;
+
+        // Caching is best effort; the manifest is still returned if the write fails
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, &content);
+        Some(manifest)
+    }
+
+    fn package_names(&self, dir: &Path, _transport: &dyn Transport) -> Vec<String> {
+        let mut names = Self::index(dir);
+        names.sort();
+        names
+    }
+}
+
+/// Helper: Empty the local registry copy
+pub(crate) fn reset_dir(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
+/// Where a mirror keeps artifacts, named by their sha256, next to its `packages/`
+/// (and where the cache dir keeps them too)
+pub(crate) const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Where the artifact with this sha256 belongs under `dir`; `None` unless `sha256` is a
+/// bare hex digest, so it can't name a path outside `dir/artifacts`
+pub(crate) fn artifact_file(dir: &Path, sha256: &str) -> Option<PathBuf> {
+    if sha256.is_empty() || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(dir.join(ARTIFACTS_DIR).join(sha256))
+}
+
+/// The commit a synced copy came from, when its source is a git checkout
+pub(crate) const REVISION_FILE: &str = "revision";
+
+/// Helper: Note the commit `checkout` is at in `dir`, if it is a git checkout (and git
+/// is installed); provenance records it, but syncing doesn't depend on it
+fn record_revision(checkout: &Path, dir: &Path) {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(checkout)
+        .output();
+    if let Some(output) = output.ok().filter(|o| o.status.success()) {
+        let _ = fs::write(dir.join(REVISION_FILE), &output.stdout);
+    }
+}
+
+/// Helper: Copy `packages/` (and the signed index, if any) from a registry checkout
+/// into `dir`
+pub(crate) fn copy_packages(source_path: &Path, dir: &Path) -> Result<()> {
+    for file in [INDEX_FILE, SIGNATURE_FILE] {
+        if source_path.join(file).is_file() {
+            fs::copy(source_path.join(file), dir.join(file))?;
+        }
+    }
+
+    let pkg_source = source_path.join("packages");
+    if !pkg_source.exists() {
+        return Ok(());
+    }
+
+    let pkg_dest = dir.join("packages");
+    for entry in WalkDir::new(&pkg_source) {
+        let entry = entry?;
+        if let Ok(rel_path) = entry.path().strip_prefix(&pkg_source) {
+            let dest_path = pkg_dest.join(rel_path);
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&dest_path)?;
+            } else {
+                fs::copy(entry.path(), &dest_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Helper: Unpack the `packages/` folder of a downloaded registry tarball into `dir`,
+/// with the signed index from the repository root
+pub(crate) fn unpack_packages(content: &[u8], dir: &Path) -> Result<()> {
+    let tar = GzDecoder::new(content);
+    let mut archive = Archive::new(tar);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?;
+
+        // GitHub archives put the repository root one level down ("repo-main/")
+        let root_file = match path.components().count() {
+            1 | 2 => path.file_name().and_then(|n| n.to_str()),
+            _ => None,
+        };
+        if let Some(name @ (INDEX_FILE | SIGNATURE_FILE)) = root_file {
+            let dest = dir.join(name);
+            entry.unpack(dest)?;
+            continue;
+        }
+
+        if let Some(idx) = path.to_string_lossy().find("packages/") {
+            let relative_path = &path.to_string_lossy()[idx..];
+            let dest = dir.join(relative_path);
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            entry.unpack(dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transport::mock::MockTransport;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_backend_for_source() {
+        assert!(!backend_for_source("/srv/registry").is_remote());
+        assert!(backend_for_source("https://example.com/r.tar.gz").is_remote());
+        assert!(!backend_for_source("file:///tmp/r.tar.gz").is_remote());
+        assert!(backend_for_source("git+https://example.com/r.git#main").is_remote());
+        assert!(backend_for_source("sparse+https://example.com/r").is_remote());
+        assert_eq!(
+            backend_for_source("git+https://example.com/r.git#main").source(),
+            "git+https://example.com/r.git#main"
+        );
+    }
+
+    #[test]
+    fn test_sparse_backend_fetches_on_demand() {
+        use crate::core::transport::mock::MockResponse;
+
+        let manifest = b"version = \"2.0.0\"\n[targets]\n".to_vec();
+        let transport = MockTransport::default()
+            .with(
+                "https://example.com/reg/index",
+                MockResponse::Body(b"fzf\nbroken\n../../escape\n".to_vec()),
+            )
+            .with(
+                "https://example.com/reg/packages/f/fzf.toml",
+                MockResponse::Body(manifest.clone()),
+            )
+            .with(
+                "https://example.com/reg/packages/./../../escape.toml",
+                MockResponse::Body(manifest),
+            )
+            .with(
+                "https://example.com/reg/packages/b/broken.toml",
+                MockResponse::Body(b"not toml".to_vec()),
+            );
+
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("copy");
+        let backend = backend_for_source("sparse+https://example.com/reg/");
+        backend.sync(&dir, &transport, &mut |_| {}).unwrap();
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        // Unlisted names never hit the network
+        assert!(backend.find_package(&dir, &transport, "../etc").is_none());
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        // Nor do names that would lead out of the local copy, even if the index lists them
+        assert!(
+            backend
+                .find_package(&dir, &transport, "../../escape")
+                .is_none()
+        );
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+        assert!(!temp_dir.path().join("escape.toml").exists());
+
+        let fzf = backend.find_package(&dir, &transport, "fzf").unwrap();
+        assert_eq!(fzf.version, "2.0.0");
+        // Second lookup is served from the cache
+        backend.find_package(&dir, &transport, "fzf").unwrap();
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+
+        // Names come from the index alone; a broken manifest only shows when it's read
+        assert_eq!(backend.package_names(&dir, &transport), ["broken", "fzf"]);
+        assert_eq!(transport.requests.lock().unwrap().len(), 2);
+        assert!(backend.find_package(&dir, &transport, "broken").is_none());
+    }
+
+    #[test]
+    fn test_git_backend_sync() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path().join("repo");
+        fs::create_dir_all(repo.join("packages/b")).unwrap();
+        fs::write(
+            repo.join("packages/b/bat.toml"),
+            "version = \"1.0.0\"\n[targets]\n",
+        )
+        .unwrap();
+        git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+        git(&repo, &["add", "."]);
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+        );
+
+        let dir = temp_dir.path().join("copy");
+        let backend = backend_for_source(&format!("git+file://{}#main", repo.display()));
+        backend
+            .sync(&dir, &MockTransport::default(), &mut |_| {})
+            .unwrap();
+
+        assert_eq!(
+            backend
+                .find_package(&dir, &MockTransport::default(), "bat")
+                .unwrap()
+                .version,
+            "1.0.0"
+        );
+        assert_eq!(
+            backend.package_names(&dir, &MockTransport::default()),
+            ["bat"]
+        );
+
+        let missing = backend_for_source(&format!(
+            "git+file://{}",
+            temp_dir.path().join("nope").display()
+        ));
+        assert!(
+            missing
+                .sync(&dir, &MockTransport::default(), &mut |_| {})
+                .is_err()
+        );
+        // A failed clone leaves the previous copy alone
+        assert!(
+            backend
+                .find_package(&dir, &MockTransport::default(), "bat")
+                .is_some()
+        );
+    }
+}
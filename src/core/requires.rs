@@ -0,0 +1,269 @@
+//! `requires` in a manifest: the system libraries a binary needs at runtime.
+//!
+//! rush doesn't install system packages, so the field is informational. Before an install
+//! the obvious cases are checked (the glibc version, and whether a library is in one of
+//! the usual lib dirs); afterwards `ldd` says whether the binary's libraries resolve.
+//! Either way the result is only a warning about a likely failure at first run.
+
+use crate::core::{RushEngine, sandbox};
+use crate::models::{PackageManifest, RequirementWarning};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// How long `ldd` may take on one binary
+const LDD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The manifest's `requires` this system doesn't seem to meet. Only Linux is checked.
+pub fn unmet_requirements(name: &str, manifest: &PackageManifest) -> Vec<RequirementWarning> {
+    if !cfg!(target_os = "linux") || manifest.requires.is_empty() {
+        return Vec::new();
+    }
+    let glibc = glibc_version();
+    let dirs = lib_dirs();
+
+    manifest
+        .requires
+        .iter()
+        .filter_map(|requirement| {
+            Some(RequirementWarning {
+                package: name.to_string(),
+                requirement: requirement.clone(),
+                problem: unmet(requirement, glibc.as_deref(), &dirs)?,
+            })
+        })
+        .collect()
+}
+
+/// Libraries the installed binaries of `name` link against that the dynamic linker can't
+/// find, per `ldd` (run in the sandbox, as it can end up running the binary's loader).
+/// Nothing is reported where `ldd` or the sandbox isn't available.
+pub fn missing_libraries(engine: &RushEngine, name: &str) -> Vec<RequirementWarning> {
+    let Some(installed) = engine.state.packages.get(name) else {
+        return Vec::new();
+    };
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    for bin in &installed.binaries {
+        let path = engine.bin_path.join(bin);
+        let Some(path) = path.to_str().filter(|_| path.is_file()) else {
+            continue;
+        };
+        let output = match sandbox::run(Path::new("ldd"), &[path], LDD_TIMEOUT) {
+            Ok(output) => output,
+            Err(e) => {
+                tracing::debug!(error = %e, "ldd not run");
+                return Vec::new();
+            }
+        };
+        // A static binary makes ldd fail, with nothing to report
+        for library in not_found(&output.stdout) {
+            warnings.push(RequirementWarning {
+                package: name.to_string(),
+                requirement: library.to_string(),
+                problem: format!("{} links against it, but it isn't installed", bin),
+            });
+        }
+    }
+    warnings
+}
+
+/// Helper: Why `requirement` ("libssl", "glibc>=2.31", "libssl>=3") looks unmet, if it does
+fn unmet(requirement: &str, glibc: Option<&str>, lib_dirs: &[PathBuf]) -> Option<String> {
+    let (library, min) = match requirement.split_once(">=") {
+        Some((library, min)) => (library.trim(), Some(min.trim())),
+        None => (requirement.trim(), None),
+    };
+
+    if library == "glibc" {
+        let Some(have) = glibc else {
+            return Some("glibc not found (a musl-based system?)".to_string());
+        };
+        return min
+            .filter(|min| compare_versions(have, min) == Ordering::Less)
+            .map(|min| format!("needs glibc {} or newer, this system has {}", min, have));
+    }
+
+    let soname = if library.starts_with("lib") {
+        library.to_string()
+    } else {
+        format!("lib{}", library)
+    };
+    let versions = library_versions(&soname, lib_dirs);
+    if versions.is_empty() {
+        return Some(format!("{} not found in the system lib dirs", soname));
+    }
+    // A bare `libfoo.so` says nothing about the version
+    let versioned: Vec<&str> = versions
+        .iter()
+        .map(String::as_str)
+        .filter(|v| !v.is_empty())
+        .collect();
+    let min = min.filter(|_| !versioned.is_empty())?;
+    if versioned
+        .iter()
+        .any(|v| compare_versions(v, min) != Ordering::Less)
+    {
+        return None;
+    }
+    Some(format!(
+        "needs {} {} or newer, found {}",
+        soname,
+        min,
+        versioned.join(", ")
+    ))
+}
+
+/// Helper: The versions of `soname` in `lib_dirs`, from file names like `libssl.so.3`
+/// ("" for a bare `libssl.so`); empty if there is none
+fn library_versions(soname: &str, lib_dirs: &[PathBuf]) -> Vec<String> {
+    let prefix = format!("{}.so", soname);
+    let mut versions: Vec<String> = lib_dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let rest = name.strip_prefix(&prefix)?;
+            // "libssl.so.3" but not "libssl.somethingelse"
+            (rest.is_empty() || rest.starts_with('.'))
+                .then(|| rest.trim_start_matches('.').to_string())
+        })
+        .collect();
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+/// Helper: Where shared libraries usually live
+fn lib_dirs() -> Vec<PathBuf> {
+    let multiarch = format!("{}-linux-gnu", std::env::consts::ARCH);
+    ["/lib", "/lib64", "/usr/lib", "/usr/lib64", "/usr/local/lib"]
+        .into_iter()
+        .map(PathBuf::from)
+        .chain(
+            ["/lib", "/usr/lib"]
+                .into_iter()
+                .map(|dir| Path::new(dir).join(&multiarch)),
+        )
+        .collect()
+}
+
+/// Helper: The system's glibc version, from `ldd --version`; None if it isn't glibc
+fn glibc_version() -> Option<String> {
+    let output = Command::new("ldd").arg("--version").output().ok()?;
+    parse_glibc_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Helper: "ldd (Debian GLIBC 2.36-9) 2.36" -> "2.36". musl's ldd prints something else.
+fn parse_glibc_version(ldd_version: &str) -> Option<String> {
+    let first = ldd_version.lines().next()?;
+    let upper = first.to_uppercase();
+    if !(upper.contains("GLIBC") || upper.contains("GNU LIBC")) {
+        return None;
+    }
+    first
+        .split_whitespace()
+        .last()
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// Helper: The libraries `ldd` output lists as "libfoo.so.1 => not found"
+fn not_found(ldd_output: &str) -> Vec<&str> {
+    ldd_output
+        .lines()
+        .filter_map(|line| line.trim().strip_suffix("=> not found"))
+        .map(str::trim)
+        .collect()
+}
+
+/// Helper: Compare dotted versions numerically ("2.9" < "2.31"); a missing part counts as 0
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.split(['.', '-'])
+            .map(|p| {
+                p.chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_unmet_requirements() {
+        let temp_dir = tempdir().unwrap();
+        let lib = temp_dir.path().to_path_buf();
+        for file in ["libssl.so.3", "libz.so.1.2.13", "libz.so", "libsslx.so.9"] {
+            fs::write(lib.join(file), "").unwrap();
+        }
+        let dirs = [lib];
+        let unmet = |requirement| unmet(requirement, Some("2.31"), &dirs);
+
+        assert_eq!(unmet("libssl"), None);
+        assert_eq!(unmet("ssl>=3"), None);
+        assert_eq!(
+            unmet("libssl>=3.1").unwrap(),
+            "needs libssl 3.1 or newer, found 3"
+        );
+        assert_eq!(unmet("libz >= 1.2.11"), None);
+        assert_eq!(
+            unmet("libgtk-3").unwrap(),
+            "libgtk-3 not found in the system lib dirs"
+        );
+
+        assert_eq!(unmet("glibc>=2.28"), None);
+        assert_eq!(
+            unmet("glibc>=2.34").unwrap(),
+            "needs glibc 2.34 or newer, this system has 2.31"
+        );
+        assert!(super::unmet("glibc", None, &dirs).unwrap().contains("musl"));
+    }
+
+    #[test]
+    fn test_parse_tool_output() {
+        assert_eq!(
+            parse_glibc_version("ldd (Debian GLIBC 2.36-9+deb12u4) 2.36\nCopyright"),
+            Some("2.36".to_string())
+        );
+        assert_eq!(
+            parse_glibc_version("ldd (GNU libc) 2.39\n"),
+            Some("2.39".to_string())
+        );
+        assert_eq!(
+            parse_glibc_version("musl libc (x86_64)\nVersion 1.2.4"),
+            None
+        );
+
+        let ldd = "\tlinux-vdso.so.1 (0x00007ffd)\n\
+                   \tlibssl.so.1.1 => not found\n\
+                   \tlibc.so.6 => /lib/x86_64-linux-gnu/libc.so.6 (0x00007f)\n";
+        assert_eq!(not_found(ldd), ["libssl.so.1.1"]);
+
+        assert_eq!(compare_versions("2.9", "2.31"), Ordering::Less);
+        assert_eq!(compare_versions("3", "3.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.1.1w", "1.1.1"), Ordering::Equal);
+    }
+}
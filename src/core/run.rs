@@ -0,0 +1,210 @@
+//! `rush run`: a package's binary, ready to execute, without installing it.
+//!
+//! An installed package at the wanted version is run from the bin dir. Anything else is
+//! unpacked into `~/.cache/rush/run/<package>/<version>/` and run from there, never
+//! recorded as installed, with the same checksum and pin checks as an install. Only the
+//! latest version run is kept per package.
+
+use crate::core::{RushEngine, appimage, install, pins, util};
+use crate::models::{ArtifactFormat, NewPackage, ProgressEvent, ProgressKind, TargetDefinition};
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where run packages are unpacked, under the cache dir
+pub(crate) const RUN_DIR: &str = "run";
+
+/// The path of `name`'s binary at `version` (default: the registry's), fetching it into
+/// the run cache if it isn't installed or cached already
+pub fn prepare_run<F>(
+    engine: &RushEngine,
+    name: &str,
+    version: Option<&str>,
+    on_event: F,
+) -> Result<PathBuf>
+where
+    F: FnMut(ProgressEvent),
+{
+    if !engine.targets_host() {
+        bail!(
+            "Can't run binaries built for {} on this machine",
+            engine.target()
+        );
+    }
+
+    if let Some(installed) = engine.state.packages.get(name)
+        && version.is_none_or(|v| v == installed.version)
+        && let Some(bin) = installed.binaries.first()
+    {
+        let path = engine.bin_path.join(bin);
+        if path.is_file() {
+            return Ok(path);
+        }
+    }
+
+    let package_dir = engine.cache_dir.join(RUN_DIR).join(name);
+    fetch_version(engine, name, version, &package_dir, on_event)
+}
+
+/// The binary of `name` at `version` (default: the registry's) in `package_dir/<version>/`,
+/// fetched there unless it already is. Only versions the registry offers (its current one
+/// or its `history`) can be fetched; once one is, the other versions in `package_dir` are
+/// removed.
+pub(crate) fn fetch_version<F>(
+    engine: &RushEngine,
+    name: &str,
+    version: Option<&str>,
+    package_dir: &Path,
+    on_event: F,
+) -> Result<PathBuf>
+where
+    F: FnMut(ProgressEvent),
+{
+    NewPackage::validate_name(name).map_err(anyhow::Error::msg)?;
+    let manifest = engine
+        .find_package(name)
+        .with_context(|| format!("Package '{}' not found", name))?;
+    let version = version.unwrap_or(&manifest.version);
+    validate_version(version)?;
+    let release = manifest.release(version);
+    let target =
+        install::manifest_target(name, release.as_ref().unwrap_or(&manifest), engine.target())?;
+    if target.bin.is_empty() {
+        bail!("'{}' has no binary to run", name);
+    }
+
+    let version_dir = package_dir.join(version);
+    let path = version_dir.join(target.main_bin());
+    if path.is_file() {
+        return Ok(path);
+    }
+    if release.is_none() {
+        bail!(
+            "The registry has {} {}, not {}, and it hasn't been fetched before",
+            name,
+            manifest.version,
+            version
+        );
+    }
+
+    let path = fetch_binary(engine, name, version, target, &version_dir, on_event)?;
+    remove_other_versions(package_dir, version)?;
+    Ok(path)
+}
+
+/// A version is a directory name under a package's dir, so it can't leave it
+pub(crate) fn validate_version(version: &str) -> Result<()> {
+    if version.is_empty() || version.starts_with('.') || version.contains(['/', '\\']) {
+        bail!("Invalid version '{}'", version);
+    }
+    Ok(())
+}
+
+/// Download `target`'s artifact for `name` at `version`, check it as an install would
+/// (pins and checksum), and unpack its binary into `dir`
+fn fetch_binary<F>(
+    engine: &RushEngine,
+    name: &str,
+    version: &str,
+    target: &TargetDefinition,
+    dir: &Path,
+    mut on_event: F,
+) -> Result<PathBuf>
+where
+    F: FnMut(ProgressEvent),
+{
+    let mut on_event = util::tag_progress(Some(name), &mut on_event);
+    let content = match install::local_artifact(engine, target) {
+        Some(content) => content,
+        None => {
+            engine.ensure_online(&target.url)?;
+            util::download_url(engine.transport.as_ref(), &target.url, &mut on_event)?
+        }
+    };
+    pins::check_install(engine, name, version, target)?;
+    on_event(ProgressKind::VerifyingChecksum);
+    util::verify_checksum(&content, &target.sha256)?;
+
+    on_event(ProgressKind::Extracting);
+    fs::create_dir_all(dir)?;
+    let path = unpack(engine, target, &content, dir)?;
+    on_event(ProgressKind::Success);
+    Ok(path)
+}
+
+/// Helper: Put the binary of `target` in `dir`; an AppImage or bare binary is the binary
+/// itself
+fn unpack(
+    engine: &RushEngine,
+    target: &TargetDefinition,
+    content: &[u8],
+    dir: &Path,
+) -> Result<PathBuf> {
+    match target.format {
+        ArtifactFormat::Archive => {
+            install::extract_binary_into(content, dir, target.main_bin(), &engine.cancel)
+        }
+        ArtifactFormat::AppImage => {
+            let path = dir.join(target.main_bin());
+            fs::write(&path, content)?;
+            appimage::make_executable(&path)?;
+            Ok(path)
+        }
+        ArtifactFormat::Binary => {
+            install::write_binary_into(content, dir, target.main_bin(), &engine.cancel)
+        }
+    }
+}
+
+/// Helper: Drop the cached versions of a package other than `keep`
+fn remove_other_versions(package_dir: &Path, keep: &str) -> Result<()> {
+    for entry in fs::read_dir(package_dir)?.flatten() {
+        if entry.file_name() != keep {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+
+    #[test]
+    fn test_prepare_run() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let path = prepare_run(&engine, "tool", None, |_| {}).unwrap();
+        assert_eq!(path, engine.cache_dir.join("run/tool/1.0.0/tool"));
+        assert!(path.is_file());
+        // Run, not installed
+        assert!(!engine.state.packages.contains_key("tool"));
+        assert!(!engine.bin_path.join("tool").exists());
+
+        // A second run uses the cached copy, even offline
+        engine.offline = true;
+        assert_eq!(prepare_run(&engine, "tool", None, |_| {}).unwrap(), path);
+
+        // Only versions the registry offers can be fetched
+        let err = prepare_run(&engine, "tool", Some("0.9.0"), |_| {}).unwrap_err();
+        assert!(err.to_string().contains("not 0.9.0"));
+        assert!(prepare_run(&engine, "tool", Some("../../bin"), |_| {}).is_err());
+        assert!(prepare_run(&engine, "missing", None, |_| {}).is_err());
+
+        // Once installed, the installed binary is run
+        engine.offline = false;
+        let manifest = engine.find_package("tool").unwrap();
+        let target = engine.target().to_string();
+        engine
+            .install_from_manifest("tool", &manifest, &target, &mut ())
+            .unwrap();
+        assert_eq!(
+            prepare_run(&engine, "tool", Some("1.0.0"), |_| {}).unwrap(),
+            engine.bin_path.join("tool")
+        );
+    }
+}
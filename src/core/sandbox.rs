@@ -0,0 +1,297 @@
+//! Running a binary we just downloaded (a package's `check`) without trusting it.
+//!
+//! The command gets no network and can write only to a scratch directory, which is also
+//! its working directory and `$HOME`. On Linux that is a Landlock ruleset plus a seccomp
+//! filter, applied to a short-lived thread that then spawns the command, so the child
+//! inherits them and rush itself doesn't. On macOS the command runs under `sandbox-exec`.
+//! Anywhere else it isn't run at all.
+
+use crate::Error;
+use anyhow::{Context, Result};
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How a sandboxed command went
+#[derive(Debug)]
+pub(crate) struct SandboxOutput {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run `program` with `args` in the sandbox, killing it after `timeout`
+pub(crate) fn run(program: &Path, args: &[&str], timeout: Duration) -> Result<SandboxOutput> {
+    let work_dir = tempfile::tempdir()?;
+    run_in(program, args, work_dir.path(), timeout)
+}
+
+/// `run`, with `work_dir` as the one directory the command may write to, for commands
+/// whose output is wanted afterwards
+pub(crate) fn run_in(
+    program: &Path,
+    args: &[&str],
+    work_dir: &Path,
+    timeout: Duration,
+) -> Result<SandboxOutput> {
+    // The canonical path: sandbox rules are checked against resolved paths
+    let work_path = work_dir.canonicalize()?;
+
+    let mut command = platform::command(program, args, &work_path)?;
+    command
+        .current_dir(&work_path)
+        .env_clear()
+        .env("PATH", "/usr/bin:/bin")
+        .env("HOME", &work_path)
+        .env("TMPDIR", &work_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Its own process group, so a timeout kills anything it started too
+    #[cfg(unix)]
+    command.process_group(0);
+
+    platform::spawn_confined(command, &work_path, timeout)
+}
+
+/// Helper: Wait for `child` (draining its output as it goes), killing it after `timeout`
+fn wait(mut child: Child, timeout: Duration) -> Result<SandboxOutput> {
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            timed_out = true;
+            kill_group(&mut child);
+            break child.wait()?;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let text = |reader: std::thread::JoinHandle<Vec<u8>>| {
+        let bytes = reader.join().unwrap_or_default();
+        String::from_utf8_lossy(&bytes).trim().to_string()
+    };
+    Ok(SandboxOutput {
+        exit_code: status.code(),
+        timed_out,
+        stdout: text(stdout),
+        stderr: text(stderr),
+    })
+}
+
+/// Helper: SIGKILL the process group `child` leads
+#[cfg(unix)]
+fn kill_group(child: &mut Child) {
+    // SAFETY: kill(2) takes no pointers; a negative pid names the group `child` leads
+    let killed = unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+    if killed != 0 {
+        tracing::debug!(error = %std::io::Error::last_os_error(), "killing the check's group");
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
+/// Helper: Read a pipe to the end on its own thread, so a chatty child can't block on it
+fn drain<R>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>>
+where
+    R: Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+    use landlock::{
+        ABI, Access, AccessFs, AccessNet, Ruleset, RulesetAttr, RulesetCreatedAttr, RulesetStatus,
+        path_beneath_rules,
+    };
+    use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, TargetArch};
+    use std::collections::BTreeMap;
+
+    pub(super) fn command(program: &Path, args: &[&str], _work_dir: &Path) -> Result<Command> {
+        let mut command = Command::new(program);
+        command.args(args);
+        Ok(command)
+    }
+
+    pub(super) fn spawn_confined(
+        mut command: Command,
+        work_dir: &Path,
+        timeout: Duration,
+    ) -> Result<SandboxOutput> {
+        // Built up front, so a platform that can't filter sockets fails before anything runs
+        let filter = deny_network()?;
+        let work_dir = work_dir.to_path_buf();
+
+        std::thread::spawn(move || {
+            confine_fs(&work_dir)?;
+            seccompiler::apply_filter(&filter).context("Failed to install the seccomp filter")?;
+            wait(command.spawn()?, timeout)
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("The sandbox thread panicked"))?
+    }
+
+    /// Helper: Restrict the calling thread to reading (and running) anything, but writing
+    /// only under `work_dir` and to /dev/null, with no TCP. A kernel that can't enforce all
+    /// of that makes the sandbox unavailable.
+    fn confine_fs(work_dir: &Path) -> Result<()> {
+        let abi = ABI::V5;
+        let status = Ruleset::default()
+            .handle_access(AccessFs::from_all(abi))?
+            .handle_access(AccessNet::from_all(abi))?
+            .create()?
+            .add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi)))?
+            .add_rules(path_beneath_rules(
+                [work_dir, Path::new("/dev/null")],
+                AccessFs::from_all(abi),
+            ))?
+            .restrict_self()
+            .context("Failed to apply the Landlock ruleset")?;
+
+        if status.ruleset != RulesetStatus::FullyEnforced {
+            return Err(Error::SandboxUnavailable {
+                reason: format!("Landlock is {:?} by this kernel", status.ruleset),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Helper: A seccomp filter failing every `socket()` (Unix ones too, which reach local
+    /// daemons Landlock doesn't cover) and io_uring (which could open them behind the
+    /// filter's back) with EACCES. `socketpair()` is still allowed.
+    fn deny_network() -> Result<BpfProgram> {
+        let arch = TargetArch::try_from(std::env::consts::ARCH).map_err(|_| {
+            Error::SandboxUnavailable {
+                reason: format!("no seccomp filter for {}", std::env::consts::ARCH),
+            }
+        })?;
+
+        let rules = BTreeMap::from([
+            (libc::SYS_socket, vec![]),
+            (libc::SYS_io_uring_setup, vec![]),
+        ]);
+        let filter = SeccompFilter::new(
+            rules,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EACCES as u32),
+            arch,
+        )?;
+        Ok(filter.try_into()?)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+
+    pub(super) fn command(program: &Path, args: &[&str], work_dir: &Path) -> Result<Command> {
+        if !Path::new(SANDBOX_EXEC).exists() {
+            return Err(Error::SandboxUnavailable {
+                reason: format!("{} is missing", SANDBOX_EXEC),
+            }
+            .into());
+        }
+        let profile = format!(
+            "(version 1)(allow default)(deny network*)(deny file-write*)\
+             (allow file-write* (subpath {:?}) (literal \"/dev/null\"))",
+            work_dir.display().to_string()
+        );
+
+        let mut command = Command::new(SANDBOX_EXEC);
+        command.arg("-p").arg(profile).arg(program).args(args);
+        Ok(command)
+    }
+
+    pub(super) fn spawn_confined(
+        mut command: Command,
+        _work_dir: &Path,
+        timeout: Duration,
+    ) -> Result<SandboxOutput> {
+        wait(command.spawn()?, timeout)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    pub(super) fn command(_program: &Path, _args: &[&str], _work_dir: &Path) -> Result<Command> {
+        Err(Error::SandboxUnavailable {
+            reason: format!("no sandbox support on {}", std::env::consts::OS),
+        }
+        .into())
+    }
+
+    pub(super) fn spawn_confined(
+        _command: Command,
+        _work_dir: &Path,
+        _timeout: Duration,
+    ) -> Result<SandboxOutput> {
+        unreachable!("`command` never succeeds here")
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sandbox_confines_writes_and_kills_stragglers() {
+        let temp_dir = tempdir().unwrap();
+        let outside = temp_dir.path().join("escaped");
+        let script = temp_dir.path().join("probe");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$1\"\necho scratch > \"$HOME/ok\" && cat \"$HOME/ok\"\n\
+                 echo out > '{}'\n[ \"$1\" = slow ] && sleep 5\nexit 3\n",
+                outside.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        // A kernel without full Landlock runs nothing at all
+        let output = match run(&script, &["hello"], Duration::from_secs(10)) {
+            Err(e) if matches!(e.downcast_ref(), Some(Error::SandboxUnavailable { .. })) => {
+                return;
+            }
+            output => output.unwrap(),
+        };
+        assert_eq!(output.exit_code, Some(3));
+        assert!(!output.timed_out);
+        assert_eq!(output.stdout, "hello\nscratch");
+        assert!(!outside.exists());
+        assert!(!output.stderr.is_empty());
+
+        let output = run(&script, &["slow"], Duration::from_millis(300)).unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, None);
+    }
+}
@@ -0,0 +1,226 @@
+//! `rush schedule`: a daily `rush update && rush outdated --notify`, run by the system's
+//! own scheduler for the user.
+//!
+//! On Linux that is a systemd user timer (`~/.config/systemd/user/rush-update.{service,timer}`),
+//! on macOS a launchd agent (`~/Library/LaunchAgents/<LABEL>.plist`). Either runs the
+//! rush that installed it, by its full path.
+
+use crate::core::hooks::shell_quote;
+use crate::core::{Os, RushEngine, Target};
+use crate::models::ScheduleResult;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the systemd units
+const UNIT: &str = "rush-update";
+/// launchd job label, also the plist's name
+const LABEL: &str = "io.github.ekourtakis.rush.update";
+/// Seconds between runs under launchd, which has no "daily"
+const LAUNCHD_INTERVAL: u32 = 24 * 60 * 60;
+
+/// Write the scheduler's files for this OS and have it pick them up
+pub fn install_schedule(engine: &RushEngine) -> Result<ScheduleResult> {
+    let os = Target::host().os;
+    let exe = std::env::current_exe().context("Can't tell where the running rush is")?;
+    let files = schedule_files(os, home(engine)?, &exe)?;
+
+    for (path, contents) in &files {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents).with_context(|| format!("Failed to write {:?}", path))?;
+    }
+    let paths: Vec<PathBuf> = files.into_iter().map(|(path, _)| path).collect();
+    match os {
+        Os::Linux => {
+            systemctl(&["daemon-reload"])?;
+            systemctl(&["enable", "--now", &format!("{}.timer", UNIT)])?;
+        }
+        _ => launchctl(&["load", "-w"], &paths[0])?,
+    }
+    Ok(ScheduleResult { files: paths })
+}
+
+/// Stop the scheduled check and delete its files; nothing is removed if none are there
+pub fn remove_schedule(engine: &RushEngine) -> Result<ScheduleResult> {
+    let os = Target::host().os;
+    let paths: Vec<PathBuf> = schedule_files(os, home(engine)?, Path::new("rush"))?
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| path.exists())
+        .collect();
+    if paths.is_empty() {
+        return Ok(ScheduleResult { files: paths });
+    }
+
+    // Stopping what is already stopped fails; the files go either way
+    match os {
+        Os::Linux => {
+            let _ = systemctl(&["disable", "--now", &format!("{}.timer", UNIT)]);
+        }
+        _ => {
+            let _ = launchctl(&["unload", "-w"], &paths[0]);
+        }
+    }
+    for path in &paths {
+        fs::remove_file(path)?;
+    }
+    if os == Os::Linux {
+        let _ = systemctl(&["daemon-reload"]);
+    }
+    Ok(ScheduleResult { files: paths })
+}
+
+/// Helper: The files (path, contents) scheduling `exe` on `os`, under `home`
+fn schedule_files(os: Os, home: &Path, exe: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let exe = shell_quote(&exe.to_string_lossy());
+    let script = format!("{} update && {} outdated --notify", exe, exe);
+
+    match os {
+        Os::Linux => {
+            let dir = home.join(".config/systemd/user");
+            let service = format!(
+                "[Unit]\n\
+                 Description=Update the rush registry and report outdated packages\n\n\
+                 [Service]\n\
+                 Type=oneshot\n\
+                 ExecStart=/bin/sh -c \"{}\"\n",
+                systemd_escape(&script)
+            );
+            let timer = "[Unit]\n\
+                 Description=Daily rush update check\n\n\
+                 [Timer]\n\
+                 OnCalendar=daily\n\
+                 Persistent=true\n\
+                 RandomizedDelaySec=1h\n\n\
+                 [Install]\n\
+                 WantedBy=timers.target\n"
+                .to_string();
+            Ok(vec![
+                (dir.join(format!("{}.service", UNIT)), service),
+                (dir.join(format!("{}.timer", UNIT)), timer),
+            ])
+        }
+        Os::Mac => {
+            let plist = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n\
+                 <dict>\n\
+                 \x20 <key>Label</key>\n\
+                 \x20 <string>{}</string>\n\
+                 \x20 <key>ProgramArguments</key>\n\
+                 \x20 <array>\n\
+                 \x20   <string>/bin/sh</string>\n\
+                 \x20   <string>-c</string>\n\
+                 \x20   <string>{}</string>\n\
+                 \x20 </array>\n\
+                 \x20 <key>StartInterval</key>\n\
+                 \x20 <integer>{}</integer>\n\
+                 </dict>\n\
+                 </plist>\n",
+                LABEL,
+                xml_escape(&script),
+                LAUNCHD_INTERVAL
+            );
+            let path = home
+                .join("Library/LaunchAgents")
+                .join(format!("{}.plist", LABEL));
+            Ok(vec![(path, plist)])
+        }
+        _ => bail!("Scheduled checks need systemd (Linux) or launchd (macOS)"),
+    }
+}
+
+/// Helper: The home dir the engine's layout hangs off (its config dir is `~/.config/rush`)
+pub(crate) fn home(engine: &RushEngine) -> Result<&Path> {
+    engine
+        .config_dir
+        .parent()
+        .and_then(Path::parent)
+        .context("No home dir")
+}
+
+/// Helper: Run `systemctl --user`, failing on a non-zero exit
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .context("Failed to run systemctl")?;
+    if !status.success() {
+        bail!("systemctl --user {} failed", args.join(" "));
+    }
+    Ok(())
+}
+
+/// Helper: Run `launchctl` on a plist, failing on a non-zero exit
+fn launchctl(args: &[&str], plist: &Path) -> Result<()> {
+    let status = Command::new("launchctl")
+        .args(args)
+        .arg(plist)
+        .status()
+        .context("Failed to run launchctl")?;
+    if !status.success() {
+        bail!("launchctl {} {} failed", args.join(" "), plist.display());
+    }
+    Ok(())
+}
+
+/// Helper: `value` inside a double-quoted systemd `ExecStart` argument, where `%`
+/// (specifiers) and `$` (variables) are expanded
+fn systemd_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$")
+}
+
+/// Helper: `value` as XML character data
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_files() {
+        let home = Path::new("/home/me");
+        let exe = Path::new("/opt/my tools/rush");
+
+        let files = schedule_files(Os::Linux, home, exe).unwrap();
+        assert_eq!(
+            files[0].0,
+            Path::new("/home/me/.config/systemd/user/rush-update.service")
+        );
+        assert!(files[0].1.contains(
+            "ExecStart=/bin/sh -c \"'/opt/my tools/rush' update && '/opt/my tools/rush' outdated --notify\"\n"
+        ));
+        assert_eq!(
+            files[1].0,
+            Path::new("/home/me/.config/systemd/user/rush-update.timer")
+        );
+        assert!(files[1].1.contains("OnCalendar=daily\n"));
+
+        let files = schedule_files(Os::Mac, home, exe).unwrap();
+        assert_eq!(
+            files[0].0,
+            Path::new("/home/me/Library/LaunchAgents/io.github.ekourtakis.rush.update.plist")
+        );
+        assert!(files[0].1.contains(
+            "<string>'/opt/my tools/rush' update &amp;&amp; '/opt/my tools/rush' outdated --notify</string>"
+        ));
+        assert!(files[0].1.contains("<integer>86400</integer>"));
+
+        assert!(schedule_files(Os::Windows, home, exe).is_err());
+        assert_eq!(systemd_escape("a%b$c\"d"), "a%%b$$c\\\"d");
+    }
+}
@@ -0,0 +1,180 @@
+//! `rush self-update`: replace the running rush with the newest release of its own repo.
+//!
+//! The asset for the host is picked the way `dev import` picks one, and it must be listed
+//! in a checksum file the release publishes: rush won't replace itself with a download it
+//! can't verify. The new binary is unpacked next to the running one and renamed over it,
+//! never written in place, so an interrupted update leaves the old rush working.
+
+use crate::core::dev::{
+    IMPORT_MIN_SCORE, calculate_asset_score, fetch_release, fetch_upstream_checksum,
+    find_checksum_asset,
+};
+use crate::core::requires::compare_versions;
+use crate::core::{RushEngine, Target, appimage, archive, clean, install, pending, util};
+use crate::models::{GitHubAsset, GitHubRelease, ProgressEvent, ProgressKind, SelfUpdateResult};
+use anyhow::{Context, Result};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+/// Where rush's own releases are published
+const RUSH_REPO: &str = "ekourtakis/rush";
+
+/// Look up the latest release of rush and, unless `check_only`, replace the running
+/// executable with it if it is newer
+pub fn self_update<F>(
+    engine: &RushEngine,
+    check_only: bool,
+    mut on_event: F,
+) -> Result<SelfUpdateResult>
+where
+    F: FnMut(ProgressEvent),
+{
+    engine.ensure_online("https://api.github.com")?;
+    let release = fetch_release(engine, RUSH_REPO, None, None)?;
+    let current = env!("CARGO_PKG_VERSION").to_string();
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let mut result = SelfUpdateResult {
+        newer: compare_versions(&latest, &current) == Ordering::Greater,
+        current,
+        latest,
+        updated: None,
+    };
+    if check_only || !result.newer {
+        return Ok(result);
+    }
+
+    let exe = std::env::current_exe().context("Can't tell where the running rush is")?;
+    let target = Target::host().to_string();
+    let asset = select_asset(&release, &target)
+        .with_context(|| format!("rush {} has no build for {}", result.latest, target))?;
+    let checksum = find_checksum_asset(&release, &asset.name).with_context(|| {
+        format!(
+            "rush {} publishes no checksum for {}, so it can't be verified",
+            result.latest, asset.name
+        )
+    })?;
+    let sha256 = fetch_upstream_checksum(
+        engine.transport.as_ref(),
+        &checksum.browser_download_url,
+        &asset.name,
+    )?;
+
+    let mut on_event = util::tag_progress(Some("rush"), &mut on_event);
+    let content = util::download_url(
+        engine.transport.as_ref(),
+        &asset.browser_download_url,
+        &mut on_event,
+    )?;
+    on_event(ProgressKind::VerifyingChecksum);
+    util::verify_checksum(&content, &sha256)?;
+
+    on_event(ProgressKind::Extracting);
+    replace_executable(engine, &content, &asset.name, &exe)?;
+    on_event(ProgressKind::Success);
+    result.updated = Some(exe);
+    Ok(result)
+}
+
+/// Helper: The release asset that fits `target` best, if any fits well enough
+fn select_asset<'a>(release: &'a GitHubRelease, target: &str) -> Option<&'a GitHubAsset> {
+    release
+        .assets
+        .iter()
+        .map(|asset| (calculate_asset_score(&asset.name, target), asset))
+        .filter(|(score, _)| *score >= IMPORT_MIN_SCORE)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, asset)| asset)
+}
+
+/// Helper: Unpack the new rush from `content` (an archive, or the bare binary) next to
+/// `exe`, on the same filesystem, and move it into place
+fn replace_executable(
+    engine: &RushEngine,
+    content: &[u8],
+    asset_name: &str,
+    exe: &Path,
+) -> Result<()> {
+    let dir = exe
+        .parent()
+        .with_context(|| format!("{} has no parent directory", exe.display()))?;
+    let staging = tempfile::Builder::new()
+        .prefix(&clean::own_trash_prefix())
+        .tempdir_in(dir)
+        .with_context(|| format!("Can't write to {} to update rush", dir.display()))?;
+
+    let bin = if cfg!(windows) { "rush.exe" } else { "rush" };
+    let new = if archive::is_archive_name(Path::new(asset_name)) {
+        install::extract_binary_into(content, staging.path(), bin, &engine.cancel)?
+    } else {
+        let path = staging.path().join(bin);
+        fs::write(&path, content)?;
+        appimage::make_executable(&path)?;
+        path
+    };
+
+    engine.cancel.check()?;
+    pending::swap(&new, exe).with_context(|| format!("Failed to replace {}", exe.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn release(assets: &[&str]) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: "v9.0.0".to_string(),
+            published_at: None,
+            prerelease: false,
+            assets: assets
+                .iter()
+                .map(|name| GitHubAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{}", name),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_select_asset() {
+        let release = release(&[
+            "rush-x86_64-unknown-linux-gnu.tar.gz",
+            "rush-x86_64-unknown-linux-gnu.tar.gz.sha256",
+            "rush-aarch64-apple-darwin.tar.gz",
+            "SHA256SUMS",
+        ]);
+        let pick = |target| select_asset(&release, target).map(|a| a.name.as_str());
+        assert_eq!(
+            pick("x86_64-linux"),
+            Some("rush-x86_64-unknown-linux-gnu.tar.gz")
+        );
+        assert_eq!(
+            pick("aarch64-macos"),
+            Some("rush-aarch64-apple-darwin.tar.gz")
+        );
+        assert_eq!(pick("x86_64-windows"), None);
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let temp_dir = tempdir().unwrap();
+        let engine = RushEngine::builder()
+            .root(temp_dir.path().join("root"))
+            .build()
+            .unwrap();
+        let exe = temp_dir.path().join("rush");
+        fs::write(&exe, "old").unwrap();
+
+        replace_executable(&engine, b"new", "rush-x86_64-linux", &exe).unwrap();
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+        // Nothing is left next to it
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        // An archive without rush in it leaves the old binary alone
+        let err = replace_executable(&engine, &[0x1f, 0x8b, 0], "rush.tar.gz", &exe);
+        assert!(err.is_err());
+        assert_eq!(fs::read(&exe).unwrap(), b"new");
+    }
+}
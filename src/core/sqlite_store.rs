@@ -0,0 +1,349 @@
+//! Installed-package state in a sqlite database (behind the `sqlite` feature).
+//!
+//! Selected with `[state] backend = "sqlite"` in `config.toml`. Each save is one
+//! transaction, so a crash mid-write leaves the previous state intact instead of a
+//! half-written JSON file.
+
+use crate::core::{JsonFileStore, StateStore};
+use crate::models::{InstalledPackage, State};
+use anyhow::{Context, Result};
+use rusqlite::{Connection, params};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Bumped whenever `migrate` learns a new step
+const SCHEMA_VERSION: i64 = 6;
+
+/// Keeps state in `state.db`: one row per package, per installed binary, and per data file
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open state database {:?}", path))?;
+        Self::from_connection(conn)
+    }
+
+    /// A throwaway database, for tests
+    pub fn in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Create or upgrade the schema, tracked in sqlite's `user_version`
+fn migrate(conn: &Connection) -> Result<()> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "State database is from a newer rush (schema {}, this build knows {})",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+
+    if version < 1 {
+        conn.execute_batch(
+            "BEGIN;
+             CREATE TABLE packages (
+                 name    TEXT PRIMARY KEY,
+                 version TEXT NOT NULL
+             );
+             CREATE TABLE binaries (
+                 package  TEXT NOT NULL REFERENCES packages(name) ON DELETE CASCADE,
+                 name     TEXT NOT NULL,
+                 position INTEGER NOT NULL,
+                 PRIMARY KEY (package, name)
+             );
+             CREATE INDEX binaries_by_name ON binaries(name);
+             PRAGMA user_version = 1;
+             COMMIT;",
+        )?;
+    }
+    if version < 2 {
+        // Provenance is only ever read whole, so it is kept as JSON
+        conn.execute_batch(
+            "BEGIN;
+             ALTER TABLE packages ADD COLUMN provenance TEXT;
+             PRAGMA user_version = 2;
+             COMMIT;",
+        )?;
+    }
+    if version < 3 {
+        conn.execute_batch(
+            "BEGIN;
+             CREATE TABLE files (
+                 package TEXT NOT NULL REFERENCES packages(name) ON DELETE CASCADE,
+                 path    TEXT NOT NULL,
+                 PRIMARY KEY (package, path)
+             );
+             PRAGMA user_version = 3;
+             COMMIT;",
+        )?;
+    }
+    if version < 4 {
+        conn.execute_batch(
+            "BEGIN;
+             ALTER TABLE packages ADD COLUMN version_constraint TEXT;
+             PRAGMA user_version = 4;
+             COMMIT;",
+        )?;
+    }
+    if version < 5 {
+        conn.execute_batch(
+            "BEGIN;
+             ALTER TABLE packages ADD COLUMN dependency INTEGER NOT NULL DEFAULT 0;
+             PRAGMA user_version = 5;
+             COMMIT;",
+        )?;
+    }
+    if version < 6 {
+        conn.execute_batch(
+            "BEGIN;
+             ALTER TABLE packages ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+             PRAGMA user_version = 6;
+             COMMIT;",
+        )?;
+    }
+    Ok(())
+}
+
+impl StateStore for SqliteStore {
+    fn load(&self) -> Result<State> {
+        let conn = self.lock();
+        let mut state = State::default();
+
+        let mut packages = conn.prepare(
+            "SELECT name, version, provenance, version_constraint, pinned, dependency
+             FROM packages",
+        )?;
+        let rows = packages.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })?;
+        for row in rows {
+            let (name, version, provenance, constraint, pinned, dependency): (
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                bool,
+                bool,
+            ) = row?;
+            let provenance = provenance
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .with_context(|| format!("Corrupt provenance for '{}'", name))?;
+            state.packages.insert(
+                name,
+                InstalledPackage {
+                    version,
+                    binaries: Vec::new(),
+                    files: Vec::new(),
+                    provenance,
+                    constraint,
+                    pinned,
+                    dependency,
+                },
+            );
+        }
+
+        let mut binaries =
+            conn.prepare("SELECT package, name FROM binaries ORDER BY package, position")?;
+        let rows = binaries.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (package, bin): (String, String) = row?;
+            if let Some(installed) = state.packages.get_mut(&package) {
+                installed.binaries.push(bin);
+            }
+        }
+
+        let mut files = conn.prepare("SELECT package, path FROM files ORDER BY package, path")?;
+        let rows = files.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (package, path): (String, String) = row?;
+            if let Some(installed) = state.packages.get_mut(&package) {
+                installed.files.push(path);
+            }
+        }
+
+        Ok(state)
+    }
+
+    fn save(&self, state: &State) -> Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM packages", [])?;
+        {
+            let mut insert_package = tx.prepare(
+                "INSERT INTO packages
+                     (name, version, provenance, version_constraint, pinned, dependency)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            let mut insert_binary =
+                tx.prepare("INSERT INTO binaries (package, name, position) VALUES (?1, ?2, ?3)")?;
+            let mut insert_file =
+                tx.prepare("INSERT INTO files (package, path) VALUES (?1, ?2)")?;
+            for (name, installed) in &state.packages {
+                let provenance = installed
+                    .provenance
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?;
+                insert_package.execute(params![
+                    name,
+                    installed.version,
+                    provenance,
+                    installed.constraint,
+                    installed.pinned,
+                    installed.dependency
+                ])?;
+                for (position, bin) in installed.binaries.iter().enumerate() {
+                    insert_binary.execute(params![name, bin, position as i64])?;
+                }
+                for path in &installed.files {
+                    insert_file.execute(params![name, path])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// The store for `[state] backend = "sqlite"`: `state.db` in `state_dir`, seeded from
+/// `installed.json` the first time so switching backends keeps what is installed
+pub(crate) fn open_store(state_dir: &Path, json: JsonFileStore) -> Result<Box<dyn StateStore>> {
+    let path = state_dir.join("state.db");
+    let fresh = !path.exists();
+    let store = SqliteStore::open(&path)?;
+    if fresh {
+        store.save(&json.load()?)?;
+    }
+    Ok(Box::new(store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn installed(version: &str, binaries: &[&str]) -> InstalledPackage {
+        InstalledPackage {
+            version: version.to_string(),
+            binaries: binaries.iter().map(|b| b.to_string()).collect(),
+            files: Vec::new(),
+            provenance: None,
+            constraint: None,
+            pinned: false,
+            dependency: false,
+        }
+    }
+
+    #[test]
+    fn test_sqlite_round_trip() {
+        let store = SqliteStore::in_memory().unwrap();
+        assert!(store.load().unwrap().packages.is_empty());
+
+        let mut state = State::default();
+        state
+            .packages
+            .insert("ripgrep".to_string(), installed("14.0.0", &["rg"]));
+        state
+            .packages
+            .insert("multi".to_string(), installed("1.0.0", &["zeta", "alpha"]));
+        store.save(&state).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.packages.len(), 2);
+        // Binary order survives the round trip
+        assert_eq!(loaded.packages["multi"].binaries, vec!["zeta", "alpha"]);
+
+        state.packages.remove("multi");
+        store.save(&state).unwrap();
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.packages.len(), 1);
+        assert_eq!(loaded.packages["ripgrep"], installed("14.0.0", &["rg"]));
+
+        // Provenance is kept whole
+        let mut with_provenance = installed("1.0.0", &["fd"]);
+        with_provenance.provenance = Some(crate::models::Provenance {
+            registry: None,
+            url: "https://example.com/fd.tar.gz".to_string(),
+            sha256: "abc".to_string(),
+            installed_at: "2025-01-31T12:00:00Z".to_string(),
+        });
+        state
+            .packages
+            .insert("fd".to_string(), with_provenance.clone());
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap().packages["fd"], with_provenance);
+
+        // So are a data package's files
+        let mut themes = installed("2.0.0", &[]);
+        themes.files = vec!["tool/themes/dark.toml".to_string()];
+        state.packages.insert("themes".to_string(), themes.clone());
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap().packages["themes"], themes);
+
+        // And a version constraint or pin
+        let mut node = installed("20.1.0", &["node"]);
+        node.constraint = Some("~20".to_string());
+        node.pinned = true;
+        state.packages.insert("node".to_string(), node.clone());
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap().packages["node"], node);
+
+        // And whether it was only installed as a dependency
+        let mut libtool = installed("1.0.0", &[]);
+        libtool.dependency = true;
+        state
+            .packages
+            .insert("libtool".to_string(), libtool.clone());
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap().packages["libtool"], libtool);
+    }
+
+    #[test]
+    fn test_sqlite_imports_json_state() {
+        let temp_dir = tempdir().unwrap();
+        let json = JsonFileStore::new(temp_dir.path().join("installed.json"));
+        let mut state = State::default();
+        state
+            .packages
+            .insert("fd".to_string(), installed("9.0.0", &["fd"]));
+        json.save(&state).unwrap();
+
+        let store = open_store(temp_dir.path(), json).unwrap();
+        assert!(store.load().unwrap().packages.contains_key("fd"));
+
+        // Later opens use the database, not the (now stale) JSON file
+        store.save(&State::default()).unwrap();
+        let json = JsonFileStore::new(temp_dir.path().join("installed.json"));
+        let store = open_store(temp_dir.path(), json).unwrap();
+        assert!(store.load().unwrap().packages.is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! `rush registry stats`: how complete the registry is, for the people maintaining it.
+//!
+//! Everything but the manifests' ages is read from the synced copy. A synced copy doesn't
+//! know when a manifest last changed (`rush update` writes every file anew), so for a
+//! registry checkout (a directory source) ages come from its git history, and failing
+//! that from the files' modification times there; other registries fall back to the
+//! synced files' times.
+
+use crate::core::provenance::rfc3339;
+use crate::core::query::{find_package, package_names};
+use crate::core::{RegistryBackend, RushEngine};
+use crate::models::{ManifestAge, RegistryStats, TargetCoverage};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+/// How many of the newest and oldest manifests are listed
+const AGES_LISTED: usize = 5;
+
+/// Count the registry's packages and targets, and list its gaps
+pub fn registry_stats(engine: &RushEngine) -> RegistryStats {
+    let mut stats = RegistryStats::default();
+    let mut coverage: BTreeMap<String, usize> = BTreeMap::new();
+    for name in package_names(engine) {
+        let Some(manifest) = find_package(engine, &name) else {
+            stats.unreadable.push(name);
+            continue;
+        };
+        stats.packages += 1;
+        for target in manifest.targets.keys() {
+            *coverage.entry(target.clone()).or_default() += 1;
+        }
+        if manifest
+            .description
+            .as_deref()
+            .is_none_or(|d| d.trim().is_empty())
+        {
+            stats.missing_description.push(name.clone());
+        }
+        if manifest.targets.values().any(|t| t.size.is_none()) {
+            stats.missing_size.push(name);
+        }
+    }
+
+    stats.targets = coverage
+        .into_iter()
+        .map(|(target, packages)| TargetCoverage { target, packages })
+        .collect();
+    // Best covered first; ties stay in name order
+    stats.targets.sort_by_key(|t| std::cmp::Reverse(t.packages));
+
+    let mut ages: Vec<(String, u64)> =
+        manifest_times(engine.registry.as_ref(), &engine.registry_dir)
+            .into_iter()
+            .collect();
+    ages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let age = |(package, secs): &(String, u64)| ManifestAge {
+        package: package.clone(),
+        changed_at: rfc3339(*secs),
+    };
+    stats.newest = ages.iter().take(AGES_LISTED).map(age).collect();
+    stats.oldest = ages.iter().rev().take(AGES_LISTED).map(age).collect();
+    stats
+}
+
+/// Helper: When each package's manifest last changed, in Unix seconds
+fn manifest_times(registry: &dyn RegistryBackend, registry_dir: &Path) -> HashMap<String, u64> {
+    if !registry.is_remote() {
+        let checkout = Path::new(registry.source());
+        if checkout.is_dir() {
+            let times = git_times(checkout);
+            if !times.is_empty() {
+                return times;
+            }
+            return file_times(checkout);
+        }
+    }
+    file_times(registry_dir)
+}
+
+/// Helper: The time of the last commit touching each manifest under `checkout/packages`;
+/// empty if it isn't a git checkout
+fn git_times(checkout: &Path) -> HashMap<String, u64> {
+    let output = Command::new("git")
+        .args(["log", "--format=%ct", "--name-only", "--", "packages"])
+        .current_dir(checkout)
+        .output();
+    let Some(output) = output.ok().filter(|o| o.status.success()) else {
+        return HashMap::new();
+    };
+
+    // Newest commit first, so the first time a manifest shows up is its last change
+    let mut times = HashMap::new();
+    let mut commit_time = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Ok(secs) = line.parse::<u64>() {
+            commit_time = secs;
+        } else if let Some(name) = package_of(Path::new(line)) {
+            times.entry(name).or_insert(commit_time);
+        }
+    }
+    // Deleted manifests are in the history too
+    times.retain(|name, _| checkout.join(manifest_relative(name)).is_file());
+    times
+}
+
+/// Helper: Each manifest's modification time under `dir/packages`
+fn file_times(dir: &Path) -> HashMap<String, u64> {
+    WalkDir::new(dir.join("packages"))
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let name = package_of(entry.path())?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            let secs = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some((name, secs))
+        })
+        .collect()
+}
+
+/// Helper: The package a `packages/f/fzf.toml` path is the manifest of
+fn package_of(path: &Path) -> Option<String> {
+    if path.extension()? != "toml" {
+        return None;
+    }
+    Some(path.file_stem()?.to_str()?.to_string())
+}
+
+/// Helper: Where `name`'s manifest is, relative to a registry's root
+fn manifest_relative(name: &str) -> String {
+    let prefix = name.chars().next().unwrap_or_default();
+    format!("packages/{}/{}.toml", prefix, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockEnvironment;
+    use std::fs;
+
+    #[test]
+    fn test_registry_stats() {
+        let mock = MockEnvironment::new();
+        mock.add_package("alpha", "1.0.0", "alpha");
+        mock.add_package("beta", "1.0.0", "beta");
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let edit = |name: &str, change: &dyn Fn(&mut crate::models::PackageManifest)| {
+            let path = engine.registry_dir.join(manifest_relative(name));
+            let mut manifest = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            change(&mut manifest);
+            fs::write(&path, toml::to_string(&manifest).unwrap()).unwrap();
+        };
+        // Give alpha a size and a second target, and take beta's description away
+        edit("alpha", &|manifest| {
+            let mut target = manifest.targets.values().next().unwrap().clone();
+            target.size = Some(42);
+            manifest.targets = BTreeMap::from([
+                (engine.target().to_string(), target.clone()),
+                ("riscv64-linux".to_string(), target),
+            ]);
+        });
+        edit("beta", &|manifest| manifest.description = None);
+        fs::write(engine.registry_dir.join("packages/b/broken.toml"), "nope").unwrap();
+
+        let stats = registry_stats(&engine);
+        assert_eq!(stats.packages, 2);
+        assert_eq!(stats.unreadable, ["broken"]);
+        assert_eq!(
+            stats.targets[0],
+            TargetCoverage {
+                target: engine.target().to_string(),
+                packages: 2
+            }
+        );
+        assert_eq!(stats.targets.len(), 2);
+        assert_eq!(stats.missing_description, ["beta"]);
+        assert_eq!(stats.missing_size, ["beta"]);
+        // Ages come from the registry source, a directory here: both its manifests
+        assert_eq!(stats.newest.len(), 2);
+        assert_eq!(stats.newest[0].package, stats.oldest[1].package);
+    }
+
+    #[test]
+    fn test_git_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(dir.path())
+                .env("GIT_COMMITTER_DATE", "2024-01-01T00:00:00Z")
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        };
+        if !git(&["init", "--quiet"]) {
+            // No git on this machine
+            return;
+        }
+        fs::create_dir_all(dir.path().join("packages/t")).unwrap();
+        fs::write(dir.path().join("packages/t/tool.toml"), "").unwrap();
+        assert!(git(&["add", "."]));
+        assert!(git(&["commit", "--quiet", "-m", "Add tool"]));
+
+        let times = git_times(dir.path());
+        assert_eq!(times.len(), 1);
+        assert_eq!(rfc3339(times["tool"]), "2024-01-01T00:00:00Z");
+    }
+}
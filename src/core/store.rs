@@ -0,0 +1,148 @@
+//! Where the record of installed packages is kept.
+//!
+//! The engine loads its `State` from a `StateStore` once, when it is built, and hands the
+//! whole state back after every install or uninstall.
+
+use crate::models::State;
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Loads and saves the installed-package state
+pub trait StateStore: Send + Sync {
+    /// The saved state, or an empty one if nothing has been saved yet
+    fn load(&self) -> Result<State>;
+
+    /// Replace the saved state
+    fn save(&self, state: &State) -> Result<()>;
+}
+
+/// The default store: pretty-printed JSON (`~/.local/share/rush/installed.json`)
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl StateStore for JsonFileStore {
+    fn load(&self) -> Result<State> {
+        if !self.path.exists() {
+            return Ok(State::default());
+        }
+        // A corrupt file is treated as empty rather than locking the user out
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    fn save(&self, state: &State) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(state)?;
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
+
+/// Keeps the state in memory only. Clones share it, so a test can keep one handle and
+/// give the other to the engine.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<Mutex<State>>,
+}
+
+impl MemoryStore {
+    pub fn new(state: State) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// What was last saved
+    pub fn snapshot(&self) -> State {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl StateStore for MemoryStore {
+    fn load(&self) -> Result<State> {
+        Ok(self.snapshot())
+    }
+
+    fn save(&self, state: &State) -> Result<()> {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = state.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RushEngine;
+    use crate::models::InstalledPackage;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_json_file_store_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let store = JsonFileStore::new(temp_dir.path().join("nested/installed.json"));
+        assert!(store.load().unwrap().packages.is_empty());
+
+        let mut state = State::default();
+        state.packages.insert(
+            "fzf".to_string(),
+            InstalledPackage {
+                version: "0.1.0".to_string(),
+                binaries: vec!["fzf".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap().packages["fzf"].version, "0.1.0");
+
+        fs::write(temp_dir.path().join("nested/installed.json"), "{ nope").unwrap();
+        assert!(store.load().unwrap().packages.is_empty());
+    }
+
+    #[test]
+    fn test_engine_with_memory_store() {
+        let temp_dir = tempdir().unwrap();
+        let store = MemoryStore::default();
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .state_store(store.clone())
+            .build()
+            .unwrap();
+
+        engine.state.packages.insert(
+            "bat".to_string(),
+            InstalledPackage {
+                version: "1.0.0".to_string(),
+                binaries: vec!["bat".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
+            },
+        );
+        engine.save().unwrap();
+
+        assert!(store.snapshot().packages.contains_key("bat"));
+        assert!(
+            !temp_dir
+                .path()
+                .join(".local/share/rush/installed.json")
+                .exists()
+        );
+    }
+}
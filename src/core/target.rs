@@ -0,0 +1,301 @@
+//! Platform targets, the keys of a manifest's `[targets]` table ("x86_64-linux").
+//!
+//! The same platform goes by many names (`arm64`/`aarch64`, `darwin`/`macos`/`apple`,
+//! `x86_64-unknown-linux-musl`, ...). `Target::parse` collapses them all to one value and
+//! `Display` writes its canonical key, so a key written one way still matches the host
+//! detected another way. Asset scoring reads the same spellings out of file names.
+//! `lookup` also lets a key without a libc (like the host's) find a build that names one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Operating systems an artifact can be built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Os {
+    Linux,
+    Mac,
+    Windows,
+    /// Recognized, but never one of our targets (FreeBSD, Android, ...)
+    Other,
+}
+
+/// CPU architectures an artifact can be built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+    /// 32-bit ARM (armv7, armhf, ...)
+    Arm32,
+    /// 32-bit x86 (i686, 386, ...)
+    X86,
+    /// macOS fat binary, runs on both Intel and Apple Silicon
+    Universal,
+    Other,
+}
+
+/// C library / ABI flavour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Libc {
+    Gnu,
+    Musl,
+    Msvc,
+}
+
+/// A platform rush can install for: an architecture, an OS and, optionally, a libc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub arch: Arch,
+    pub os: Os,
+    pub libc: Option<Libc>,
+}
+
+impl Target {
+    /// The machine rush is running on. The libc is left out, as registry keys don't
+    /// carry one.
+    pub fn host() -> Target {
+        Target::parse(&format!(
+            "{}-{}",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        ))
+        .unwrap_or(Target {
+            arch: Arch::Other,
+            os: Os::Other,
+            libc: None,
+        })
+    }
+
+    /// "x86_64-linux", "arm64-darwin", "aarch64-unknown-linux-musl", ... One architecture
+    /// and one OS rush knows (`apple-darwin` names the OS twice), plus any vendor or libc
+    /// parts; None otherwise.
+    pub fn parse(key: &str) -> Option<Target> {
+        let key = key.trim().to_lowercase();
+        let (mut arch, mut os, mut libc) = (None, None, None);
+        for token in tokenize(&key) {
+            if let Some(parsed) = parse_arch(&token) {
+                if arch
+                    .replace(parsed)
+                    .is_some_and(|previous| previous != parsed)
+                {
+                    return None;
+                }
+            } else if let Some(parsed) = parse_os(&token) {
+                if os
+                    .replace(parsed)
+                    .is_some_and(|previous| previous != parsed)
+                {
+                    return None;
+                }
+            } else if let Some(parsed) = parse_libc(&token) {
+                libc = Some(parsed);
+            } else if !matches!(token.as_str(), "unknown" | "pc" | "none") {
+                return None;
+            }
+        }
+        let arch = arch.filter(|arch| *arch != Arch::Other)?;
+        let os = os.filter(|os| *os != Os::Other)?;
+        Some(Target { arch, os, libc })
+    }
+
+    /// `key` in its canonical spelling, or unchanged if it isn't a target rush knows
+    pub fn normalize(key: &str) -> String {
+        Target::parse(key).map_or_else(|| key.to_string(), |target| target.to_string())
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arch = match self.arch {
+            Arch::X86_64 => "x86_64",
+            Arch::Aarch64 => "aarch64",
+            Arch::Arm32 => "armv7",
+            Arch::X86 => "i686",
+            Arch::Universal => "universal",
+            Arch::Other => "unknown",
+        };
+        let os = match self.os {
+            Os::Linux => "linux",
+            Os::Mac => "macos",
+            Os::Windows => "windows",
+            Os::Other => "unknown",
+        };
+        write!(f, "{}-{}", arch, os)?;
+        match self.libc {
+            Some(Libc::Gnu) => write!(f, "-gnu"),
+            Some(Libc::Musl) => write!(f, "-musl"),
+            Some(Libc::Msvc) => write!(f, "-msvc"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The entry of `targets` (keyed canonically, as manifests are) for `key`, in any spelling,
+/// with the key it is under. If there is none under `key` itself, a build for the same
+/// architecture and OS that only differs in naming a libc will do: `x86_64-linux` (the
+/// host, whose libc isn't detected) takes `x86_64-linux-musl`, which runs on glibc
+/// systems too, or else `x86_64-linux-gnu`; `x86_64-linux-gnu` takes `x86_64-linux`.
+pub(crate) fn lookup<'a, V>(
+    targets: &'a BTreeMap<String, V>,
+    key: &str,
+) -> Option<(&'a String, &'a V)> {
+    let key = Target::normalize(key);
+    if let Some(entry) = targets.get_key_value(&key) {
+        return Some(entry);
+    }
+    let wanted = Target::parse(&key)?;
+    let fallbacks = match wanted.libc {
+        Some(_) => vec![None],
+        None => vec![Some(Libc::Musl), Some(Libc::Gnu), Some(Libc::Msvc)],
+    };
+    fallbacks.into_iter().find_map(|libc| {
+        let key = Target { libc, ..wanted }.to_string();
+        targets.get_key_value(&key)
+    })
+}
+
+/// Splits on separators, keeping "x86_64" / "x86-64" whole
+pub(crate) fn tokenize(stem: &str) -> Vec<String> {
+    let parts: Vec<&str> = stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < parts.len() {
+        if parts[i] == "x86" && parts.get(i + 1) == Some(&"64") {
+            tokens.push("x86_64".to_string());
+            i += 2;
+        } else {
+            tokens.push(parts[i].to_string());
+            i += 1;
+        }
+    }
+    tokens
+}
+
+pub(crate) fn parse_os(token: &str) -> Option<Os> {
+    match token {
+        "linux" => Some(Os::Linux),
+        "darwin" | "macos" | "osx" | "apple" | "mac" => Some(Os::Mac),
+        "windows" | "win" | "win64" | "win32" => Some(Os::Windows),
+        "freebsd" | "netbsd" | "openbsd" | "illumos" | "solaris" | "android" | "ios" => {
+            Some(Os::Other)
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_arch(token: &str) -> Option<Arch> {
+    match token {
+        "x86_64" | "amd64" | "x64" | "win64" => Some(Arch::X86_64),
+        "aarch64" | "arm64" => Some(Arch::Aarch64),
+        "arm" | "armv6" | "armv7" | "armv7l" | "armhf" | "armel" | "gnueabihf" | "musleabihf" => {
+            Some(Arch::Arm32)
+        }
+        "i386" | "i686" | "x86" | "386" | "win32" => Some(Arch::X86),
+        "universal" | "universal2" => Some(Arch::Universal),
+        "riscv64" | "riscv64gc" | "ppc64le" | "powerpc64le" | "s390x" | "mips" | "loongarch64" => {
+            Some(Arch::Other)
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_libc(token: &str) -> Option<Libc> {
+    match token {
+        "musl" | "musleabi" | "musleabihf" | "static" => Some(Libc::Musl),
+        "gnu" | "glibc" | "gnueabi" | "gnueabihf" => Some(Libc::Gnu),
+        "msvc" => Some(Libc::Msvc),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spellings() {
+        for key in [
+            "aarch64-macos",
+            "arm64-darwin",
+            "aarch64-apple-darwin",
+            "ARM64-macOS",
+            "arm64_osx",
+        ] {
+            assert_eq!(Target::normalize(key), "aarch64-macos", "{}", key);
+        }
+        assert_eq!(Target::normalize("amd64-linux"), "x86_64-linux");
+        assert_eq!(Target::normalize("x86-64-linux"), "x86_64-linux");
+        assert_eq!(
+            Target::normalize("x86_64-pc-windows-msvc"),
+            "x86_64-windows-msvc"
+        );
+
+        let musl = Target::parse("x86_64-unknown-linux-musl").unwrap();
+        assert_eq!(musl.libc, Some(Libc::Musl));
+        assert_eq!(musl.to_string(), "x86_64-linux-musl");
+        assert_eq!(Target::parse(&musl.to_string()), Some(musl));
+
+        // Not targets: unknown platforms, missing or repeated parts, stray words
+        for key in [
+            "riscv64-linux",
+            "x86_64-freebsd",
+            "linux",
+            "x86_64-aarch64-linux",
+            "x86_64-linux-extra",
+            "",
+        ] {
+            assert_eq!(Target::parse(key), None, "{}", key);
+        }
+        assert_eq!(Target::normalize("riscv64-linux"), "riscv64-linux");
+    }
+
+    #[test]
+    fn test_host_matches_manifest_keys() {
+        let host = Target::host();
+        assert_eq!(host.libc, None);
+        if cfg!(all(target_arch = "x86_64", target_os = "linux")) {
+            assert_eq!(host.to_string(), "x86_64-linux");
+        }
+        if cfg!(all(target_arch = "aarch64", target_os = "macos")) {
+            assert_eq!(host.to_string(), "aarch64-macos");
+        }
+    }
+
+    #[test]
+    fn test_lookup_falls_back_on_libc() {
+        let targets = |keys: &[&str]| -> BTreeMap<String, ()> {
+            keys.iter().map(|k| (k.to_string(), ())).collect()
+        };
+        let key = |targets: &BTreeMap<String, ()>, key: &str| {
+            lookup(targets, key).map(|(key, _)| key.clone())
+        };
+
+        let gnu = targets(&["x86_64-linux-gnu", "aarch64-macos"]);
+        assert_eq!(
+            key(&gnu, "x86_64-linux").as_deref(),
+            Some("x86_64-linux-gnu")
+        );
+        assert_eq!(
+            key(&gnu, "x86_64-unknown-linux-gnu").as_deref(),
+            Some("x86_64-linux-gnu")
+        );
+        assert_eq!(key(&gnu, "x86_64-linux-musl"), None);
+        assert_eq!(key(&gnu, "aarch64-linux"), None);
+
+        let both = targets(&["x86_64-linux-gnu", "x86_64-linux-musl"]);
+        assert_eq!(
+            key(&both, "x86_64-linux").as_deref(),
+            Some("x86_64-linux-musl")
+        );
+
+        let plain = targets(&["x86_64-linux"]);
+        assert_eq!(
+            key(&plain, "x86_64-linux-gnu").as_deref(),
+            Some("x86_64-linux")
+        );
+        assert_eq!(key(&plain, "arm64-linux"), None);
+    }
+}
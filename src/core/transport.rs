@@ -0,0 +1,267 @@
+//! How the engine fetches artifacts, registry tarballs, and checksum files.
+//!
+//! Everything that downloads a URL goes through the engine's `Transport`, so tests (and
+//! library users) can swap in canned responses instead of talking to real servers.
+
+use crate::models::HttpConfig;
+use anyhow::Result;
+use std::fs;
+use std::io::Read;
+use std::time::Duration;
+
+/// How often an open connection is probed, so one dropped by a NAT or proxy is noticed
+pub(crate) const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// An open download: its advertised size (if any) and the body to stream from
+pub struct TransportResponse {
+    pub content_length: Option<u64>,
+    pub body: Box<dyn Read + Send>,
+}
+
+/// Fetches the bytes behind a URL.
+/// Failures (unreachable host, 404, timeout) are returned as errors from `get`; errors
+/// while streaming come from `body.read`.
+pub trait Transport: Send + Sync {
+    fn get(&self, url: &str) -> Result<TransportResponse>;
+
+    /// The advertised size of the body behind `url` (a HEAD request), without downloading
+    /// it; None when the server doesn't say. Transports that can't ask say nothing.
+    fn head(&self, _url: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
+}
+
+/// The default transport: HTTP(S) via reqwest, plus `file://` URLs for local artifacts
+pub struct HttpTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl HttpTransport {
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+}
+
+/// The HTTP client the engine makes when it isn't handed one: a single client, so every
+/// download and API call shares its connection pool. Connections to the same host are
+/// kept open between downloads, and HTTP/2 is used where the server offers it (unless
+/// `config.http1_only`), so a batch of downloads from one CDN shares a connection.
+pub fn http_client(config: &HttpConfig) -> Result<reqwest::blocking::Client> {
+    let mut builder = reqwest::blocking::Client::builder()
+        .user_agent(concat!("rush/", env!("CARGO_PKG_VERSION")))
+        .pool_max_idle_per_host(config.max_idle_per_host())
+        .pool_idle_timeout(config.idle_timeout())
+        .tcp_keepalive(TCP_KEEPALIVE);
+    if config.http1_only {
+        builder = builder.http1_only();
+    }
+    Ok(builder.build()?)
+}
+
+impl Transport for HttpTransport {
+    fn get(&self, url: &str) -> Result<TransportResponse> {
+        if let Some(path) = url.strip_prefix("file://") {
+            let file = fs::File::open(path)?;
+            return Ok(TransportResponse {
+                content_length: Some(file.metadata()?.len()),
+                body: Box::new(file),
+            });
+        }
+
+        let response = self.client.get(url).send()?.error_for_status()?;
+        Ok(TransportResponse {
+            content_length: response.content_length(),
+            body: Box::new(response),
+        })
+    }
+
+    fn head(&self, url: &str) -> Result<Option<u64>> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Ok(Some(fs::metadata(path)?.len()));
+        }
+
+        let response = self.client.head(url).send()?.error_for_status()?;
+        // The header itself: a HEAD response's body is always empty
+        Ok(response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse().ok()))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    //! A scripted `Transport` for unit tests
+
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    pub enum MockResponse {
+        /// 200 with this body
+        Body(Vec<u8>),
+        /// Advertises `length` bytes but ends after `body`
+        Truncated { body: Vec<u8>, length: u64 },
+        /// Serves the body a few bytes per read
+        Slow(Vec<u8>),
+        /// `get` fails with this message (404, timeout, ...)
+        Error(String),
+    }
+
+    #[derive(Default)]
+    pub struct MockTransport {
+        responses: HashMap<String, MockResponse>,
+        pub requests: Mutex<Vec<String>>,
+    }
+
+    impl MockTransport {
+        pub fn with(mut self, url: &str, response: MockResponse) -> Self {
+            self.responses.insert(url.to_string(), response);
+            self
+        }
+    }
+
+    /// Reads at most `chunk` bytes at a time
+    struct SlowReader {
+        data: std::io::Cursor<Vec<u8>>,
+        chunk: usize,
+    }
+
+    impl Read for SlowReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.chunk);
+            self.data.read(&mut buf[..len])
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn get(&self, url: &str) -> Result<TransportResponse> {
+            self.requests.lock().unwrap().push(url.to_string());
+            let (content_length, body): (u64, Box<dyn Read + Send>) = match self.responses.get(url)
+            {
+                None => anyhow::bail!("HTTP status client error (404 Not Found) for url ({})", url),
+                Some(MockResponse::Error(message)) => anyhow::bail!("{}", message),
+                Some(MockResponse::Body(body)) => (
+                    body.len() as u64,
+                    Box::new(std::io::Cursor::new(body.clone())),
+                ),
+                Some(MockResponse::Truncated { body, length }) => {
+                    (*length, Box::new(std::io::Cursor::new(body.clone())))
+                }
+                Some(MockResponse::Slow(body)) => (
+                    body.len() as u64,
+                    Box::new(SlowReader {
+                        data: std::io::Cursor::new(body.clone()),
+                        chunk: 3,
+                    }),
+                ),
+            };
+            Ok(TransportResponse {
+                content_length: Some(content_length),
+                body,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves `body` for every request on a local port, keeping connections open; returns
+    /// the base URL and how many connections were accepted
+    fn serve(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut stream = stream;
+                    let mut line = String::new();
+                    loop {
+                        line.clear();
+                        match reader.read_line(&mut line) {
+                            Ok(0) | Err(_) => return,
+                            Ok(_) if line == "\r\n" => {
+                                let _ = write!(
+                                    stream,
+                                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                                    body.len(),
+                                    body
+                                );
+                            }
+                            Ok(_) => {}
+                        }
+                    }
+                });
+            }
+        });
+        (format!("http://{}", addr), connections)
+    }
+
+    fn fetch(transport: &HttpTransport, url: &str) -> String {
+        let mut body = String::new();
+        transport
+            .get(url)
+            .unwrap()
+            .body
+            .read_to_string(&mut body)
+            .unwrap();
+        body
+    }
+
+    #[test]
+    fn test_http_client_reuses_connections() {
+        let (base, connections) = serve("ok");
+        let transport = HttpTransport::new(http_client(&HttpConfig::default()).unwrap());
+        for i in 0..5 {
+            assert_eq!(fetch(&transport, &format!("{}/{}", base, i)), "ok");
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // Without a pool, each download opens its own connection
+        let (base, connections) = serve("ok");
+        let config = HttpConfig {
+            pool_max_idle_per_host: Some(0),
+            http1_only: true,
+            ..Default::default()
+        };
+        let transport = HttpTransport::new(http_client(&config).unwrap());
+        for i in 0..3 {
+            assert_eq!(fetch(&transport, &format!("{}/{}", base, i)), "ok");
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_head_asks_for_the_size_only() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tool.tar.gz", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let mut stream = stream;
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: 1234\r\n\r\n").unwrap();
+            request
+        });
+
+        let transport = HttpTransport::new(http_client(&HttpConfig::default()).unwrap());
+        assert_eq!(transport.head(&url).unwrap(), Some(1234));
+        assert!(server.join().unwrap().starts_with("HEAD /tool.tar.gz "));
+    }
+}
@@ -1,7 +1,6 @@
-use super::RushEngine;
+use super::{RushEngine, appimage, datafiles, lock, pending};
 use crate::models::UninstallResult;
 use anyhow::Result;
-use std::fs;
 
 pub fn uninstall_package(engine: &mut RushEngine, name: &str) -> Result<Option<UninstallResult>> {
     let Some(pkg) = engine.state.packages.get(name) else {
@@ -11,12 +10,13 @@ pub fn uninstall_package(engine: &mut RushEngine, name: &str) -> Result<Option<U
     let mut removed_bins = Vec::new();
 
     for binary in &pkg.binaries {
-        let p = engine.bin_path.join(binary);
-        if p.exists() {
-            fs::remove_file(&p)?;
+        let _lock = lock::lock_binary(&engine.bin_path, binary);
+        if pending::remove_binary(&engine.bin_path, binary)? {
             removed_bins.push(binary.clone());
         }
     }
+    appimage::remove(engine, name)?;
+    let removed_files = datafiles::remove(engine, &pkg.files)?;
 
     engine.state.packages.remove(name);
     engine.save()?;
@@ -24,6 +24,7 @@ pub fn uninstall_package(engine: &mut RushEngine, name: &str) -> Result<Option<U
     Ok(Some(UninstallResult {
         package_name: name.to_string(),
         binaries_removed: removed_bins,
+        files_removed: removed_files,
     }))
 }
 
@@ -31,16 +32,17 @@ pub fn uninstall_package(engine: &mut RushEngine, name: &str) -> Result<Option<U
 mod tests {
     use super::*;
     use crate::models::InstalledPackage;
+    use std::fs;
     use tempfile::tempdir;
 
     #[test]
     fn test_uninstall_deletes_files() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let bin_path = root.join(".local/bin");
+        let bin_path = root.join(".local/share/rush/shims");
 
         // Setup: Create a fake installed package and a fake binary file
-        let mut engine = RushEngine::with_root(root.clone()).unwrap();
+        let mut engine = RushEngine::builder().root(root.clone()).build().unwrap();
 
         // Create the dummy binary file
         fs::create_dir_all(&bin_path).unwrap();
@@ -53,6 +55,11 @@ mod tests {
             InstalledPackage {
                 version: "1.0.0".to_string(),
                 binaries: vec!["dummy-tool".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
             },
         );
         engine.save().unwrap();
@@ -69,7 +76,7 @@ mod tests {
 
         // Assert: State should be clean
         // We reload the engine to ensure the file on disk was actually updated
-        let reloaded_engine = RushEngine::with_root(root.clone()).unwrap();
+        let reloaded_engine = RushEngine::builder().root(root.clone()).build().unwrap();
         assert!(!reloaded_engine.state.packages.contains_key("dummy-tool"));
     }
 
@@ -77,7 +84,7 @@ mod tests {
     fn test_uninstall_missing_package() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
-        let mut engine = RushEngine::with_root(root).unwrap();
+        let mut engine = RushEngine::builder().root(root).build().unwrap();
 
         // Try to uninstall something that isn't there
         let result = uninstall_package(&mut engine, "ghost-pkg").unwrap();
@@ -1,99 +1,115 @@
-use crate::core::{RushEngine, util};
-use crate::models::{UpdateEvent, UpdateResult};
+use crate::Error;
+use crate::core::{RushEngine, dev, pins, registry, util};
+use crate::models::{ProgressEvent, ProgressKind, UpdateResult};
 use anyhow::Result;
-use flate2::read::GzDecoder;
-use std::fs;
-use std::path::PathBuf;
-use tar::Archive;
-use walkdir::WalkDir;
 
 /// Update the package registry from the configured source.
-pub fn update_registry<F>(engine: &RushEngine, mut on_event: F) -> Result<UpdateResult>
+#[tracing::instrument(skip_all, fields(source = engine.registry.source()))]
+pub fn update_registry<F>(engine: &RushEngine, on_event: F) -> Result<UpdateResult>
 where
-    F: FnMut(UpdateEvent),
+    F: FnMut(ProgressEvent),
 {
-    let source = &engine.registry_source;
+    let source = engine.registry.source().to_string();
+    let mut on_event = util::tag_progress(None, on_event);
 
-    on_event(UpdateEvent::Fetching {
+    on_event(ProgressKind::FetchingRegistry {
         source: source.clone(),
     });
 
-    // 1. Wipe old registry
-    if engine.registry_dir.exists() {
-        fs::remove_dir_all(&engine.registry_dir)?;
+    if engine.registry.is_remote() {
+        engine.ensure_online(&source)?;
     }
-    fs::create_dir_all(&engine.registry_dir)?;
 
-    // 2. Handle Local Directory
-    if !source.starts_with("http") && !source.starts_with("file://") {
-        let source_path = PathBuf::from(source);
-        if !source_path.exists() {
-            anyhow::bail!("Local registry path not found: {:?}", source_path);
-        }
-
-        let pkg_source = source_path.join("packages");
-        if !pkg_source.exists() {
-            return Ok(UpdateResult {
-                source: source.clone(),
-            });
-        }
-
-        let pkg_dest = engine.registry_dir.join("packages");
-        for entry in WalkDir::new(&pkg_source) {
-            let entry = entry?;
-            if let Ok(rel_path) = entry.path().strip_prefix(&pkg_source) {
-                let dest_path = pkg_dest.join(rel_path);
-                if entry.file_type().is_dir() {
-                    fs::create_dir_all(&dest_path)?;
-                } else {
-                    fs::copy(entry.path(), &dest_path)?;
-                }
-            }
-        }
-        return Ok(UpdateResult {
-            source: source.clone(),
-        });
+    // An archive is fetched here rather than by the backend, so it can be checked against
+    // its pin before the old copy is replaced. Either way, the new copy's artifact hashes
+    // are then checked against the ones seen before.
+    if let Some(url) = engine.registry.archive_url() {
+        let content = util::download_url(engine.transport.as_ref(), url, &mut on_event)?;
+        verify_archive(engine, url, &content)?;
+        on_event(ProgressKind::Unpacking);
+        registry::reset_dir(&engine.registry_dir)?;
+        registry::unpack_packages(&content, &engine.registry_dir)?;
+    } else {
+        engine.registry.sync(
+            &engine.registry_dir,
+            engine.transport.as_ref(),
+            &mut on_event,
+        )?;
     }
 
-    // 3. Handle Remote Tarball via util::download_url
-    let content = util::download_url(&engine.client, source, &mut |event| {
-        if let crate::models::InstallEvent::Progress { bytes, total } = event {
-            on_event(UpdateEvent::Progress { bytes, total });
-        }
-    })?;
-
-    on_event(UpdateEvent::Unpacking);
+    Ok(UpdateResult {
+        source,
+        hash_changes: pins::observe_registry(engine)?,
+    })
+}
 
-    let tar = GzDecoder::new(&content[..]);
-    let mut archive = Archive::new(tar);
+/// Check a downloaded registry archive against its `[registry.archives]` pin. Archives
+/// without a pin pass; a pinned one must match everything its pin gives.
+pub(crate) fn verify_archive(engine: &RushEngine, url: &str, content: &[u8]) -> Result<()> {
+    let Some(pin) = engine.config.registry.archives.get(url) else {
+        return Ok(());
+    };
+    if pin.sha256.is_none() && pin.checksum_url.is_none() {
+        return Err(unverified(
+            url,
+            "its pin gives neither sha256 nor checksum_url",
+        ));
+    }
 
-    for entry in archive.entries()? {
-        let mut entry = entry?;
-        let path = entry.path()?;
+    if let Some(sha256) = &pin.sha256 {
+        util::verify_checksum(content, &sha256.to_lowercase())
+            .map_err(|e| unverified(url, &e.to_string()))?;
+    }
+    if let Some(checksum_url) = &pin.checksum_url {
+        let sha256 = signed_checksum(engine, url, checksum_url)?;
+        util::verify_checksum(content, &sha256).map_err(|e| unverified(url, &e.to_string()))?;
+    }
+    Ok(())
+}
 
-        if let Some(idx) = path.to_string_lossy().find("packages/") {
-            let relative_path = &path.to_string_lossy()[idx..];
-            let dest = engine.registry_dir.join(relative_path);
+/// Helper: The sha256 a signed checksum file lists for the archive at `url`
+fn signed_checksum(engine: &RushEngine, url: &str, checksum_url: &str) -> Result<String> {
+    let Some(public_key) = &engine.config.registry.public_key else {
+        return Err(unverified(
+            url,
+            "checksum_url needs [registry] public_key to check the file's signature",
+        ));
+    };
 
-            if let Some(parent) = dest.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            entry.unpack(dest)?;
-        }
+    let signature_url = format!("{}.sig", checksum_url);
+    engine.ensure_online(checksum_url)?;
+    let checksums = util::download_url(engine.transport.as_ref(), checksum_url, &mut |_| {})?;
+    let signature = util::download_url(engine.transport.as_ref(), &signature_url, &mut |_| {})
+        .map_err(|e| unverified(url, &format!("no signature at {}: {}", signature_url, e)))?;
+    if !util::verify_signature(public_key, &checksums, &String::from_utf8_lossy(&signature)) {
+        return Err(unverified(
+            url,
+            &format!("{} is not signed by [registry] public_key", checksum_url),
+        ));
     }
 
-    Ok(UpdateResult {
-        source: source.clone(),
+    let name = url.rsplit('/').next().unwrap_or(url);
+    dev::parse_checksum_file(&String::from_utf8_lossy(&checksums), name).ok_or_else(|| {
+        unverified(
+            url,
+            &format!("{} lists no sha256 for {}", checksum_url, name),
+        )
     })
 }
 
+/// Helper: `Error::RegistryUnverified` for `url`
+fn unverified(url: &str, reason: &str) -> anyhow::Error {
+    Error::RegistryUnverified {
+        url: url.to_string(),
+        reason: reason.to_string(),
+    }
+    .into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use flate2::Compression;
-    use flate2::write::GzEncoder;
-    use std::fs::File;
-    use tar::Builder;
+    use crate::test_utils::tar_gz;
     use tempfile::tempdir;
 
     #[test]
@@ -110,13 +126,13 @@ mod tests {
         let dummy_toml = pkg_dir.join("test-tool.toml");
         std::fs::write(&dummy_toml, "content").unwrap();
 
-        let engine = RushEngine::with_root_and_registry(
-            root.clone(),
-            source_dir.to_str().unwrap().to_string(),
-        )
-        .unwrap();
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(source_dir.to_str().unwrap().to_string())
+            .build()
+            .unwrap();
 
-        engine.update_registry(|_| {}).unwrap();
+        engine.update_registry(&mut ()).unwrap();
 
         let expected_dest = engine.registry_dir.join("packages/t/test-tool.toml");
         assert!(expected_dest.exists());
@@ -127,43 +143,55 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
 
-        let engine =
-            RushEngine::with_root_and_registry(root, "/path/that/does/not/exist".to_string())
-                .unwrap();
+        let engine = RushEngine::builder()
+            .root(root)
+            .registry_source("/path/that/does/not/exist".to_string())
+            .build()
+            .unwrap();
 
-        let result = engine.update_registry(|_| {});
+        let result = engine.update_registry(&mut ());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    /// A registry tarball with one package in `dir`, as a `file://` URL
+    fn registry_tarball(dir: &std::path::Path) -> String {
+        let archive_path = dir.join("registry.tar.gz");
+        let tarball = tar_gz(&[("packages/z/zipped-tool.toml", 0o644, b"data")]);
+        std::fs::write(&archive_path, tarball).unwrap();
+
+        format!("file://{}", archive_path.to_str().unwrap())
+    }
+
     #[test]
     fn test_update_from_tarball() {
         let temp_dir = tempdir().unwrap();
         let root = temp_dir.path().to_path_buf();
 
-        let archive_path = temp_dir.path().join("registry.tar.gz");
-        let file = File::create(&archive_path).unwrap();
-        let enc = GzEncoder::new(file, Compression::default());
-        let mut tar = Builder::new(enc);
-
-        let mut header = tar::Header::new_gnu();
-        header.set_path("packages/z/zipped-tool.toml").unwrap();
-        header.set_size(4);
-        header.set_cksum();
-        tar.append_data(
-            &mut header,
-            "packages/z/zipped-tool.toml",
-            "data".as_bytes(),
-        )
-        .unwrap();
-
-        let enc = tar.into_inner().unwrap();
-        enc.finish().unwrap();
+        let url = registry_tarball(temp_dir.path());
+        let engine = RushEngine::builder()
+            .root(root.clone())
+            .registry_source(url)
+            .build()
+            .unwrap();
 
-        let url = format!("file://{}", archive_path.to_str().unwrap());
-        let engine = RushEngine::with_root_and_registry(root.clone(), url).unwrap();
-
-        engine.update_registry(|_| {}).unwrap();
+        // Only progress events are overridden; everything else is the default no-op
+        #[derive(Default)]
+        struct Recorder(Vec<String>);
+        impl crate::models::Reporter for Recorder {
+            fn progress(&mut self, event: ProgressEvent) {
+                assert!(event.package.is_none());
+                self.0.push(match event.kind {
+                    ProgressKind::FetchingRegistry { .. } => "fetching".to_string(),
+                    ProgressKind::Unpacking => "unpacking".to_string(),
+                    _ => "download".to_string(),
+                });
+            }
+        }
+        let mut recorder = Recorder::default();
+        engine.update_registry(&mut recorder).unwrap();
+        assert_eq!(recorder.0.first().map(String::as_str), Some("fetching"));
+        assert_eq!(recorder.0.last().map(String::as_str), Some("unpacking"));
 
         let expected_file = engine
             .registry_dir
@@ -175,4 +203,79 @@ mod tests {
             "Registry tarball was not extracted correctly"
         );
     }
+
+    #[test]
+    fn test_pinned_archive_must_verify() {
+        use crate::models::ArchivePin;
+        use ed25519_dalek::{Signer, SigningKey};
+        use sha2::Digest;
+
+        let temp_dir = tempdir().unwrap();
+        let url = registry_tarball(temp_dir.path());
+        let sha256 = hex::encode(sha2::Sha256::digest(
+            std::fs::read(temp_dir.path().join("registry.tar.gz")).unwrap(),
+        ));
+        let mut engine = RushEngine::builder()
+            .root(temp_dir.path())
+            .registry_source(url.clone())
+            .build()
+            .unwrap();
+        let synced = engine.registry_dir.join("packages/z/zipped-tool.toml");
+        let pin = |engine: &mut RushEngine, pin: ArchivePin| {
+            engine.config.registry.archives.insert(url.clone(), pin);
+        };
+
+        // A wrong pin leaves the local registry alone
+        pin(
+            &mut engine,
+            ArchivePin {
+                sha256: Some("0".repeat(64)),
+                checksum_url: None,
+            },
+        );
+        let err = engine.update_registry(&mut ()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::RegistryUnverified { .. })
+        ));
+        assert!(!synced.exists());
+
+        pin(
+            &mut engine,
+            ArchivePin {
+                sha256: Some(sha256.to_uppercase()),
+                checksum_url: None,
+            },
+        );
+        engine.update_registry(&mut ()).unwrap();
+        assert!(synced.exists());
+
+        // A checksum file counts once it is signed by the configured key
+        let sums = temp_dir.path().join("SHA256SUMS");
+        let content = format!("{}  registry.tar.gz\n", sha256);
+        std::fs::write(&sums, &content).unwrap();
+        let key = SigningKey::from_bytes(&[3; 32]);
+        std::fs::write(
+            temp_dir.path().join("SHA256SUMS.sig"),
+            hex::encode(key.sign(content.as_bytes()).to_bytes()),
+        )
+        .unwrap();
+        pin(
+            &mut engine,
+            ArchivePin {
+                sha256: None,
+                checksum_url: Some(format!("file://{}", sums.display())),
+            },
+        );
+        let err = engine.update_registry(&mut ()).unwrap_err();
+        assert!(err.to_string().contains("needs [registry] public_key"));
+
+        engine.config.registry.public_key = Some(hex::encode(key.verifying_key().as_bytes()));
+        engine.update_registry(&mut ()).unwrap();
+
+        let other = SigningKey::from_bytes(&[4; 32]);
+        engine.config.registry.public_key = Some(hex::encode(other.verifying_key().as_bytes()));
+        let err = engine.update_registry(&mut ()).unwrap_err();
+        assert!(err.to_string().contains("is not signed by"));
+    }
 }
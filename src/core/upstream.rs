@@ -0,0 +1,127 @@
+//! Checking the registry's hashes against the checksum files releases publish themselves.
+//!
+//! A manifest target can name the checksum file its artifact was imported from
+//! (`checksum_url`). Re-reading that file shows whether the registry still lists the hash
+//! upstream does, catching a registry edited after the fact or a hash copied wrong.
+
+use crate::core::{RushEngine, dev};
+use crate::models::{UpstreamCheck, UpstreamResult, UpstreamStatus};
+use anyhow::{Context, Result};
+
+/// Compare every target of `name`'s manifest with its upstream checksum file
+#[tracing::instrument(skip(engine))]
+pub fn verify_upstream(engine: &RushEngine, name: &str) -> Result<UpstreamResult> {
+    let manifest = engine
+        .find_package(name)
+        .with_context(|| format!("Package '{}' not found", name))?;
+
+    let targets = manifest
+        .targets
+        .iter()
+        .map(|(target, artifact)| {
+            let status = match &artifact.checksum_url {
+                Some(checksum_url) => check(engine, checksum_url, &artifact.url, &artifact.sha256),
+                None => UpstreamStatus::NoChecksumFile,
+            };
+            UpstreamCheck {
+                target: target.clone(),
+                sha256: artifact.sha256.clone(),
+                status,
+            }
+        })
+        .collect();
+
+    Ok(UpstreamResult {
+        package: name.to_string(),
+        version: manifest.version,
+        targets,
+    })
+}
+
+/// Helper: What the checksum file at `checksum_url` says about the artifact at `url`.
+/// Checksum files list artifacts by file name, the last segment of its URL.
+fn check(engine: &RushEngine, checksum_url: &str, url: &str, sha256: &str) -> UpstreamStatus {
+    let asset_name = url.rsplit('/').next().unwrap_or(url);
+    let upstream = engine.ensure_online(checksum_url).and_then(|()| {
+        dev::fetch_upstream_checksum(engine.transport.as_ref(), checksum_url, asset_name)
+    });
+
+    match upstream {
+        Ok(upstream) if upstream.eq_ignore_ascii_case(sha256) => UpstreamStatus::Match {
+            checksum_url: checksum_url.to_string(),
+        },
+        Ok(upstream) => UpstreamStatus::Mismatch {
+            checksum_url: checksum_url.to_string(),
+            upstream,
+        },
+        Err(e) => UpstreamStatus::Failed {
+            checksum_url: checksum_url.to_string(),
+            error: format!("{:#}", e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEnvironment, current_target};
+    use sha2::{Digest, Sha256};
+    use std::fs;
+
+    #[test]
+    fn test_verify_upstream() {
+        let mock = MockEnvironment::new();
+        mock.add_package("tool", "1.0.0", "tool");
+        mock.add_malicious_package("evil", "1.0.0", "evil");
+        mock.add_package("bare", "1.0.0", "bare");
+
+        // The "release" publishes the real hashes of both archives
+        let mut sums = String::new();
+        for archive in ["tool-1.0.0.tar.gz", "evil-1.0.0.tar.gz"] {
+            let content = fs::read(mock.registry_source.join(archive)).unwrap();
+            sums += &format!("{}  {}\n", hex::encode(Sha256::digest(&content)), archive);
+        }
+        let sums_path = mock.registry_source.join("SHA256SUMS");
+        fs::write(&sums_path, sums).unwrap();
+        for manifest in ["t/tool.toml", "e/evil.toml"] {
+            let path = mock.registry_source.join("packages").join(manifest);
+            let content = fs::read_to_string(&path).unwrap();
+            let line = format!("checksum_url = \"file://{}\"\n", sums_path.display());
+            fs::write(&path, content + &line).unwrap();
+        }
+
+        let engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let result = verify_upstream(&engine, "tool").unwrap();
+        assert_eq!(result.targets.len(), 1);
+        assert_eq!(result.targets[0].target, current_target());
+        assert!(matches!(
+            result.targets[0].status,
+            UpstreamStatus::Match { .. }
+        ));
+        assert_eq!(result.failed().count(), 0);
+
+        // The registry's "0000..." isn't what the release published
+        let result = verify_upstream(&engine, "evil").unwrap();
+        assert!(matches!(
+            &result.targets[0].status,
+            UpstreamStatus::Mismatch { upstream, .. } if upstream.len() == 64
+        ));
+        assert_eq!(result.failed().count(), 1);
+
+        let result = verify_upstream(&engine, "bare").unwrap();
+        assert_eq!(result.targets[0].status, UpstreamStatus::NoChecksumFile);
+        assert_eq!(result.failed().count(), 0);
+
+        // A checksum file that went missing can't vouch for anything
+        fs::remove_file(&sums_path).unwrap();
+        let result = verify_upstream(&engine, "tool").unwrap();
+        assert!(matches!(
+            result.targets[0].status,
+            UpstreamStatus::Failed { .. }
+        ));
+
+        assert!(verify_upstream(&engine, "ghost").is_err());
+    }
+}
@@ -1,46 +1,52 @@
-use crate::models::InstallEvent;
+use crate::Error;
+use crate::core::transport::Transport;
+use crate::models::{OperationId, ProgressEvent, ProgressKind};
 use anyhow::Result;
-use reqwest::blocking::Client;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
-use std::fs;
 use std::io::Read;
 
-/// Generic download with progress events
-pub fn download_url<F>(client: &Client, url: &str, on_event: &mut F) -> Result<Vec<u8>>
+/// Starts a new operation: `on_event` gets each stage tagged with its id and `package`
+pub fn tag_progress<F>(package: Option<&str>, on_event: F) -> impl FnMut(ProgressKind) + use<F>
 where
-    F: FnMut(InstallEvent),
+    F: FnMut(ProgressEvent),
 {
-    // Testing
-    if url.starts_with("file://") {
-        let path = url.trim_start_matches("file://");
-        let metadata = fs::metadata(path)?;
-        let total_size = metadata.len();
-
-        on_event(InstallEvent::Downloading {
-            total_bytes: total_size,
-        });
-        on_event(InstallEvent::Progress {
-            bytes: 0,
-            total: total_size,
-        });
-
-        let content = fs::read(path)?;
-
-        on_event(InstallEvent::Progress {
-            bytes: total_size,
-            total: total_size,
-        });
+    tag_operation(OperationId::next(), package, on_event)
+}
 
-        return Ok(content);
+/// `tag_progress` for an operation that already has an id, e.g. one whose stages are
+/// reported from more than one thread
+pub fn tag_operation<F>(
+    operation: OperationId,
+    package: Option<&str>,
+    mut on_event: F,
+) -> impl FnMut(ProgressKind) + use<F>
+where
+    F: FnMut(ProgressEvent),
+{
+    let package = package.map(str::to_string);
+    move |kind| {
+        on_event(ProgressEvent {
+            operation,
+            package: package.clone(),
+            kind,
+        })
     }
+}
 
-    let mut response = client.get(url).send()?.error_for_status()?;
-    let total_size = response.content_length().unwrap_or(0);
+/// Generic download with progress events
+#[tracing::instrument(level = "debug", skip(transport, on_event), fields(bytes))]
+pub fn download_url<F>(transport: &dyn Transport, url: &str, on_event: &mut F) -> Result<Vec<u8>>
+where
+    F: FnMut(ProgressKind),
+{
+    let mut response = transport.get(url)?;
+    let total_size = response.content_length.unwrap_or(0);
 
-    on_event(InstallEvent::Downloading {
+    on_event(ProgressKind::Downloading {
         total_bytes: total_size,
     });
-    on_event(InstallEvent::Progress {
+    on_event(ProgressKind::Progress {
         bytes: 0,
         total: total_size,
     });
@@ -49,39 +55,126 @@ where
     let mut buffer = [0; 8192];
 
     loop {
-        let bytes_read = response.read(&mut buffer)?;
+        let bytes_read = response.body.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
         }
         content.extend_from_slice(&buffer[..bytes_read]);
-        on_event(InstallEvent::Progress {
+        on_event(ProgressKind::Progress {
             bytes: bytes_read as u64,
             total: total_size,
         });
     }
 
+    // A dropped connection can look like a clean end of stream
+    if let Some(expected) = response.content_length
+        && (content.len() as u64) < expected
+    {
+        return Err(Error::TruncatedDownload {
+            url: url.to_string(),
+            received: content.len() as u64,
+            expected,
+        }
+        .into());
+    }
+
+    tracing::Span::current().record("bytes", content.len());
+    Ok(content)
+}
+
+/// Async `download_url`, for `AsyncRushEngine`
+#[cfg(feature = "async")]
+#[tracing::instrument(level = "debug", skip(client, cancel, on_event), fields(bytes))]
+pub async fn download_url_async<F>(
+    client: &reqwest::Client,
+    url: &str,
+    cancel: &crate::core::CancellationToken,
+    on_event: &mut F,
+) -> Result<Vec<u8>>
+where
+    F: FnMut(ProgressKind),
+{
+    cancel.check()?;
+
+    // Testing
+    if let Some(path) = url.strip_prefix("file://") {
+        let content = tokio::fs::read(path).await?;
+        let total_size = content.len() as u64;
+
+        on_event(ProgressKind::Downloading {
+            total_bytes: total_size,
+        });
+        on_event(ProgressKind::Progress {
+            bytes: total_size,
+            total: total_size,
+        });
+
+        tracing::Span::current().record("bytes", content.len());
+        return Ok(content);
+    }
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    let total_size = response.content_length().unwrap_or(0);
+
+    on_event(ProgressKind::Downloading {
+        total_bytes: total_size,
+    });
+
+    let mut content = Vec::with_capacity(total_size as usize);
+    while let Some(chunk) = response.chunk().await? {
+        cancel.check()?;
+        content.extend_from_slice(&chunk);
+        on_event(ProgressKind::Progress {
+            bytes: chunk.len() as u64,
+            total: total_size,
+        });
+    }
+
+    tracing::Span::current().record("bytes", content.len());
     Ok(content)
 }
 
 /// Verify checksum of given content against expected hash
+#[tracing::instrument(level = "debug", skip_all, fields(bytes = content.len()))]
 pub fn verify_checksum(content: &[u8], expected_hash: &str) -> Result<()> {
     let mut hasher = Sha256::new();
     hasher.update(content);
     let hash = hex::encode(hasher.finalize());
 
     if hash != expected_hash {
-        anyhow::bail!(
-            "Security check failed: Checksum mismatch. Expected: {}, Got: {}",
-            expected_hash,
-            hash
-        );
+        return Err(Error::ChecksumMismatch {
+            expected: expected_hash.to_string(),
+            actual: hash,
+        }
+        .into());
     }
     Ok(())
 }
 
+/// Whether `signature` (hex) is `public_key`'s (hex ed25519, as `rush dev keygen` prints
+/// it) signature of `message`. Malformed keys and signatures don't verify.
+pub(crate) fn verify_signature(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let key = hex::decode(public_key.trim())
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .and_then(|b| VerifyingKey::from_bytes(&b).ok());
+    let signature = hex::decode(signature.trim())
+        .ok()
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .map(|b| Signature::from_bytes(&b));
+
+    match (key, signature) {
+        (Some(key), Some(signature)) => key.verify(message, &signature).is_ok(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::transport::HttpTransport;
+    use crate::core::transport::mock::{MockResponse, MockTransport};
+    use reqwest::blocking::Client;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -104,10 +197,10 @@ mod tests {
         let path = temp_file.path().to_str().unwrap();
         let url = format!("file://{}", path);
 
-        let client = Client::new();
+        let transport = HttpTransport::new(Client::new());
         let mut progress_count = 0;
 
-        let result = download_url(&client, &url, &mut |_| {
+        let result = download_url(&transport, &url, &mut |_| {
             progress_count += 1;
         })
         .unwrap();
@@ -121,15 +214,81 @@ mod tests {
 
     #[test]
     fn test_download_url_file_missing() {
-        let client = Client::new();
+        let transport = HttpTransport::new(Client::new());
 
         let url = "file:///path/to/nowhere/ghost.tar.gz";
 
-        let result = download_url(&client, url, &mut |_| {});
+        let result = download_url(&transport, url, &mut |_| {});
 
         assert!(result.is_err());
 
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("No such file") || err_msg.contains("cannot find"));
     }
+
+    #[test]
+    fn test_download_url_mocked_responses() {
+        let transport = MockTransport::default()
+            .with("https://x/ok", MockResponse::Body(b"hello".to_vec()))
+            .with(
+                "https://x/slow",
+                MockResponse::Slow(b"hello world".to_vec()),
+            )
+            .with(
+                "https://x/cut",
+                MockResponse::Truncated {
+                    body: b"hel".to_vec(),
+                    length: 5,
+                },
+            )
+            .with(
+                "https://x/timeout",
+                MockResponse::Error("operation timed out".to_string()),
+            );
+
+        assert_eq!(
+            download_url(&transport, "https://x/ok", &mut |_| {}).unwrap(),
+            b"hello"
+        );
+
+        let mut received = 0;
+        let body = download_url(&transport, "https://x/slow", &mut |e| {
+            if let ProgressKind::Progress { bytes, .. } = e {
+                received += bytes;
+            }
+        })
+        .unwrap();
+        assert_eq!(body, b"hello world");
+        assert_eq!(received, 11);
+
+        let err = download_url(&transport, "https://x/cut", &mut |_| {}).unwrap_err();
+        assert!(err.to_string().contains("cut short (3 of 5 bytes)"));
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::TruncatedDownload { received: 3, .. })
+        ));
+
+        let err = download_url(&transport, "https://x/timeout", &mut |_| {}).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let err = download_url(&transport, "https://x/missing", &mut |_| {}).unwrap_err();
+        assert!(err.to_string().contains("404"));
+        assert_eq!(transport.requests.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_tag_progress_separates_operations() {
+        let mut events = Vec::new();
+        {
+            let mut first = tag_progress(Some("bat"), |e| events.push(e));
+            first(ProgressKind::Extracting);
+            first(ProgressKind::Success);
+        }
+        tag_progress(None, |e| events.push(e))(ProgressKind::Unpacking);
+
+        assert_eq!(events[0].operation, events[1].operation);
+        assert_ne!(events[0].operation, events[2].operation);
+        assert_eq!(events[0].package.as_deref(), Some("bat"));
+        assert!(events[2].package.is_none());
+    }
 }
@@ -0,0 +1,155 @@
+use crate::models::{State, StateChange, SubscriptionId};
+
+type Callback = Box<dyn Fn(&StateChange) + Send + Sync>;
+
+/// The callbacks registered with `RushEngine::subscribe()`
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    next_id: u64,
+    callbacks: Vec<(SubscriptionId, Callback)>,
+}
+
+impl Subscribers {
+    pub(crate) fn add(&mut self, callback: Callback) -> SubscriptionId {
+        self.next_id += 1;
+        let id = SubscriptionId(self.next_id);
+        self.callbacks.push((id, callback));
+        id
+    }
+
+    /// Returns whether `id` was subscribed
+    pub(crate) fn remove(&mut self, id: SubscriptionId) -> bool {
+        let before = self.callbacks.len();
+        self.callbacks.retain(|(existing, _)| *existing != id);
+        self.callbacks.len() != before
+    }
+
+    pub(crate) fn notify(&self, changes: &[StateChange]) {
+        for change in changes {
+            for (_, callback) in &self.callbacks {
+                callback(change);
+            }
+        }
+    }
+}
+
+/// What changed between two saved states, sorted by package name
+pub(crate) fn diff(old: &State, new: &State) -> Vec<StateChange> {
+    let mut changes = Vec::new();
+
+    for (name, pkg) in &new.packages {
+        match old.packages.get(name) {
+            None => changes.push(StateChange::Installed {
+                package: name.clone(),
+                version: pkg.version.clone(),
+            }),
+            Some(previous) if previous.version != pkg.version => {
+                changes.push(StateChange::Upgraded {
+                    package: name.clone(),
+                    from: previous.version.clone(),
+                    to: pkg.version.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, pkg) in &old.packages {
+        if !new.packages.contains_key(name) {
+            changes.push(StateChange::Removed {
+                package: name.clone(),
+                version: pkg.version.clone(),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| package_of(a).cmp(package_of(b)));
+    changes
+}
+
+fn package_of(change: &StateChange) -> &str {
+    match change {
+        StateChange::Installed { package, .. }
+        | StateChange::Removed { package, .. }
+        | StateChange::Upgraded { package, .. } => package,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RushEngine;
+    use crate::models::InstalledPackage;
+    use std::sync::{Arc, Mutex};
+    use tempfile::tempdir;
+
+    fn installed(version: &str) -> InstalledPackage {
+        InstalledPackage {
+            version: version.to_string(),
+            binaries: vec![],
+            files: Vec::new(),
+            provenance: None,
+            constraint: None,
+            pinned: false,
+            dependency: false,
+        }
+    }
+
+    #[test]
+    fn test_subscribers_see_each_saved_change() {
+        let temp_dir = tempdir().unwrap();
+        let mut engine = RushEngine::builder().root(temp_dir.path()).build().unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let id = engine.subscribe(move |change| sink.lock().unwrap().push(change.clone()));
+
+        engine
+            .state
+            .packages
+            .insert("bat".to_string(), installed("1.0.0"));
+        engine
+            .state
+            .packages
+            .insert("fd".to_string(), installed("9.0.0"));
+        engine.save().unwrap();
+
+        engine
+            .state
+            .packages
+            .insert("bat".to_string(), installed("1.1.0"));
+        engine.state.packages.remove("fd");
+        engine.save().unwrap();
+
+        // Saving without changes notifies nobody
+        engine.save().unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                StateChange::Installed {
+                    package: "bat".to_string(),
+                    version: "1.0.0".to_string()
+                },
+                StateChange::Installed {
+                    package: "fd".to_string(),
+                    version: "9.0.0".to_string()
+                },
+                StateChange::Upgraded {
+                    package: "bat".to_string(),
+                    from: "1.0.0".to_string(),
+                    to: "1.1.0".to_string()
+                },
+                StateChange::Removed {
+                    package: "fd".to_string(),
+                    version: "9.0.0".to_string()
+                },
+            ]
+        );
+
+        assert!(engine.unsubscribe(id));
+        assert!(!engine.unsubscribe(id));
+        engine.state.packages.clear();
+        engine.save().unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 4);
+    }
+}
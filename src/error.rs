@@ -0,0 +1,172 @@
+//! Failures that callers of the library may want to handle.
+//!
+//! Engine methods return `anyhow::Result` so every failure carries its context, but the
+//! meaningful ones are raised as a `rush::Error` inside it. Recover them with
+//! `downcast_ref`:
+//!
+//! ```no_run
+//! # fn check(result: anyhow::Result<()>) {
+//! if let Err(e) = result {
+//!     match e.downcast_ref::<rush::Error>() {
+//!         Some(rush::Error::ChecksumMismatch { .. }) => eprintln!("tampered download!"),
+//!         Some(rush::Error::RateLimited(limit)) => eprintln!("retry in {:?}", limit.wait_time()),
+//!         _ => eprintln!("{:#}", e),
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::core::{RateLimitError, TARGETS};
+use crate::models::{Conflict, HookPoint};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A download doesn't hash to the sha256 in its manifest
+    ChecksumMismatch { expected: String, actual: String },
+    /// The archive was fetched, but the manifest's binary isn't in it
+    BinaryNotFound { bin: String },
+    /// The connection ended before the advertised number of bytes arrived
+    TruncatedDownload {
+        url: String,
+        received: u64,
+        expected: u64,
+    },
+    /// The engine was built with `offline(true)` and was asked to use the network
+    Offline { url: String },
+    /// The registry source is a directory that doesn't exist
+    RegistryNotFound { path: PathBuf },
+    /// A developer tool that writes manifests was pointed at a remote registry
+    NotLocalRegistry,
+    /// `create_package` was asked to create a package the registry already has
+    PackageExists { name: String },
+    /// A target slug rush doesn't know (e.g. "sparc-solaris")
+    UnknownTarget { target: String },
+    /// The package's manifest has no binary for the requested target
+    NoCompatibleTarget { package: String, target: String },
+    /// `RushEngine::apply()` was handed a plan with conflicts
+    PlanConflicts(Vec<Conflict>),
+    /// The GitHub API rate limit is used up
+    RateLimited(RateLimitError),
+    /// The operation was stopped through the engine's `CancellationToken`
+    Cancelled,
+    /// The operation ran past its time limit (e.g. `dev refresh-all --timeout`)
+    TimedOut { after: Duration },
+    /// A package's `check` can't be confined on this platform, so it was not run
+    SandboxUnavailable { reason: String },
+    /// The artifact's sha256 differs from the one first seen for that package version;
+    /// `RushEngine::accept_hash_changes()` lets it through
+    HashChanged {
+        package: String,
+        version: String,
+        known: String,
+        now: String,
+    },
+    /// A registry archive failed its `[registry.archives]` pin, so the local registry was
+    /// left as it was
+    RegistryUnverified { url: String, reason: String },
+    /// A `pre_install`/`pre_uninstall` hook failed, so the operation was not done
+    HookFailed {
+        point: HookPoint,
+        package: String,
+        exit_code: Option<i32>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Security check failed: Checksum mismatch. Expected: {}, Got: {}",
+                expected, actual
+            ),
+            Error::BinaryNotFound { bin } => write!(f, "Binary '{}' not found in archive", bin),
+            Error::TruncatedDownload {
+                url,
+                received,
+                expected,
+            } => write!(
+                f,
+                "Download of {} was cut short ({} of {} bytes)",
+                url, received, expected
+            ),
+            Error::Offline { url } => write!(f, "Offline mode: refusing to download {}", url),
+            Error::RegistryNotFound { path } => {
+                write!(f, "Local registry path not found: {:?}", path)
+            }
+            Error::NotLocalRegistry => write!(
+                f,
+                "RUSH_REGISTRY_URL must be set to your local git repository path to alter the local registry. \
+                 \nTry: \n\texport RUSH_REGISTRY_URL=\"$(pwd)\""
+            ),
+            Error::PackageExists { name } => write!(
+                f,
+                "'{}' is already in the registry; use `rush dev add` or `rush dev bump` to change it",
+                name
+            ),
+            Error::UnknownTarget { target } => {
+                let known: Vec<&str> = TARGETS.iter().map(|(slug, _)| *slug).collect();
+                write!(
+                    f,
+                    "Unknown target '{}' (expected one of: {})",
+                    target,
+                    known.join(", ")
+                )
+            }
+            Error::NoCompatibleTarget { package, target } => {
+                write!(f, "No compatible binary for {} in '{}'", target, package)
+            }
+            Error::PlanConflicts(conflicts) => {
+                let reasons: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+                write!(f, "Can't apply the plan: {}", reasons.join("; "))
+            }
+            Error::RateLimited(limit) => limit.fmt(f),
+            Error::Cancelled => write!(f, "Operation cancelled"),
+            Error::TimedOut { after } => write!(f, "Gave up after {}s", after.as_secs()),
+            Error::SandboxUnavailable { reason } => {
+                write!(f, "Refusing to run the check outside a sandbox: {}", reason)
+            }
+            Error::HashChanged {
+                package,
+                version,
+                known,
+                now,
+            } => write!(
+                f,
+                "The artifact of {} v{} has changed since it was first seen (was {}, now {}). \
+                 It may have been re-published or tampered with; if you trust the change, run \
+                 `rush update --accept-changed {}`",
+                package, version, known, now, package
+            ),
+            Error::RegistryUnverified { url, reason } => write!(
+                f,
+                "Refusing to update the registry from {}: {}",
+                url, reason
+            ),
+            Error::HookFailed {
+                point,
+                package,
+                exit_code,
+            } => match exit_code {
+                Some(code) => write!(
+                    f,
+                    "{} hook for '{}' exited with status {}",
+                    point, package, code
+                ),
+                None => write!(f, "{} hook for '{}' did not finish", point, package),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<RateLimitError> for Error {
+    fn from(limit: RateLimitError) -> Self {
+        Error::RateLimited(limit)
+    }
+}
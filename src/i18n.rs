@@ -0,0 +1,209 @@
+//! The text the CLI prints, from [Fluent](https://projectfluent.org) catalogs.
+//!
+//! Each language has a `locales/<lang>/rush.ftl` built into the binary; `ui` looks every
+//! message up by id with `t!`. The language comes from the environment the way gettext
+//! picks it (`LC_ALL`, then `LC_MESSAGES`, then `LANG`), and a catalog for the language
+//! alone (`pt`) serves its regions (`pt_BR.UTF-8`) too. English is complete; a message
+//! another catalog doesn't have yet is printed in English.
+//!
+//! Layout (indentation, bullets, columns) and what isn't prose, like shell code, porcelain
+//! output, and the commands a message suggests, stay in `ui`.
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// The catalogs built in, by language; the first is the fallback for every other
+const CATALOGS: &[(&str, &str)] = &[("en", include_str!("../locales/en/rush.ftl"))];
+
+/// The message `$id` with `name = value` arguments, in the user's language
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+pub(crate) use t;
+
+/// The catalog for the environment's locale, loaded on first use
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Format the message `id`, falling back to English, then to the id itself
+pub(crate) fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    CATALOG
+        .get_or_init(|| Catalog::new(env_locale().as_ref(), CATALOGS))
+        .format(id, args)
+}
+
+/// The bundles to look a message up in, the user's language first
+struct Catalog {
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    /// The catalog of `catalogs` matching `locale` best, backed by the first of them
+    fn new(locale: Option<&LanguageIdentifier>, catalogs: &[(&str, &str)]) -> Self {
+        let parsed: Vec<(LanguageIdentifier, &str)> = catalogs
+            .iter()
+            .map(|(lang, source)| (lang.parse().expect("catalog language"), *source))
+            .collect();
+
+        let mut picked = vec![0];
+        if let Some(locale) = locale {
+            // The exact locale, else its language alone
+            let best = parsed
+                .iter()
+                .position(|(lang, _)| lang == locale)
+                .or_else(|| {
+                    parsed
+                        .iter()
+                        .position(|(lang, _)| lang.language == locale.language)
+                });
+            if let Some(best) = best.filter(|&i| i != 0) {
+                picked.insert(0, best);
+            }
+        }
+
+        let bundles = picked
+            .into_iter()
+            .map(|i| {
+                let (lang, source) = &parsed[i];
+                let resource = FluentResource::try_new(source.to_string())
+                    .unwrap_or_else(|(_, errors)| panic!("{} catalog: {:?}", lang, errors));
+                let mut bundle = FluentBundle::new_concurrent(vec![lang.clone()]);
+                // No Unicode isolation marks around arguments; terminals print them
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|errors| panic!("{} catalog: {:?}", lang, errors));
+                bundle
+            })
+            .collect();
+        Catalog { bundles }
+    }
+
+    /// Helper: `id` from the first bundle that has it
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in &self.bundles {
+            let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            return bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned();
+        }
+        id.to_string()
+    }
+}
+
+/// Helper: The locale messages are wanted in, from the first of `LC_ALL`, `LC_MESSAGES`
+/// and `LANG` that is set
+fn env_locale() -> Option<LanguageIdentifier> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_locale(&value))
+}
+
+/// Helper: A POSIX locale (`de_DE.UTF-8`, `sr_RS@latin`) as a language identifier;
+/// None for `C` and `POSIX`, which mean no translation
+fn parse_locale(value: &str) -> Option<LanguageIdentifier> {
+    let name = value.split(['.', '@']).next()?;
+    if name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.replace('_', "-").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Helper: The message ids in a catalog's source
+    fn catalog_ids(source: &str) -> BTreeSet<String> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" ="))
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    /// Helper: The ids `t!` is called with in a source file
+    fn used_ids(source: &str) -> BTreeSet<String> {
+        source
+            .match_indices("t!(")
+            // Not `print!(`
+            .filter(|&(i, _)| !source[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+            .filter_map(|(i, call)| source[i + call.len()..].trim_start().strip_prefix('"'))
+            .filter_map(|call| call.split_once('"'))
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs_cover_the_ui() {
+        let catalog = Catalog::new(None, CATALOGS);
+        let used = used_ids(include_str!("ui.rs"));
+        assert!(used.len() > 100);
+        for id in &used {
+            assert!(
+                catalog.bundles[0].has_message(id),
+                "{} isn't in the English catalog",
+                id
+            );
+        }
+        // And nothing in it is left over
+        let english = catalog_ids(CATALOGS[0].1);
+        let unused: Vec<_> = english.difference(&used).collect();
+        assert!(unused.is_empty(), "unused messages: {:?}", unused);
+        // Other catalogs only translate what English has
+        for (lang, source) in &CATALOGS[1..] {
+            let extra: Vec<_> = catalog_ids(source).difference(&english).cloned().collect();
+            assert!(
+                extra.is_empty(),
+                "{} has unknown messages: {:?}",
+                lang,
+                extra
+            );
+        }
+    }
+
+    #[test]
+    fn test_catalog_picks_and_falls_back() {
+        let catalogs = [
+            ("en", "greeting = Hello, { $name }!\nfarewell = Bye"),
+            ("de", "greeting = Hallo, { $name }!"),
+        ];
+        let mut args = FluentArgs::new();
+        args.set("name", "rush");
+
+        let german = Catalog::new(parse_locale("de_AT.UTF-8").as_ref(), &catalogs);
+        assert_eq!(german.format("greeting", Some(&args)), "Hallo, rush!");
+        // Untranslated messages are English, unknown ones their id
+        assert_eq!(german.format("farewell", None), "Bye");
+        assert_eq!(german.format("nope", None), "nope");
+
+        for locale in [None, parse_locale("fr_FR"), parse_locale("C")] {
+            let catalog = Catalog::new(locale.as_ref(), &catalogs);
+            assert_eq!(catalog.format("greeting", Some(&args)), "Hello, rush!");
+        }
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(parse_locale("pt_BR.UTF-8").unwrap().to_string(), "pt-BR");
+        assert_eq!(parse_locale("sr_RS@latin").unwrap().to_string(), "sr-RS");
+        assert_eq!(parse_locale("de").unwrap().to_string(), "de");
+        assert!(parse_locale("C.UTF-8").is_none());
+        assert!(parse_locale("POSIX").is_none());
+    }
+}
@@ -1,4 +1,13 @@
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod core;
+pub mod error;
+#[cfg(feature = "cli")]
+mod i18n;
 pub mod models;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+#[cfg(feature = "cli")]
 pub mod ui;
+
+pub use error::Error;
@@ -8,20 +8,193 @@
 
 use anyhow::Result;
 use clap::{CommandFactory, Parser};
-use clap_complete::generate;
-
-use rush::cli::{Cli, Commands, DevCommands};
-use rush::core::RushEngine;
+use clap_complete::{Shell, generate};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rush::cli::{Cli, Commands, DevCommands, ExportFormat, RegistryCommands, ScheduleCommands};
+use rush::core::{RushEngine, TARGETS, sort_packages};
+use rush::models::PackageManifest;
 use rush::ui;
 
+/// Exit status after Ctrl-C (128 + SIGINT, as shells report it)
+const EXIT_INTERRUPTED: i32 = 130;
+/// Exit status of `rush outdated --exit-code` when something is outdated, apart from the
+/// 1 of a failed check
+const EXIT_OUTDATED: i32 = 10;
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    ui::init_logging(cli.verbose, cli.log_file.as_deref())?;
 
     // Initialize Engine
     let mut engine = RushEngine::new()?;
+    let mut reporter = ui::TerminalReporter::new(cli.progress);
+
+    // Ctrl-C cancels installs and updates so they can clean up; anything else (and a
+    // second Ctrl-C) stops right away
+    let cancellable = matches!(
+        cli.command,
+        Commands::Install { .. }
+            | Commands::Find { .. }
+            | Commands::Run { .. }
+            | Commands::Shell { .. }
+            | Commands::Sync { check: false }
+            | Commands::SelfUpdate { .. }
+            | Commands::Env { project: true, .. }
+            | Commands::Upgrade { .. }
+            | Commands::Update { .. }
+            | Commands::Mirror { .. }
+            | Commands::ExportBundle { .. }
+            | Commands::ImportBundle { .. }
+    );
+    let token = engine.cancellation_token();
+    ctrlc::set_handler(move || {
+        if !cancellable || token.is_cancelled() {
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        token.cancel();
+    })?;
+
+    let result = run(&cli, &mut engine, &mut reporter);
+    if result.as_ref().is_err_and(is_interrupted) {
+        // Dropping the reporter settles any progress bar still on screen
+        drop(reporter);
+        let _ = engine.clean_own_trash();
+        ui::print_interrupted();
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    result
+}
+
+/// Replace rush with `command` (elsewhere than Unix: run it, and exit with its status)
+fn exec(mut command: std::process::Command) -> Result<()> {
+    #[cfg(unix)]
+    let err = {
+        use std::os::unix::process::CommandExt;
+        command.exec()
+    };
+    #[cfg(not(unix))]
+    let err = match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => e,
+    };
+    Err(anyhow::anyhow!(
+        "Failed to run {}: {}",
+        command.get_program().to_string_lossy(),
+        err
+    ))
+}
+
+/// A manual page's name, as `rush man` takes it: "tool.conf.5.gz" -> "tool.conf"
+fn man_page_name(path: &Path) -> String {
+    let file = path.file_name().unwrap_or_default().to_string_lossy();
+    let file = file.strip_suffix(".gz").unwrap_or(&file);
+    file.rsplit_once('.')
+        .map_or(file, |(page, _)| page)
+        .to_string()
+}
+
+/// The user's interactive shell, for `rush shell`
+fn user_shell() -> std::ffi::OsString {
+    let (var, fallback) = if cfg!(windows) {
+        ("COMSPEC", "cmd.exe")
+    } else {
+        ("SHELL", "sh")
+    };
+    std::env::var_os(var).unwrap_or_else(|| fallback.into())
+}
+
+/// Install `name` from `manifest` for the engine's target (with `replace`, in place of
+/// another installed version), reporting the outcome; a failure other than an interrupt
+/// exits with status 1
+fn install(
+    engine: &mut RushEngine,
+    name: &str,
+    manifest: &PackageManifest,
+    replace: bool,
+    reporter: &mut ui::TerminalReporter,
+) -> Result<()> {
+    let changes = match engine.state.packages.get(name) {
+        Some(installed) => replace && installed.version != manifest.version,
+        None => true,
+    };
+    if engine.targets_host() && changes {
+        ui::print_requirement_warnings(&engine.unmet_requirements(name, manifest));
+    }
+    let target = engine.target().to_string();
+    let outcome = if replace {
+        engine.replace_from_manifest(name, manifest, &target, reporter)
+    } else {
+        engine.install_from_manifest(name, manifest, &target, reporter)
+    };
+    match outcome {
+        Ok(outcome) => {
+            ui::print_install_outcome(&outcome);
+            if outcome.is_installed() {
+                run_check(engine, name, manifest);
+                if !engine.bin_dir_on_path() {
+                    offer_path_fix(engine)?;
+                }
+            }
+            Ok(())
+        }
+        Err(e) if is_interrupted(&e) => Err(e),
+        Err(e) => {
+            ui::print_error(&e.to_string());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// After an install into a bin dir that isn't on PATH: show the line that fixes it for the
+/// user's shell, and offer to add it to their startup file, as `rush init` does. Only once
+/// per run, and only asked when someone is at the terminal to answer.
+fn offer_path_fix(engine: &RushEngine) -> Result<()> {
+    static OFFERED: AtomicBool = AtomicBool::new(false);
+    if OFFERED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let (line, rc_file) = ui::shell_init(Shell::from_env());
+    ui::print_path_hint(engine.bin_dir(), line, rc_file);
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    if let Some(rc_file) = rc_file
+        && interactive
+        && ui::confirm_init(rc_file)?
+    {
+        ui::print_init_result(&engine.init_shell(Path::new(rc_file), line)?, line);
+    }
+    Ok(())
+}
+
+/// Smoke-test a package that was just installed: are its libraries there, and does its
+/// manifest's `check` pass? The package stays installed either way; a failure is only
+/// reported. Binaries installed for another target aren't run.
+fn run_check(engine: &RushEngine, name: &str, manifest: &PackageManifest) {
+    if !engine.targets_host() {
+        return;
+    }
+    ui::print_requirement_warnings(&engine.missing_libraries(name));
+    match engine.check_package(name, manifest) {
+        Ok(Some(result)) => ui::print_check_result(&result),
+        Ok(None) => {}
+        Err(e) => ui::print_check_not_run(name, &e),
+    }
+}
+
+/// Whether `e` is the engine giving up because of Ctrl-C
+fn is_interrupted(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<rush::Error>(),
+        Some(rush::Error::Cancelled)
+    )
+}
 
-    // DETECT SYSTEM ARCHITECTURE
-    let current_target = format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+fn run(cli: &Cli, engine: &mut RushEngine, reporter: &mut ui::TerminalReporter) -> Result<()> {
+    // The host, unless RUSH_TARGET or the config says otherwise
+    let current_target = engine.target().to_string();
 
     match &cli.command {
         Commands::Completions { shell } => {
@@ -31,91 +204,404 @@ fn main() -> Result<()> {
             return Ok(());
         }
 
-        Commands::List => {
-            ui::print_installed_packages(&engine.state.packages);
+        Commands::Init { shell } => {
+            let (line, rc_file) = ui::shell_init(shell.or_else(Shell::from_env));
+            match rc_file {
+                Some(rc_file) => {
+                    ui::print_init_result(&engine.init_shell(Path::new(rc_file), line)?, line)
+                }
+                None => ui::print_init_manual(line),
+            }
         }
 
-        Commands::Search => {
-            let packages = engine.list_available_packages();
-            ui::print_available_packages(&packages, &current_target);
+        Commands::Env {
+            shell,
+            project: false,
+        } => {
+            ui::print_env(engine.bin_dir(), *shell);
         }
 
-        Commands::Install { name } => {
-            if engine.state.packages.contains_key(name) {
-                ui::print_warning(&format!("{} is already installed", name));
-                return Ok(());
+        Commands::Env {
+            shell,
+            project: true,
+        } => {
+            // Quietly: stdout is for the shell (or direnv) to eval
+            let project = engine.sync_project(&std::env::current_dir()?, &mut ())?;
+            ui::print_env(&project.bin_dir, *shell);
+        }
+
+        Commands::Outdated {
+            notify,
+            json,
+            porcelain,
+            exit_code,
+        } => {
+            let outdated = engine.outdated();
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&outdated)?);
+            } else if *porcelain {
+                ui::print_outdated_porcelain(&outdated);
+            } else {
+                ui::print_outdated(&outdated);
+            }
+            if *notify && !outdated.is_empty() {
+                ui::notify_outdated(&outdated);
             }
+            if *exit_code && !outdated.is_empty() {
+                std::process::exit(EXIT_OUTDATED);
+            }
+        }
 
-            if let Some(manifest) = engine.find_package(name) {
-                if let Some(target) = manifest.targets.get(&current_target) {
-                    ui::print_install_start(name, &manifest.version);
+        Commands::Schedule {
+            command: ScheduleCommands::Install,
+        } => {
+            let result = engine.install_schedule()?;
+            ui::print_schedule_installed(&result);
+        }
 
-                    let event_handler = ui::create_install_handler();
+        Commands::Schedule {
+            command: ScheduleCommands::Remove,
+        } => {
+            let result = engine.remove_schedule()?;
+            ui::print_schedule_removed(&result);
+        }
 
-                    match engine.install_package(name, &manifest.version, target, event_handler) {
-                        Ok(result) => ui::print_install_success(&result.path),
-                        Err(e) => {
-                            ui::print_error(&e.to_string());
-                            std::process::exit(1);
+        Commands::SelfUpdate { check } => {
+            let result = engine.self_update(*check, reporter)?;
+            ui::print_self_update(&result);
+        }
+
+        Commands::Direnv => {
+            ui::print_direnv();
+        }
+
+        Commands::Registry {
+            command: RegistryCommands::Stats { json },
+        } => {
+            let stats = engine.registry_stats();
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                ui::print_registry_stats(&stats);
+            }
+        }
+
+        Commands::List { porcelain } => {
+            if *porcelain {
+                ui::print_installed_porcelain(&engine.state.packages);
+            } else {
+                ui::print_installed_packages(&engine.state.packages);
+            }
+        }
+
+        Commands::Search {
+            porcelain,
+            targets,
+            sort,
+        } => {
+            let mut packages = engine.list_available_packages();
+            sort_packages(&mut packages, *sort, &current_target);
+            if *porcelain {
+                ui::print_available_porcelain(&packages, &current_target, *targets);
+            } else {
+                ui::print_available_packages(&packages, &current_target, *targets);
+            }
+        }
+
+        Commands::Info {
+            name,
+            provenance,
+            json,
+        } => {
+            let Some(info) = engine.package_info(name) else {
+                ui::print_package_not_found(name);
+                std::process::exit(1);
+            };
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&info)?);
+            } else {
+                ui::print_package_info(&info, *provenance, &current_target);
+            }
+        }
+
+        Commands::Install { name, dry_run } => {
+            // A path to a manifest (e.g. one being written) installs without the registry
+            let local = Path::new(name);
+            let (name, manifest, version) = if name.ends_with(".toml") {
+                let stem = local.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+                (stem, rush::core::load_manifest(local)?, None)
+            } else {
+                let (name, version) = match name.split_once('@') {
+                    Some((name, version)) => (name, Some(version)),
+                    None => (name.as_str(), None),
+                };
+                match engine.find_package_version(name, version) {
+                    Some(manifest) => (name, manifest, version),
+                    None => {
+                        match (version, engine.find_package(name)) {
+                            (Some(version), Some(manifest)) => {
+                                ui::print_version_not_found(name, version, &manifest)
+                            }
+                            _ => ui::print_package_not_found(name),
                         }
+                        std::process::exit(1);
                     }
+                }
+            };
+
+            // Asked for a version by name, another installed version is replaced
+            let replace = version.is_some();
+            if *dry_run {
+                let changes = if replace {
+                    engine.preview_replace_from_manifest(name, &manifest, &current_target)
                 } else {
-                    ui::print_error(&format!("No compatible binary for {}", current_target));
-                    std::process::exit(1);
+                    engine.preview_install_from_manifest(name, &manifest, &current_target)
+                };
+                match changes {
+                    Ok(changes) => ui::print_dry_run(&changes),
+                    Err(e) => {
+                        ui::print_error(&e.to_string());
+                        std::process::exit(1);
+                    }
                 }
-            } else {
-                ui::print_error(&format!("Package '{}' not found.", name));
+                return Ok(());
+            }
+
+            install(engine, name, &manifest, replace, reporter)?;
+        }
+
+        Commands::Find { query } => {
+            let entries = engine.finder_entries();
+            let Some(name) = ui::find_package(&entries, query.as_deref())? else {
+                return Ok(());
+            };
+            let Some(manifest) = engine.find_package(&name) else {
+                ui::print_package_not_found(&name);
+                std::process::exit(1);
+            };
+            install(engine, &name, &manifest, false, reporter)?;
+        }
+
+        Commands::Man { name, page } => {
+            let Some(pages) = engine.man_pages(name) else {
+                ui::print_uninstall_result(&None, name);
                 std::process::exit(1);
+            };
+            let names: Vec<String> = pages.iter().map(|p| man_page_name(p)).collect();
+            let chosen: Vec<&Path> = pages
+                .iter()
+                .zip(&names)
+                .filter(|(_, n)| page.as_ref().is_none_or(|page| page == *n))
+                .map(|(path, _)| path.as_path())
+                .collect();
+            if let [path] = chosen.as_slice() {
+                let mut command = std::process::Command::new("man");
+                command.arg(path);
+                return exec(command);
             }
+            ui::print_man_pages_error(name, page.as_deref(), &names);
+            std::process::exit(1);
         }
 
-        Commands::Uninstall { name } => {
-            let result = engine.uninstall_package(name)?;
-            ui::print_uninstall_result(&result, name);
+        Commands::Run { package, args } => {
+            let (name, version) = match package.split_once('@') {
+                Some((name, version)) => (name, Some(version)),
+                None => (package.as_str(), None),
+            };
+            // Quietly: the binary's output is what a caller of `rush run` wants on stdout
+            let path = engine.prepare_run(name, version, &mut ())?;
+            let mut command = std::process::Command::new(path);
+            command.args(args);
+            exec(command)?;
         }
 
-        Commands::Upgrade => {
-            ui::print_upgrade_check();
+        Commands::Sync { check: false } => {
+            let project = engine.sync_project(&std::env::current_dir()?, reporter)?;
+            ui::print_project_synced(&project);
+        }
 
-            let installed_names: Vec<String> = engine.state.packages.keys().cloned().collect();
-            let mut count = 0;
+        Commands::Sync { check: true } => {
+            let check = engine.check_project(&std::env::current_dir()?)?;
+            ui::print_project_check(&check);
+            if check.failed().next().is_some() {
+                std::process::exit(1);
+            }
+        }
 
-            for name in installed_names {
-                let current_ver = engine.state.packages.get(&name).unwrap().version.clone();
+        Commands::Shell { command } => {
+            let project = engine.sync_project(&std::env::current_dir()?, reporter)?;
+            let mut shell = match command.split_first() {
+                Some((program, args)) => {
+                    let mut shell = std::process::Command::new(program);
+                    shell.args(args);
+                    shell
+                }
+                None => {
+                    ui::print_project_shell(&project);
+                    std::process::Command::new(user_shell())
+                }
+            };
+            let path = std::env::var_os("PATH").unwrap_or_default();
+            let paths =
+                std::iter::once(project.bin_dir.clone()).chain(std::env::split_paths(&path));
+            shell
+                .env("PATH", std::env::join_paths(paths)?)
+                .env("RUSH_PROJECT", &project.root);
+            exec(shell)?;
+        }
 
-                // Logic to find update
-                let Some(manifest) = engine.find_package(&name) else {
-                    continue;
-                };
-                let Some(target) = manifest.targets.get(&current_target) else {
-                    continue;
-                };
+        Commands::Uninstall {
+            name,
+            dry_run: true,
+            ..
+        } => match engine.preview_uninstall(name) {
+            Some(changes) => ui::print_dry_run(&changes),
+            None => ui::print_uninstall_result(&None, name),
+        },
 
-                if manifest.version == current_ver {
-                    continue;
+        Commands::Uninstall { name, force, .. } => {
+            let dependents = engine.dependents(name);
+            if !dependents.is_empty() {
+                ui::print_needed_by(name, &dependents, *force);
+                if !*force {
+                    std::process::exit(1);
                 }
+            }
+            let result = engine.uninstall_package(name, reporter)?;
+            ui::print_uninstall_result(&result, name);
+        }
 
-                ui::print_upgrade_start(&name, &current_ver, &manifest.version);
+        Commands::Pin { name, constraint } => {
+            let pinned = match constraint {
+                Some(constraint) => engine.pin_package(name, constraint)?,
+                None => engine.hold_package(name)?,
+            };
+            ui::print_pin_result(pinned, name, constraint.as_deref());
+        }
 
-                let event_handler = ui::create_install_handler();
+        Commands::Unpin { name } => {
+            let unpinned = engine.unpin_package(name)?;
+            ui::print_unpin_result(unpinned, name);
+        }
 
-                engine.install_package(&name, &manifest.version, target, event_handler)?;
-                count += 1;
+        Commands::Upgrade { dry_run } => {
+            ui::print_upgrade_check();
+            let _span = tracing::info_span!("upgrade").entered();
+
+            let plan = engine.plan_upgrade(&current_target);
+            if *dry_run {
+                ui::print_dry_run(&engine.preview_apply(&plan)?);
+                return Ok(());
             }
-            ui::print_upgrade_summary(count);
+            let outcomes = engine.apply_upgrade(&plan, reporter)?;
+            for outcome in outcomes.iter().filter(|o| o.is_installed()) {
+                if let Some(manifest) = engine.find_package(outcome.package()) {
+                    run_check(engine, outcome.package(), &manifest);
+                }
+            }
+            ui::print_upgrade_summary(&outcomes);
+        }
+
+        Commands::Update { dry_run: true, .. } => {
+            ui::print_dry_run(&engine.preview_update_registry());
         }
 
-        Commands::Update => {
-            let event_handler = ui::create_update_handler();
-            let result = engine.update_registry(event_handler)?;
+        Commands::Update { accept_changed, .. } => {
+            let result = engine.update_registry(reporter)?;
             ui::print_update_success(&result.source);
+
+            let accepted = engine.accept_hash_changes(accept_changed)?;
+            let pending: Vec<_> = result
+                .hash_changes
+                .iter()
+                .filter(|c| !accept_changed.contains(&c.package))
+                .collect();
+            ui::print_hash_changes(&pending);
+            ui::print_accepted_hash_changes(&accepted);
         }
 
-        Commands::Clean => {
+        Commands::Clean { all: true, dry_run } => {
+            let result = if *dry_run {
+                engine.find_leftovers()?
+            } else {
+                engine.clean_all()?
+            };
+            ui::print_clean_all_result(&result, *dry_run);
+        }
+
+        Commands::Clean { dry_run: true, .. } => {
+            ui::print_dry_run(&engine.preview_clean()?);
+        }
+
+        Commands::Clean { .. } => {
             let result = engine.clean_trash()?;
             ui::print_clean_result(&result);
         }
 
+        Commands::Audit => {
+            let result = engine.audit()?;
+            ui::print_audit_result(&result);
+            if result.vulnerable().next().is_some() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Verify { upstream } => {
+            let result = engine.verify_upstream(upstream)?;
+            ui::print_upstream_result(&result);
+            if result.failed().next().is_some() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::ExportBundle { out } => {
+            let result = engine.export_bundle(out, &current_target, reporter)?;
+            ui::print_bundle_result(&result);
+        }
+
+        Commands::Export { format, out } => {
+            let export = match format {
+                ExportFormat::Nix => engine.export_nix(&current_target),
+                ExportFormat::Dockerfile => engine.export_dockerfile(&current_target)?,
+            };
+            match out {
+                Some(out) => std::fs::write(out, &export.contents)?,
+                None => print!("{}", export.contents),
+            }
+            ui::print_export_result(&export, out.as_deref());
+        }
+
+        Commands::ImportBundle { bundle } => {
+            let outcomes = engine.import_bundle(bundle, &current_target, reporter)?;
+            for outcome in &outcomes {
+                ui::print_install_outcome(outcome);
+                if outcome.is_installed()
+                    && let Some(manifest) = engine.find_package(outcome.package())
+                {
+                    run_check(engine, outcome.package(), &manifest);
+                }
+            }
+        }
+
+        Commands::Mirror {
+            dir,
+            targets,
+            all_targets,
+        } => {
+            let targets: Vec<String> = if *all_targets {
+                TARGETS.iter().map(|(key, _)| key.to_string()).collect()
+            } else if targets.is_empty() {
+                vec![current_target.clone()]
+            } else {
+                targets.clone()
+            };
+            let result = engine.mirror(dir, &targets, reporter)?;
+            ui::print_mirror_result(&result);
+            if !result.failures.is_empty() {
+                std::process::exit(1);
+            }
+        }
+
         Commands::Dev { command } => match command {
             DevCommands::Add {
                 name,
@@ -123,73 +609,265 @@ fn main() -> Result<()> {
                 target,
                 url,
                 bin,
+                copy,
             } => {
-                ui::print_fetching_msg(url);
-                let event_handler = ui::create_install_handler();
+                let url = engine.resolve_artifact_location(name, version, url, *copy)?;
+
+                ui::print_fetching_msg(&url);
 
-                engine.add_package_manual(
+                let bin_name = engine.add_package_manual(
                     name.clone(),
                     version.clone(),
                     target.clone(),
-                    url.clone(),
+                    url,
                     bin.clone(),
-                    event_handler,
+                    reporter,
                 )?;
+                if bin.is_none() {
+                    ui::print_detected_binary(&bin_name);
+                }
                 ui::print_dev_add_success(name);
             }
-            DevCommands::Import { repo } => {
+            DevCommands::Import {
+                repo,
+                tag,
+                recent,
+                auto,
+                min_score,
+                targets,
+                verify,
+                wait,
+                include_prereleases,
+            } => {
                 engine.ensure_local_registry()?;
 
                 ui::print_fetching_metadata(repo);
 
-                // 1. Get Candidates from Core
-                let (pkg_name, version, candidates) =
-                    engine.fetch_github_import_candidates(repo)?;
+                // 1. Get Candidates from Core (asking which release if no tag was given)
+                let (pkg_name, version, candidates) = match tag {
+                    Some(tag) => retry_rate_limited(*wait, || {
+                        engine.fetch_github_import_candidates(repo, Some(tag), targets)
+                    })?,
+                    // GitHub's "latest" release is never a prerelease
+                    None if *auto && !*include_prereleases => retry_rate_limited(*wait, || {
+                        engine.fetch_github_import_candidates(repo, None, targets)
+                    })?,
+                    None => {
+                        let limit = if *auto { 1 } else { *recent };
+                        let releases = retry_rate_limited(*wait, || {
+                            engine.list_github_releases(repo, limit, *include_prereleases)
+                        })?;
+                        let selection = if *auto {
+                            (!releases.is_empty()).then_some(0)
+                        } else {
+                            ui::prompt_select_release(&releases)?
+                        };
+                        let Some(idx) = selection else {
+                            ui::print_no_releases(repo);
+                            std::process::exit(1);
+                        };
+                        engine.import_candidates_from_release(repo, &releases[idx], targets)?
+                    }
+                };
                 ui::print_found_release(&version);
 
-                // 2. Interactive Wizard
+                // 2. Interactive Wizard (or best match per target in --auto mode)
+                let mut imported = 0;
                 for candidate in candidates {
-                    // Ask UI to prompt the user
-                    let selection_index = ui::prompt_select_asset(&candidate)?;
-
-                    match selection_index {
-                        Some(idx) => {
-                            let asset = &candidate.assets[idx].asset;
-                            let url = asset.browser_download_url.clone();
-
-                            ui::print_fetching_msg(&url);
-                            let event_handler = ui::create_install_handler();
+                    let selection = if *auto {
+                        candidate.best_asset(*min_score)
+                    } else {
+                        // Ask UI to prompt the user
+                        ui::prompt_select_asset(&candidate)?.map(|idx| &candidate.assets[idx])
+                    };
+
+                    match selection {
+                        Some(scored) if !*auto => {
+                            let url = &scored.asset.browser_download_url;
+                            ui::print_fetching_msg(url);
+
+                            // Look inside so the user can confirm the binary
+                            let preview =
+                                engine.preview_import_asset(&pkg_name, scored, reporter)?;
+                            let Some(idx) = ui::prompt_select_binary(&preview)? else {
+                                ui::print_skipping_target(&candidate.target_slug);
+                                continue;
+                            };
+
+                            engine.add_package_hashed(
+                                &pkg_name,
+                                &version,
+                                &candidate.target_slug,
+                                url,
+                                preview.entries[idx].file_name().to_string(),
+                                &preview.sha256,
+                            )?;
+                            if let Some(checksum_url) = &scored.checksum_url {
+                                engine.set_checksum_url(
+                                    &pkg_name,
+                                    &candidate.target_slug,
+                                    checksum_url,
+                                )?;
+                            }
+                            imported += 1;
+                        }
+                        Some(scored) => {
+                            if scored.checksum_url.is_none() || *verify {
+                                ui::print_fetching_msg(&scored.asset.browser_download_url);
+                            }
 
-                            engine.add_package_manual(
+                            let source = engine.add_import_asset(
                                 pkg_name.clone(),
                                 version.clone(),
                                 candidate.target_slug.clone(),
-                                url,
-                                None,
-                                event_handler,
+                                scored,
+                                *verify,
+                                reporter,
                             )?;
+                            ui::print_checksum_source(&scored.asset.name, source);
+                            imported += 1;
                         }
                         None => {
                             ui::print_skipping_target(&candidate.target_slug);
                         }
                     }
                 }
+
+                // Remember the upstream so `dev bump` can find newer releases
+                if imported > 0 {
+                    engine.set_package_source(&pkg_name, repo)?;
+                }
                 ui::print_wizard_complete();
             }
 
-            DevCommands::Verify => {
+            DevCommands::Score {
+                repo,
+                tag,
+                targets,
+                wait,
+            } => {
+                ui::print_fetching_metadata(repo);
+
+                let report = retry_rate_limited(*wait, || {
+                    engine.explain_scores(repo, tag.as_deref(), targets)
+                })?;
+                ui::print_score_report(&report);
+            }
+
+            DevCommands::ImportCrate {
+                name,
+                version,
+                targets,
+                wait,
+            } => {
+                ui::print_fetching_metadata(name);
+
+                let result = retry_rate_limited(*wait, || {
+                    engine.import_cargo_dist(name, version.as_deref(), targets)
+                })?;
+                ui::print_dist_import_result(&result);
+
+                if result.imported.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+
+            DevCommands::New => {
                 engine.ensure_local_registry()?;
 
-                // 1. Auto-Update (Silent) to ensure we verify what's on disk
-                // We pass an empty closure to ignore update progress
-                let _ = engine.update_registry(|_| {});
+                let mut spec = ui::prompt_new_package(TARGETS)?;
+                for target in &mut spec.targets {
+                    target.url = engine.resolve_artifact_location(
+                        &spec.name,
+                        &spec.version,
+                        &target.url,
+                        false,
+                    )?;
+                }
 
-                ui::print_verify_start();
+                let path = engine.create_package(&spec, reporter)?;
+                ui::print_new_package_success(&spec.name, &path);
+            }
+
+            DevCommands::Bump { name, force } => {
+                ui::print_fetching_metadata(name);
+
+                let result = engine.bump_package(name, *force, reporter)?;
+                ui::print_bump_result(&result);
+
+                if !result.failures.is_empty() {
+                    std::process::exit(1);
+                }
+            }
 
-                // 2. Use the new dedicated handler
-                let event_handler = ui::create_verify_handler();
+            DevCommands::RefreshAll { jobs, timeout } => {
+                let result = engine.refresh_all(
+                    *jobs,
+                    std::time::Duration::from_secs(*timeout),
+                    reporter,
+                )?;
 
-                let result = engine.verify_registry(event_handler)?;
+                ui::print_refresh_summary(&result);
+
+                if !result.failed.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+
+            DevCommands::Publish {
+                remote,
+                base,
+                branch,
+            } => {
+                let result = engine.publish(remote, base, branch.as_deref())?;
+                ui::print_publish_result(&result);
+            }
+
+            DevCommands::Serve { port, artifacts } => {
+                engine.serve_registry(*port, artifacts.as_deref(), reporter)?;
+            }
+
+            DevCommands::Keygen { force } => {
+                let result = engine.generate_signing_key(*force)?;
+                ui::print_keygen_result(&result);
+            }
+
+            DevCommands::Sign { key } => {
+                let result = engine.sign_registry(key.as_deref())?;
+                ui::print_sign_result(&result);
+            }
+
+            DevCommands::Test { package } => {
+                let result = engine.trial_install(package, reporter)?;
+                ui::print_trial_result(&result);
+                if !result.succeeded() {
+                    std::process::exit(1);
+                }
+            }
+
+            DevCommands::Verify { deep, sample } => {
+                engine.ensure_local_registry()?;
+
+                // 1. Lint manifests first; no point downloading from a broken registry
+                let lint = engine.lint_registry()?;
+                ui::print_lint_summary(&lint);
+
+                if lint.error_count() > 0 {
+                    std::process::exit(1);
+                }
+
+                if !deep {
+                    return Ok(());
+                }
+
+                // 2. Auto-Update (Silent) to ensure we verify what's on disk
+                // We pass the silent reporter to ignore update progress
+                let _ = engine.update_registry(&mut ());
+
+                ui::print_verify_start();
+
+                // 3. Check every target, with progress
+                let result = engine.verify_registry(*sample, reporter)?;
 
                 ui::print_verify_summary(&result);
 
@@ -197,8 +875,37 @@ fn main() -> Result<()> {
                     std::process::exit(1);
                 }
             }
+
+            DevCommands::CheckUrls { jobs } => {
+                engine.ensure_local_registry()?;
+                let _ = engine.update_registry(&mut ());
+
+                let result = engine.check_urls(*jobs, reporter)?;
+
+                ui::print_link_check_summary(&result);
+
+                if result.problems().next().is_some() {
+                    std::process::exit(1);
+                }
+            }
         },
     }
 
     Ok(())
 }
+
+/// Runs a GitHub API call; on a rate limit, sleeps until the reset and retries if `wait` is set
+fn retry_rate_limited<T>(wait: bool, mut call: impl FnMut() -> Result<T>) -> Result<T> {
+    loop {
+        match call() {
+            Err(e) if wait => match e.downcast_ref::<rush::Error>() {
+                Some(rush::Error::RateLimited(limit)) => {
+                    ui::print_rate_limit_wait(limit.wait_time());
+                    std::thread::sleep(limit.wait_time() + std::time::Duration::from_secs(1));
+                }
+                _ => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
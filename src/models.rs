@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 // --- REGISTRY DATA ---
 /// Represents one file (e.g. `packages/f/fzf.toml`)
@@ -9,125 +9,1632 @@ use std::path::PathBuf;
 pub struct PackageManifest {
     pub version: String,
     pub description: Option<String>,
+    /// Where releases come from, in `dev import` syntax ("owner/repo", "gitlab:group/project")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// SPDX license identifier (e.g. "MIT OR Apache-2.0")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// When a dev tool last wrote a release into the manifest (RFC 3339, UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    /// Arguments to smoke-test the installed binary with (e.g. "--version"); it must exit 0.
+    /// Run in a sandbox, with no network and only a scratch directory to write to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    /// Where the OSV database (osv.dev) tracks this package, for `rush audit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osv: Option<OsvPackage>,
+    /// System libraries the binary needs at runtime, e.g. `["libssl", "glibc>=2.31"]`.
+    /// Informational: rush only warns when one looks missing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
+    /// Other registry packages it needs, installed along with it when missing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    /// Keyed by target; keys are normalized on load ("arm64-darwin" -> "aarch64-macos")
+    #[serde(deserialize_with = "deserialize_targets")]
     pub targets: BTreeMap<String, TargetDefinition>,
+    /// Older releases still offered (`[[history]]`), for installed packages whose version
+    /// constraint rules out `version`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<Release>,
+}
+
+/// One of a manifest's older releases
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Release {
+    pub version: String,
+    #[serde(deserialize_with = "deserialize_targets")]
+    pub targets: BTreeMap<String, TargetDefinition>,
+}
+
+impl PackageManifest {
+    /// The manifest as it was for `version`: the current release, or one from `history`
+    /// with the same description and metadata. None if it doesn't offer that version.
+    pub fn release(&self, version: &str) -> Option<PackageManifest> {
+        let (version, targets) = self.releases().find(|(v, _)| *v == version)?;
+        Some(PackageManifest {
+            version: version.to_string(),
+            targets: targets.clone(),
+            history: Vec::new(),
+            ..self.clone()
+        })
+    }
+
+    /// The build for target `key` (in any spelling) and the key it is under; one that only
+    /// differs in naming a libc does when there is no exact match (see `Target`)
+    pub fn target(&self, key: &str) -> Option<(&str, &TargetDefinition)> {
+        crate::core::target::lookup(&self.targets, key).map(|(key, target)| (key.as_str(), target))
+    }
+
+    /// Every release the manifest offers as `(version, targets)`, the current one first
+    pub fn releases(&self) -> impl Iterator<Item = (&str, &BTreeMap<String, TargetDefinition>)> {
+        std::iter::once((self.version.as_str(), &self.targets)).chain(
+            self.history
+                .iter()
+                .map(|release| (release.version.as_str(), &release.targets)),
+        )
+    }
+}
+
+/// Helper: A manifest's `targets`, keys in their canonical spelling. Two keys naming the
+/// same target are an error rather than one silently shadowing the other.
+fn deserialize_targets<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, TargetDefinition>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = BTreeMap::<String, TargetDefinition>::deserialize(deserializer)?;
+    let mut targets = BTreeMap::new();
+    for (key, target) in raw {
+        let canonical = crate::core::Target::normalize(&key);
+        if targets.insert(canonical.clone(), target).is_some() {
+            return Err(serde::de::Error::custom(format!(
+                "target '{}' is listed more than once (as '{}' and another spelling)",
+                canonical, key
+            )));
+        }
+    }
+    Ok(targets)
+}
+
+/// Helper: `bin = "tool"` or `bin = ["uv", "uvx"]`; an empty name is no binary. A name
+/// listed twice, or anything but a plain file name (it is joined to the bin dir), is an
+/// error.
+fn deserialize_bins<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Bins {
+        One(String),
+        Several(Vec<String>),
+    }
+    let bins = match Bins::deserialize(deserializer)? {
+        Bins::One(bin) => vec![bin],
+        Bins::Several(bins) => bins,
+    };
+    let bins: Vec<String> = bins.into_iter().filter(|b| !b.is_empty()).collect();
+    for (i, bin) in bins.iter().enumerate() {
+        let mut components = Path::new(bin).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        ) {
+            return Err(serde::de::Error::custom(format!(
+                "binary '{}' is not a plain file name",
+                bin
+            )));
+        }
+        if bins[..i].contains(bin) {
+            return Err(serde::de::Error::custom(format!(
+                "binary '{}' is listed more than once",
+                bin
+            )));
+        }
+    }
+    Ok(bins)
+}
+
+/// Helper: A single binary is written as a plain string, as manifests always had it
+fn serialize_bins<S>(bins: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match bins {
+        [bin] => serializer.serialize_str(bin),
+        _ => bins.serialize(serializer),
+    }
+}
+
+/// User configuration (`~/.config/rush/config.toml`)
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// Install for this target instead of the machine rush runs on (e.g. "aarch64-linux");
+    /// `RUSH_TARGET` overrides it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub scoring: ScoringConfig,
+    #[serde(default)]
+    pub state: StateConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub registry: RegistryConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+}
+
+/// `[http]`: how connections are kept open and reused between downloads
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct HttpConfig {
+    /// Idle connections kept open per host (default: 16; 0 opens a new one per request)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle connection stays open (default: 90)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_idle_timeout: Option<u64>,
+    /// Never negotiate HTTP/2, for proxies that mishandle it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http1_only: bool,
+}
+
+impl HttpConfig {
+    /// `pool_max_idle_per_host`, or its default
+    pub fn max_idle_per_host(&self) -> usize {
+        self.pool_max_idle_per_host.unwrap_or(16)
+    }
+
+    /// `pool_idle_timeout`, or its default
+    pub fn idle_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.pool_idle_timeout.unwrap_or(90))
+    }
+}
+
+/// `[registry]`: how far to trust the synced registry
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct RegistryConfig {
+    /// Hex ed25519 key the registry's index must be signed with (from `rush dev keygen`);
+    /// without it, signatures are recorded but not checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// What each registry archive (by URL) must hash to before it replaces the local copy,
+    /// e.g. `[registry.archives."https://host/registry.tar.gz"]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub archives: BTreeMap<String, ArchivePin>,
+}
+
+/// How one registry archive is verified; with both set, both must agree
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ArchivePin {
+    /// The archive's sha256
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// A `sha256sum`-style file listing the archive, signed (`<checksum_url>.sig`) with the
+    /// key in `public_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum_url: Option<String>,
+}
+
+/// `[hooks]`: shell commands run around installs and uninstalls.
+/// `{pkg}`, `{version}`, and `{bin_dir}` are replaced (already shell-quoted, so never
+/// inside quotes) before running, e.g. `post_install = "notify-send installed {pkg}"`, or
+/// `post_install = "notify-send \"installed $RUSH_PACKAGE $RUSH_VERSION\""`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct HooksConfig {
+    /// A failure aborts the install
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_install: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install: Option<String>,
+    /// A failure aborts the uninstall
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_uninstall: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_uninstall: Option<String>,
+}
+
+impl HooksConfig {
+    /// The command configured for `point`, if any
+    pub fn command(&self, point: HookPoint) -> Option<&str> {
+        match point {
+            HookPoint::PreInstall => self.pre_install.as_deref(),
+            HookPoint::PostInstall => self.post_install.as_deref(),
+            HookPoint::PreUninstall => self.pre_uninstall.as_deref(),
+            HookPoint::PostUninstall => self.post_uninstall.as_deref(),
+        }
+    }
+}
+
+/// Where in a package's lifecycle a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPoint {
+    PreInstall,
+    PostInstall,
+    PreUninstall,
+    PostUninstall,
+}
+
+impl HookPoint {
+    /// "pre" hooks can veto the operation; "post" hooks only report
+    pub fn is_pre(self) -> bool {
+        matches!(self, HookPoint::PreInstall | HookPoint::PreUninstall)
+    }
+}
+
+impl std::fmt::Display for HookPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HookPoint::PreInstall => "pre_install",
+            HookPoint::PostInstall => "post_install",
+            HookPoint::PreUninstall => "pre_uninstall",
+            HookPoint::PostUninstall => "post_uninstall",
+        })
+    }
+}
+
+/// `[state]`: where installed-package state is kept
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct StateConfig {
+    #[serde(default)]
+    pub backend: StateBackend,
+}
+
+/// `backend = "json" | "sqlite"`
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StateBackend {
+    /// `installed.json`, rewritten on every change
+    #[default]
+    Json,
+    /// `state.db`; needs rush built with the `sqlite` feature
+    Sqlite,
+}
+
+/// `[scoring]`: tweaks to how import ranks release assets
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ScoringConfig {
+    #[serde(default)]
+    pub rules: Vec<ScoringRule>,
+}
+
+/// A `[[scoring.rules]]` entry: add `weight` to assets whose name contains `keyword`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ScoringRule {
+    /// Matched case-insensitively against the asset file name
+    pub keyword: String,
+    /// Added to the score (negative to penalize)
+    pub weight: i32,
+    /// Only apply for these targets (empty = all)
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+impl ScoringRule {
+    /// Whether this rule fires for `asset_name` when importing for `target`
+    pub fn matches(&self, asset_name: &str, target: &str) -> bool {
+        (self.targets.is_empty()
+            || self
+                .targets
+                .iter()
+                .any(|t| crate::core::Target::normalize(t) == target))
+            && asset_name
+                .to_lowercase()
+                .contains(&self.keyword.to_lowercase())
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct PackageDefinition {
     pub version: String,
-    pub targets: HashMap<String, TargetDefinition>,
+    pub targets: HashMap<String, TargetDefinition>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TargetDefinition {
+    pub url: String,
+    /// The binaries to install (`bin = "tool"`, or a list for an archive with several, the
+    /// main one first); empty for a package that only installs `files`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bins",
+        serialize_with = "serialize_bins",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub bin: Vec<String>,
+    pub sha256: String,
+    /// The checksum file the release itself published for this artifact (`SHA256SUMS`,
+    /// `<asset>.sha256`, ...), for `rush verify --upstream`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum_url: Option<String>,
+    /// The artifact's size in bytes, so it can be shown before anything is downloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// What the artifact is: an archive to take `bin` out of unless it says otherwise
+    #[serde(default, skip_serializing_if = "ArtifactFormat::is_archive")]
+    pub format: ArtifactFormat,
+    /// AppImages only: unpack the image at install time and run its contents directly,
+    /// which starts faster than mounting the image on every run
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extract: bool,
+    /// Data (fonts, color schemes, grammars...) to copy out of the archive into the XDG
+    /// data directory, alongside or instead of `bin`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<DataFile>,
+}
+
+/// One of a target's `files = [{ src, dest }]`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DataFile {
+    /// A file or directory in the archive, matched like `bin` regardless of the archive's
+    /// top-level directory (e.g. "themes/dracula.toml" or "themes")
+    pub src: String,
+    /// Where it goes, relative to the data directory (`~/.local/share`)
+    pub dest: String,
+}
+
+/// `format` in a manifest target
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactFormat {
+    /// A tarball or .zip (or, with the `distro-packages` feature, a .deb or .rpm) holding
+    /// `bin`
+    #[default]
+    Archive,
+    /// A self-contained AppImage, kept as-is and run through a shim named `bin`
+    AppImage,
+    /// The executable itself, as some projects publish it, installed as `bin`
+    Binary,
+}
+
+impl TargetDefinition {
+    /// The main binary: the first in `bin`, the one `rush run` runs and an AppImage's shim
+    /// is named after; empty if there is none
+    pub fn main_bin(&self) -> &str {
+        self.bin.first().map_or("", String::as_str)
+    }
+}
+
+impl ArtifactFormat {
+    pub fn is_archive(&self) -> bool {
+        *self == ArtifactFormat::Archive
+    }
+}
+
+/// A package as the OSV database knows it (`[osv]` in a manifest)
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OsvPackage {
+    /// OSV ecosystem (e.g. "crates.io", "Go", "npm")
+    pub ecosystem: String,
+    /// Name in that ecosystem (e.g. "github.com/junegunn/fzf"); the registry name if absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+// --- GITHUB API DATA ---
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    /// ISO 8601 timestamp, absent for drafts
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    pub assets: Vec<GitHubAsset>,
+}
+
+/// Tag fragments that mark a build as unstable even when the release isn't flagged
+const PRERELEASE_MARKERS: &[&str] = &[
+    "alpha", "beta", "-rc", ".rc", "nightly", "canary", "-dev", "-pre", "snapshot",
+];
+
+impl GitHubRelease {
+    /// Flagged as a prerelease upstream, or tagged like one (e.g. "v2.0.0-rc.1", "nightly")
+    pub fn is_prerelease(&self) -> bool {
+        let tag = self.tag_name.to_lowercase();
+        self.prerelease || PRERELEASE_MARKERS.iter().any(|m| tag.contains(m))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)] // Added Clone
+pub struct GitHubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+// --- GITLAB API DATA ---
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitLabRelease {
+    pub tag_name: String,
+    /// ISO 8601 timestamp
+    #[serde(default)]
+    pub released_at: Option<String>,
+    /// Set for releases scheduled in the future
+    #[serde(default)]
+    pub upcoming_release: bool,
+    pub assets: GitLabAssets,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitLabAssets {
+    #[serde(default)]
+    pub links: Vec<GitLabAssetLink>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitLabAssetLink {
+    pub name: String,
+    pub url: String,
+    /// Stable permalink, preferred over `url` when present
+    #[serde(default)]
+    pub direct_asset_url: Option<String>,
+}
+
+/// GitLab releases are mapped onto the GitHub shape so the import flow stays the same
+impl From<GitLabRelease> for GitHubRelease {
+    fn from(release: GitLabRelease) -> Self {
+        GitHubRelease {
+            tag_name: release.tag_name,
+            published_at: release.released_at,
+            prerelease: release.upcoming_release,
+            assets: release
+                .assets
+                .links
+                .into_iter()
+                .map(|link| GitHubAsset {
+                    name: link.name,
+                    browser_download_url: link.direct_asset_url.unwrap_or(link.url),
+                })
+                .collect(),
+        }
+    }
+}
+
+// --- CRATES.IO / CARGO-DIST DATA ---
+/// Response of `https://crates.io/api/v1/crates/<name>`
+#[derive(Deserialize, Debug)]
+pub struct CratesIoResponse {
+    #[serde(rename = "crate")]
+    pub krate: CratesIoCrate,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CratesIoCrate {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub repository: Option<String>,
+    pub max_version: String,
+    #[serde(default)]
+    pub max_stable_version: Option<String>,
+}
+
+/// The `dist-manifest.json` cargo-dist attaches to every release
+#[derive(Deserialize, Debug)]
+pub struct DistManifest {
+    #[serde(default)]
+    pub artifacts: BTreeMap<String, DistArtifact>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DistArtifact {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// e.g. "executable-zip", "checksum", "installer"
+    pub kind: String,
+    #[serde(default)]
+    pub target_triples: Vec<String>,
+    #[serde(default)]
+    pub assets: Vec<DistAsset>,
+    /// Name of the companion checksum artifact (e.g. "<artifact>.sha256")
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// Inline checksums, keyed by algorithm (newer cargo-dist versions)
+    #[serde(default)]
+    pub checksums: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DistAsset {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// e.g. "executable", "readme", "license"
+    pub kind: String,
+}
+
+// --- OSV.DEV API DATA ---
+/// Response of `POST https://api.osv.dev/v1/query`
+#[derive(Deserialize, Debug, Default)]
+pub struct OsvQueryResponse {
+    #[serde(default)]
+    pub vulns: Vec<OsvVulnerability>,
+    /// Present when there are more results; pass it back as `page_token`
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OsvVulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Other IDs for the same issue (e.g. a CVE for a GHSA)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    pub affected: Vec<OsvAffected>,
+    /// Free-form extras; GitHub advisories keep their rating ("HIGH", ...) in `severity`
+    #[serde(default)]
+    pub database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OsvSeverity {
+    /// e.g. "CVSS_V3"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// e.g. a CVSS vector
+    pub score: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OsvAffected {
+    #[serde(default)]
+    pub package: Option<OsvAffectedPackage>,
+    #[serde(default)]
+    pub ranges: Vec<OsvRange>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OsvAffectedPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OsvRange {
+    #[serde(default)]
+    pub events: Vec<OsvEvent>,
+}
+
+/// One point of an affected range; only the versions that fix it matter to rush
+#[derive(Deserialize, Debug)]
+pub struct OsvEvent {
+    #[serde(default)]
+    pub fixed: Option<String>,
+}
+
+// --- DATA TRANSFER OBJECTS (Core -> UI) ---
+
+/// Represents a candidate for import found in a GitHub release
+#[derive(Debug)]
+pub struct ImportCandidate {
+    /// The specific system target (e.g. "x86_64-linux")
+    pub target_slug: String,
+    /// A human-readable description (e.g. "Linux (x86_64)")
+    pub target_desc: String,
+    /// List of assets sorted by relevance score
+    pub assets: Vec<ScoredAsset>,
+}
+
+impl ImportCandidate {
+    /// The highest-scoring asset, if it scores at least `min_score`
+    pub fn best_asset(&self, min_score: i32) -> Option<&ScoredAsset> {
+        self.assets
+            .iter()
+            .max_by_key(|a| a.score)
+            .filter(|a| a.score >= min_score)
+    }
+}
+
+#[derive(Debug)]
+pub struct ScoredAsset {
+    pub score: i32,
+    pub asset: GitHubAsset,
+    /// Upstream checksum file covering this asset (`SHA256SUMS`, `<asset>.sha256`, ...)
+    pub checksum_url: Option<String>,
+}
+
+/// One reason an asset gained or lost points during import scoring
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreFactor {
+    pub reason: String,
+    pub points: i32,
+}
+
+/// Result of `RushEngine::explain_scores()`
+#[derive(Debug)]
+pub struct ScoreReport {
+    pub repo: String,
+    pub tag: String,
+    /// Assets scoring below this are never picked automatically
+    pub min_score: i32,
+    pub targets: Vec<TargetScores>,
+}
+
+/// Every asset of a release, ranked for one target
+#[derive(Debug)]
+pub struct TargetScores {
+    pub target_slug: String,
+    /// Sorted best first
+    pub assets: Vec<AssetScore>,
+}
+
+#[derive(Debug)]
+pub struct AssetScore {
+    pub name: String,
+    pub score: i32,
+    pub factors: Vec<ScoreFactor>,
+}
+
+/// A regular file inside a release archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    /// Path inside the archive (e.g. "tool-1.0/bin/tool")
+    pub path: String,
+    pub size: u64,
+    /// Any execute bit is set
+    pub executable: bool,
+}
+
+impl ArchiveEntry {
+    /// The last path component, which is what manifests call `bin`
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Result of `RushEngine::preview_import_asset()`
+#[derive(Debug)]
+pub struct AssetPreview {
+    /// sha256 of the downloaded asset (checked against upstream when published)
+    pub sha256: String,
+    /// Regular files in the archive
+    pub entries: Vec<ArchiveEntry>,
+    /// Index into `entries` of the most likely binary
+    pub suggested: Option<usize>,
+}
+
+/// Where `RushEngine::add_import_asset()` got a manifest's sha256 from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumSource {
+    /// Taken from the release's checksum file without downloading the asset
+    Upstream,
+    /// Taken from the release's checksum file and confirmed by a download
+    UpstreamVerified,
+    /// No checksum file; the asset was downloaded and hashed
+    Computed,
+}
+
+// --- STATE DATA ---
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct State {
+    pub packages: HashMap<String, InstalledPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InstalledPackage {
+    pub version: String,
+    pub binaries: Vec<String>,
+    /// Data files installed from the target's `files`, relative to the data directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// How it was obtained; absent for packages installed before rush recorded it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Versions upgrades may move it to (`rush pin`), e.g. "~20" or ">=1.2, <1.5"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<String>,
+    /// Held at its installed version (`rush pin <name>`), so upgrades leave it alone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Installed only because another package depends on it, not asked for by name
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dependency: bool,
+}
+
+/// Where an installed binary came from, recorded at install time
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Provenance {
+    /// The registry manifest that listed the artifact; `None` when it came from a
+    /// manifest the registry doesn't have (e.g. `rush install ./fzf.toml`)
+    pub registry: Option<RegistryProvenance>,
+    /// The artifact as downloaded, and the sha256 it was checked against
+    pub url: String,
+    pub sha256: String,
+    /// UTC, RFC 3339 (e.g. "2025-01-31T12:00:00Z")
+    pub installed_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegistryProvenance {
+    /// The registry's source (`RUSH_REGISTRY_URL`) at install time
+    pub source: String,
+    /// The commit the synced copy came from, for git checkouts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// sha256 of the manifest file in the synced copy
+    pub manifest_sha256: String,
+    pub signature: SignatureStatus,
+}
+
+/// Whether the registry's signed index (`rush dev sign`) vouched for the manifest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStatus {
+    /// The registry has no signed index
+    Unsigned,
+    /// The index lists the manifest, but no `[registry] public_key` is configured to
+    /// check its signature against
+    Unverified,
+    /// The index lists the manifest and is signed by the configured key
+    Verified,
+    /// The index doesn't list this manifest, or its signature doesn't check out
+    Invalid,
+}
+
+impl std::fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SignatureStatus::Unsigned => "unsigned",
+            SignatureStatus::Unverified => "signed, not verified (no public_key configured)",
+            SignatureStatus::Verified => "verified",
+            SignatureStatus::Invalid => "INVALID",
+        })
+    }
+}
+
+/// A change to the installed set, as seen by `RushEngine::subscribe()` callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateChange {
+    Installed {
+        package: String,
+        version: String,
+    },
+    Removed {
+        package: String,
+        version: String,
+    },
+    /// Reinstalled at a different version (upgrades and downgrades alike)
+    Upgraded {
+        package: String,
+        from: String,
+        to: String,
+    },
+}
+
+/// Handle for undoing a `RushEngine::subscribe()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionId(pub u64);
+
+/// `rush.lock`: exactly which artifact each installed package came from
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// The target the artifact is for (e.g. "x86_64-linux")
+    pub target: String,
+    pub url: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bins",
+        serialize_with = "serialize_bins",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub bin: Vec<String>,
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "ArtifactFormat::is_archive")]
+    pub format: ArtifactFormat,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extract: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<DataFile>,
+}
+
+// -- FUNCTION RESULTS ---
+
+/// Result of `RushEngine::clean_trash()`
+#[derive(Debug)]
+pub struct CleanResult {
+    pub files_cleaned: Vec<String>,
+}
+
+/// What a leftover `rush clean --all` finds is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverKind {
+    /// An atomic install's temp file, or a staging directory left in the cache
+    TempFile,
+    /// A cached artifact or `rush run` copy nothing in the registry refers to anymore
+    Cache,
+    /// An AppImage version other than the installed one
+    OldVersion,
+    /// A `<bin>.new` or `<bin>.old` copy beside a binary that is no longer installed
+    Staged,
+}
+
+/// A file or directory `rush clean --all` removes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leftover {
+    pub kind: LeftoverKind,
+    pub path: PathBuf,
+    /// On disk, counting everything under a directory
+    pub bytes: u64,
+}
+
+/// Result of `RushEngine::clean_all()`, or what `find_leftovers()` says it would remove
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CleanAllResult {
+    pub leftovers: Vec<Leftover>,
+    /// Installed packages whose binaries and files are all gone; their records are dropped
+    pub receipts: Vec<String>,
+}
+
+impl CleanAllResult {
+    pub fn is_empty(&self) -> bool {
+        self.leftovers.is_empty() && self.receipts.is_empty()
+    }
+
+    /// The space the leftovers take up
+    pub fn bytes(&self) -> u64 {
+        self.leftovers.iter().map(|l| l.bytes).sum()
+    }
+}
+
+/// Result of `RushEngine::uninstall_package()`
+#[derive(Debug)]
+pub struct UninstallResult {
+    /// The name of the package that was uninstalled.
+    pub package_name: String,
+    /// The list of binary files that were deleted.
+    pub binaries_removed: Vec<String>,
+    /// The data files that were deleted, relative to the data directory.
+    pub files_removed: Vec<String>,
+}
+
+/// Result of RushEngine::update_registry()
+#[derive(Debug)]
+pub struct UpdateResult {
+    /// The source URL or path the registry was updated from.
+    pub source: String,
+    /// Artifacts the registry now lists with a different hash than when rush first saw
+    /// them; installing them is refused until accepted
+    pub hash_changes: Vec<HashChange>,
+}
+
+/// An artifact whose sha256 changed for the same package version and URL: a release
+/// re-published in place, or tampered with
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashChange {
+    pub package: String,
+    pub version: String,
+    pub target: String,
+    pub url: String,
+    /// The sha256 first seen
+    pub known: String,
+    /// The sha256 the registry lists now
+    pub now: String,
+}
+
+/// Result of RushEngine::install_package()
+#[derive(Debug)]
+pub struct InstallResult {
+    /// The name of the package installed
+    pub package_name: String,
+    /// The version installed
+    pub version: String,
+    /// The final path to the binary on disk
+    pub path: PathBuf,
+    /// The old binary was running and couldn't be replaced, so the new one waits beside it
+    /// as `<bin>.new` and takes its place the next time rush runs
+    pub staged: bool,
+}
+
+/// A system requirement of a package that looks unmet: from the manifest's `requires`
+/// before an install, or a library `ldd` can't resolve after one
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequirementWarning {
+    pub package: String,
+    /// As written in `requires` ("glibc>=2.31"), or the missing library ("libssl.so.1.1")
+    pub requirement: String,
+    pub problem: String,
+}
+
+/// Result of RushEngine::check_package(): the manifest's `check` run against the binary
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub package: String,
+    /// The binary and arguments as run
+    pub command: String,
+    /// `None` if it was killed (by a signal, or for running past the time limit)
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// What it printed, trimmed
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CheckResult {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+// --- PLANS (RushEngine::resolve() -> RushEngine::apply()) ---
+
+/// What `RushEngine::resolve()` should plan, for one target (e.g. "x86_64-linux")
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveRequest {
+    /// Install these registry packages
+    Install {
+        packages: Vec<String>,
+        target: String,
+    },
+    /// Move these installed packages to the registry's version; all of them if empty
+    Upgrade {
+        packages: Vec<String>,
+        target: String,
+    },
+}
+
+/// Everything a request would change, worked out without touching the bin dir.
+/// `RushEngine::apply()` carries it out, unless it has conflicts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub target: String,
+    /// Installs to run, in order
+    pub steps: Vec<PlannedInstall>,
+    /// Requested packages that need nothing done
+    pub skipped: Vec<SkippedPackage>,
+    /// Problems that stop the plan from being applied
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Plan {
+    /// Total advertised download size, if every step's size is known
+    pub fn download_size(&self) -> Option<u64> {
+        self.steps.iter().map(|step| step.download_size).sum()
+    }
+}
+
+/// One package the plan would download and install
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedInstall {
+    pub package: String,
+    /// The installed version this replaces, for upgrades
+    pub from: Option<String>,
+    pub to: String,
+    /// The manifest entry for the plan's target (url, binary, checksum)
+    pub artifact: TargetDefinition,
+    /// Advertised size of the download; None offline or when the server doesn't say
+    pub download_size: Option<u64>,
+    /// Pulled in because a planned package needs it, rather than asked for
+    pub dependency: bool,
+}
+
+/// A requested package that needs nothing done
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPackage {
+    pub package: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    /// Asked to install something that is already installed
+    AlreadyInstalled { version: String },
+    /// Asked to upgrade something already at the registry's version
+    UpToDate { version: String },
+    /// Left alone by `plan_upgrade`: the registry no longer has it
+    NotInRegistry,
+    /// Left alone by `plan_upgrade`: the new version has no binary for this target
+    NoCompatibleTarget { target: String },
+    /// Asked to upgrade a package whose version constraint allows nothing newer than what
+    /// is installed, though the registry's `latest` is
+    Held { constraint: String, latest: String },
+    /// Asked to upgrade a package whose version constraint can't be parsed
+    BadConstraint { constraint: String, error: String },
+    /// Left alone by `plan_upgrade`: the new version would overwrite `bin`, which `owner`
+    /// installed
+    BinaryClash { bin: String, owner: String },
+    /// Left alone by `plan_upgrade`: the new version would write `file` in the data dir,
+    /// where `owner` installed files
+    DataFileClash { file: String, owner: String },
+    /// Asked to upgrade a package pinned at its installed version, though the registry's
+    /// `latest` is newer
+    Pinned { latest: String },
+    /// Left alone by `plan_upgrade`: the new version needs `dependency`, which can't be
+    /// installed
+    UnmetDependency { dependency: String },
+    /// Left alone by `plan_upgrade`: the new version's dependencies lead round in a `cycle`
+    DependencyCycle { cycle: Vec<String> },
+}
+
+/// What an install or upgrade of one package came to, from `install_from_manifest()`,
+/// `apply()`, and `apply_upgrade()`
+#[derive(Debug)]
+pub enum InstallOutcome {
+    /// Downloaded and put in place (a fresh install or an upgrade)
+    Installed(InstallResult),
+    /// Asked to install a package that is already installed; nothing was done
+    AlreadyInstalled { package: String, version: String },
+    /// Asked to upgrade a package already at the registry's version
+    UpToDate { package: String, version: String },
+    /// Left alone for another reason
+    Skipped { package: String, reason: SkipReason },
+}
+
+impl InstallOutcome {
+    pub fn package(&self) -> &str {
+        match self {
+            InstallOutcome::Installed(result) => &result.package_name,
+            InstallOutcome::AlreadyInstalled { package, .. }
+            | InstallOutcome::UpToDate { package, .. }
+            | InstallOutcome::Skipped { package, .. } => package,
+        }
+    }
+
+    /// Whether anything was installed
+    pub fn is_installed(&self) -> bool {
+        matches!(self, InstallOutcome::Installed(_))
+    }
+}
+
+impl From<SkippedPackage> for InstallOutcome {
+    fn from(skipped: SkippedPackage) -> Self {
+        let package = skipped.package;
+        match skipped.reason {
+            SkipReason::AlreadyInstalled { version } => {
+                InstallOutcome::AlreadyInstalled { package, version }
+            }
+            SkipReason::UpToDate { version } => InstallOutcome::UpToDate { package, version },
+            reason => InstallOutcome::Skipped { package, reason },
+        }
+    }
+}
+
+/// Why a plan can't be applied
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    /// The registry has no such package
+    NotFound { package: String },
+    /// Asked to upgrade a package that isn't installed
+    NotInstalled { package: String },
+    /// The manifest has no binary for the plan's target
+    NoCompatibleTarget { package: String, target: String },
+    /// Installing `package` would overwrite a binary `owner` installed (or is about to)
+    BinaryClash {
+        package: String,
+        bin: String,
+        owner: String,
+    },
+    /// Installing `package` would write `file` in the data dir, where `owner` installed
+    /// files (or is about to)
+    DataFileClash {
+        package: String,
+        file: String,
+        owner: String,
+    },
+    /// `package`'s dependencies lead round in a `cycle`, e.g. ["a", "b", "a"]
+    DependencyCycle { package: String, cycle: Vec<String> },
+    /// `package` needs `dependency` (directly or through another), which the registry
+    /// doesn't have
+    DependencyNotFound { package: String, dependency: String },
+    /// A package depends on one that can't be installed (see the conflict recorded for it)
+    DependencyConflict { package: String, dependency: String },
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Conflict::NotFound { package } => write!(f, "Package '{}' not found", package),
+            Conflict::NotInstalled { package } => write!(f, "'{}' is not installed", package),
+            Conflict::NoCompatibleTarget { package, target } => {
+                write!(f, "No compatible binary for {} in '{}'", target, package)
+            }
+            Conflict::BinaryClash {
+                package,
+                bin,
+                owner,
+            } => write!(
+                f,
+                "'{}' would overwrite '{}', which belongs to '{}'",
+                package, bin, owner
+            ),
+            Conflict::DataFileClash {
+                package,
+                file,
+                owner,
+            } => write!(
+                f,
+                "'{}' would overwrite '{}' in the data dir, which belongs to '{}'",
+                package, file, owner
+            ),
+            Conflict::DependencyCycle { cycle, .. } => {
+                write!(f, "Dependency cycle: {}", cycle.join(" -> "))
+            }
+            Conflict::DependencyNotFound {
+                package,
+                dependency,
+            } => write!(
+                f,
+                "'{}' needs '{}', which is not in the registry",
+                package, dependency
+            ),
+            Conflict::DependencyConflict {
+                package,
+                dependency,
+            } => write!(
+                f,
+                "'{}' needs '{}', which can't be installed",
+                package, dependency
+            ),
+        }
+    }
+}
+
+/// What a mutating operation would do, from its `preview_*` variant (`rush ... --dry-run`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeSet {
+    /// URLs that would be downloaded
+    pub downloads: Vec<String>,
+    /// Files or directories that would be created or replaced
+    pub writes: Vec<PathBuf>,
+    /// Files or directories that would be deleted
+    pub deletes: Vec<PathBuf>,
+    /// How the installed state would change
+    pub state: Vec<StateChange>,
+    /// Configured hooks that would run, in order
+    pub hooks: Vec<PlannedHook>,
+}
+
+impl ChangeSet {
+    /// Whether the operation would do nothing at all
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Append everything `other` would do after this
+    pub fn extend(&mut self, other: ChangeSet) {
+        self.downloads.extend(other.downloads);
+        self.writes.extend(other.writes);
+        self.deletes.extend(other.deletes);
+        self.state.extend(other.state);
+        self.hooks.extend(other.hooks);
+    }
+}
+
+/// A configured hook a previewed operation would run
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedHook {
+    pub point: HookPoint,
+    pub package: String,
+}
+
+// REAL-TIME EVENTS
+
+/// Identifies one operation (an install, a registry update, a download) within this process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OperationId(pub u64);
+
+impl OperationId {
+    /// A fresh id, never handed out before by this process
+    pub fn next() -> Self {
+        static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+/// Event from `RushEngine::install_package()`, `update_registry()`, `add_package_manual()`,
+/// and every other operation that downloads something.
+/// Tagged with its operation, so one handler can follow several at once.
+pub struct ProgressEvent {
+    pub operation: OperationId,
+    /// The package being worked on; `None` for registry updates
+    pub package: Option<String>,
+    pub kind: ProgressKind,
+}
+
+/// What happened in a `ProgressEvent`
+pub enum ProgressKind {
+    /// A registry update has started
+    FetchingRegistry { source: String },
+    /// The download has started
+    Downloading { total_bytes: u64 },
+    /// A chunk of the download has been received
+    Progress { bytes: u64, total: u64 },
+    /// Calculating SHA256
+    VerifyingChecksum,
+    /// Extracting the archive
+    Extracting,
+    /// The registry download is complete and is being unpacked
+    Unpacking,
+    /// Installation complete (before returning result)
+    Success,
+    /// The last event of an install, right after `Success`: what it took, so a UI can
+    /// report it without timing anything itself
+    Summary(InstallSummary),
+}
+
+/// How an install went, from `ProgressKind::Summary`; the package is the event's own
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallSummary {
+    pub version: String,
+    /// Bytes downloaded; 0 if the artifact was already on this machine
+    pub downloaded_bytes: u64,
+    pub download_time: std::time::Duration,
+    /// Checking the artifact's SHA256
+    pub verify_time: std::time::Duration,
+    /// Extracting the artifact and recording the install
+    pub install_time: std::time::Duration,
+    /// Where the binary (or, for a data-only package, its files) went
+    pub path: PathBuf,
+}
+
+/// Event from a `[hooks]` command, after it has run
+#[derive(Debug, Clone)]
+pub struct HookEvent {
+    pub point: HookPoint,
+    pub package: String,
+    /// The command as run, after substitution
+    pub command: String,
+    /// `None` if it couldn't be started or was killed by a signal
+    pub exit_code: Option<i32>,
+    /// What it printed to stderr (or why it couldn't start), trimmed
+    pub stderr: String,
+}
+
+impl HookEvent {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Receives the progress of long-running `RushEngine` operations.
+///
+/// Every method defaults to a no-op, so an implementation only handles what it renders
+/// (a terminal UI, a JSON stream, a TUI, ...). Pass `&mut ()` to ignore progress entirely.
+pub trait Reporter {
+    /// From `install_package`, `update_registry`, `add_package_manual`, and the import helpers
+    fn progress(&mut self, _event: ProgressEvent) {}
+    /// From `bump_package` and `create_package`
+    fn bump(&mut self, _event: BumpEvent) {}
+    /// From `refresh_all`
+    fn refresh(&mut self, _event: RefreshEvent) {}
+    /// From `verify_registry`
+    fn verify(&mut self, _event: VerifyEvent) {}
+    /// From `check_urls`
+    fn link_check(&mut self, _event: LinkCheckEvent) {}
+    /// From `serve_registry`
+    fn serve(&mut self, _event: ServeEvent) {}
+    /// From `install_package` and `uninstall_package`, once per configured hook
+    fn hook(&mut self, _event: HookEvent) {}
+    /// From `apply` and `apply_upgrade`, as each planned install starts
+    fn step(&mut self, _step: &PlannedInstall) {}
+}
+
+/// The silent reporter
+impl Reporter for () {}
+
+/// How `rush search` orders packages (`sort_packages`, and `--sort`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum PackageOrder {
+    #[default]
+    Name,
+    /// Most recently `updated` manifests first; those without a timestamp last
+    Updated,
+    /// Smallest artifact for the target first; unknown sizes last
+    Size,
+}
+
+/// An installed package the registry has another version of, from `RushEngine::outdated()`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OutdatedPackage {
+    pub package: String,
+    pub installed: String,
+    pub available: String,
+}
+
+/// A registry package as `rush find` lists it, from `RushEngine::finder_entries()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinderEntry {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+}
+
+/// Result of `RushEngine::package_info()`: what is installed, and what the registry has
+#[derive(Serialize, Debug)]
+pub struct PackageInfo {
+    pub name: String,
+    pub installed: Option<InstalledPackage>,
+    pub available: Option<PackageManifest>,
+}
+
+// --- UPSTREAM VERIFICATION RESULTS ---
+
+/// Result of `RushEngine::verify_upstream()`
+#[derive(Debug)]
+pub struct UpstreamResult {
+    pub package: String,
+    pub version: String,
+    /// One per target in the manifest
+    pub targets: Vec<UpstreamCheck>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct TargetDefinition {
-    pub url: String,
-    pub bin: String,
-    pub sha256: String,
+impl UpstreamResult {
+    /// The targets whose hash the upstream checksum file contradicts, or that couldn't be
+    /// checked against the file the manifest names
+    pub fn failed(&self) -> impl Iterator<Item = &UpstreamCheck> {
+        self.targets.iter().filter(|t| {
+            matches!(
+                t.status,
+                UpstreamStatus::Mismatch { .. } | UpstreamStatus::Failed { .. }
+            )
+        })
+    }
 }
 
-// --- GITHUB API DATA ---
-#[derive(Deserialize, Debug)]
-pub struct GitHubRelease {
-    pub tag_name: String,
-    pub assets: Vec<GitHubAsset>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpstreamCheck {
+    pub target: String,
+    /// The sha256 the registry lists
+    pub sha256: String,
+    pub status: UpstreamStatus,
 }
 
-#[derive(Deserialize, Debug, Clone)] // Added Clone
-pub struct GitHubAsset {
-    pub name: String,
-    pub browser_download_url: String,
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpstreamStatus {
+    /// The upstream checksum file lists the same hash
+    Match { checksum_url: String },
+    /// The upstream checksum file lists another hash
+    Mismatch {
+        checksum_url: String,
+        upstream: String,
+    },
+    /// The checksum file couldn't be fetched, or doesn't list the artifact
+    Failed { checksum_url: String, error: String },
+    /// The manifest names no checksum file for this target
+    NoChecksumFile,
 }
 
-// --- DATA TRANSFER OBJECTS (Core -> UI) ---
+// --- AUDIT RESULTS ---
 
-/// Represents a candidate for import found in a GitHub release
+/// Result of `RushEngine::audit()`
 #[derive(Debug)]
-pub struct ImportCandidate {
-    /// The specific system target (e.g. "x86_64-linux")
-    pub target_slug: String,
-    /// A human-readable description (e.g. "Linux (x86_64)")
-    pub target_desc: String,
-    /// List of assets sorted by relevance score
-    pub assets: Vec<ScoredAsset>,
+pub struct AuditResult {
+    /// Every installed package that was looked up, vulnerable or not
+    pub packages: Vec<PackageAudit>,
+    /// Installed packages that couldn't be looked up: no `[osv]` in their manifest, or
+    /// no longer in the registry
+    pub unchecked: Vec<String>,
+}
+
+impl AuditResult {
+    /// The packages with known vulnerabilities
+    pub fn vulnerable(&self) -> impl Iterator<Item = &PackageAudit> {
+        self.packages
+            .iter()
+            .filter(|p| !p.vulnerabilities.is_empty())
+    }
 }
 
 #[derive(Debug)]
-pub struct ScoredAsset {
-    pub score: i32,
-    pub asset: GitHubAsset,
+pub struct PackageAudit {
+    pub package: String,
+    /// The installed version, as queried
+    pub version: String,
+    pub vulnerabilities: Vec<Vulnerability>,
 }
 
-// --- STATE DATA ---
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
-pub struct State {
-    pub packages: HashMap<String, InstalledPackage>,
+/// A known vulnerability affecting an installed version
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vulnerability {
+    /// OSV ID (e.g. "GHSA-xxxx-xxxx-xxxx", "RUSTSEC-2024-0001")
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    /// The advisory's rating (e.g. "HIGH"), or else its CVSS vector
+    pub severity: Option<String>,
+    /// Versions that fix it, oldest first as the advisory lists them; empty if unfixed
+    pub fixed: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct InstalledPackage {
-    pub version: String,
-    pub binaries: Vec<String>,
+// --- REGISTRY STATISTICS ---
+
+/// Result of `RushEngine::registry_stats()`
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct RegistryStats {
+    /// Packages whose manifest could be read
+    pub packages: usize,
+    /// How many packages have a build for each target, best covered first
+    pub targets: Vec<TargetCoverage>,
+    /// The most recently changed manifests, newest first
+    pub newest: Vec<ManifestAge>,
+    /// The least recently changed manifests, oldest first
+    pub oldest: Vec<ManifestAge>,
+    pub missing_description: Vec<String>,
+    /// Packages with a target that doesn't record its artifact's `size`
+    pub missing_size: Vec<String>,
+    /// Packages listed in the registry whose manifest is missing or doesn't parse
+    pub unreadable: Vec<String>,
 }
 
-// -- FUNCTION RESULTS ---
+/// One line of `RegistryStats::targets`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TargetCoverage {
+    pub target: String,
+    pub packages: usize,
+}
 
-/// Result of `RushEngine::clean_trash()`
+/// When a package's manifest last changed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ManifestAge {
+    pub package: String,
+    /// RFC 3339, UTC
+    pub changed_at: String,
+}
+
+// --- SELF-UPDATE ---
+
+/// Result of `RushEngine::self_update()`
 #[derive(Debug)]
-pub struct CleanResult {
-    pub files_cleaned: Vec<String>,
+pub struct SelfUpdateResult {
+    /// The running rush's version
+    pub current: String,
+    /// The newest release's version
+    pub latest: String,
+    /// Whether `latest` is newer than `current`
+    pub newer: bool,
+    /// The executable that was replaced; None if nothing was (up to date, or only checked)
+    pub updated: Option<PathBuf>,
 }
 
-/// Result of `RushEngine::uninstall_package()`
+/// Result of `RushEngine::install_schedule()` and `remove_schedule()`
 #[derive(Debug)]
-pub struct UninstallResult {
-    /// The name of the package that was uninstalled.
-    pub package_name: String,
-    /// The list of binary files that were deleted.
-    pub binaries_removed: Vec<String>,
+pub struct ScheduleResult {
+    /// The scheduler files written, or removed; none if there were none to remove
+    pub files: Vec<PathBuf>,
 }
 
-/// Result of RushEngine::update_registry()
+/// Result of `RushEngine::init_shell()`
 #[derive(Debug)]
-pub struct UpdateResult {
-    /// The source URL or path the registry was updated from.
-    pub source: String,
+pub struct InitResult {
+    /// The startup file, in the home directory
+    pub rc_file: PathBuf,
+    /// Whether the line was added; false if it was there already
+    pub added: bool,
 }
 
-/// Result of RushEngine::install_package()
+// --- MIRROR RESULTS ---
+
+/// Result of `RushEngine::mirror()`
 #[derive(Debug)]
-pub struct InstallResult {
-    /// The name of the package installed
-    pub package_name: String,
-    /// The version installed
-    pub version: String,
-    /// The final path to the binary on disk
+pub struct MirrorResult {
+    /// The mirror directory, to point `RUSH_REGISTRY_URL` at
+    pub dir: PathBuf,
+    /// Manifests written
+    pub packages: usize,
+    /// Artifacts fetched this run
+    pub downloaded: usize,
+    /// Artifacts already in the mirror with the right checksum
+    pub reused: usize,
+    /// Packages with no build for a selected target, as (package, target)
+    pub unsupported: Vec<(String, String)>,
+    pub failures: Vec<MirrorFailure>,
+}
+
+/// An artifact `rush mirror` couldn't fetch or verify
+#[derive(Debug)]
+pub struct MirrorFailure {
+    pub package: String,
+    pub target: String,
+    pub error: String,
+}
+
+/// Result of `RushEngine::export_bundle()`
+#[derive(Debug)]
+pub struct BundleResult {
+    /// The bundle written
     pub path: PathBuf,
+    /// What went into it, as recorded in its `rush.lock`
+    pub lockfile: Lockfile,
+    /// Installed packages left out: installed before rush recorded provenance, and the
+    /// registry no longer has that version
+    pub skipped: Vec<String>,
 }
 
-// REAL-TIME EVENTS
+/// Result of `RushEngine::export_nix()` and `export_dockerfile()`
+#[derive(Debug)]
+pub struct ExportResult {
+    /// What was exported: the Nix expression, or the Dockerfile
+    pub contents: String,
+    /// The packages it builds
+    pub packages: Vec<String>,
+    /// Installed packages it leaves out, and why
+    pub skipped: Vec<SkippedExport>,
+}
 
-/// Event from `RushEngine::install_package()` and `add_package_manual`
-pub enum InstallEvent {
-    /// The download has started
-    Downloading { total_bytes: u64 },
-    /// A chunk of the download has been received
-    Progress { bytes: u64, total: u64 },
-    /// Calculating SHA256
-    VerifyingChecksum,
-    /// Extracting the archive
-    Extracting,
-    /// Installation complete (before returning result)
-    Success,
+/// An installed package an export couldn't include
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedExport {
+    pub package: String,
+    pub reason: String,
 }
 
-/// Event from `RushEngine::update_registry()`
-pub enum UpdateEvent {
-    /// The download of the registry has started.
-    Fetching { source: String },
-    /// A chunk of the download has been received.
-    Progress { bytes: u64, total: u64 },
-    /// The download is complete and is being unpacked.
-    Unpacking,
+// --- PROJECTS ---
+
+/// A project's `rush.toml`: the tools working on it takes
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ProjectManifest {
+    /// Package name -> version, e.g. `jq = "1.7.1"`; "latest" for the registry's version
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
+}
+
+/// Result of `RushEngine::sync_project()`
+#[derive(Debug)]
+pub struct ProjectEnv {
+    /// The directory holding `rush.toml`
+    pub root: PathBuf,
+    /// Where the project's binaries are, to put first on PATH
+    pub bin_dir: PathBuf,
+    /// The project's tools, as (package, version)
+    pub tools: Vec<(String, String)>,
+}
+
+/// Result of `RushEngine::check_project()`
+#[derive(Debug)]
+pub struct ProjectCheck {
+    /// The directory holding `rush.toml`
+    pub root: PathBuf,
+    /// One per tool `rush.toml` lists, in name order
+    pub tools: Vec<ProjectToolCheck>,
+}
+
+impl ProjectCheck {
+    /// The tools `rush sync` would still have to fetch or link
+    pub fn failed(&self) -> impl Iterator<Item = &ProjectToolCheck> {
+        self.tools
+            .iter()
+            .filter(|t| t.status != ProjectToolStatus::Ready)
+    }
+}
+
+/// One tool of a `ProjectCheck`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectToolCheck {
+    pub package: String,
+    /// As `rush.toml` pins it, or the registry's version for "latest"
+    pub version: String,
+    pub status: ProjectToolStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectToolStatus {
+    /// Fetched at the version, and in the project's bin dir
+    Ready,
+    /// Not fetched at the version
+    Missing,
+    /// Fetched, but not in the project's bin dir
+    NotLinked,
+    /// The registry has no such package, or no build of it for this machine
+    Unavailable,
 }
 
 // --- VERIFICATION RESULTS ---
@@ -147,14 +1654,302 @@ pub struct VerificationFailure {
     pub error: String,
 }
 
+/// Result of `RushEngine::import_cargo_dist()`
+#[derive(Debug)]
+pub struct DistImportResult {
+    pub package_name: String,
+    pub version: String,
+    /// Targets written to the manifest
+    pub imported: Vec<String>,
+    /// Requested targets that couldn't be imported, with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Input to `RushEngine::create_package()`, as collected by the `dev new` wizard
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewPackage {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub targets: Vec<NewTarget>,
+}
+
+/// One target of a `NewPackage`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewTarget {
+    /// Target slug (e.g. "x86_64-linux")
+    pub target: String,
+    /// Download URL or local artifact path
+    pub url: String,
+    /// Binary name; detected from the archive when None
+    pub bin: Option<String>,
+}
+
+impl NewPackage {
+    /// Registry names are lowercase ASCII letters, digits, '-' and '_', starting alphanumeric
+    pub fn validate_name(name: &str) -> Result<(), String> {
+        let starts_ok = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+        let chars_ok = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !starts_ok || !chars_ok {
+            return Err(format!(
+                "'{}' is not a valid package name (use lowercase letters, digits, '-' and '_')",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Versions are written without a leading 'v' and must start with a digit
+    pub fn validate_version(version: &str) -> Result<(), String> {
+        if !version.starts_with(|c: char| c.is_ascii_digit())
+            || version.chars().any(char::is_whitespace)
+        {
+            return Err(format!(
+                "'{}' is not a valid version (expected e.g. 1.2.3, without a leading 'v')",
+                version
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn validate_homepage(url: &str) -> Result<(), String> {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(format!("'{}' is not an http(s) URL", url));
+        }
+        Ok(())
+    }
+
+    /// Checks every field; the wizard validates as it goes, but callers may not
+    pub fn validate(&self) -> Result<(), String> {
+        Self::validate_name(&self.name)?;
+        Self::validate_version(&self.version)?;
+        if let Some(homepage) = &self.homepage {
+            Self::validate_homepage(homepage)?;
+        }
+        if self.targets.is_empty() {
+            return Err("At least one target is required".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Result of `RushEngine::bump_package()`
+#[derive(Debug)]
+pub struct BumpResult {
+    pub package_name: String,
+    pub old_version: String,
+    /// Equal to `old_version` when the package is already up to date
+    pub new_version: String,
+    /// Where the release was looked up (e.g. "sharkdp/bat")
+    pub source: String,
+    /// Targets re-resolved and re-hashed
+    pub updated: Vec<String>,
+    /// Targets that couldn't be bumped, with the reason. The manifest is untouched if any failed.
+    pub failures: Vec<(String, String)>,
+}
+
+/// Result of `RushEngine::refresh_all()`
+#[derive(Debug, Default)]
+pub struct RefreshResult {
+    /// Packages moved to a newer release
+    pub bumped: Vec<BumpResult>,
+    /// Packages already at their newest release
+    pub up_to_date: Vec<String>,
+    /// Packages that couldn't be checked or bumped, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+/// Result of `RushEngine::publish()`
+#[derive(Debug)]
+pub struct PublishResult {
+    /// Branch pushed to the remote
+    pub branch: String,
+    /// Web URL of the opened pull request
+    pub pr_url: String,
+    pub packages: Vec<PublishedPackage>,
+}
+
+/// A manifest included in a `dev publish` pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedPackage {
+    pub name: String,
+    /// Path relative to the registry root (e.g. "packages/b/bat.toml")
+    pub path: String,
+    /// None when the manifest was removed
+    pub version: Option<String>,
+    pub targets: Vec<String>,
+    pub change: PackageChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PackageChange {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// Result of `RushEngine::generate_signing_key()`
+#[derive(Debug)]
+pub struct KeygenResult {
+    /// Where the secret key was written
+    pub key_path: PathBuf,
+    /// Hex-encoded ed25519 public key, for clients to verify against
+    pub public_key: String,
+}
+
+/// Result of `RushEngine::trial_install()` (`rush dev test`)
+#[derive(Debug)]
+pub struct TrialResult {
+    pub package: String,
+    pub version: String,
+    pub target: String,
+    /// What the install put in the throwaway root (since deleted)
+    pub binaries: Vec<String>,
+    pub files: Vec<String>,
+    /// The manifest's `check`, as run against the installed binary
+    pub check: Option<CheckResult>,
+    /// Why the manifest's `check` wasn't run (another target, no sandbox, ...)
+    pub check_skipped: Option<String>,
+}
+
+impl TrialResult {
+    /// Installed, and its check (if it ran) passed
+    pub fn succeeded(&self) -> bool {
+        self.check.as_ref().is_none_or(CheckResult::succeeded)
+    }
+}
+
+/// Result of `RushEngine::sign_registry()`
+#[derive(Debug)]
+pub struct SignResult {
+    pub index_path: PathBuf,
+    pub signature_path: PathBuf,
+    pub packages_signed: usize,
+    /// Hex-encoded public key matching the signature
+    pub public_key: String,
+}
+
+/// Result of `RushEngine::lint_registry()`
+#[derive(Debug)]
+pub struct LintResult {
+    pub packages_checked: usize,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintResult {
+    /// Number of issues that should fail the lint
+    pub fn error_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == LintSeverity::Error)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug)]
+pub struct LintIssue {
+    /// Package name (file stem of the manifest)
+    pub package_name: String,
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+/// Result of `RushEngine::check_urls()`
+#[derive(Debug)]
+pub struct LinkCheckResult {
+    /// Every checked URL, in registry order
+    pub checks: Vec<LinkCheck>,
+}
+
+impl LinkCheckResult {
+    /// Links that are dead or permanently moved
+    pub fn problems(&self) -> impl Iterator<Item = &LinkCheck> {
+        self.checks
+            .iter()
+            .filter(|c| !matches!(c.status, LinkStatus::Ok))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkCheck {
+    pub package_name: String,
+    pub target: String,
+    pub url: String,
+    pub status: LinkStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkStatus {
+    /// Reachable (possibly via temporary redirects, e.g. GitHub's CDN)
+    Ok,
+    /// A permanent redirect (301/308) points elsewhere; the manifest should be updated
+    Redirected { location: String },
+    /// Unreachable or an error status
+    Dead { reason: String },
+}
+
 // REAL TIME EVENTS
 
+/// Event from `RushEngine::check_urls()`
+pub enum LinkCheckEvent {
+    /// The number of URLs that will be checked
+    Started { total: usize },
+    /// A single URL has been checked
+    Checked(LinkCheck),
+}
+
+/// Event from `RushEngine::bump_package()` and `RushEngine::create_package()`
+pub enum BumpEvent {
+    /// Downloading the new asset for a target, to hash it
+    Downloading { target: String, url: String },
+    /// Progress updates from the underlying download
+    Progress(ProgressEvent),
+}
+
+/// Event from `RushEngine::refresh_all()`
+pub enum RefreshEvent {
+    /// The number of packages that will be checked
+    Started { total: usize },
+    /// A package has been checked (and bumped, if it was behind)
+    Checked { name: String },
+}
+
+/// Event from `RushEngine::serve_registry()`
+pub enum ServeEvent {
+    /// The server is accepting connections
+    Listening {
+        /// Point `RUSH_REGISTRY_URL` here
+        registry_url: String,
+        /// Base URL for artifacts referenced by manifests
+        artifacts_url: String,
+    },
+    /// A request has been answered
+    Request {
+        method: String,
+        path: String,
+        status: u16,
+    },
+}
+
 /// Event from `RushEngine::verify_registry()`
 pub enum VerifyEvent {
     /// We are starting to check a specific target
     Checking { name: String, target: String },
     /// Progress updates from the underlying download/install logic
-    Progress(InstallEvent),
+    Progress(ProgressEvent),
 }
 
 #[cfg(test)]
@@ -182,7 +1977,84 @@ mod tests {
         assert_eq!(manifest.version, "1.0.0");
 
         let target = &manifest.targets["x86_64-linux"];
-        assert_eq!(target.bin, "tool");
+        assert_eq!(target.bin, ["tool"]);
+        assert_eq!(target.main_bin(), "tool");
+        // A single binary is still written as a string
+        assert!(
+            toml::to_string(target)
+                .unwrap()
+                .contains("bin = \"tool\"\n")
+        );
+    }
+
+    #[test]
+    fn test_target_with_several_binaries() {
+        let target: TargetDefinition = toml::from_str(
+            r#"
+            url = "https://example.com/uv.tar.gz"
+            bin = ["uv", "uvx"]
+            sha256 = "abc"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(target.bin, ["uv", "uvx"]);
+        assert_eq!(target.main_bin(), "uv");
+        let round_trip: TargetDefinition =
+            toml::from_str(&toml::to_string(&target).unwrap()).unwrap();
+        assert_eq!(round_trip, target);
+
+        // No binary at all, however it's spelled
+        for bin in ["", "bin = \"\"", "bin = []"] {
+            let toml = format!("url = \"u\"\nsha256 = \"s\"\n{}", bin);
+            let target: TargetDefinition = toml::from_str(&toml).unwrap();
+            assert!(target.bin.is_empty() && target.main_bin().is_empty());
+        }
+
+        // The same binary twice is a mistake, not two binaries
+        let err = toml::from_str::<TargetDefinition>(
+            "url = \"u\"\nsha256 = \"s\"\nbin = [\"tool\", \"tool\"]",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'tool' is listed more than once"));
+
+        // It is joined to the bin dir, so it can't lead anywhere else
+        for bin in ["../../.profile", "/etc/passwd", "bin/tool", ".", ".."] {
+            let toml = format!("url = \"u\"\nsha256 = \"s\"\nbin = {:?}", bin);
+            let err = toml::from_str::<TargetDefinition>(&toml).unwrap_err();
+            assert!(err.to_string().contains("not a plain file name"), "{}", bin);
+        }
+    }
+
+    #[test]
+    fn test_manifest_target_keys_normalized() {
+        let target = |key: &str| {
+            format!(
+                "[targets.{}]\nurl = \"https://example.com/t.tar.gz\"\nbin = \"t\"\nsha256 = \"ab\"\n",
+                key
+            )
+        };
+        let toml_input = format!(
+            "version = \"1.0.0\"\n{}{}",
+            target("arm64-darwin"),
+            target("x86_64-unknown-linux-gnu")
+        );
+        let manifest: PackageManifest = toml::from_str(&toml_input).unwrap();
+        let keys: Vec<&String> = manifest.targets.keys().collect();
+        assert_eq!(keys, ["aarch64-macos", "x86_64-linux-gnu"]);
+        // The host's key names no libc, and still finds the glibc build
+        assert_eq!(
+            manifest.target("x86_64-linux").map(|(key, _)| key),
+            Some("x86_64-linux-gnu")
+        );
+
+        // The same target twice under different spellings
+        let toml_input = format!(
+            "version = \"1.0.0\"\n{}{}",
+            target("aarch64-macos"),
+            target("arm64-darwin")
+        );
+        let err = toml::from_str::<PackageManifest>(&toml_input).unwrap_err();
+        assert!(err.to_string().contains("more than once"));
     }
 
     #[test]
@@ -213,6 +2085,11 @@ mod tests {
             InstalledPackage {
                 version: "1.0".to_string(),
                 binaries: vec!["bar".to_string()],
+                files: Vec::new(),
+                provenance: None,
+                constraint: None,
+                pinned: false,
+                dependency: false,
             },
         );
 
@@ -241,7 +2118,132 @@ mod tests {
             serde_json::from_str(json).expect("Failed to parse GitHub JSON");
 
         assert_eq!(release.tag_name, "v1.0.0");
+        assert!(!release.prerelease, "prerelease should default to false");
         assert_eq!(release.assets.len(), 1);
         assert_eq!(release.assets[0].name, "example.zip");
     }
+
+    #[test]
+    /// Verify we can parse the release list endpoint, including prerelease metadata
+    fn test_github_release_list_deserialization() {
+        let json = r#"[
+            {"tag_name": "v2.0.0-rc.1", "prerelease": true, "published_at": "2024-03-01T10:00:00Z", "assets": []},
+            {"tag_name": "v1.9.0", "prerelease": false, "published_at": "2024-01-15T08:30:00Z", "assets": []}
+        ]"#;
+
+        let releases: Vec<GitHubRelease> =
+            serde_json::from_str(json).expect("Failed to parse GitHub JSON");
+
+        assert_eq!(releases.len(), 2);
+        assert!(releases[0].prerelease);
+        assert!(releases[0].is_prerelease());
+        assert!(!releases[1].is_prerelease());
+        assert_eq!(
+            releases[1].published_at.as_deref(),
+            Some("2024-01-15T08:30:00Z")
+        );
+    }
+
+    #[test]
+    fn test_prerelease_detected_from_tag() {
+        let release = |tag: &str| GitHubRelease {
+            tag_name: tag.to_string(),
+            published_at: None,
+            prerelease: false,
+            assets: vec![],
+        };
+
+        assert!(release("nightly").is_prerelease());
+        assert!(release("v0.5.0-beta.2").is_prerelease());
+        assert!(release("v1.0.0-RC1").is_prerelease());
+        assert!(!release("v1.0.0").is_prerelease());
+        assert!(!release("14.1.0").is_prerelease());
+    }
+
+    #[test]
+    fn test_new_package_validation() {
+        assert!(NewPackage::validate_name("ripgrep").is_ok());
+        assert!(NewPackage::validate_name("cargo-dist_2").is_ok());
+        assert!(NewPackage::validate_name("").is_err());
+        assert!(NewPackage::validate_name("RipGrep").is_err());
+        assert!(NewPackage::validate_name("-tool").is_err());
+        assert!(NewPackage::validate_name("../tool").is_err());
+
+        assert!(NewPackage::validate_version("14.1.0").is_ok());
+        assert!(NewPackage::validate_version("1.0.0-rc.1").is_ok());
+        assert!(NewPackage::validate_version("v14.1.0").is_err());
+        assert!(NewPackage::validate_version("1.0 beta").is_err());
+
+        assert!(NewPackage::validate_homepage("https://example.com").is_ok());
+        assert!(NewPackage::validate_homepage("example.com").is_err());
+    }
+
+    #[test]
+    fn test_config_scoring_rules() {
+        let config: Config = toml::from_str(
+            r#"
+            [[scoring.rules]]
+            keyword = "-static"
+            weight = -20
+
+            [[scoring.rules]]
+            keyword = "acme"
+            weight = 15
+            targets = ["x86_64-linux"]
+            "#,
+        )
+        .unwrap();
+
+        let rules = &config.scoring.rules;
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].matches("tool-linux-STATIC.tar.gz", "aarch64-macos"));
+        assert!(rules[1].matches("tool-acme-linux.tar.gz", "x86_64-linux"));
+        assert!(!rules[1].matches("tool-acme-linux.tar.gz", "aarch64-linux"));
+
+        // Everything is optional
+        assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_gitlab_release_maps_to_github_shape() {
+        let json = r#"
+        {
+            "tag_name": "v2.1.0",
+            "released_at": "2024-03-01T12:00:00Z",
+            "upcoming_release": false,
+            "assets": {
+                "count": 2,
+                "sources": [{ "format": "zip", "url": "https://gitlab.com/g/p/-/archive/v2.1.0/p.zip" }],
+                "links": [
+                    {
+                        "name": "tool-x86_64-linux.tar.gz",
+                        "url": "https://gitlab.com/g/p/-/jobs/1/artifacts/raw/tool.tar.gz",
+                        "direct_asset_url": "https://gitlab.com/g/p/-/releases/v2.1.0/downloads/tool.tar.gz"
+                    },
+                    { "name": "notes.txt", "url": "https://example.com/notes.txt" }
+                ]
+            }
+        }
+        "#;
+
+        let release: GitLabRelease =
+            serde_json::from_str(json).expect("Failed to parse GitLab JSON");
+        let release: GitHubRelease = release.into();
+
+        assert_eq!(release.tag_name, "v2.1.0");
+        assert_eq!(
+            release.published_at.as_deref(),
+            Some("2024-03-01T12:00:00Z")
+        );
+        // Source archives are not release assets
+        assert_eq!(release.assets.len(), 2);
+        assert_eq!(
+            release.assets[0].browser_download_url,
+            "https://gitlab.com/g/p/-/releases/v2.1.0/downloads/tool.tar.gz"
+        );
+        assert_eq!(
+            release.assets[1].browser_download_url,
+            "https://example.com/notes.txt"
+        );
+    }
 }
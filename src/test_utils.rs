@@ -0,0 +1,204 @@
+//! Helpers for testing code that embeds the engine (the `test-utils` feature).
+//!
+//! `MockEnvironment` builds a throwaway home directory and a local registry whose
+//! packages point at real tarballs on disk, so installs work end to end without a network:
+//!
+//! ```no_run
+//! use rush::test_utils::MockEnvironment;
+//!
+//! let mock = MockEnvironment::new();
+//! mock.add_package("hello", "1.0.0", "hello");
+//!
+//! let mut engine = mock.engine().unwrap();
+//! engine.update_registry(&mut ()).unwrap();
+//! let manifest = engine.find_package("hello").unwrap();
+//! let target = &manifest.targets[&rush::test_utils::current_target()];
+//! engine.install_package("hello", &manifest.version, target, &mut ()).unwrap();
+//! ```
+
+use crate::core::RushEngine;
+use anyhow::Result;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use tar::Builder;
+use tempfile::TempDir;
+
+/// The target slug of the machine running the tests (e.g. "x86_64-linux")
+pub fn current_target() -> String {
+    crate::core::Target::host().to_string()
+}
+
+/// A gzipped tarball holding `files`, each given as its path, mode and content
+pub fn tar_gz(files: &[(&str, u32, &[u8])]) -> Vec<u8> {
+    let mut tar = Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for &(path, mode, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        tar.append_data(&mut header, path, data).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap()
+}
+
+/// A helper to simulate a remote registry environment locally.
+/// Everything lives in a temp dir that is removed when this is dropped.
+pub struct MockEnvironment {
+    _temp: TempDir,
+    /// The fake `$HOME`
+    pub home: PathBuf,
+    /// A local registry (`packages/` plus the tarballs it points at)
+    pub registry_source: PathBuf,
+}
+
+impl Default for MockEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockEnvironment {
+    pub fn new() -> Self {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let root = temp.path();
+
+        let home = root.join("home");
+        fs::create_dir(&home).expect("Failed to create mock home");
+
+        let registry_source = root.join("registry_source");
+        fs::create_dir(&registry_source).expect("Failed to create registry source");
+        fs::create_dir(registry_source.join("packages")).expect("Failed to create packages dir");
+
+        Self {
+            _temp: temp,
+            home,
+            registry_source,
+        }
+    }
+
+    /// Adds a valid package to the mock registry
+    pub fn add_package(&self, name: &str, version: &str, bin_name: &str) {
+        self.create_package_internal(name, version, bin_name, None)
+    }
+
+    /// Adds a package with a deliberately wrong checksum to test security
+    pub fn add_malicious_package(&self, name: &str, version: &str, bin_name: &str) {
+        // Well-formed digest so it passes linting, but matches nothing
+        self.create_package_internal(name, version, bin_name, Some(&"0".repeat(64)))
+    }
+
+    fn create_package_internal(
+        &self,
+        name: &str,
+        version: &str,
+        bin_name: &str,
+        checksum_override: Option<&str>,
+    ) {
+        // 1. Create Script
+        let script_content = format!("#!/bin/sh\necho 'Hello from {} v{}'", name, version);
+        let archive_name = format!("{}-{}.tar.gz", name, version);
+        let archive_path = self.registry_source.join(&archive_name);
+
+        // 2. Create Tarball
+        let tarball = tar_gz(&[(bin_name, 0o755, script_content.as_bytes())]);
+        fs::write(&archive_path, tarball).expect("Failed to write tarball");
+
+        // 3. Calculate SHA256
+        let sha256 = if let Some(bad_hash) = checksum_override {
+            bad_hash.to_string()
+        } else {
+            let bytes = fs::read(&archive_path).unwrap();
+            let mut hasher = Sha256::new();
+            hasher.update(&bytes);
+            hex::encode(hasher.finalize())
+        };
+
+        // 4. Write Manifest
+        let target_arch = current_target();
+        let url = format!("file://{}", archive_path.to_str().unwrap());
+
+        let toml_content = format!(
+            r#"
+            version = "{version}"
+            description = "Mock package"
+            [targets.{target_arch}]
+            url = "{url}"
+            bin = "{bin_name}"
+            sha256 = "{sha256}"
+            "#
+        );
+
+        let prefix = name.chars().next().unwrap();
+        let package_dir = self
+            .registry_source
+            .join("packages")
+            .join(prefix.to_string());
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(package_dir.join(format!("{}.toml", name)), toml_content).unwrap();
+    }
+
+    /// Gives an added package these `dependencies`
+    pub fn add_dependencies(&self, name: &str, dependencies: &[&str]) {
+        let path = self
+            .registry_source
+            .join(format!("packages/{}/{}.toml", &name[..1], name));
+        let content = fs::read_to_string(&path).unwrap();
+        let list: Vec<String> = dependencies.iter().map(|d| format!("{:?}", d)).collect();
+        let content = format!("dependencies = [{}]\n{}", list.join(", "), content);
+        fs::write(&path, content).unwrap();
+    }
+
+    /// An engine rooted in the mock home, reading the mock registry
+    pub fn engine(&self) -> Result<RushEngine> {
+        RushEngine::builder()
+            .root(&self.home)
+            .registry_source(self.registry_source.to_string_lossy())
+            .build()
+    }
+
+    /// Environment variables that point the `rush` binary at the mock home and registry
+    pub fn envs(&self) -> Vec<(&str, String)> {
+        vec![
+            ("HOME", self.home.to_str().unwrap().to_string()),
+            (
+                "RUSH_REGISTRY_URL",
+                self.registry_source.to_str().unwrap().to_string(),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_environment_installs_end_to_end() {
+        let mock = MockEnvironment::new();
+        mock.add_package("hello", "1.0.0", "hello");
+        mock.add_malicious_package("evil", "1.0.0", "evil");
+
+        let mut engine = mock.engine().unwrap();
+        engine.update_registry(&mut ()).unwrap();
+
+        let manifest = engine.find_package("hello").unwrap();
+        let target = &manifest.targets[&current_target()];
+        let result = engine
+            .install_package("hello", &manifest.version, target, &mut ())
+            .unwrap();
+        assert_eq!(result.path, mock.home.join(".local/share/rush/shims/hello"));
+
+        let manifest = engine.find_package("evil").unwrap();
+        let target = &manifest.targets[&current_target()];
+        let err = engine
+            .install_package("evil", &manifest.version, target, &mut ())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::Error>(),
+            Some(crate::Error::ChecksumMismatch { .. })
+        ));
+    }
+}
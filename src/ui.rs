@@ -1,28 +1,93 @@
+use crate::cli::ProgressMode;
+use crate::core::fuzzy_filter;
+use crate::i18n::t;
 use crate::models::{
-    CleanResult, ImportCandidate, InstallEvent, InstalledPackage, PackageManifest, UninstallResult,
-    UpdateEvent, VerifyEvent, VerifyResult,
+    AssetPreview, AuditResult, BumpEvent, BumpResult, BundleResult, ChangeSet, CheckResult,
+    ChecksumSource, CleanAllResult, CleanResult, DistImportResult, ExportResult, FinderEntry,
+    GitHubRelease, HashChange, HookEvent, ImportCandidate, InitResult, InstallOutcome,
+    InstallResult, InstallSummary, InstalledPackage, KeygenResult, LeftoverKind, LinkCheckEvent,
+    LinkCheckResult, LinkStatus, LintResult, LintSeverity, MirrorResult, NewPackage, NewTarget,
+    OperationId, OutdatedPackage, PackageChange, PackageInfo, PackageManifest, PlannedInstall,
+    ProgressEvent, ProgressKind, ProjectCheck, ProjectEnv, ProjectToolStatus, PublishResult,
+    RefreshEvent, RefreshResult, RegistryStats, Reporter, RequirementWarning, ScheduleResult,
+    ScoreReport, SelfUpdateResult, ServeEvent, SignResult, SignatureStatus, SkipReason,
+    StateChange, TrialResult, UninstallResult, UpstreamResult, UpstreamStatus, VerifyEvent,
+    VerifyResult,
 };
 use anyhow::Result;
 use colored::*;
-use dialoguer::{Select, theme::ColorfulTheme};
-use indicatif::{ProgressBar, ProgressStyle};
+use console::{Key, Term};
+use dialoguer::{Confirm, Input, MultiSelect, Select, theme::ColorfulTheme};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressFinish, ProgressStyle};
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
 
 // -- GENERAL UI FUNCTIONS --
 
+/// Send the engine's tracing spans to stderr (`-v` for operations, `-vv` for every step,
+/// `-vvv` for everything) and, if given, to `log_file` at debug level or above.
+/// Each span is logged when it closes, with how long it took.
+pub fn init_logging(verbose: u8, log_file: Option<&Path>) -> Result<()> {
+    let level = match verbose {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_filter(level);
+
+    let file = match log_file {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(std::sync::Mutex::new(file))
+                .with_ansi(false)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_filter(level.max(LevelFilter::DEBUG));
+            Some(layer)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .try_init()?;
+    Ok(())
+}
+
 /// Display a standard error message
 pub fn print_error(msg: &str) {
-    println!("{} {}", "Error:".red(), msg);
+    println!("{} {}", t!("label-error").red(), msg);
 }
 
 /// Display a standard warning message
 pub fn print_warning(msg: &str) {
-    println!("{} {}", "Warning:".yellow(), msg);
+    println!("{} {}", t!("label-warning").yellow(), msg);
+}
+
+/// Shown after Ctrl-C stopped an operation
+pub fn print_interrupted() {
+    println!(
+        "{} {}",
+        t!("label-interrupted").yellow(),
+        t!("interrupted-left-as-is")
+    );
 }
 
 /// Display a standard success message
 pub fn print_success(msg: &str) {
-    println!("{} {}", "Success:".green(), msg);
+    println!("{} {}", t!("label-success").green(), msg);
 }
 
 // -- INTERNAL HELPERS --
@@ -32,21 +97,247 @@ fn make_progress_bar(total_bytes: u64) -> ProgressBar {
     let pb = ProgressBar::new(total_bytes);
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}")
             .unwrap()
             .progress_chars("#>-"),
     );
-    pb
+    // Only reached if the bar is dropped mid-download (e.g. on Ctrl-C)
+    pb.with_finish(ProgressFinish::AbandonWithMessage(
+        t!("progress-interrupted").into(),
+    ))
+}
+
+/// Whether `--progress` comes out as plain lines: asked for, or on `auto` when stderr
+/// (where bars are drawn) isn't a terminal or the `CI` variable is set, as CI services do
+pub fn plain_progress(mode: ProgressMode) -> bool {
+    match mode {
+        ProgressMode::Bar => false,
+        ProgressMode::Plain => true,
+        ProgressMode::Auto => {
+            !std::io::stderr().is_terminal()
+                || std::env::var("CI").is_ok_and(|ci| !ci.is_empty() && ci != "false" && ci != "0")
+        }
+    }
+}
+
+/// Percentage points between the lines of a plain meter
+const PLAIN_STEP: u64 = 10;
+
+/// How far a download or a batch of checks is: a bar redrawn in place, or in plain mode
+/// a line on stderr each `PLAIN_STEP` percent, with no control characters for CI logs
+enum Meter {
+    Bar(ProgressBar),
+    Plain(PlainMeter),
+}
+
+/// A `Meter` in plain mode
+struct PlainMeter {
+    /// What is being downloaded, if that isn't clear from the line above
+    label: Option<String>,
+    /// Whether the total is in bytes, rather than a count
+    bytes: bool,
+    position: u64,
+    /// 0 if unknown
+    total: u64,
+    /// The last percentage printed
+    printed: u64,
+    finished: bool,
+}
+
+impl Meter {
+    /// `bar()` for a terminal; in plain mode, a line-printing meter for `total`
+    fn new(
+        plain: bool,
+        label: Option<String>,
+        total: u64,
+        bytes: bool,
+        bar: impl FnOnce() -> ProgressBar,
+    ) -> Self {
+        if !plain {
+            let bar = bar();
+            if let Some(label) = label {
+                bar.set_message(label);
+            }
+            return Meter::Bar(bar);
+        }
+        Meter::Plain(PlainMeter {
+            label,
+            bytes,
+            position: 0,
+            total,
+            printed: 0,
+            finished: false,
+        })
+    }
+
+    fn inc(&mut self, delta: u64) {
+        match self {
+            Meter::Bar(bar) => bar.inc(delta),
+            Meter::Plain(meter) => {
+                meter.position += delta;
+                if meter.total == 0 {
+                    return;
+                }
+                let percent = (meter.position * 100 / meter.total).min(100);
+                let step = percent / PLAIN_STEP * PLAIN_STEP;
+                if step > meter.printed {
+                    meter.printed = step;
+                    eprintln!("{}", meter.line(Some(step)));
+                }
+            }
+        }
+    }
+
+    /// The bar's message (a package being checked); plain lines leave it out
+    fn set_message(&self, message: String) {
+        if let Meter::Bar(bar) = self {
+            bar.set_message(message);
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        match self {
+            Meter::Bar(bar) => bar.position() == bar.length().unwrap_or(0),
+            Meter::Plain(meter) => meter.position >= meter.total,
+        }
+    }
+
+    /// Take the bar down, or leave it with `message`; a plain meter prints its last line
+    /// if it hasn't, e.g. when the total wasn't known
+    fn finish(self, message: Option<String>) {
+        match self {
+            Meter::Bar(bar) => match message {
+                Some(message) => bar.finish_with_message(message),
+                None => bar.finish_and_clear(),
+            },
+            Meter::Plain(mut meter) => {
+                meter.finished = true;
+                if meter.printed < 100 {
+                    let percent = (meter.total > 0).then_some(100);
+                    eprintln!("{}", meter.line(percent));
+                }
+            }
+        }
+    }
+}
+
+impl PlainMeter {
+    /// Helper: "ripgrep:  40% (1.20 MiB/3.00 MiB)", or without a percentage, how much so far
+    fn line(&self, percent: Option<u64>) -> String {
+        let amount = |n: u64| {
+            if self.bytes {
+                HumanBytes(n).to_string()
+            } else {
+                n.to_string()
+            }
+        };
+        let progress = match percent {
+            Some(percent) => format!(
+                "{:>3}% ({}/{})",
+                percent,
+                amount(self.position.min(self.total)),
+                amount(self.total)
+            ),
+            None => amount(self.position),
+        };
+        match &self.label {
+            Some(label) => format!("{}: {}", label, progress),
+            None => progress,
+        }
+    }
+}
+
+impl Drop for PlainMeter {
+    fn drop(&mut self) {
+        // As a bar is abandoned mid-download (e.g. on Ctrl-C)
+        if !self.finished && self.position < self.total {
+            let line = self.line(None);
+            eprintln!("{} {}", line, t!("progress-interrupted"));
+        }
+    }
+}
+
+// -- REPORTER --
+
+/// Renders the progress of every engine operation in the terminal.
+/// Each kind of event is handed to its `create_*_handler`, built on first use.
+/// The default draws bars; `new` picks them or plain lines as `--progress` says.
+#[derive(Default)]
+pub struct TerminalReporter {
+    plain: bool,
+    progress: Option<Box<dyn FnMut(ProgressEvent)>>,
+    bump: Option<Box<dyn FnMut(BumpEvent)>>,
+    refresh: Option<Box<dyn FnMut(RefreshEvent)>>,
+    verify: Option<Box<dyn FnMut(VerifyEvent)>>,
+    link_check: Option<Box<dyn FnMut(LinkCheckEvent)>>,
+    serve: Option<Box<dyn FnMut(ServeEvent)>>,
+}
+
+impl TerminalReporter {
+    pub fn new(progress: ProgressMode) -> Self {
+        TerminalReporter {
+            plain: plain_progress(progress),
+            ..Default::default()
+        }
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn progress(&mut self, event: ProgressEvent) {
+        let plain = self.plain;
+        self.progress
+            .get_or_insert_with(|| Box::new(create_progress_handler(plain)))(event)
+    }
+
+    fn bump(&mut self, event: BumpEvent) {
+        let plain = self.plain;
+        self.bump
+            .get_or_insert_with(|| Box::new(create_bump_handler(plain)))(event)
+    }
+
+    fn refresh(&mut self, event: RefreshEvent) {
+        let plain = self.plain;
+        self.refresh
+            .get_or_insert_with(|| Box::new(create_refresh_handler(plain)))(event)
+    }
+
+    fn verify(&mut self, event: VerifyEvent) {
+        let plain = self.plain;
+        self.verify
+            .get_or_insert_with(|| Box::new(create_verify_handler(plain)))(event)
+    }
+
+    fn link_check(&mut self, event: LinkCheckEvent) {
+        let plain = self.plain;
+        self.link_check
+            .get_or_insert_with(|| Box::new(create_link_check_handler(plain)))(event)
+    }
+
+    fn serve(&mut self, event: ServeEvent) {
+        self.serve
+            .get_or_insert_with(|| Box::new(create_serve_handler()))(event)
+    }
+
+    fn hook(&mut self, event: HookEvent) {
+        print_hook_result(&event)
+    }
+
+    fn step(&mut self, step: &PlannedInstall) {
+        match &step.from {
+            Some(from) => print_upgrade_start(&step.package, from, &step.to),
+            None => print_install_start(&step.package, &step.to),
+        }
+    }
 }
 
 // -- LIST FUNCTIONS --
 
 /// Display the list of installed packages given
 pub fn print_installed_packages(packages: &HashMap<String, InstalledPackage>) {
-    println!("{}", "Installed Packages:".bold());
+    println!("{}", t!("list-title").bold());
 
     if packages.is_empty() {
-        println!("   (No packages installed)");
+        println!("   {}", t!("list-empty"));
     } else {
         // We might want to sort them for consistent display
         let mut sorted_keys: Vec<_> = packages.keys().collect();
@@ -54,95 +345,1256 @@ pub fn print_installed_packages(packages: &HashMap<String, InstalledPackage>) {
 
         for name in sorted_keys {
             let pkg = &packages[name];
-            println!(" - {} (v{})", name.bold(), pkg.version);
+            let mut notes = Vec::new();
+            if pkg.pinned {
+                notes.push(t!("list-pinned"));
+            } else if let Some(constraint) = &pkg.constraint {
+                notes.push(t!("list-constrained", constraint = constraint.as_str()));
+            }
+            if pkg.dependency {
+                notes.push(t!("list-dependency"));
+            }
+            if notes.is_empty() {
+                println!(" - {} (v{})", name.bold(), pkg.version);
+            } else {
+                let notes = format!("({})", notes.join(", "));
+                println!(" - {} (v{}) {}", name.bold(), pkg.version, notes.dimmed());
+            }
+        }
+    }
+}
+
+/// `rush list --porcelain`: `name\tversion\tbinaries` (comma-separated), sorted by name
+pub fn print_installed_porcelain(packages: &HashMap<String, InstalledPackage>) {
+    let mut names: Vec<_> = packages.keys().collect();
+    names.sort();
+    for name in names {
+        let pkg = &packages[name];
+        print_porcelain(&[name, &pkg.version, &pkg.binaries.join(",")]);
+    }
+}
+
+/// `rush info`: installed version and binaries, the registry's version, details and
+/// targets with their artifacts (`target`, the one installs are for, marked), and (with
+/// `provenance`) where the installed binary came from
+pub fn print_package_info(info: &PackageInfo, provenance: bool, target: &str) {
+    println!("{}", info.name.bold());
+    if let Some(manifest) = &info.available {
+        if let Some(description) = &manifest.description {
+            println!("   {}", description);
         }
+        print_field(10, &t!("info-registry"), &format!("v{}", manifest.version));
+        for (label, value) in [
+            (t!("info-license"), &manifest.license),
+            (t!("info-homepage"), &manifest.homepage),
+        ] {
+            if let Some(value) = value {
+                print_field(10, &label, value);
+            }
+        }
+        println!("   {}:", t!("info-targets"));
+        let current = manifest.target(target).map(|(slug, _)| slug);
+        for (slug, artifact) in &manifest.targets {
+            if current == Some(slug.as_str()) {
+                let current = format!("{} ({})", slug, t!("target-current"));
+                println!("     {}", current.green().bold());
+            } else {
+                println!("     {}", slug);
+            }
+            let size = artifact.size.map(|size| HumanBytes(size).to_string());
+            for (label, value) in [
+                (t!("info-url"), Some(&artifact.url)),
+                (t!("info-sha256"), Some(&artifact.sha256)),
+                (t!("info-size"), size.as_ref()),
+            ] {
+                if let Some(value) = value {
+                    println!("        {:<7} {}", format!("{}:", label), value.dimmed());
+                }
+            }
+        }
+        if current.is_none() {
+            print_warning(&t!(
+                "info-no-target",
+                package = info.name.as_str(),
+                target = target
+            ));
+        }
+    } else {
+        print_field(
+            10,
+            &t!("info-registry"),
+            &t!("info-not-in-registry").dimmed(),
+        );
+    }
+
+    let Some(installed) = &info.installed else {
+        print_field(
+            10,
+            &t!("info-installed"),
+            &t!("info-not-installed").dimmed(),
+        );
+        return;
+    };
+    print_field(
+        10,
+        &t!("info-installed"),
+        &format!("v{} ({})", installed.version, installed.binaries.join(", ")),
+    );
+    if !installed.files.is_empty() {
+        print_field(
+            10,
+            &t!("info-files"),
+            &t!("info-files-count", count = installed.files.len()),
+        );
+    }
+    if !provenance {
+        return;
+    }
+
+    println!("{}", t!("info-provenance").bold());
+    let Some(p) = &installed.provenance else {
+        println!("   {}", t!("info-provenance-unrecorded").dimmed());
+        return;
+    };
+    print_field(13, &t!("info-installed-at"), &p.installed_at);
+    print_field(13, &t!("info-artifact"), &p.url);
+    print_field(13, &t!("info-sha256"), &p.sha256);
+    match &p.registry {
+        Some(registry) => {
+            print_field(13, &t!("info-registry"), &registry.source);
+            if let Some(revision) = &registry.revision {
+                print_field(13, &t!("info-revision"), revision);
+            }
+            print_field(
+                13,
+                &t!("info-manifest"),
+                &format!("sha256 {}", registry.manifest_sha256),
+            );
+            let signature = match registry.signature {
+                SignatureStatus::Verified => registry.signature.to_string().green(),
+                SignatureStatus::Invalid => registry.signature.to_string().red(),
+                _ => registry.signature.to_string().yellow(),
+            };
+            print_field(13, &t!("info-signature"), &signature);
+        }
+        None => print_field(
+            13,
+            &t!("info-registry"),
+            &t!("info-registry-unlisted").yellow(),
+        ),
+    }
+}
+
+/// Helper: An indented `label: value` line, the label padded to `width` so values line up
+fn print_field(width: usize, label: &str, value: &dyn std::fmt::Display) {
+    println!("   {:<width$} {}", format!("{}:", label), value);
+}
+
+/// A package the registry (or the command line) doesn't have
+pub fn print_package_not_found(name: &str) {
+    print_error(&t!("package-not-found", package = name));
+}
+
+/// A version of a package the registry doesn't offer, and the ones it does
+pub fn print_version_not_found(name: &str, version: &str, manifest: &PackageManifest) {
+    let versions: Vec<&str> = manifest.releases().map(|(v, _)| v).collect();
+    print_error(&t!(
+        "version-not-found",
+        package = name,
+        version = version,
+        versions = versions.join(", ")
+    ));
+}
+
+// -- SEARCH FUNCTIONS --
+
+/// `rush search`. Only packages with a build for `target` are shown, unless `all_targets`,
+/// which shows every package with the targets it has.
+pub fn print_available_packages(
+    packages: &[(String, PackageManifest)],
+    target: &str,
+    all_targets: bool,
+) {
+    println!("{} ({}):", t!("search-title").bold(), target);
+
+    if packages.is_empty() {
+        println!("   {}", t!("registry-empty"));
+        return;
+    }
+
+    for (name, manifest) in packages {
+        let compatible = manifest.target(target).is_some();
+        if all_targets {
+            let name = if compatible {
+                name.bold()
+            } else {
+                name.dimmed()
+            };
+            println!(
+                " - {} (v{}) [{}]",
+                name,
+                manifest.version,
+                target_list(manifest, target)
+            );
+        } else if compatible {
+            // The View decides to only show packages compatible with the current system
+            println!(" - {} (v{})", name.bold(), manifest.version);
+        }
+    }
+}
+
+/// Helper: A manifest's targets, `target` highlighted and marked as the current one
+fn target_list(manifest: &PackageManifest, target: &str) -> String {
+    manifest
+        .targets
+        .keys()
+        .map(|slug| {
+            if slug == target {
+                format!("{} ({})", slug, t!("target-current"))
+                    .green()
+                    .bold()
+                    .to_string()
+            } else {
+                slug.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Matches shown below the search box at once
+const FINDER_ROWS: usize = 12;
+
+/// `rush find`: narrow `entries` down as the user types, fzf-style (arrows move, enter
+/// picks, escape gives up). Returns the picked package's name.
+pub fn find_package(entries: &[FinderEntry], query: Option<&str>) -> Result<Option<String>> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        anyhow::bail!(t!("find-needs-terminal"));
+    }
+    if entries.is_empty() {
+        println!("   {}", t!("registry-empty"));
+        return Ok(None);
+    }
+
+    let mut query = query.unwrap_or_default().to_string();
+    let mut selected = 0;
+    let mut drawn = 0;
+    term.hide_cursor()?;
+    let picked = (|| -> Result<Option<String>> {
+        loop {
+            let matches = fuzzy_filter(entries, &query);
+            selected = selected.min(matches.len().saturating_sub(1));
+            term.clear_last_lines(drawn)?;
+            drawn = draw_finder(&term, &query, &matches, selected, entries.len())?;
+
+            match term.read_key()? {
+                Key::Enter if !matches.is_empty() => {
+                    return Ok(Some(matches[selected].name.clone()));
+                }
+                Key::Escape | Key::CtrlC => return Ok(None),
+                Key::ArrowUp => selected = selected.saturating_sub(1),
+                Key::ArrowDown if selected + 1 < matches.len() => selected += 1,
+                Key::Backspace => {
+                    query.pop();
+                    selected = 0;
+                }
+                Key::Char(c) if !c.is_control() => {
+                    query.push(c);
+                    selected = 0;
+                }
+                _ => {}
+            }
+        }
+    })();
+    // Whatever happened, leave the terminal as it was
+    let _ = term.clear_last_lines(drawn);
+    let _ = term.show_cursor();
+    picked
+}
+
+/// Helper: Draw the search box and the matches around `selected`; returns the lines drawn
+fn draw_finder(
+    term: &Term,
+    query: &str,
+    matches: &[&FinderEntry],
+    selected: usize,
+    total: usize,
+) -> Result<usize> {
+    let width = term.size().1 as usize;
+    term.write_line(&format!(
+        "{} {}{}  {}",
+        ">".cyan().bold(),
+        query,
+        "_".dimmed(),
+        format!("{}/{}", matches.len(), total).dimmed()
+    ))?;
+
+    // Scroll so the selection stays in view
+    let first = (selected + 1).saturating_sub(FINDER_ROWS);
+    for (i, entry) in matches.iter().enumerate().skip(first).take(FINDER_ROWS) {
+        let (marker, name) = if i == selected {
+            (">".cyan().bold(), entry.name.cyan().bold())
+        } else {
+            (" ".normal(), entry.name.bold())
+        };
+        let line = format!(
+            "{} {} {}  {}",
+            marker,
+            name,
+            entry.version.dimmed(),
+            entry.description.as_deref().unwrap_or_default()
+        );
+        term.write_line(&console::truncate_str(&line, width.saturating_sub(1), "…"))?;
+    }
+    Ok(1 + matches.len().saturating_sub(first).min(FINDER_ROWS))
+}
+
+/// `rush search --porcelain`: `name\tversion\tdescription` for each package with a build
+/// for `target`, in the order given; with `all_targets`, every package, with a fourth column of
+/// its comma-separated targets
+pub fn print_available_porcelain(
+    packages: &[(String, PackageManifest)],
+    target: &str,
+    all_targets: bool,
+) {
+    for (name, manifest) in packages {
+        let description = manifest.description.as_deref().unwrap_or_default();
+        if all_targets {
+            let targets: Vec<&str> = manifest.targets.keys().map(String::as_str).collect();
+            print_porcelain(&[name, &manifest.version, description, &targets.join(",")]);
+        } else if manifest.target(target).is_some() {
+            print_porcelain(&[name, &manifest.version, description]);
+        }
+    }
+}
+
+/// Helper: One `--porcelain` line. The columns only ever grow at the end; tabs and line
+/// breaks inside a field become spaces, so every line splits into the same columns.
+fn print_porcelain(fields: &[&str]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| field.replace(['\t', '\n', '\r'], " "))
+        .collect();
+    println!("{}", fields.join("\t"));
+}
+
+// -- UNINSTALL FUNCTIONS --
+
+/// Before uninstalling `name`: the installed packages that need it. Without `force`,
+/// the uninstall is refused.
+pub fn print_needed_by(name: &str, dependents: &[String], force: bool) {
+    let dependents = dependents.join(", ");
+    if force {
+        print_warning(&t!(
+            "uninstall-needed-by",
+            package = name,
+            dependents = dependents
+        ));
+    } else {
+        print_error(&t!(
+            "uninstall-needed-by-refused",
+            package = name,
+            dependents = dependents
+        ));
+    }
+}
+
+/// Display the result of an uninstall operation
+pub fn print_uninstall_result(result: &Option<UninstallResult>, requested_name: &str) {
+    if let Some(res) = result {
+        println!("{} {}...", t!("uninstall-start").cyan(), res.package_name);
+        for path in res.binaries_removed.iter().chain(&res.files_removed) {
+            println!(
+                "   - {}",
+                t!("uninstall-deleted", path = format!("{:?}", path))
+            );
+        }
+        print_success(&t!("uninstall-success"));
+    } else {
+        print_error(&t!("uninstall-not-installed", package = requested_name));
+    }
+}
+
+/// `rush man` couldn't pick one page: the package has none, none called `page`, or
+/// several and no `page` was given
+pub fn print_man_pages_error(name: &str, page: Option<&str>, pages: &[String]) {
+    match (pages.is_empty(), page) {
+        (true, _) => print_error(&t!("man-none", package = name)),
+        (false, Some(page)) => print_error(&t!("man-not-found", package = name, page = page)),
+        (false, None) => print_error(&t!("man-several", package = name)),
+    }
+    for page in pages {
+        println!("   - {}", page);
+    }
+}
+
+/// `rush pin`: the constraint now kept (or the pin, without one), or that the package
+/// isn't installed
+pub fn print_pin_result(pinned: bool, name: &str, constraint: Option<&str>) {
+    match (pinned, constraint) {
+        (true, Some(constraint)) => {
+            print_success(&t!("pin-success", package = name, constraint = constraint))
+        }
+        (true, None) => print_success(&t!("pin-held", package = name)),
+        (false, _) => print_error(&t!("uninstall-not-installed", package = name)),
+    }
+}
+
+/// `rush unpin`: upgrades may move the package again, or it isn't installed
+pub fn print_unpin_result(unpinned: bool, name: &str) {
+    if unpinned {
+        print_success(&t!("unpin-success", package = name));
+    } else {
+        print_error(&t!("uninstall-not-installed", package = name));
+    }
+}
+
+// -- CLEAN FUNCTIONS --
+
+/// Display the result of a cleaning operation
+pub fn print_clean_result(result: &CleanResult) {
+    if result.files_cleaned.is_empty() {
+        println!("{}", t!("clean-nothing").green());
+    } else {
+        for filename in &result.files_cleaned {
+            println!("{} {:?}", t!("clean-deleted").yellow(), filename);
+        }
+        print_success(&t!("clean-success", count = result.files_cleaned.len()));
+    }
+}
+
+/// What `clean --all` removed, or with `dry_run` would, each with its size, and the total
+pub fn print_clean_all_result(result: &CleanAllResult, dry_run: bool) {
+    if result.is_empty() {
+        println!("{}", t!("clean-nothing").green());
+        return;
+    }
+    if dry_run {
+        println!("{}", t!("clean-all-would").yellow());
+    } else {
+        println!("{}", t!("clean-all-removed").yellow());
+    }
+
+    for leftover in &result.leftovers {
+        let kind = match leftover.kind {
+            LeftoverKind::TempFile => t!("leftover-temp-file"),
+            LeftoverKind::Cache => t!("leftover-cache"),
+            LeftoverKind::OldVersion => t!("leftover-old-version"),
+            LeftoverKind::Staged => t!("leftover-staged"),
+        };
+        println!(
+            "   {} {:>10}  {}",
+            format!("{:<13}", kind).cyan(),
+            HumanBytes(leftover.bytes).to_string(),
+            leftover.path.display()
+        );
+    }
+    for package in &result.receipts {
+        println!(
+            "   {} {:>10}  {}",
+            format!("{:<13}", t!("leftover-receipt")).cyan(),
+            "",
+            package
+        );
+    }
+
+    let total = t!(
+        "clean-all-total",
+        count = result.leftovers.len() + result.receipts.len(),
+        size = HumanBytes(result.bytes()).to_string()
+    );
+    if dry_run {
+        println!("{}", total.bold());
+        println!("{}", t!("dry-run").yellow());
+    } else {
+        print_success(&total);
+    }
+}
+
+/// What went into a bundle, what was left out, and how to install from it
+pub fn print_bundle_result(result: &BundleResult) {
+    if !result.skipped.is_empty() {
+        print_warning(&t!("bundle-left-out", packages = result.skipped.join(", ")));
+    }
+    print_success(&t!(
+        "bundle-success",
+        count = result.lockfile.packages.len(),
+        path = result.path.display().to_string()
+    ));
+    println!(
+        "   {} {}",
+        t!("install-from-it"),
+        format!(
+            "tar xzf {} -C <dir> && RUSH_REGISTRY_URL=<dir> rush update",
+            result.path.display()
+        )
+        .dimmed()
+    );
+}
+
+/// Shell code that puts `bin_dir` on PATH, once: `rush env` prints it for the user's
+/// shell rc file, so removing that one line turns everything rush installed off
+pub fn print_env(bin_dir: &Path, shell: Option<clap_complete::Shell>) {
+    use clap_complete::Shell;
+
+    let dir = bin_dir.to_string_lossy();
+    // Single-quoted, in each shell's way of escaping a quote
+    let sh = dir.replace('\'', r"'\''");
+    let doubled = dir.replace('\'', "''");
+    match shell {
+        Some(Shell::Fish) => println!("fish_add_path --path '{}'", dir.replace('\'', r"\'")),
+        Some(Shell::PowerShell) => println!(
+            "$env:PATH = '{}' + [IO.Path]::PathSeparator + $env:PATH",
+            doubled
+        ),
+        Some(Shell::Elvish) => println!("set paths = ['{}' $@paths]", doubled),
+        _ => println!(
+            "case \":$PATH:\" in *':{}:'*) ;; *) export PATH='{}'\":$PATH\" ;; esac",
+            sh, sh
+        ),
+    }
+}
+
+/// `rush outdated`
+pub fn print_outdated(outdated: &[OutdatedPackage]) {
+    if outdated.is_empty() {
+        println!("{}", t!("outdated-none"));
+        return;
+    }
+    for package in outdated {
+        println!(
+            " - {} (v{} -> v{})",
+            package.package.bold(),
+            package.installed,
+            package.available.green()
+        );
+    }
+    println!(
+        "{}",
+        t!(
+            "outdated-run-upgrade",
+            command = "rush upgrade".bold().to_string()
+        )
+    );
+}
+
+/// `rush outdated --porcelain`: `name\tinstalled\tavailable`, sorted by name
+pub fn print_outdated_porcelain(outdated: &[OutdatedPackage]) {
+    for package in outdated {
+        print_porcelain(&[&package.package, &package.installed, &package.available]);
+    }
+}
+
+/// `rush registry stats`
+pub fn print_registry_stats(stats: &RegistryStats) {
+    println!("{} {}", t!("stats-packages").bold(), stats.packages);
+
+    println!("{}", t!("stats-targets").bold());
+    for coverage in &stats.targets {
+        let share = coverage.packages * 100 / stats.packages.max(1);
+        println!(
+            "   {:<16} {:>5} ({}%)",
+            coverage.target, coverage.packages, share
+        );
+    }
+
+    for (title, ages) in [
+        (t!("stats-newest"), &stats.newest),
+        (t!("stats-oldest"), &stats.oldest),
+    ] {
+        if !ages.is_empty() {
+            println!("{}", title.bold());
+            for age in ages {
+                println!("   {:<24} {}", age.package, age.changed_at.dimmed());
+            }
+        }
+    }
+
+    for (title, packages) in [
+        (t!("stats-missing-description"), &stats.missing_description),
+        (t!("stats-missing-size"), &stats.missing_size),
+        (t!("stats-unreadable"), &stats.unreadable),
+    ] {
+        if packages.is_empty() {
+            continue;
+        }
+        println!("{} ({}):", title.yellow().bold(), packages.len());
+        println!("   {}", packages.join(", "));
+    }
+}
+
+/// `rush outdated --notify`: a desktop notification, through `notify-send` on Linux or
+/// `osascript` on macOS. Best effort; a failure is only a warning.
+pub fn notify_outdated(outdated: &[OutdatedPackage]) {
+    let title = t!("notify-title", count = outdated.len());
+    let body = outdated
+        .iter()
+        .map(|p| format!("{} {}", p.package, p.available))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut command = if cfg!(target_os = "macos") {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut command = std::process::Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            quote(&body),
+            quote(&title)
+        ));
+        command
+    } else {
+        let mut command = std::process::Command::new("notify-send");
+        command.args(["--app-name=rush", &title, &body]);
+        command
+    };
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(_) | Err(_) => print_warning(&t!("notify-failed")),
+    }
+}
+
+/// `rush schedule install`
+pub fn print_schedule_installed(result: &ScheduleResult) {
+    print_success(&t!("schedule-installed"));
+    for file in &result.files {
+        println!("   {}", file.display());
+    }
+}
+
+/// `rush schedule remove`
+pub fn print_schedule_removed(result: &ScheduleResult) {
+    if result.files.is_empty() {
+        println!("{}", t!("schedule-none"));
+        return;
+    }
+    print_success(&t!("schedule-removed"));
+    for file in &result.files {
+        println!("   {}", file.display());
+    }
+}
+
+/// What `rush self-update` found, and did
+pub fn print_self_update(result: &SelfUpdateResult) {
+    match &result.updated {
+        Some(path) => print_success(&t!(
+            "self-update-updated",
+            current = result.current.as_str(),
+            latest = result.latest.bold().to_string(),
+            path = path.display().to_string()
+        )),
+        None if !result.newer => println!(
+            "{}",
+            t!(
+                "self-update-up-to-date",
+                current = result.current.as_str(),
+                latest = result.latest.as_str()
+            )
+        ),
+        None => println!(
+            "{}",
+            t!(
+                "self-update-available",
+                latest = result.latest.bold().to_string(),
+                current = result.current.as_str(),
+                command = "rush self-update".bold().to_string()
+            )
+        ),
+    }
+}
+
+/// `rush direnv`: a direnv stdlib extension. `watch_file` makes direnv reload when
+/// rush.toml changes, which fetches whatever was added to it.
+pub fn print_direnv() {
+    print!(
+        "# Generated by `rush direnv`. With this in ~/.config/direnv/lib/, `use rush` in an\n\
+         # .envrc puts the tools in the project's rush.toml first on PATH.\n\
+         use_rush() {{\n  \
+         watch_file rush.toml\n  \
+         eval \"$(rush env --project)\"\n\
+         }}\n"
+    );
+}
+
+/// Before `rush shell` hands over to the subshell
+pub fn print_project_shell(project: &ProjectEnv) {
+    let tools: Vec<String> = project
+        .tools
+        .iter()
+        .map(|(name, version)| format!("{} {}", name, version))
+        .collect();
+    println!(
+        "{} {}",
+        t!("shell-entering").green(),
+        t!(
+            "shell-entering-project",
+            root = project.root.display().to_string().bold().to_string(),
+            tools = if tools.is_empty() {
+                t!("shell-no-tools")
+            } else {
+                tools.join(", ")
+            },
+            command = "exit".bold().to_string()
+        )
+    );
+}
+
+/// `rush sync`: the project's tools, now in its bin dir
+pub fn print_project_synced(project: &ProjectEnv) {
+    for (name, version) in &project.tools {
+        println!(" - {} (v{})", name.bold(), version);
+    }
+    print_success(&t!(
+        "sync-success",
+        count = project.tools.len(),
+        path = project.bin_dir.display().to_string()
+    ));
+}
+
+/// `rush sync --check`: each tool's status, and what to run if any aren't ready
+pub fn print_project_check(check: &ProjectCheck) {
+    for tool in &check.tools {
+        let status = match tool.status {
+            ProjectToolStatus::Ready => t!("status-ok").green(),
+            ProjectToolStatus::Missing => t!("sync-check-missing").red(),
+            ProjectToolStatus::NotLinked => t!("sync-check-not-linked").red(),
+            ProjectToolStatus::Unavailable => t!("sync-check-unavailable").red(),
+        };
+        println!(" - {} (v{}): {}", tool.package.bold(), tool.version, status);
+    }
+    let failed = check.failed().count();
+    if failed == 0 {
+        print_success(&t!("sync-check-success"));
+    } else {
+        print_error(&t!(
+            "sync-check-failed",
+            failed = failed,
+            count = check.tools.len(),
+            command = "rush sync",
+            root = check.root.display().to_string()
+        ));
+    }
+}
+
+/// After an install, when the binaries it put in place can't be run by name
+/// The line that puts rush's bin dir on PATH each time `shell` starts (POSIX sh when
+/// unknown), and its startup file relative to the home directory. PowerShell's profile
+/// moves around, so it has none.
+pub fn shell_init(shell: Option<clap_complete::Shell>) -> (&'static str, Option<&'static str>) {
+    use clap_complete::Shell;
+
+    let posix = "eval \"$(rush env)\"";
+    match shell {
+        Some(Shell::Bash) => (posix, Some(".bashrc")),
+        Some(Shell::Zsh) => (posix, Some(".zshrc")),
+        Some(Shell::Fish) => (
+            "rush env --shell fish | source",
+            Some(".config/fish/config.fish"),
+        ),
+        Some(Shell::Elvish) => (
+            "eval (rush env --shell elvish | slurp)",
+            Some(".config/elvish/rc.elv"),
+        ),
+        Some(Shell::PowerShell) => (
+            "rush env --shell powershell | Out-String | Invoke-Expression",
+            None,
+        ),
+        _ => (posix, Some(".profile")),
+    }
+}
+
+/// The bin dir isn't on PATH: the `line` that fixes it, and where it goes
+pub fn print_path_hint(bin_dir: &Path, line: &str, rc_file: Option<&str>) {
+    let path = bin_dir.display().to_string();
+    let line = line.bold().to_string();
+    match rc_file {
+        Some(rc_file) => print_warning(&t!(
+            "path-hint",
+            path = path,
+            line = line,
+            file = format!("~/{}", rc_file),
+            command = "rush init".bold().to_string()
+        )),
+        None => print_warning(&t!("path-hint-profile", path = path, line = line)),
+    }
+}
+
+/// Ask whether to add the PATH line to `rc_file` now
+pub fn confirm_init(rc_file: &str) -> Result<bool> {
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("prompt-init", file = format!("~/{}", rc_file)))
+        .default(true)
+        .interact()?)
+}
+
+/// `rush init`: whether `line` was added, and how to use it right away
+pub fn print_init_result(result: &InitResult, line: &str) {
+    let file = result.rc_file.display().to_string();
+    if result.added {
+        print_success(&t!(
+            "init-added",
+            line = line.bold().to_string(),
+            file = file
+        ));
+        println!("   {}", t!("init-new-shell"));
+    } else {
+        println!("{}", t!("init-already", file = file));
+    }
+}
+
+/// `rush init` for a shell whose startup file it doesn't know
+pub fn print_init_manual(line: &str) {
+    println!("{}", t!("init-manual", line = line.bold().to_string()));
+}
+
+/// What `rush export` left out, and where it wrote. With no `out` the export itself went
+/// to stdout, so this goes to stderr to keep it clean.
+pub fn print_export_result(export: &ExportResult, out: Option<&Path>) {
+    for skip in &export.skipped {
+        eprintln!(
+            "{} {}",
+            t!("label-warning").yellow(),
+            t!(
+                "export-left-out",
+                package = skip.package.bold().to_string(),
+                reason = skip.reason.to_string()
+            )
+        );
+    }
+    if let Some(out) = out {
+        print_success(&t!(
+            "export-success",
+            count = export.packages.len(),
+            path = out.display().to_string()
+        ));
+    }
+}
+
+/// What `rush mirror` fetched and failed to fetch, and how to use the mirror
+pub fn print_mirror_result(result: &MirrorResult) {
+    for fail in &result.failures {
+        println!(
+            " {} {} ({})\n    └─ {}",
+            t!("label-failed").red(),
+            fail.package.bold(),
+            fail.target.yellow(),
+            fail.error.red()
+        );
+    }
+    if !result.unsupported.is_empty() {
+        println!(
+            "{}",
+            t!("mirror-unsupported", count = result.unsupported.len()).dimmed()
+        );
+    }
+
+    let summary = t!(
+        "mirror-summary",
+        count = result.packages,
+        path = result.dir.display().to_string(),
+        downloaded = result.downloaded,
+        reused = result.reused
+    );
+    if result.failures.is_empty() {
+        print_success(&summary);
+    } else {
+        print_error(&format!(
+            "{} {}",
+            summary,
+            t!("mirror-failures", count = result.failures.len())
+        ));
+    }
+    println!(
+        "   {} {}",
+        t!("install-from-it"),
+        format!("RUSH_REGISTRY_URL={} rush update", result.dir.display()).dimmed()
+    );
+}
+
+/// Show each vulnerable package's advisories, then what couldn't be checked
+pub fn print_audit_result(result: &AuditResult) {
+    for audit in result.vulnerable() {
+        println!(
+            "{} v{}: {}",
+            audit.package.bold(),
+            audit.version,
+            t!("audit-known", count = audit.vulnerabilities.len())
+        );
+        for vuln in &audit.vulnerabilities {
+            let severity = vuln.severity.clone().unwrap_or_else(|| t!("audit-unrated"));
+            let fix = if vuln.fixed.is_empty() {
+                t!("audit-no-fix").red().to_string()
+            } else {
+                t!("audit-fixed-in", versions = vuln.fixed.join(", "))
+                    .green()
+                    .to_string()
+            };
+            println!("   {} [{}] {}", vuln.id.red(), severity, fix);
+            if let Some(summary) = &vuln.summary {
+                println!("      {}", summary.dimmed());
+            }
+            if !vuln.aliases.is_empty() {
+                println!(
+                    "      {}",
+                    t!("audit-aka", aliases = vuln.aliases.join(", ")).dimmed()
+                );
+            }
+        }
+    }
+
+    if !result.unchecked.is_empty() {
+        print_warning(&t!(
+            "audit-unchecked",
+            packages = result.unchecked.join(", ")
+        ));
+    }
+    let vulnerable = result.vulnerable().count();
+    if vulnerable == 0 {
+        print_success(&t!("audit-clean", count = result.packages.len()));
+    } else {
+        print_error(&t!(
+            "audit-vulnerable",
+            vulnerable = vulnerable,
+            count = result.packages.len()
+        ));
+    }
+}
+
+pub fn print_upstream_result(result: &UpstreamResult) {
+    println!("{} v{}", result.package.bold(), result.version);
+    for check in &result.targets {
+        let (status, checksum_url) = match &check.status {
+            UpstreamStatus::Match { checksum_url } => {
+                (t!("upstream-match").green(), Some(checksum_url))
+            }
+            UpstreamStatus::Mismatch {
+                checksum_url,
+                upstream,
+            } => (
+                t!(
+                    "upstream-mismatch",
+                    registry = check.sha256.as_str(),
+                    upstream = upstream.as_str()
+                )
+                .red(),
+                Some(checksum_url),
+            ),
+            UpstreamStatus::Failed {
+                checksum_url,
+                error,
+            } => (error.red(), Some(checksum_url)),
+            UpstreamStatus::NoChecksumFile => (t!("upstream-no-checksum-file").dimmed(), None),
+        };
+        println!(" - {}: {}", check.target, status);
+        if let Some(url) = checksum_url {
+            println!("    └─ {}", url.dimmed());
+        }
+    }
+
+    let failed = result.failed().count();
+    let checked = result
+        .targets
+        .iter()
+        .filter(|t| t.status != UpstreamStatus::NoChecksumFile)
+        .count();
+    if failed > 0 {
+        print_error(&t!("upstream-failed", failed = failed, count = checked));
+    } else if checked == 0 {
+        print_warning(&t!("upstream-nothing-checked"));
+    } else {
+        print_success(&t!("upstream-success", count = checked));
+    }
+}
+
+// -- DRY RUN FUNCTIONS --
+
+/// Display what a `--dry-run` operation would have done
+pub fn print_dry_run(changes: &ChangeSet) {
+    println!("{}", t!("dry-run").yellow());
+
+    if changes.is_empty() {
+        println!("   {}", t!("dry-run-nothing"));
+        return;
+    }
+
+    for change in &changes.state {
+        let line = match change {
+            StateChange::Installed { package, version } => t!(
+                "dry-run-install",
+                package = package.bold().to_string(),
+                version = version.as_str()
+            ),
+            StateChange::Upgraded { package, from, to } => t!(
+                "dry-run-upgrade",
+                package = package.bold().to_string(),
+                from = from.as_str(),
+                to = to.as_str()
+            ),
+            StateChange::Removed { package, version } => t!(
+                "dry-run-remove",
+                package = package.bold().to_string(),
+                version = version.as_str()
+            ),
+        };
+        println!(" - {}", line);
+    }
+    for url in &changes.downloads {
+        println!("   {} {}", t!("dry-run-download").cyan(), url);
+    }
+    for path in &changes.writes {
+        println!("   {} {}", t!("dry-run-write").green(), path.display());
+    }
+    for path in &changes.deletes {
+        println!("   {} {}", t!("dry-run-delete").red(), path.display());
+    }
+    for hook in &changes.hooks {
+        println!(
+            "   {} {}",
+            t!("dry-run-run").yellow(),
+            t!(
+                "dry-run-hook",
+                point = hook.point.to_string(),
+                package = hook.package.as_str()
+            )
+        );
     }
 }
 
-// -- SEARCH FUNCTIONS --
+// --- INSTALLATION FUNCTIONS ---
 
-/// Display the list of available packages given
-pub fn print_available_packages(packages: &[(String, PackageManifest)], target: &str) {
-    println!("{} ({}):", "Available Packages".bold(), target);
+pub fn print_install_start(name: &str, version: &str) {
+    println!("{} {} (v{})...", t!("install-start").cyan(), name, version);
+}
 
-    if packages.is_empty() {
-        println!("   (Registry empty or not found. Run 'rush update')");
+/// Show how a `[hooks]` command went; its stderr only matters when it failed
+pub fn print_hook_result(event: &HookEvent) {
+    if event.succeeded() {
+        println!(
+            "   {} {}",
+            t!("hook-label", point = event.point.to_string()).dimmed(),
+            t!("status-ok").green()
+        );
         return;
     }
+    let status = match event.exit_code {
+        Some(code) => t!("exited-with-status", code = code),
+        None => t!("hook-unfinished"),
+    };
+    print_warning(&t!(
+        "hook-failed",
+        point = event.point.to_string(),
+        status = status,
+        command = event.command.as_str()
+    ));
+    for line in event.stderr.lines() {
+        println!("   {}", line.dimmed());
+    }
+}
 
-    for (name, manifest) in packages {
-        // The View decides to only show packages compatible with the current system
-        if manifest.targets.contains_key(target) {
-            println!(" - {} (v{})", name.bold(), manifest.version);
-        }
+/// Show how a package's sandboxed `check` went; its output only matters when it failed
+pub fn print_check_result(result: &CheckResult) {
+    if result.succeeded() {
+        println!(
+            "   {} {}",
+            t!("check-label").dimmed(),
+            t!("status-ok").green()
+        );
+        return;
+    }
+    let status = match (result.timed_out, result.exit_code) {
+        (true, _) => t!("check-timed-out"),
+        (false, Some(code)) => t!("exited-with-status", code = code),
+        (false, None) => t!("check-killed"),
+    };
+    print_warning(&t!(
+        "check-failed",
+        status = status,
+        command = result.command.as_str()
+    ));
+    for line in result.stdout.lines().chain(result.stderr.lines()) {
+        println!("   {}", line.dimmed());
     }
 }
 
-// -- UNINSTALL FUNCTIONS --
+/// When a package's `check` couldn't be run at all
+pub fn print_check_not_run(name: &str, error: &anyhow::Error) {
+    print_warning(&t!(
+        "check-not-run",
+        package = name,
+        error = error.to_string()
+    ));
+}
 
-/// Display the result of an uninstall operation
-pub fn print_uninstall_result(result: &Option<UninstallResult>, requested_name: &str) {
-    if let Some(res) = result {
-        println!("{} {}...", "Uninstalling".cyan(), res.package_name);
-        for binary in &res.binaries_removed {
-            println!("   - Deleted {:?}", binary);
-        }
-        print_success("Success: Uninstalled");
-    } else {
-        print_error(&format!("Package '{}' is not installed", requested_name));
+/// Warn about system requirements that look unmet; the install goes ahead regardless
+pub fn print_requirement_warnings(warnings: &[RequirementWarning]) {
+    for warning in warnings {
+        print_warning(&t!(
+            "requirement-unmet",
+            package = warning.package.as_str(),
+            requirement = warning.requirement.to_string(),
+            problem = warning.problem.to_string()
+        ));
     }
 }
 
-// -- CLEAN FUNCTIONS --
+pub fn print_install_success(path: &std::path::Path) {
+    print_success(&t!("install-success", path = format!("{:?}", path)));
+}
 
-/// Display the result of a cleaning operation
-pub fn print_clean_result(result: &CleanResult) {
-    if result.files_cleaned.is_empty() {
-        println!("{}", "No trash found. System is clean.".green());
-    } else {
-        for filename in &result.files_cleaned {
-            println!("{} {:?}", "Deleted trash:".yellow(), filename);
+/// Show how one package's install or upgrade came out
+pub fn print_install_outcome(outcome: &InstallOutcome) {
+    match outcome {
+        InstallOutcome::Installed(result) => {
+            print_install_success(&result.path);
+            print_staged(result);
         }
-        print_success(&format!(
-            "Cleaned {} temporary files.",
-            result.files_cleaned.len()
-        ));
+        InstallOutcome::AlreadyInstalled { package, .. } => {
+            print_warning(&t!("install-already-installed", package = package.as_str()))
+        }
+        InstallOutcome::UpToDate { package, version } => println!(
+            "{}",
+            t!(
+                "install-up-to-date",
+                package = package.as_str(),
+                version = version.as_str()
+            )
+        ),
+        InstallOutcome::Skipped { package, reason } => print_warning(&t!(
+            "install-skipped",
+            package = package.as_str(),
+            reason = skip_reason(reason)
+        )),
     }
 }
 
-// --- INSTALLATION FUNCTIONS ---
-
-pub fn print_install_start(name: &str, version: &str) {
-    println!("{} {} (v{})...", "Installing".cyan(), name, version);
+/// Helper: If the old binary was running, that the new one waits until the next rush run
+fn print_staged(result: &InstallResult) {
+    if result.staged {
+        print_warning(&t!(
+            "install-staged",
+            package = result.package_name.as_str(),
+            path = format!("{:?}", result.path)
+        ));
+    }
 }
 
-pub fn print_install_success(path: &std::path::Path) {
-    print_success(&format!("Installed to {:?}", path));
+/// Helper: Why a package was left alone, in words
+fn skip_reason(reason: &SkipReason) -> String {
+    match reason {
+        SkipReason::AlreadyInstalled { version } => {
+            t!("skip-already-installed", version = version.as_str())
+        }
+        SkipReason::UpToDate { version } => t!("skip-up-to-date", version = version.as_str()),
+        SkipReason::NotInRegistry => t!("skip-not-in-registry"),
+        SkipReason::NoCompatibleTarget { target } => {
+            t!("skip-no-compatible-target", target = target.as_str())
+        }
+        SkipReason::Held { constraint, latest } => t!(
+            "skip-held",
+            constraint = constraint.as_str(),
+            latest = latest.as_str()
+        ),
+        SkipReason::BadConstraint { constraint, error } => t!(
+            "skip-bad-constraint",
+            constraint = constraint.as_str(),
+            error = error.as_str()
+        ),
+        SkipReason::BinaryClash { bin, owner } => t!(
+            "skip-binary-clash",
+            bin = bin.as_str(),
+            owner = owner.as_str()
+        ),
+        SkipReason::DataFileClash { file, owner } => t!(
+            "skip-data-file-clash",
+            file = file.as_str(),
+            owner = owner.as_str()
+        ),
+        SkipReason::Pinned { latest } => t!("skip-pinned", latest = latest.as_str()),
+        SkipReason::UnmetDependency { dependency } => {
+            t!("skip-unmet-dependency", dependency = dependency.as_str())
+        }
+        SkipReason::DependencyCycle { cycle } => {
+            t!("skip-dependency-cycle", cycle = cycle.join(" -> "))
+        }
+    }
 }
 
-/// Factory: Creates a closure that handles InstallEvents and updates the progress bar
-pub fn create_install_handler() -> impl FnMut(InstallEvent) {
-    let mut pb: Option<ProgressBar> = None;
+/// Factory: Creates a closure that handles ProgressEvents (installs, registry updates, and
+/// dev downloads). Each operation gets its own bar, so concurrent ones render side by side;
+/// with `plain`, their lines are labelled with the package instead.
+pub fn create_progress_handler(plain: bool) -> impl FnMut(ProgressEvent) {
+    let multi = MultiProgress::new();
+    let mut bars: HashMap<OperationId, Meter> = HashMap::new();
 
-    move |event: InstallEvent| match event {
-        InstallEvent::Downloading { total_bytes } => {
-            pb = Some(make_progress_bar(total_bytes));
+    move |event: ProgressEvent| match event.kind {
+        ProgressKind::FetchingRegistry { source } => {
+            println!(
+                "{} {}...",
+                t!("registry-fetching").cyan(),
+                t!("registry-fetching-from", source = source)
+            );
+        }
+        ProgressKind::Downloading { total_bytes } => {
+            let bar = Meter::new(plain, event.package, total_bytes, true, || {
+                multi.add(make_progress_bar(total_bytes))
+            });
+            bars.insert(event.operation, bar);
+        }
+        ProgressKind::Progress { bytes, total } => {
+            bars.entry(event.operation)
+                .or_insert_with(|| {
+                    Meter::new(plain, event.package, total, true, || {
+                        multi.add(make_progress_bar(total))
+                    })
+                })
+                .inc(bytes);
         }
-        InstallEvent::Progress { bytes, total: _ } => {
-            if let Some(bar) = &pb {
-                bar.inc(bytes);
+        ProgressKind::VerifyingChecksum => {
+            if let Some(bar) = bars.remove(&event.operation) {
+                bar.finish(None);
             }
+            println!("{}", t!("checksum-verifying").cyan());
         }
-        InstallEvent::VerifyingChecksum => {
-            if let Some(bar) = &pb {
-                bar.finish_and_clear();
+        ProgressKind::Unpacking => {
+            if let Some(bar) = bars.remove(&event.operation) {
+                bar.finish(Some(t!("download-complete")));
             }
-            println!("{}", "Verifying checksum...".cyan());
         }
-        InstallEvent::Success => {
-            print_success("Checksum verified.");
+        ProgressKind::Success => {
+            print_success(&t!("checksum-verified"));
         }
-        _ => {}
+        ProgressKind::Summary(summary) => {
+            println!("{}", install_summary(&summary).dimmed());
+        }
+        ProgressKind::Extracting => {}
+    }
+}
+
+/// Helper: "Downloaded 1.20 MiB in 0.4s, verified in 3ms, installed in 12ms"
+fn install_summary(summary: &InstallSummary) -> String {
+    let fetched = if summary.downloaded_bytes == 0 {
+        t!("summary-local")
+    } else {
+        t!(
+            "summary-downloaded",
+            size = HumanBytes(summary.downloaded_bytes).to_string(),
+            time = short_duration(summary.download_time)
+        )
+    };
+    t!(
+        "summary",
+        fetched = fetched,
+        verify = short_duration(summary.verify_time),
+        install = short_duration(summary.install_time)
+    )
+}
+
+/// Helper: Milliseconds under a second, tenths of a second above
+fn short_duration(duration: std::time::Duration) -> String {
+    if duration.as_millis() < 1000 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.1}s", duration.as_secs_f64())
     }
 }
 
@@ -150,73 +1602,353 @@ pub fn create_install_handler() -> impl FnMut(InstallEvent) {
 
 /// Display the successful result of an update operation
 pub fn print_update_success(source: &str) {
-    print_success(&format!("Registry updated from {}.", source));
+    print_success(&t!("registry-updated", source = source));
 }
 
-/// Factory: Creates a closure that handles UpdateEvents
-pub fn create_update_handler() -> impl FnMut(UpdateEvent) {
-    let mut pb: Option<ProgressBar> = None;
+/// Warn about artifacts re-published under a version rush had already seen
+pub fn print_hash_changes(changes: &[&HashChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    println!(
+        "{} {}",
+        t!("label-warning-loud").red().bold(),
+        t!("hash-changes", count = changes.len())
+    );
+    for change in changes {
+        println!(
+            " - {} (v{}, {}): {} -> {}",
+            change.package.bold(),
+            change.version,
+            change.target,
+            change.known.dimmed(),
+            change.now.red()
+        );
+        println!("   {}", change.url.dimmed());
+    }
+    println!(
+        "{}",
+        t!(
+            "hash-changes-accept",
+            command = "`rush update --accept-changed <package>`"
+        )
+    );
+}
 
-    move |event: UpdateEvent| match event {
-        UpdateEvent::Fetching { source } => {
-            println!("{} from {}...", "Fetching registry".cyan(), source);
-        }
-        UpdateEvent::Progress { bytes, total } => {
-            let bar = pb.get_or_insert_with(|| make_progress_bar(total));
-            bar.inc(bytes);
-        }
-        UpdateEvent::Unpacking => {
-            if let Some(bar) = pb.take() {
-                bar.finish_with_message("Download complete");
-            }
-        }
+pub fn print_accepted_hash_changes(accepted: &[HashChange]) {
+    for change in accepted {
+        print_success(&t!(
+            "hash-change-accepted",
+            package = change.package.as_str(),
+            version = change.version.as_str(),
+            target = change.target.as_str()
+        ));
     }
 }
 
 // --- UPGRADE UI ---
 
 pub fn print_upgrade_check() {
-    println!("{}", "Checking for upgrades...".cyan());
+    println!("{}", t!("upgrade-checking").cyan());
 }
 
 pub fn print_upgrade_start(name: &str, old_v: &str, new_v: &str) {
     println!(
         "{} {} (v{} -> v{})...",
-        "Upgrading".cyan(),
+        t!("upgrade-start").cyan(),
         name,
         old_v,
         new_v
     );
 }
 
-pub fn print_upgrade_summary(count: usize) {
-    print_success(&format!("{} packages upgraded.", count));
+/// Mention anything the upgrade had to skip, then how many packages were upgraded
+pub fn print_upgrade_summary(outcomes: &[InstallOutcome]) {
+    for outcome in outcomes {
+        match outcome {
+            InstallOutcome::Skipped { .. } => print_install_outcome(outcome),
+            InstallOutcome::Installed(result) => print_staged(result),
+            _ => {}
+        }
+    }
+    let count = outcomes.iter().filter(|o| o.is_installed()).count();
+    print_success(&t!("upgrade-success", count = count));
 }
 
 // --- DEV / WIZARD UI ---
 
 pub fn print_fetching_msg(url: &str) {
-    println!("{} {}", "Fetching and hashing:".cyan(), url);
+    println!("{} {}", t!("dev-fetching").cyan(), url);
+}
+
+pub fn print_checksum_source(asset_name: &str, source: ChecksumSource) {
+    match source {
+        ChecksumSource::Upstream => {
+            println!("{} {}", t!("dev-upstream-checksum").cyan(), asset_name)
+        }
+        ChecksumSource::UpstreamVerified => {
+            print_success(&t!("dev-upstream-checksum-verified", asset = asset_name))
+        }
+        ChecksumSource::Computed => {}
+    }
+}
+
+pub fn print_detected_binary(bin_name: &str) {
+    println!(
+        "{} '{}' {}",
+        t!("dev-using-binary").cyan(),
+        bin_name.bold(),
+        t!("dev-using-binary-override", flag = "--bin")
+    );
 }
 
 pub fn print_dev_add_success(name: &str) {
-    print_success(&format!("Added {} to local registry.", name));
+    print_success(&t!("dev-added", package = name));
 }
 
 pub fn print_fetching_metadata(repo: &str) {
-    println!("{} metadata for {}...", "Fetching".cyan(), repo);
+    println!(
+        "{} {}...",
+        t!("dev-fetching-metadata").cyan(),
+        t!("dev-fetching-metadata-for", repo = repo)
+    );
+}
+
+pub fn print_rate_limit_wait(wait: std::time::Duration) {
+    let secs = wait.as_secs();
+    print_warning(&t!(
+        "dev-rate-limited",
+        minutes = secs / 60,
+        seconds = secs % 60
+    ));
 }
 
 pub fn print_found_release(version: &str) {
-    println!("Found Release: {}", version.green());
+    println!("{} {}", t!("dev-found-release"), version.green());
+}
+
+/// `dev import` found nothing to pick from
+pub fn print_no_releases(repo: &str) {
+    print_error(&t!("dev-no-releases", repo = repo));
 }
 
 pub fn print_wizard_complete() {
-    println!("{}", "Import wizard complete.".green());
+    println!("{}", t!("dev-wizard-complete").green());
 }
 
 pub fn print_skipping_target(target: &str) {
-    println!("Skipping {}", target);
+    println!("{}", t!("dev-skipping-target", target = target));
+}
+
+/// Interactive Prompt: Asks the user which archive entry is the binary.
+/// Returns Ok(None) if the user skips (or there is nothing to pick).
+pub fn prompt_select_binary(preview: &AssetPreview) -> Result<Option<usize>> {
+    if preview.entries.is_empty() {
+        print_warning(&t!("prompt-archive-empty"));
+        return Ok(None);
+    }
+
+    let mut menu_items: Vec<String> = preview
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let mut item = entry.path.clone();
+            if entry.executable {
+                item.push_str(&format!(" {}", t!("prompt-executable").dimmed()));
+            }
+            if preview.suggested == Some(i) {
+                item.push_str(&format!(" {}", t!("prompt-recommended")));
+            }
+            item
+        })
+        .collect();
+    menu_items.push(t!("prompt-skip-target"));
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("prompt-which-binary"))
+        .default(preview.suggested.unwrap_or(0))
+        .items(&menu_items)
+        .max_length(15)
+        .interact()?;
+
+    if selection == menu_items.len() - 1 {
+        Ok(None)
+    } else {
+        Ok(Some(selection))
+    }
+}
+
+/// Interactive Prompt: The `dev new` wizard.
+/// Inputs are validated as they are typed; artifacts are not downloaded here.
+pub fn prompt_new_package(targets: &[(&str, &str)]) -> Result<NewPackage> {
+    let theme = ColorfulTheme::default();
+    let optional = |value: String| (!value.trim().is_empty()).then(|| value.trim().to_string());
+
+    let name: String = Input::with_theme(&theme)
+        .with_prompt(t!("prompt-package-name"))
+        .validate_with(|input: &String| NewPackage::validate_name(input))
+        .interact_text()?;
+    let version: String = Input::with_theme(&theme)
+        .with_prompt(t!("prompt-version"))
+        .validate_with(|input: &String| NewPackage::validate_version(input))
+        .interact_text()?;
+    let description: String = Input::with_theme(&theme)
+        .with_prompt(t!("prompt-description"))
+        .allow_empty(true)
+        .interact_text()?;
+    let license: String = Input::with_theme(&theme)
+        .with_prompt(t!("prompt-license"))
+        .allow_empty(true)
+        .interact_text()?;
+    let homepage: String = Input::with_theme(&theme)
+        .with_prompt(t!("prompt-homepage"))
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            if input.is_empty() {
+                Ok(())
+            } else {
+                NewPackage::validate_homepage(input)
+            }
+        })
+        .interact_text()?;
+
+    let labels: Vec<String> = targets
+        .iter()
+        .map(|(slug, desc)| format!("{} ({})", slug, desc))
+        .collect();
+    let chosen = loop {
+        let chosen = MultiSelect::with_theme(&theme)
+            .with_prompt(t!("prompt-targets"))
+            .items(&labels)
+            .interact()?;
+        if !chosen.is_empty() {
+            break chosen;
+        }
+        print_warning(&t!("prompt-pick-a-target"));
+    };
+
+    let mut new_targets = Vec::new();
+    for i in chosen {
+        let (slug, _) = targets[i];
+        let url: String = Input::with_theme(&theme)
+            .with_prompt(t!("prompt-target-url", target = slug))
+            .interact_text()?;
+        let bin: String = Input::with_theme(&theme)
+            .with_prompt(t!("prompt-target-bin", target = slug))
+            .allow_empty(true)
+            .interact_text()?;
+        new_targets.push(NewTarget {
+            target: slug.to_string(),
+            url: url.trim().to_string(),
+            bin: optional(bin),
+        });
+    }
+
+    Ok(NewPackage {
+        name,
+        version,
+        description: optional(description),
+        license: optional(license),
+        homepage: optional(homepage),
+        targets: new_targets,
+    })
+}
+
+pub fn print_keygen_result(result: &KeygenResult) {
+    print_success(&t!(
+        "keygen-success",
+        path = result.key_path.display().to_string()
+    ));
+    println!("  {} {}", t!("keygen-public-key"), result.public_key.bold());
+    println!("  {}", t!("keygen-keep-private").dimmed());
+}
+
+/// `rush dev test`: what the trial install put in place, and how its check went
+pub fn print_trial_result(result: &TrialResult) {
+    println!(
+        "{} {} v{} ({})",
+        t!("trial-installed").cyan(),
+        result.package.bold(),
+        result.version,
+        result.target
+    );
+    for path in result.binaries.iter().chain(&result.files) {
+        println!("   - {}", path);
+    }
+    match (&result.check, &result.check_skipped) {
+        (Some(check), _) => print_check_result(check),
+        (None, Some(reason)) => print_warning(&t!(
+            "check-not-run",
+            package = result.package.as_str(),
+            error = reason.as_str()
+        )),
+        (None, None) => println!("   {}", t!("trial-no-check").dimmed()),
+    }
+    if result.succeeded() {
+        print_success(&t!("trial-success", package = result.package.as_str()));
+    } else {
+        print_error(&t!("trial-failed", package = result.package.as_str()));
+    }
+}
+
+pub fn print_sign_result(result: &SignResult) {
+    print_success(&t!(
+        "sign-success",
+        count = result.packages_signed,
+        path = result.index_path.display().to_string()
+    ));
+    println!(
+        "  {:<11} {}",
+        format!("{}:", t!("sign-signature")),
+        result.signature_path.display()
+    );
+    println!(
+        "  {} {}",
+        t!("keygen-public-key"),
+        result.public_key.dimmed()
+    );
+}
+
+pub fn print_new_package_success(name: &str, path: &std::path::Path) {
+    print_success(&t!(
+        "new-package-success",
+        package = name,
+        path = path.display().to_string()
+    ));
+}
+
+/// Interactive Prompt: Asks the user which release to import.
+/// Returns Ok(None) if there is nothing to choose from.
+pub fn prompt_select_release(releases: &[GitHubRelease]) -> Result<Option<usize>> {
+    if releases.is_empty() {
+        return Ok(None);
+    }
+
+    let menu_items: Vec<String> = releases
+        .iter()
+        .map(|r| {
+            let date = r
+                .published_at
+                .as_deref()
+                .and_then(|d| d.get(..10))
+                .map(str::to_string)
+                .unwrap_or_else(|| t!("prompt-unpublished"));
+            let mut item = format!("{} ({})", r.tag_name, date);
+            if r.is_prerelease() {
+                item.push_str(&format!(" {}", t!("prompt-prerelease").yellow().bold()));
+            }
+            item
+        })
+        .collect();
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("prompt-select-release"))
+        .default(0)
+        .items(&menu_items)
+        .interact()?;
+
+    Ok(Some(selection))
 }
 
 /// Interactive Prompt: Asks the user to select an asset from a list.
@@ -229,7 +1961,7 @@ pub fn prompt_select_asset(candidate: &ImportCandidate) -> Result<Option<usize>>
         .iter()
         .map(|scored| {
             if scored.score > 0 {
-                format!("{} (Recommended)", scored.asset.name)
+                format!("{} {}", scored.asset.name, t!("prompt-recommended"))
             } else {
                 scored.asset.name.clone()
             }
@@ -237,11 +1969,14 @@ pub fn prompt_select_asset(candidate: &ImportCandidate) -> Result<Option<usize>>
         .collect();
 
     // 2. Add the "Skip" option
-    menu_items.push("Skip this target".to_string());
+    menu_items.push(t!("prompt-skip-target"));
 
     // 3. Render the menu
     let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt(format!("Select asset for {}", candidate.target_desc.bold()))
+        .with_prompt(t!(
+            "prompt-select-asset",
+            target = candidate.target_desc.bold().to_string()
+        ))
         .default(0)
         .items(&menu_items)
         .interact()?;
@@ -256,46 +1991,146 @@ pub fn prompt_select_asset(candidate: &ImportCandidate) -> Result<Option<usize>>
 
 // --- VERIFY UI ---
 
+pub fn print_score_report(report: &ScoreReport) {
+    println!(
+        "{} {} ({})",
+        report.repo.bold(),
+        report.tag,
+        t!("score-minimum", score = report.min_score)
+    );
+
+    for target in &report.targets {
+        println!("\n{}", target.target_slug.bold().underline());
+        for (i, asset) in target.assets.iter().enumerate() {
+            let score = format!("{:>5}", asset.score);
+            let score = if asset.score >= report.min_score {
+                score.green()
+            } else if asset.score >= 0 {
+                score.yellow()
+            } else {
+                score.red()
+            };
+            let marker = if i == 0 && asset.score >= report.min_score {
+                format!(" {}", t!("prompt-recommended"))
+            } else {
+                String::new()
+            };
+            println!("{}  {}{}", score, asset.name, marker);
+
+            for factor in &asset.factors {
+                println!(
+                    "{}",
+                    format!("         {:+4}  {}", factor.points, factor.reason).dimmed()
+                );
+            }
+        }
+    }
+}
+
+pub fn print_dist_import_result(result: &DistImportResult) {
+    println!("{} {}", t!("dev-found-release"), result.version.green());
+    for target in &result.imported {
+        println!("  {} {}", t!("status-imported").green(), target);
+    }
+    for (target, reason) in &result.skipped {
+        println!("  {} {}: {}", t!("status-skipped").yellow(), target, reason);
+    }
+
+    if result.imported.is_empty() {
+        print_error(&t!(
+            "import-nothing",
+            package = result.package_name.as_str()
+        ));
+    } else {
+        print_success(&t!(
+            "import-success",
+            count = result.imported.len(),
+            package = result.package_name.as_str(),
+            version = result.version.as_str()
+        ));
+    }
+}
+
+pub fn print_lint_summary(result: &LintResult) {
+    for issue in &result.issues {
+        let label = match issue.severity {
+            LintSeverity::Error => t!("lint-error").red(),
+            LintSeverity::Warning => t!("lint-warning").yellow(),
+        };
+        println!(
+            " {} {}: {}",
+            label,
+            issue.package_name.bold(),
+            issue.message
+        );
+    }
+
+    let errors = result.error_count();
+    let warnings = result.issues.len() - errors;
+
+    if errors == 0 {
+        print_success(&t!(
+            "lint-success",
+            count = result.packages_checked,
+            warnings = warnings
+        ));
+    } else {
+        print_error(&t!(
+            "lint-failed",
+            errors = errors,
+            warnings = warnings,
+            count = result.packages_checked
+        ));
+    }
+}
+
 pub fn print_verify_start() {
-    println!("{}", "Verifying registry integrity...".cyan());
+    println!("{}", t!("verify-start").cyan());
 }
 
 /// Factory: Creates a closure that handles VerifyEvents
-pub fn create_verify_handler() -> impl FnMut(VerifyEvent) {
-    let mut pb: Option<ProgressBar> = None;
+pub fn create_verify_handler(plain: bool) -> impl FnMut(VerifyEvent) {
+    let mut pb: Option<Meter> = None;
 
     move |event: VerifyEvent| match event {
         VerifyEvent::Checking { name, target } => {
             // Clear previous bar if it existed (though Success usually handles it)
-            if let Some(bar) = &pb {
-                bar.finish_and_clear();
+            if let Some(bar) = pb.take() {
+                bar.finish(None);
             }
             // Print the line: "Checking package (target)..."
             // We use print! (no newline) so the progress bar can appear on the same line or below
             // Actually, simplest is just println! and let the bar appear below.
-            println!("{} {} ({})", "Checking".blue(), name, target.dimmed());
-        }
-        VerifyEvent::Progress(install_event) => {
-            // Reuse logic similar to install handler
-            match install_event {
-                InstallEvent::Downloading { total_bytes } => {
-                    let b = ProgressBar::new(total_bytes);
-                    b.set_style(
-                        ProgressStyle::default_bar()
-                            .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                            .unwrap()
-                            .progress_chars("#>-"),
-                    );
-                    pb = Some(b);
+            println!(
+                "{} {} ({})",
+                t!("verify-checking").blue(),
+                name,
+                target.dimmed()
+            );
+        }
+        VerifyEvent::Progress(progress_event) => {
+            // Reuse logic similar to the progress handler
+            match progress_event.kind {
+                ProgressKind::Downloading { total_bytes } => {
+                    pb = Some(Meter::new(plain, None, total_bytes, true, || {
+                        let b = ProgressBar::new(total_bytes);
+                        b.set_style(
+                            ProgressStyle::default_bar()
+                                .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+                                .unwrap()
+                                .progress_chars("#>-"),
+                        );
+                        b
+                    }));
                 }
-                InstallEvent::Progress { bytes, total: _ } => {
-                    if let Some(bar) = &pb {
+                ProgressKind::Progress { bytes, total: _ } => {
+                    if let Some(bar) = &mut pb {
                         bar.inc(bytes);
                     }
                 }
-                InstallEvent::Success => {
-                    if let Some(bar) = &pb {
-                        bar.finish_and_clear();
+                ProgressKind::Success => {
+                    if let Some(bar) = pb.take() {
+                        bar.finish(None);
                     }
                 }
                 // We can ignore VerifyingChecksum text here to keep it cleaner
@@ -305,21 +2140,184 @@ pub fn create_verify_handler() -> impl FnMut(VerifyEvent) {
     }
 }
 
-pub fn print_verify_summary(result: &VerifyResult) {
+/// Factory: Prints each target being re-hashed with a download progress bar
+pub fn create_bump_handler(plain: bool) -> impl FnMut(BumpEvent) {
+    let mut progress_handler = create_progress_handler(plain);
+
+    move |event: BumpEvent| match event {
+        BumpEvent::Downloading { target, url } => {
+            println!(
+                "{} {} ({})",
+                t!("bump-hashing").blue(),
+                url,
+                target.dimmed()
+            );
+        }
+        BumpEvent::Progress(progress_event) => progress_handler(progress_event),
+    }
+}
+
+pub fn print_bump_result(result: &BumpResult) {
+    if result.old_version == result.new_version {
+        print_success(&t!(
+            "bump-up-to-date",
+            package = result.package_name.as_str(),
+            version = result.old_version.as_str()
+        ));
+        return;
+    }
+
+    for (target, reason) in &result.failures {
+        println!("  {} {}: {}", t!("status-failed").red(), target, reason);
+    }
+
     if result.failures.is_empty() {
-        print_success(&format!(
-            "Verified {} packages ({} targets). All clean.",
-            result.packages_checked, result.targets_checked
+        print_success(&t!(
+            "bump-success",
+            package = result.package_name.as_str(),
+            from = result.old_version.as_str(),
+            to = result.new_version.as_str(),
+            count = result.updated.len()
         ));
     } else {
-        print_error(&format!(
-            "Verification failed for {} targets!",
-            result.failures.len()
+        print_error(&t!(
+            "bump-failed",
+            package = result.package_name.as_str(),
+            version = result.new_version.as_str()
+        ));
+    }
+}
+
+/// Factory: Counts packages as `dev refresh-all` checks them
+pub fn create_refresh_handler(plain: bool) -> impl FnMut(RefreshEvent) {
+    let mut pb: Option<Meter> = None;
+
+    move |event: RefreshEvent| match event {
+        RefreshEvent::Started { total } => {
+            println!(
+                "{} {}",
+                t!("verify-checking").cyan(),
+                t!("refresh-checking", count = total)
+            );
+            pb = Some(Meter::new(plain, None, total as u64, false, || {
+                let bar = ProgressBar::new(total as u64);
+                bar.set_style(
+                    ProgressStyle::default_bar()
+                        .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                        .unwrap()
+                        .progress_chars("#>-"),
+                );
+                bar
+            }));
+        }
+        RefreshEvent::Checked { name } => {
+            if let Some(bar) = &mut pb {
+                bar.set_message(name);
+                bar.inc(1);
+                if bar.is_done() {
+                    pb.take().unwrap().finish(None);
+                }
+            }
+        }
+    }
+}
+
+pub fn create_serve_handler() -> impl FnMut(ServeEvent) {
+    move |event: ServeEvent| match event {
+        ServeEvent::Listening {
+            registry_url,
+            artifacts_url,
+        } => {
+            println!(
+                "{} {}",
+                t!("serve-serving").green().bold(),
+                registry_url.bold()
+            );
+            println!("  {} {}", t!("serve-artifacts"), artifacts_url);
+            println!(
+                "  {} {}",
+                t!("serve-try"),
+                format!("RUSH_REGISTRY_URL={} rush update", registry_url).dimmed()
+            );
+            println!("{}", t!("serve-stop"));
+        }
+        ServeEvent::Request {
+            method,
+            path,
+            status,
+        } => {
+            let status = if status < 400 {
+                status.to_string().green()
+            } else {
+                status.to_string().red()
+            };
+            println!(" {} {} {}", status, method, path.dimmed());
+        }
+    }
+}
+
+pub fn print_refresh_summary(result: &RefreshResult) {
+    for bump in &result.bumped {
+        println!(
+            " {} {} {} → {}",
+            t!("status-bumped").green(),
+            bump.package_name.bold(),
+            bump.old_version.dimmed(),
+            bump.new_version.green()
+        );
+    }
+    for (name, reason) in &result.failed {
+        println!(" {} {}: {}", t!("status-failed").red(), name.bold(), reason);
+    }
+
+    let summary = t!(
+        "refresh-summary",
+        bumped = result.bumped.len(),
+        current = result.up_to_date.len(),
+        failed = result.failed.len()
+    );
+    if result.failed.is_empty() {
+        print_success(&summary);
+    } else {
+        print_error(&summary);
+    }
+}
+
+pub fn print_publish_result(result: &PublishResult) {
+    for package in &result.packages {
+        let change = match package.change {
+            PackageChange::Added => t!("status-added").green(),
+            PackageChange::Updated => t!("status-updated").yellow(),
+            PackageChange::Removed => t!("status-removed").red(),
+        };
+        println!(
+            " {} {} {}",
+            change,
+            package.name.bold(),
+            package.version.as_deref().unwrap_or_default().dimmed()
+        );
+    }
+    print_success(&t!(
+        "publish-success",
+        branch = result.branch.as_str(),
+        url = result.pr_url.as_str()
+    ));
+}
+
+pub fn print_verify_summary(result: &VerifyResult) {
+    if result.failures.is_empty() {
+        print_success(&t!(
+            "verify-success",
+            count = result.packages_checked,
+            targets = result.targets_checked
         ));
-        println!("{}", "Failures:".bold().underline());
+    } else {
+        print_error(&t!("verify-failed", count = result.failures.len()));
+        println!("{}", t!("verify-failures").bold().underline());
         for fail in &result.failures {
             println!(
-                " Error: {} v{} ({})\n    └─ {}",
+                " {} {} v{} ({})\n    └─ {}",
+                t!("label-error"),
                 fail.package_name.bold(),
                 fail.version,
                 fail.target.yellow(),
@@ -328,3 +2326,69 @@ pub fn print_verify_summary(result: &VerifyResult) {
         }
     }
 }
+
+// --- LINK CHECK UI ---
+
+/// Factory: Creates a closure that handles LinkCheckEvents
+pub fn create_link_check_handler(plain: bool) -> impl FnMut(LinkCheckEvent) {
+    let mut pb: Option<Meter> = None;
+
+    move |event: LinkCheckEvent| match event {
+        LinkCheckEvent::Started { total } => {
+            println!(
+                "{} {}",
+                t!("verify-checking").cyan(),
+                t!("links-checking", count = total)
+            );
+            pb = Some(Meter::new(plain, None, total as u64, false, || {
+                let bar = ProgressBar::new(total as u64);
+                bar.set_style(
+                    ProgressStyle::default_bar()
+                        .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len}")
+                        .unwrap()
+                        .progress_chars("#>-"),
+                );
+                bar
+            }));
+        }
+        LinkCheckEvent::Checked(_) => {
+            if let Some(bar) = &mut pb {
+                bar.inc(1);
+                if bar.is_done() {
+                    pb.take().unwrap().finish(None);
+                }
+            }
+        }
+    }
+}
+
+pub fn print_link_check_summary(result: &LinkCheckResult) {
+    let problems: Vec<_> = result.problems().collect();
+
+    if problems.is_empty() {
+        print_success(&t!("links-success", count = result.checks.len()));
+        return;
+    }
+
+    print_error(&t!(
+        "links-failed",
+        failed = problems.len(),
+        count = result.checks.len()
+    ));
+    for check in problems {
+        let detail = match &check.status {
+            LinkStatus::Redirected { location } => {
+                t!("links-moved", location = location.as_str()).yellow()
+            }
+            LinkStatus::Dead { reason } => reason.red(),
+            LinkStatus::Ok => continue,
+        };
+        println!(
+            " {} ({})\n    {}\n    └─ {}",
+            check.package_name.bold(),
+            check.target.yellow(),
+            check.url.dimmed(),
+            detail
+        );
+    }
+}
@@ -1,7 +1,6 @@
-mod common;
 use assert_cmd::Command;
-use common::MockEnvironment;
 use predicates::prelude::*;
+use rush::test_utils::MockEnvironment;
 
 #[test]
 fn test_binary_runs_help() {
@@ -50,6 +49,43 @@ fn test_clean_command_runs() {
         .stdout(predicate::str::contains("No trash found").or(predicate::str::contains("Cleaned")));
 }
 
+#[test]
+fn test_clean_all_lists_then_removes() {
+    let mock = MockEnvironment::new();
+    mock.add_package("kept", "1.0.0", "kept-bin");
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update.envs(mock.envs()).arg("update").assert().success();
+
+    let orphan = mock
+        .home
+        .join(".cache/rush/artifacts")
+        .join("ab".repeat(32));
+    std::fs::create_dir_all(orphan.parent().unwrap()).unwrap();
+    std::fs::write(&orphan, vec![0; 2048]).unwrap();
+
+    let mut dry_run = Command::new(env!("CARGO_BIN_EXE_rush"));
+    dry_run
+        .envs(mock.envs())
+        .args(["clean", "--all", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Would remove")
+                .and(predicate::str::contains(orphan.to_str().unwrap()))
+                .and(predicate::str::contains("1 item, 2.00 KiB")),
+        );
+    assert!(orphan.exists());
+
+    let mut clean = Command::new(env!("CARGO_BIN_EXE_rush"));
+    clean
+        .envs(mock.envs())
+        .args(["clean", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed"));
+    assert!(!orphan.exists());
+}
+
 #[test]
 fn test_security_checksum_mismatch() {
     let mock = MockEnvironment::new();
@@ -77,7 +113,7 @@ fn test_security_checksum_mismatch() {
         ));
 
     // 3. Verify it was NOT installed
-    assert!(!mock.home.join(".local/bin/malware").exists());
+    assert!(!mock.home.join(".local/share/rush/shims/malware").exists());
 }
 
 #[test]
@@ -128,7 +164,7 @@ fn test_upgrade_flow() {
     install_cmd.args(["install", "my-tool"]).assert().success();
 
     // Verify v1 output
-    let tool_path = mock.home.join(".local/bin/tool");
+    let tool_path = mock.home.join(".local/share/rush/shims/tool");
     let output_v1 = std::process::Command::new(&tool_path).output().unwrap();
     assert!(
         String::from_utf8(output_v1.stdout)
@@ -172,6 +208,29 @@ fn test_upgrade_flow() {
         .stdout(predicate::str::contains("my-tool").and(predicate::str::contains("v2.0.0")));
 }
 
+#[test]
+fn test_pin_holds_back_upgrade() {
+    let mock = MockEnvironment::new();
+    mock.add_package("my-tool", "1.0.0", "tool");
+    let rush = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+        cmd.envs(mock.envs()).args(args).assert().success()
+    };
+    rush(&["update"]);
+    rush(&["install", "my-tool"]);
+    rush(&["pin", "my-tool"]);
+    rush(&["list"]).stdout(predicate::str::contains("(pinned)"));
+
+    mock.add_package("my-tool", "2.0.0", "tool");
+    rush(&["update"]);
+    rush(&["upgrade"]).stdout(predicate::str::contains("pinned (v2.0.0 is out)"));
+    rush(&["list"]).stdout(predicate::str::contains("v1.0.0"));
+
+    rush(&["unpin", "my-tool"]);
+    rush(&["list"]).stdout(predicate::str::contains("pinned").not());
+    rush(&["upgrade"]).stdout(predicate::str::contains("v1.0.0 -> v2.0.0"));
+}
+
 #[test]
 fn test_install_already_installed() {
     let mock = MockEnvironment::new();
@@ -197,6 +256,46 @@ fn test_install_already_installed() {
         .stdout(predicate::str::contains("is already installed")); // Should warn
 }
 
+#[test]
+#[cfg(unix)]
+fn test_install_a_version_replaces_the_installed_one() {
+    let mock = MockEnvironment::new();
+    let manifest = mock.registry_source.join("packages/t/tool.toml");
+    mock.add_package("tool", "1.0.0", "tool");
+    let older = std::fs::read_to_string(&manifest).unwrap();
+    mock.add_package("tool", "2.0.0", "tool");
+    // 1.0.0 stays on offer as an older release
+    let history: String = older
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("description"))
+        .map(|line| line.replace("[targets.", "[history.targets."))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let current = std::fs::read_to_string(&manifest).unwrap();
+    std::fs::write(&manifest, format!("{}\n[[history]]{}\n", current, history)).unwrap();
+
+    let rush = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+        cmd.envs(mock.envs()).args(args).assert().success()
+    };
+    rush(&["update"]);
+    rush(&["install", "tool"]);
+    rush(&["pin", "tool"]);
+
+    rush(&["install", "tool@1.0.0"]).stdout(predicate::str::contains("is already installed").not());
+    let bin = mock.home.join(".local/share/rush/shims/tool");
+    let mut tool = Command::new(&bin);
+    tool.assert().success().stdout("Hello from tool v1.0.0\n");
+    // Still pinned, now at the version asked for
+    rush(&["list"])
+        .stdout(predicate::str::contains("v1.0.0"))
+        .stdout(predicate::str::contains("(pinned)"));
+
+    // Asking again for the installed version changes nothing
+    rush(&["install", "tool@1.0.0"]).stdout(predicate::str::contains("is already installed"));
+}
+
 #[test]
 fn test_full_install_lifecycle() {
     // 1. Setup Mock Environment
@@ -226,7 +325,7 @@ fn test_full_install_lifecycle() {
         .success();
 
     // 4. Verify Binary Execution
-    let installed_bin = mock.home.join(".local/bin/dummy");
+    let installed_bin = mock.home.join(".local/share/rush/shims/dummy");
     assert!(
         installed_bin.exists(),
         "Binary was not installed to expected path"
@@ -269,6 +368,271 @@ fn test_full_install_lifecycle() {
         .stdout(predicate::str::contains("No packages installed"));
 }
 
+#[test]
+fn test_uninstall_refuses_a_needed_package() {
+    let mock = MockEnvironment::new();
+    mock.add_package("app", "1.0.0", "app");
+    mock.add_package("libfoo", "1.0.0", "libfoo");
+    mock.add_dependencies("app", &["libfoo"]);
+    let rush = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+        cmd.envs(mock.envs()).args(args).assert()
+    };
+    rush(&["update"]).success();
+    rush(&["install", "app"]).success();
+    rush(&["list"]).stdout(predicate::str::contains("libfoo"));
+
+    rush(&["uninstall", "libfoo"])
+        .failure()
+        .stdout(predicate::str::contains("libfoo is needed by app"));
+    assert!(mock.home.join(".local/share/rush/shims/libfoo").exists());
+
+    rush(&["uninstall", "libfoo", "--force"])
+        .success()
+        .stdout(predicate::str::contains("may stop working"));
+    assert!(!mock.home.join(".local/share/rush/shims/libfoo").exists());
+}
+
+#[test]
+fn test_install_from_local_manifest() {
+    let mock = MockEnvironment::new();
+    mock.add_package("local-tool", "1.0.0", "local-bin");
+    let manifest = mock.registry_source.join("packages/l/local-tool.toml");
+
+    // No `rush update`: the manifest file is all it needs
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs())
+        .arg("install")
+        .arg(&manifest)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("local-tool"));
+    assert!(mock.home.join(".local/share/rush/shims/local-bin").exists());
+
+    let mut missing = Command::new(env!("CARGO_BIN_EXE_rush"));
+    missing
+        .envs(mock.envs())
+        .args(["install", "./nope.toml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to read ./nope.toml"));
+}
+
+#[test]
+fn test_plain_progress_off_a_terminal() {
+    let mock = MockEnvironment::new();
+    mock.add_package("tool", "1.0.0", "tool-bin");
+    mock.add_package("other", "1.0.0", "other-bin");
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update.envs(mock.envs()).arg("update").assert().success();
+
+    // stderr is a pipe here, so `auto` prints lines rather than bars
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "tool"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("tool: 100%").and(predicate::str::contains("\r").not()));
+
+    let mut bars = Command::new(env!("CARGO_BIN_EXE_rush"));
+    bars.envs(mock.envs())
+        .args(["install", "other", "--progress", "bar"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("100%").not());
+}
+
+#[test]
+fn test_init_adds_the_path_line_once() {
+    let mock = MockEnvironment::new();
+    mock.add_package("tool", "1.0.0", "tool-bin");
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update.envs(mock.envs()).arg("update").assert().success();
+
+    // The install names the line and file for the user's shell
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .env("SHELL", "/usr/bin/fish")
+        .args(["install", "tool"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("rush env --shell fish | source")
+                .and(predicate::str::contains("~/.config/fish/config.fish"))
+                .and(predicate::str::contains("rush init")),
+        );
+
+    let config = mock.home.join(".config/fish/config.fish");
+    let mut init = Command::new(env!("CARGO_BIN_EXE_rush"));
+    init.envs(mock.envs())
+        .env("SHELL", "/usr/bin/fish")
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added"));
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("rush env --shell fish | source"));
+
+    let mut again = Command::new(env!("CARGO_BIN_EXE_rush"));
+    again
+        .envs(mock.envs())
+        .args(["init", "--shell", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already"));
+    assert_eq!(std::fs::read_to_string(&config).unwrap(), content);
+}
+
+#[test]
+fn test_dry_runs_change_nothing() {
+    let mock = MockEnvironment::new();
+    mock.add_package("planned", "1.0.0", "planned-bin");
+    let bin = mock.home.join(".local/share/rush/shims/planned-bin");
+
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update
+        .envs(mock.envs())
+        .args(["update", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run"));
+    assert!(
+        !mock
+            .home
+            .join(".local/share/rush/registry/packages")
+            .exists()
+    );
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "planned", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Would install planned (v1.0.0)")
+                .and(predicate::str::contains(bin.to_str().unwrap())),
+        );
+    assert!(!bin.exists());
+
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "planned"])
+        .assert()
+        .success();
+    mock.add_package("planned", "2.0.0", "planned-bin");
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let mut upgrade = Command::new(env!("CARGO_BIN_EXE_rush"));
+    upgrade
+        .envs(mock.envs())
+        .args(["upgrade", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would upgrade planned (v1.0.0 -> v2.0.0)",
+        ));
+
+    let mut uninstall = Command::new(env!("CARGO_BIN_EXE_rush"));
+    uninstall
+        .envs(mock.envs())
+        .args(["uninstall", "planned", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would remove planned (v1.0.0)"));
+    assert!(bin.exists());
+}
+
+#[test]
+fn test_verbose_logging() {
+    let mock = MockEnvironment::new();
+    mock.add_package("traced", "1.0.0", "traced-bin");
+    let log = mock.home.join("rush.log");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs())
+        .args(["update", "-v"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("update_registry"));
+
+    // Quiet on the terminal, but the log file still gets every step
+    // (bars, which stay hidden off a terminal, so stderr is only the log)
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "traced", "--progress", "bar", "--log-file"])
+        .arg(&log)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+
+    let log = std::fs::read_to_string(&log).unwrap();
+    for step in [
+        "install_from_bytes",
+        "download_url",
+        "verify_checksum",
+        "extract_binary",
+    ] {
+        assert!(log.contains(step), "no {} span in:\n{}", step, log);
+    }
+    assert!(log.contains("package=\"traced\""));
+    assert!(log.contains("time.busy"));
+}
+
+#[test]
+fn test_install_hooks() {
+    let mock = MockEnvironment::new();
+    mock.add_package("hooked", "1.0.0", "hooked-bin");
+    mock.add_package("vetoed", "1.0.0", "vetoed-bin");
+
+    let log = mock.home.join("hooks.log");
+    let config_dir = mock.home.join(".config/rush");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!(
+            "[hooks]\npre_install = \"test {{pkg}} != vetoed\"\npost_install = \"echo {{pkg}} {{version}} >> '{}'\"\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "hooked"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("post_install hook:"));
+    assert_eq!(std::fs::read_to_string(&log).unwrap(), "hooked 1.0.0\n");
+
+    // A failing pre_install hook stops the install
+    let mut vetoed = Command::new(env!("CARGO_BIN_EXE_rush"));
+    vetoed
+        .envs(mock.envs())
+        .args(["install", "vetoed"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("pre_install hook for 'vetoed'"));
+    assert!(
+        !mock
+            .home
+            .join(".local/share/rush/shims/vetoed-bin")
+            .exists()
+    );
+}
+
 #[test]
 fn test_dev_add_flow() {
     let mock = MockEnvironment::new();
@@ -308,6 +672,42 @@ fn test_dev_add_flow() {
     assert!(content.contains("x86_64-linux"));
 }
 
+#[test]
+fn test_dev_add_local_file_with_copy() {
+    let mock = MockEnvironment::new();
+
+    let dist = mock.home.join("dist");
+    std::fs::create_dir_all(&dist).unwrap();
+    std::fs::write(dist.join("tool.tar.gz"), "fake archive").unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs())
+        .current_dir(&mock.home)
+        .args([
+            "dev",
+            "add",
+            "tool",
+            "1.0.0",
+            "x86_64-linux",
+            "./dist/tool.tar.gz",
+            "--copy",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added tool"));
+
+    // The artifact was staged next to the packages, and the manifest points at the copy
+    let staged = mock
+        .registry_source
+        .join("artifacts/tool/1.0.0/tool.tar.gz");
+    assert!(staged.exists(), "Artifact was not copied");
+
+    let manifest =
+        std::fs::read_to_string(mock.registry_source.join("packages/t/tool.toml")).unwrap();
+    assert!(manifest.contains("artifacts/tool/1.0.0/tool.tar.gz"));
+    assert!(manifest.contains("file://"));
+}
+
 #[test]
 fn test_completions_generation() {
     let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
@@ -433,6 +833,286 @@ fn test_arch_mismatch() {
         .stdout(predicate::str::contains("No compatible binary for"));
 }
 
+#[test]
+fn test_target_override() {
+    let mock = MockEnvironment::new();
+    let toml_content = r#"
+        version = "1.0.0"
+        [targets.arm64-windows]
+        url = "http://ignore.me"
+        bin = "ignore.exe"
+        sha256 = "ignore"
+    "#;
+    let pkg_dir = mock.registry_source.join("packages/w");
+    std::fs::create_dir_all(&pkg_dir).unwrap();
+    std::fs::write(pkg_dir.join("win-tool.toml"), toml_content).unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    // Listed once the target is overridden, whatever the key's spelling
+    let mut search_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    search_cmd
+        .envs(mock.envs())
+        .env("RUSH_TARGET", "aarch64-windows")
+        .args(["search"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("win-tool"));
+
+    let mut bad_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    bad_cmd
+        .envs(mock.envs())
+        .env("RUSH_TARGET", "pdp11-unix")
+        .args(["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown target 'pdp11-unix'"));
+}
+
+#[test]
+fn test_porcelain_output() {
+    let mock = MockEnvironment::new();
+    mock.add_package("piped", "1.0.0", "piped-bin");
+    mock.add_package("other", "3.1.0", "other");
+
+    let rush = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_rush"))
+            .envs(mock.envs())
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    rush(&["update"]);
+    rush(&["install", "piped"]);
+
+    assert_eq!(rush(&["list", "--porcelain"]), "piped\t1.0.0\tpiped-bin\n");
+
+    let search = rush(&["search", "--porcelain"]);
+    let rows: Vec<Vec<&str>> = search.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|row| row.len() == 3));
+    assert_eq!(rows[0][..2], ["other", "3.1.0"]);
+    assert_eq!(rows[1][..2], ["piped", "1.0.0"]);
+
+    assert_eq!(rush(&["outdated", "--porcelain"]), "");
+}
+
+#[test]
+fn test_outdated_json_exit_code() {
+    let mock = MockEnvironment::new();
+    mock.add_package("drifty", "1.0.0", "drifty");
+
+    let rush = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rush"))
+            .envs(mock.envs())
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    assert!(rush(&["update"]).status.success());
+    assert!(rush(&["install", "drifty"]).status.success());
+
+    let output = rush(&["outdated", "--json", "--exit-code"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "[]");
+
+    mock.add_package("drifty", "2.0.0", "drifty");
+    assert!(rush(&["update"]).status.success());
+    let output = rush(&["outdated", "--json", "--exit-code"]);
+    assert_eq!(output.status.code(), Some(10));
+    let outdated: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        outdated,
+        serde_json::json!([{"package": "drifty", "installed": "1.0.0", "available": "2.0.0"}])
+    );
+
+    // Without --exit-code, drift isn't a failure
+    assert!(rush(&["outdated"]).status.success());
+}
+
+#[test]
+fn test_registry_stats_json() {
+    let mock = MockEnvironment::new();
+    mock.add_package("alpha", "1.0.0", "alpha");
+    mock.add_package("beta", "1.0.0", "beta");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rush"))
+        .envs(mock.envs())
+        .args(["registry", "stats", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["packages"], 2);
+    assert_eq!(stats["targets"][0]["packages"], 2);
+    assert_eq!(stats["missing_size"], serde_json::json!(["alpha", "beta"]));
+}
+
+#[test]
+fn test_find_needs_a_terminal() {
+    let mock = MockEnvironment::new();
+    mock.add_package("finder", "1.0.0", "finder");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    // Under a test harness stderr isn't a terminal; nothing gets installed blindly
+    let mut find_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    find_cmd
+        .envs(mock.envs())
+        .args(["find", "fin"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("needs a terminal"));
+    assert!(!mock.home.join(".local/share/rush/shims/finder").exists());
+}
+
+#[test]
+fn test_run_without_installing() {
+    let mock = MockEnvironment::new();
+    mock.add_package("runner", "1.0.0", "runner");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    // Only the binary's own output reaches stdout
+    let mut run_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    run_cmd
+        .envs(mock.envs())
+        .args(["run", "runner@1.0.0", "--", "--ignored"])
+        .assert()
+        .success()
+        .stdout("Hello from runner v1.0.0\n");
+
+    assert!(!mock.home.join(".local/share/rush/shims/runner").exists());
+    let mut list_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    list_cmd
+        .envs(mock.envs())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("runner").not());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_shell_runs_with_project_tools() {
+    let mock = MockEnvironment::new();
+    mock.add_package("tool", "1.0.0", "tool");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let project = mock.home.join("project");
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(project.join("rush.toml"), "[tools]\ntool = \"1.0.0\"\n").unwrap();
+
+    let mut shell_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    shell_cmd
+        .envs(mock.envs())
+        .current_dir(project.join("src"))
+        .args(["shell", "--", "sh", "-c", "tool && echo \"$RUSH_PROJECT\""])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hello from tool v1.0.0"))
+        .stdout(predicate::str::contains(project.to_str().unwrap()));
+
+    // The project's tools aren't installed globally
+    assert!(!mock.home.join(".local/share/rush/shims/tool").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_sync_check_gates_on_project_tools() {
+    let mock = MockEnvironment::new();
+    mock.add_package("tool", "1.0.0", "tool");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let project = mock.home.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("rush.toml"), "[tools]\ntool = \"1.0.0\"\n").unwrap();
+
+    // Nothing fetched yet, and checking doesn't fetch it
+    let mut check_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    check_cmd
+        .envs(mock.envs())
+        .current_dir(&project)
+        .args(["sync", "--check"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("not fetched"));
+    assert!(!project.join(".rush").exists());
+
+    let mut sync_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    sync_cmd
+        .envs(mock.envs())
+        .current_dir(&project)
+        .args(["sync"])
+        .assert()
+        .success();
+
+    let mut check_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    check_cmd
+        .envs(mock.envs())
+        .current_dir(&project)
+        .args(["sync", "--check"])
+        .assert()
+        .success();
+
+    // A version bump in rush.toml fails the check until the next sync
+    std::fs::write(project.join("rush.toml"), "[tools]\ntool = \"2.0.0\"\n").unwrap();
+    let mut check_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    check_cmd
+        .envs(mock.envs())
+        .current_dir(&project)
+        .args(["sync", "--check"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_env_project_for_direnv() {
+    let mock = MockEnvironment::new();
+    mock.add_package("tool", "1.0.0", "tool");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs()).args(["update"]).assert().success();
+
+    let project = mock.home.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("rush.toml"), "[tools]\ntool = \"latest\"\n").unwrap();
+
+    // What `use rush` evals: only the PATH line, with the project's tools fetched
+    let bin_dir = project.join(".rush").join("bin");
+    let mut env_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    env_cmd
+        .envs(mock.envs())
+        .current_dir(&project)
+        .args(["env", "--project"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("case \":$PATH:\" in"))
+        .stdout(predicate::str::contains(bin_dir.to_str().unwrap()));
+    assert!(bin_dir.join("tool").is_file());
+
+    let mut direnv_cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    direnv_cmd
+        .envs(mock.envs())
+        .args(["direnv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("use_rush() {"))
+        .stdout(predicate::str::contains("rush env --project"));
+}
+
 #[test]
 fn test_dev_verify_failure() {
     let mock = MockEnvironment::new();
@@ -442,12 +1122,28 @@ fn test_dev_verify_failure() {
     let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
     cmd.envs(mock.envs());
 
-    cmd.args(["dev", "verify"])
+    cmd.args(["dev", "verify", "--deep"])
         .assert()
         .failure() // Must exit non-zero
         .stdout(predicate::str::contains("Verification failed"));
 }
 
+#[test]
+fn test_dev_verify_without_deep_skips_downloads() {
+    let mock = MockEnvironment::new();
+    // The hash is wrong, but only a deep verify downloads the artifact to notice
+    mock.add_malicious_package("bad-pkg", "1.0.0", "bin");
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs());
+
+    cmd.args(["dev", "verify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Linted 1 manifests"))
+        .stdout(predicate::str::contains("Verifying registry integrity").not());
+}
+
 #[test]
 fn test_dev_verify_success() {
     let mock = MockEnvironment::new();
@@ -457,8 +1153,281 @@ fn test_dev_verify_success() {
     let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
     cmd.envs(mock.envs());
 
-    cmd.args(["dev", "verify"])
+    cmd.args(["dev", "verify", "--deep"])
         .assert()
         .success()
         .stdout(predicate::str::contains("All clean"));
 }
+
+#[test]
+fn test_dev_verify_lints_broken_manifest() {
+    let mock = MockEnvironment::new();
+    mock.add_package("good-pkg", "1.0.0", "bin");
+
+    // A manifest missing its checksum would be silently skipped by search
+    let pkg_dir = mock.registry_source.join("packages/b");
+    std::fs::create_dir_all(&pkg_dir).unwrap();
+    std::fs::write(
+        pkg_dir.join("broken.toml"),
+        r#"
+        version = "1.0.0"
+        [targets.x86_64-linux]
+        url = "https://example.com/broken.tar.gz"
+        bin = "broken"
+        "#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+    cmd.envs(mock.envs());
+
+    cmd.args(["dev", "verify"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("broken").and(predicate::str::contains("sha256")))
+        .stdout(predicate::str::contains("Verification failed"));
+}
+
+#[test]
+fn test_audit_lists_unmapped_packages() {
+    let mock = MockEnvironment::new();
+    mock.add_package("plain", "1.0.0", "plain-bin");
+
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update.envs(mock.envs()).arg("update").assert().success();
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "plain"])
+        .assert()
+        .success();
+
+    // Without an [osv] table there is nothing to look up, so nothing goes to osv.dev
+    let mut audit = Command::new(env!("CARGO_BIN_EXE_rush"));
+    audit
+        .envs(mock.envs())
+        .arg("audit")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Not checked")
+                .and(predicate::str::contains("plain"))
+                .and(predicate::str::contains("0 checked packages")),
+        );
+}
+
+#[test]
+fn test_info_shows_provenance() {
+    let mock = MockEnvironment::new();
+    mock.add_package("traced", "1.0.0", "traced-bin");
+
+    let mut update = Command::new(env!("CARGO_BIN_EXE_rush"));
+    update.envs(mock.envs()).arg("update").assert().success();
+    let mut install = Command::new(env!("CARGO_BIN_EXE_rush"));
+    install
+        .envs(mock.envs())
+        .args(["install", "traced"])
+        .assert()
+        .success();
+
+    let mut info = Command::new(env!("CARGO_BIN_EXE_rush"));
+    info.envs(mock.envs())
+        .args(["info", "traced", "--provenance"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("installed: v1.0.0")
+                .and(predicate::str::contains("signature:    unsigned")),
+        );
+
+    let mut json = Command::new(env!("CARGO_BIN_EXE_rush"));
+    let output = json
+        .envs(mock.envs())
+        .args(["info", "traced", "--json"])
+        .output()
+        .unwrap();
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let provenance = &info["installed"]["provenance"];
+    assert_eq!(provenance["registry"]["signature"], "unsigned");
+    assert!(provenance["url"].as_str().unwrap().ends_with(".tar.gz"));
+
+    let mut missing = Command::new(env!("CARGO_BIN_EXE_rush"));
+    missing
+        .envs(mock.envs())
+        .args(["info", "ghost"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_search_and_info_show_targets() {
+    let mock = MockEnvironment::new();
+    let pkg_dir = mock.registry_source.join("packages/t");
+    std::fs::create_dir_all(&pkg_dir).unwrap();
+    let sha256 = "ab".repeat(32);
+    let both = format!(
+        r#"
+        version = "1.0.0"
+        description = "Linux and macOS"
+        [targets.x86_64-linux]
+        url = "https://example.com/tool-linux.tar.gz"
+        bin = "tool"
+        sha256 = "{sha256}"
+        size = 2048
+        [targets.aarch64-macos]
+        url = "https://example.com/tool-macos.tar.gz"
+        bin = "tool"
+        sha256 = "{sha256}"
+    "#
+    );
+    std::fs::write(pkg_dir.join("tool-both.toml"), both).unwrap();
+    let mac = format!(
+        r#"
+        version = "2.0.0"
+        description = "macOS only"
+        [targets.aarch64-macos]
+        url = "https://example.com/mac.tar.gz"
+        bin = "mac"
+        sha256 = "{sha256}"
+    "#
+    );
+    std::fs::write(pkg_dir.join("tool-mac.toml"), mac).unwrap();
+
+    let rush = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_rush"))
+            .envs(mock.envs())
+            .env("RUSH_TARGET", "x86_64-linux")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", args);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    rush(&["update"]);
+
+    // Every package, with its targets and this machine's marked
+    let search = rush(&["search"]);
+    assert!(search.contains("tool-both") && !search.contains("tool-mac"));
+    let search = rush(&["search", "--targets"]);
+    let line = |name: &str| {
+        search
+            .lines()
+            .find(|l| l.contains(name))
+            .unwrap()
+            .to_string()
+    };
+    assert!(line("tool-both").contains("x86_64-linux (current)"));
+    assert!(line("tool-both").contains("aarch64-macos"));
+    assert!(line("tool-mac").contains("aarch64-macos"));
+    assert!(!line("tool-mac").contains("current"));
+
+    let porcelain = rush(&["search", "--porcelain", "--targets"]);
+    let rows: Vec<Vec<&str>> = porcelain.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][0], "tool-both");
+    assert_eq!(rows[0][3], "aarch64-macos,x86_64-linux");
+    assert_eq!(rows[1][0], "tool-mac");
+    assert_eq!(rows[1][3], "aarch64-macos");
+
+    // Each target's artifact, this machine's marked
+    let info = rush(&["info", "tool-both"]);
+    assert!(info.contains("x86_64-linux (current)"), "{}", info);
+    assert!(info.contains("https://example.com/tool-linux.tar.gz"));
+    assert!(info.contains(&format!("sha256: {}", sha256)));
+    assert!(info.contains("size:") && info.contains("2.00 KiB"));
+    assert!(!info.contains("has no build"));
+
+    let info = rush(&["info", "tool-mac"]);
+    assert!(!info.contains("(current)"));
+    assert!(
+        info.contains("tool-mac has no build for x86_64-linux"),
+        "{}",
+        info
+    );
+}
+
+#[test]
+fn test_mirror_serves_as_registry() {
+    let mock = MockEnvironment::new();
+    mock.add_package("mirrored", "1.0.0", "mirrored-bin");
+    let mirror_dir = mock.home.join("mirror");
+
+    let mut mirror = Command::new(env!("CARGO_BIN_EXE_rush"));
+    mirror
+        .envs(mock.envs())
+        .arg("mirror")
+        .arg(&mirror_dir)
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Mirrored 1 packages")
+                .and(predicate::str::contains("1 artifacts downloaded")),
+        );
+
+    // The source's tarballs are gone; the mirror has its own copies
+    std::fs::remove_dir_all(&mock.registry_source).unwrap();
+    for args in [&["update"][..], &["install", "mirrored"]] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+        cmd.envs(mock.envs())
+            .env("RUSH_REGISTRY_URL", &mirror_dir)
+            .args(args)
+            .assert()
+            .success();
+    }
+    assert!(
+        mock.home
+            .join(".local/share/rush/shims/mirrored-bin")
+            .exists()
+    );
+}
+
+#[test]
+fn test_export_bundle_writes_lockfile() {
+    let mock = MockEnvironment::new();
+    mock.add_package("bundled", "1.0.0", "bundled-bin");
+
+    for args in [&["update"][..], &["install", "bundled"]] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rush"));
+        cmd.envs(mock.envs()).args(args).assert().success();
+    }
+
+    let out = mock.home.join("toolchain.tar.gz");
+    let mut export = Command::new(env!("CARGO_BIN_EXE_rush"));
+    export
+        .envs(mock.envs())
+        .arg("export-bundle")
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bundled 1 packages"));
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(
+        std::fs::File::open(&out).unwrap(),
+    ));
+    let mut paths: Vec<String> = archive
+        .entries()
+        .unwrap()
+        .map(|e| e.unwrap().path().unwrap().display().to_string())
+        .collect();
+    paths.sort();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[0].starts_with("artifacts/"));
+    assert_eq!(paths[1..], ["packages/b/bundled.toml", "rush.lock"]);
+
+    // A machine that has never seen the registry installs straight from the bundle
+    let teammate = mock.home.join("teammate");
+    let mut import = Command::new(env!("CARGO_BIN_EXE_rush"));
+    import
+        .env("HOME", &teammate)
+        .env("RUSH_REGISTRY_URL", teammate.join("no-registry"))
+        .arg("import-bundle")
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bundled (v1.0.0)"));
+    assert!(
+        teammate
+            .join(".local/share/rush/shims/bundled-bin")
+            .exists()
+    );
+}